@@ -1,3 +1,5 @@
+#![allow(clippy::explicit_counter_loop)]
+
 use std::{time::SystemTime};
 use ido::{Ido};
 
@@ -0,0 +1,110 @@
+//! A gRPC service exchanging Idos, written against the `IdoService`
+//! definition in `proto/ido.proto`.
+//!
+//! The server half is what `tonic-build` would generate for that service,
+//! trimmed to a single method so the example builds without `protoc`.
+//!
+//! Run with `cargo run --example grpc_service --features grpc`.
+
+use std::convert::Infallible;
+use std::task::{Context, Poll};
+
+use ido::proto::IdoMessage;
+use ido::Ido;
+use tonic::body::Body;
+use tonic::codegen::{http, BoxFuture, Service};
+use tonic::server::{Grpc, NamedService, UnaryService};
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::{Channel, Server};
+use tonic::{Request, Response, Status};
+use tonic_prost::ProstCodec;
+
+const EXCHANGE_PATH: &str = "/ido.IdoService/Exchange";
+
+/// Replies to every request with the same Ido plus an acknowledgement field.
+fn exchange(request: Ido) -> Ido {
+    let mut reply = request;
+    reply.set_string(&999, String::from("ACK"));
+    reply
+}
+
+#[derive(Clone)]
+struct IdoServiceServer;
+
+impl NamedService for IdoServiceServer {
+    const NAME: &'static str = "ido.IdoService";
+}
+
+struct ExchangeSvc;
+
+impl UnaryService<IdoMessage> for ExchangeSvc {
+    type Response = IdoMessage;
+    type Future = BoxFuture<Response<IdoMessage>, Status>;
+
+    fn call(&mut self, request: Request<IdoMessage>) -> Self::Future {
+        Box::pin(async move {
+            let ido = Ido::from_proto(request.get_ref())
+                .map_err(|err| Status::invalid_argument(err.to_string()))?;
+            Ok(Response::new(exchange(ido).to_proto()))
+        })
+    }
+}
+
+impl Service<http::Request<Body>> for IdoServiceServer {
+    type Response = http::Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<Body>) -> Self::Future {
+        match req.uri().path() {
+            EXCHANGE_PATH => Box::pin(async move {
+                let mut grpc = Grpc::new(ProstCodec::<IdoMessage, IdoMessage>::default());
+                Ok(grpc.unary(ExchangeSvc, req).await)
+            }),
+            _ => Box::pin(async move { Ok(Status::unimplemented("").into_http()) }),
+        }
+    }
+}
+
+async fn call_exchange(channel: Channel, ido: &Ido) -> Result<Ido, Box<dyn std::error::Error>> {
+    let mut client = tonic::client::Grpc::new(channel);
+    client.ready().await?;
+
+    let response = client
+        .unary(
+            Request::new(ido.to_proto()),
+            http::uri::PathAndQuery::from_static(EXCHANGE_PATH),
+            ProstCodec::<IdoMessage, IdoMessage>::default(),
+        )
+        .await?;
+
+    Ok(Ido::from_proto(response.get_ref())?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(
+        Server::builder()
+            .add_service(IdoServiceServer)
+            .serve_with_incoming(TcpIncoming::from(listener)),
+    );
+
+    let channel = Channel::from_shared(format!("http://{}", addr))?.connect().await?;
+
+    let mut request = Ido::new();
+    request.set_string(&1, String::from("ORDER-1"));
+    request.set_integer(&2, 100);
+
+    let reply = call_exchange(channel, &request).await?;
+    println!("{}", reply);
+
+    Ok(())
+}
+
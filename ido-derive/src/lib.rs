@@ -0,0 +1,177 @@
+//! Derive macros for the `ido` crate.
+//!
+//! `#[derive(IntoIdo)]` implements `ido::convert::ToIdo` and
+//! `#[derive(FromIdo)]` implements `ido::convert::FromIdo` for structs with
+//! named fields. Every field needs an `#[ido(key = ...)]` attribute naming
+//! the key it is stored under, or `#[ido(skip)]` to leave it out; skipped
+//! fields are filled with `Default::default()` when reading. Field types
+//! must implement `ido::convert::IdoValue`.
+//!
+//! `#[derive(IdoKey)]` implements `ido::IdoKey` for a `#[repr(i32)]` enum
+//! of unit variants, each variant standing for its discriminant.
+//!
+//! Use the macros through the `derive` feature of `ido` rather than
+//! depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Expr, Fields, Ident, Result, Type};
+
+enum FieldKey {
+    Key(Expr),
+    Skip,
+}
+
+struct Field {
+    m_ident: Ident,
+    m_key: FieldKey,
+}
+
+fn parse_fields(input: &DeriveInput) -> Result<Vec<Field>> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(input, "ido derives require a struct with named fields")),
+        },
+        _ => return Err(Error::new_spanned(input, "ido derives only support structs")),
+    };
+
+    fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().unwrap();
+            let mut key = None;
+
+            for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("ido")) {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("key") {
+                        key = Some(FieldKey::Key(meta.value()?.parse()?));
+                        Ok(())
+                    } else if meta.path.is_ident("skip") {
+                        key = Some(FieldKey::Skip);
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `key = ...` or `skip`"))
+                    }
+                })?;
+            }
+
+            let key = key.ok_or_else(|| Error::new_spanned(field, "missing #[ido(key = ...)] or #[ido(skip)]"))?;
+            Ok(Field { m_ident: ident, m_key: key })
+        })
+        .collect()
+}
+
+/// Implements `ido::convert::ToIdo` for a struct.
+#[proc_macro_derive(IntoIdo, attributes(ido))]
+pub fn derive_into_ido(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_into_ido(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Implements `ido::convert::FromIdo` for a struct.
+#[proc_macro_derive(FromIdo, attributes(ido))]
+pub fn derive_from_ido(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_ido(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Implements `ido::IdoKey` for a `#[repr(i32)]` enum.
+#[proc_macro_derive(IdoKey)]
+pub fn derive_ido_key(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_ido_key(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand_into_ido(input: &DeriveInput) -> Result<TokenStream2> {
+    let fields = parse_fields(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let writes = fields.iter().filter_map(|field| match &field.m_key {
+        FieldKey::Key(key) => {
+            let ident = &field.m_ident;
+            Some(quote! { ::ido::convert::IdoValue::write(&self.#ident, &mut ido, #key); })
+        }
+        FieldKey::Skip => None,
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::ido::convert::ToIdo for #name #ty_generics #where_clause {
+            fn to_ido(&self) -> ::ido::Ido {
+                let mut ido = ::ido::Ido::new();
+                #(#writes)*
+                ido
+            }
+        }
+    })
+}
+
+fn expand_from_ido(input: &DeriveInput) -> Result<TokenStream2> {
+    let fields = parse_fields(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let reads = fields.iter().map(|field| {
+        let ident = &field.m_ident;
+        match &field.m_key {
+            FieldKey::Key(key) => quote! { #ident: ::ido::convert::IdoValue::read(ido, #key)?, },
+            FieldKey::Skip => quote! { #ident: ::core::default::Default::default(), },
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::ido::convert::FromIdo for #name #ty_generics #where_clause {
+            fn from_ido(ido: &::ido::Ido) -> ::core::result::Result<Self, ::ido::convert::FromIdoError> {
+                ::core::result::Result::Ok(#name {
+                    #(#reads)*
+                })
+            }
+        }
+    })
+}
+
+fn expand_ido_key(input: &DeriveInput) -> Result<TokenStream2> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => return Err(Error::new_spanned(input, "IdoKey can only be derived for enums")),
+    };
+
+    let mut repr_i32 = false;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            repr_i32 |= meta.path.is_ident("i32");
+            Ok(())
+        })?;
+    }
+    if !repr_i32 {
+        return Err(Error::new_spanned(input, "IdoKey requires #[repr(i32)]"));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let key_type: Type = syn::parse_quote!(::ido::IdoKeyT);
+
+    let arms = data
+        .variants
+        .iter()
+        .map(|variant| match variant.fields {
+            Fields::Unit => {
+                let ident = &variant.ident;
+                Ok(quote! { #name::#ident => #name::#ident as #key_type, })
+            }
+            _ => Err(Error::new_spanned(variant, "IdoKey requires unit variants")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl #impl_generics ::ido::IdoKey for #name #ty_generics #where_clause {
+            fn ido_key(&self) -> #key_type {
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
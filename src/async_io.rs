@@ -0,0 +1,104 @@
+//! Reading and writing Ido frames on tokio's `AsyncRead` and `AsyncWrite`.
+//!
+//! Frames use the same length-prefixed layout as [`write_frame`] and
+//! [`read_frame`], so async and blocking peers can talk to each other.
+//! Writes are vectored: string values go to the writer straight from the
+//! Ido, as with [`Ido::encode_vectored`].
+//!
+//! [`write_frame`]: crate::codec::write_frame
+//! [`read_frame`]: crate::codec::read_frame
+
+use std::io::{self, IoSlice};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::codec::{BinaryCodec, CodecError, Decode, MAX_FRAME_LEN};
+use crate::Ido;
+
+/// Writes `ido` to `writer` as a length-prefixed frame.
+///
+/// # Errors
+///
+/// Returns `CodecError::FrameTooLarge` before writing anything if the frame
+/// would exceed [`MAX_FRAME_LEN`], or `CodecError::Io` if the writer fails.
+///
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+/// use ido::async_io::{read_frame_async, write_frame_async};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let (mut client, mut server) = tokio::io::duplex(64);
+///
+/// let mut ido = Ido::new();
+/// ido.set_string(&1, "ORDER-1".to_string());
+///
+/// let (written, read) = tokio::join!(write_frame_async(&mut client, &ido), read_frame_async(&mut server));
+/// written.unwrap();
+/// assert_eq!(read.unwrap().get_string(&1), Some("ORDER-1".to_string()));
+/// # }
+/// ```
+pub async fn write_frame_async<W: AsyncWrite + Unpin>(writer: &mut W, ido: &Ido) -> Result<(), CodecError> {
+    let vectored = ido.encode_vectored();
+    if vectored.len() > MAX_FRAME_LEN {
+        return Err(CodecError::FrameTooLarge(vectored.len()));
+    }
+
+    let prefix = (vectored.len() as u32).to_le_bytes();
+    let mut slices = vec![IoSlice::new(&prefix)];
+    slices.extend(vectored.io_slices());
+    let mut remaining = &mut slices[..];
+
+    while !remaining.is_empty() {
+        match writer.write_vectored(remaining).await {
+            Ok(0) => return Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write whole frame").into()),
+            Ok(n) => IoSlice::advance_slices(&mut remaining, n),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Reads a single length-prefixed frame from `reader`.
+///
+/// # Errors
+///
+/// Returns `CodecError::FrameTooLarge` if the length prefix exceeds
+/// [`MAX_FRAME_LEN`], `CodecError::Io` if the reader fails or ends before
+/// the frame is complete, or the error of decoding the frame.
+pub async fn read_frame_async<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Ido, CodecError> {
+    let len = reader.read_u32_le().await? as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(CodecError::FrameTooLarge(len));
+    }
+
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body).await?;
+    BinaryCodec.decode(&body)
+}
+
+impl Ido {
+    /// Writes the Ido to an async writer as a length-prefixed frame; see
+    /// [`write_frame_async`].
+    ///
+    /// # Errors
+    ///
+    /// See [`write_frame_async`].
+    pub async fn encode_to_async<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> Result<(), CodecError> {
+        write_frame_async(writer, self).await
+    }
+
+    /// Reads one length-prefixed frame from an async reader; see
+    /// [`read_frame_async`].
+    ///
+    /// # Errors
+    ///
+    /// See [`read_frame_async`].
+    pub async fn decode_from_async<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Ido, CodecError> {
+        read_frame_async(reader).await
+    }
+}
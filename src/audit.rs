@@ -0,0 +1,184 @@
+//! Auditing which keys are read and written across the process.
+//!
+//! Auditing is off by default. Once [`enable`] is called, every Ido in the
+//! process counts the values read through its getters and the values
+//! stored through its setters, appends and decoders, per key, until
+//! [`disable`] is called. Keys of nested Idos are counted like top-level
+//! keys. The counts are shared by all threads and can be read with
+//! [`usage`] or as an Ido with [`snapshot`], to find fields that are never
+//! read and fields that are read on every message.
+//!
+//! With auditing disabled, a read or write costs a single atomic load.
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use crate::key::key_to_i64;
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+/// Key of the array of rows in a [`snapshot`].
+pub const ENTRIES: IdoKeyT = 1;
+/// Key of the audited key in a row of a [`snapshot`].
+pub const KEY: IdoKeyT = 1;
+/// Key of the number of reads in a row of a [`snapshot`].
+pub const READS: IdoKeyT = 2;
+/// Key of the number of writes in a row of a [`snapshot`].
+pub const WRITES: IdoKeyT = 3;
+/// Key of the time of the last read in a row of a [`snapshot`], absent if
+/// the key was never read or the `chrono` feature is disabled.
+pub const LAST_READ: IdoKeyT = 4;
+/// Key of the time of the last write in a row of a [`snapshot`], absent if
+/// the key was never written or the `chrono` feature is disabled.
+pub const LAST_WRITE: IdoKeyT = 5;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static USAGE: Mutex<BTreeMap<IdoKeyT, KeyUsage>> = Mutex::new(BTreeMap::new());
+
+thread_local! {
+    // Set while `snapshot` builds its Ido, so it does not audit itself.
+    static PAUSED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// How often a key was read and written while auditing was enabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyUsage {
+    /// Number of values read.
+    pub reads: u64,
+    /// Number of values stored.
+    pub writes: u64,
+    /// Time of the last read.
+    #[cfg(feature = "chrono")]
+    pub last_read: Option<DateTime<Utc>>,
+    /// Time of the last write.
+    #[cfg(feature = "chrono")]
+    pub last_write: Option<DateTime<Utc>>,
+}
+
+/// Starts counting reads and writes. Counts collected before are kept.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Stops counting reads and writes. Counts collected so far are kept.
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+}
+
+/// Returns `true` while auditing is enabled.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Forgets every count collected so far.
+pub fn reset() {
+    lock().clear();
+}
+
+/// Returns the usage of every key read or written while auditing was
+/// enabled, in ascending key order.
+pub fn usage() -> BTreeMap<IdoKeyT, KeyUsage> {
+    lock().clone()
+}
+
+/// Returns the usage of every key as an Ido.
+///
+/// The Ido holds an array under [`ENTRIES`] with one element per key, in
+/// ascending key order. Each element holds the key under [`KEY`], the
+/// counts under [`READS`] and [`WRITES`] and the times under
+/// [`LAST_READ`] and [`LAST_WRITE`].
+///
+/// # Example
+///
+/// ```
+/// use ido::{audit, Ido};
+///
+/// audit::enable();
+/// let mut ido = Ido::new();
+/// ido.set_integer(&7001, 5);
+/// ido.get_i64(&7001);
+/// ido.get_i64(&7001);
+/// audit::disable();
+///
+/// let snapshot = audit::snapshot();
+/// let row = snapshot
+///     .get_item(&audit::ENTRIES)
+///     .unwrap()
+///     .m_array
+///     .into_iter()
+///     .find(|row| row.get_i64(&audit::KEY) == Some(7001))
+///     .unwrap();
+/// assert_eq!(row.get_i64(&audit::READS), Some(2));
+/// assert_eq!(row.get_i64(&audit::WRITES), Some(1));
+/// ```
+pub fn snapshot() -> Ido {
+    let usage = usage();
+    PAUSED.with(|paused| paused.set(true));
+
+    let mut rows = IdoItem::new();
+    rows.m_type = IdoItemType::ARRAY;
+    for (key, usage) in usage {
+        let mut row = Ido::new();
+        row.set_integer(&KEY, key_to_i64(key));
+        row.set_integer(&READS, i64::try_from(usage.reads).unwrap_or(i64::MAX));
+        row.set_integer(&WRITES, i64::try_from(usage.writes).unwrap_or(i64::MAX));
+        #[cfg(feature = "chrono")]
+        if let Some(time) = usage.last_read {
+            row.set_value(&LAST_READ, time);
+        }
+        #[cfg(feature = "chrono")]
+        if let Some(time) = usage.last_write {
+            row.set_value(&LAST_WRITE, time);
+        }
+        rows.m_array.push(row);
+    }
+    let mut ido = Ido::new();
+    ido.set_item(&ENTRIES, rows);
+
+    PAUSED.with(|paused| paused.set(false));
+    ido
+}
+
+/// Counts a read of `key` if auditing is enabled.
+pub(crate) fn record_read(key: IdoKeyT) {
+    if is_enabled() && !PAUSED.with(Cell::get) {
+        let mut usage = lock();
+        let entry = usage.entry(key).or_default();
+        entry.reads += 1;
+        #[cfg(feature = "chrono")]
+        {
+            entry.last_read = Some(Utc::now());
+        }
+    }
+}
+
+/// Counts a write of `key` if auditing is enabled.
+pub(crate) fn record_write(key: IdoKeyT) {
+    if is_enabled() && !PAUSED.with(Cell::get) {
+        let mut usage = lock();
+        let entry = usage.entry(key).or_default();
+        entry.writes += 1;
+        #[cfg(feature = "chrono")]
+        {
+            entry.last_write = Some(Utc::now());
+        }
+    }
+}
+
+fn lock() -> MutexGuard<'static, BTreeMap<IdoKeyT, KeyUsage>> {
+    USAGE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Ido {
+    /// Returns the item a getter reads under `key`, counting the read in
+    /// the operation counts and, when auditing is enabled, in the audit.
+    pub(crate) fn read_item(&self, key: &IdoKeyT) -> Option<&IdoItem> {
+        self.m_ops.count_get();
+        record_read(*key);
+        self.live_item(key)
+    }
+}
@@ -0,0 +1,259 @@
+//! Splitting large Idos into chunks that each fit in a frame.
+//!
+//! [`write_chunked`] encodes an Ido in the binary format and writes it as a
+//! run of chunks whose payload is at most the configured size. Every chunk
+//! is a length-prefixed frame, so a transport that moves frames moves
+//! chunks as well, whatever the size of the Ido:
+//!
+//! ```text
+//! chunk  := len:u32 seq:u32 flags:u8 byte*
+//! ```
+//!
+//! `len` covers everything after it, `seq` counts the chunks of an Ido from
+//! 0, and bit 0 of `flags` marks the last chunk. The bytes of all chunks of
+//! an Ido, in order, form its binary encoding. A small Ido takes a single
+//! chunk.
+//!
+//! [`ChunkDecoder`] reassembles Idos from chunks arriving in pieces, and
+//! [`read_chunked`] reads one Ido from a blocking reader.
+
+use std::io::{Read, Write};
+
+use crate::codec::{BinaryCodec, CodecError, Decode, FrameDecoder, MAX_FRAME_LEN};
+use crate::Ido;
+
+/// Flag of the last chunk of an Ido.
+const LAST: u8 = 1;
+
+/// Length of the sequence number and flags at the start of a chunk body.
+const HEADER_LEN: usize = 5;
+
+/// Writes `ido` to `writer` as chunks carrying at most `max_chunk` bytes of
+/// the encoded Ido each.
+///
+/// # Returns
+///
+/// The number of chunks written.
+///
+/// # Errors
+///
+/// Returns `CodecError::Io` if the writer fails, in which case some of the
+/// chunks may have been written.
+///
+/// # Panics
+///
+/// Panics if `max_chunk` is 0 or leaves no room for the chunk header within
+/// [`MAX_FRAME_LEN`].
+///
+/// # Example
+///
+/// ```
+/// use ido::chunked::{read_chunked, write_chunked};
+/// use ido::Ido;
+///
+/// let mut ido = Ido::new();
+/// ido.set_string(&1, "x".repeat(100));
+///
+/// let mut wire = Vec::new();
+/// assert_eq!(write_chunked(&mut wire, &ido, 32).unwrap(), 4);
+///
+/// let decoded = read_chunked(&mut wire.as_slice()).unwrap();
+/// assert_eq!(decoded.get_string(&1), Some("x".repeat(100)));
+/// ```
+pub fn write_chunked<W: Write>(writer: &mut W, ido: &Ido, max_chunk: usize) -> Result<usize, CodecError> {
+    assert!(
+        max_chunk > 0 && max_chunk <= MAX_FRAME_LEN - HEADER_LEN,
+        "chunk size {} is out of range",
+        max_chunk
+    );
+
+    let encoded = ido.encode();
+    let count = encoded.len().div_ceil(max_chunk);
+    let mut header = [0u8; 4 + HEADER_LEN];
+    for (seq, bytes) in encoded.chunks(max_chunk).enumerate() {
+        header[..4].copy_from_slice(&((HEADER_LEN + bytes.len()) as u32).to_le_bytes());
+        header[4..8].copy_from_slice(&(seq as u32).to_le_bytes());
+        header[8] = if seq + 1 == count { LAST } else { 0 };
+        writer.write_all(&header)?;
+        writer.write_all(bytes)?;
+    }
+    Ok(count)
+}
+
+/// Reads one chunked Ido from `reader`, blocking until its last chunk has
+/// arrived.
+///
+/// # Errors
+///
+/// Returns `CodecError::ChunkOutOfOrder` if a chunk does not follow the
+/// previous one, `CodecError::InvalidChunk` for a chunk without a header,
+/// `CodecError::FrameTooLarge` for a chunk longer than [`MAX_FRAME_LEN`],
+/// `CodecError::Io` if the reader fails or ends, or the error of decoding
+/// the reassembled Ido.
+pub fn read_chunked<R: Read>(reader: &mut R) -> Result<Ido, CodecError> {
+    let mut assembly = Assembly::new(usize::MAX);
+    loop {
+        let mut len = [0u8; 4];
+        reader.read_exact(&mut len)?;
+        let len = u32::from_le_bytes(len) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(CodecError::FrameTooLarge(len));
+        }
+
+        let mut body = vec![0u8; len];
+        reader.read_exact(&mut body)?;
+        if let Some(ido) = assembly.push(&body)? {
+            return Ok(ido);
+        }
+    }
+}
+
+/// Reassembles chunked Idos from bytes that arrive in pieces.
+///
+/// Like [`FrameDecoder`], bytes are fed in as they are received and whole
+/// Idos are taken out with `next_ido`. The chunks of an Ido are kept until
+/// its last chunk arrives, up to the limit set with `with_max_len`.
+///
+/// # Example
+///
+/// ```
+/// use ido::chunked::{write_chunked, ChunkDecoder};
+/// use ido::Ido;
+///
+/// let mut ido = Ido::new();
+/// ido.set_string(&1, "x".repeat(100));
+/// let mut wire = Vec::new();
+/// write_chunked(&mut wire, &ido, 32).unwrap();
+///
+/// let mut decoder = ChunkDecoder::new();
+/// decoder.feed(&wire[..60]);
+/// assert!(decoder.next_ido().unwrap().is_none());
+///
+/// decoder.feed(&wire[60..]);
+/// assert_eq!(decoder.next_ido().unwrap().unwrap().get_string(&1), Some("x".repeat(100)));
+/// ```
+#[derive(Debug)]
+pub struct ChunkDecoder {
+    m_frames: FrameDecoder,
+    m_assembly: Assembly,
+}
+
+impl Default for ChunkDecoder {
+    fn default() -> Self {
+        ChunkDecoder::new()
+    }
+}
+
+impl ChunkDecoder {
+    /// Creates a decoder with nothing buffered and no limit on the size of
+    /// a reassembled Ido.
+    pub fn new() -> Self {
+        ChunkDecoder { m_frames: FrameDecoder::new(), m_assembly: Assembly::new(usize::MAX) }
+    }
+
+    /// Limits the encoded size of a reassembled Ido to `max_len` bytes.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.m_assembly.m_max_len = max_len;
+        self
+    }
+
+    /// Appends received bytes.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.m_frames.feed(bytes);
+    }
+
+    /// Reads whatever `reader` has available with a single `read` call; see
+    /// [`FrameDecoder::read_from`].
+    ///
+    /// # Errors
+    ///
+    /// See [`FrameDecoder::read_from`].
+    pub fn read_from<R: Read>(&mut self, reader: &mut R) -> Result<usize, CodecError> {
+        self.m_frames.read_from(reader)
+    }
+
+    /// Returns the number of bytes received but not reassembled yet,
+    /// including the chunks held for an incomplete Ido.
+    pub fn buffered(&self) -> usize {
+        self.m_frames.buffered() + self.m_assembly.m_bytes.len()
+    }
+
+    /// Reassembles the next Ido if all of its chunks have been received.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::ChunkOutOfOrder` if a chunk does not follow the
+    /// previous one, `CodecError::InvalidChunk` for a chunk without a
+    /// header, `CodecError::FrameTooLarge` for a chunk longer than
+    /// [`MAX_FRAME_LEN`] or an Ido larger than the limit, or the error of
+    /// decoding the reassembled Ido. After an error the incomplete Ido is
+    /// dropped, and chunks are skipped until the first chunk of the next
+    /// one. A first chunk that arrives before the last chunk of the
+    /// previous Ido also drops the previous Ido, which is what a writer
+    /// that failed halfway leaves behind.
+    pub fn next_ido(&mut self) -> Result<Option<Ido>, CodecError> {
+        while let Some(body) = self.m_frames.next_body()? {
+            if let Some(ido) = self.m_assembly.push(body)? {
+                return Ok(Some(ido));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The chunks received so far of the Ido being reassembled.
+#[derive(Debug)]
+struct Assembly {
+    m_bytes: Vec<u8>,
+    m_next: u32,
+    m_max_len: usize,
+    m_skipping: bool,
+}
+
+impl Assembly {
+    fn new(max_len: usize) -> Self {
+        Assembly { m_bytes: Vec::new(), m_next: 0, m_max_len: max_len, m_skipping: false }
+    }
+
+    /// Adds the chunk with body `body`, returning the Ido it completes.
+    fn push(&mut self, body: &[u8]) -> Result<Option<Ido>, CodecError> {
+        let Some((header, bytes)) = body.split_first_chunk::<HEADER_LEN>() else {
+            return Err(self.fail(CodecError::InvalidChunk));
+        };
+        let seq = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let last = header[4] & LAST != 0;
+
+        if seq == 0 {
+            self.m_bytes.clear();
+            self.m_next = 0;
+            self.m_skipping = false;
+        } else if self.m_skipping {
+            return Ok(None);
+        }
+        if seq != self.m_next {
+            return Err(self.fail(CodecError::ChunkOutOfOrder(seq)));
+        }
+        let len = self.m_bytes.len() + bytes.len();
+        if len > self.m_max_len {
+            return Err(self.fail(CodecError::FrameTooLarge(len)));
+        }
+
+        self.m_bytes.extend_from_slice(bytes);
+        self.m_next = self.m_next.wrapping_add(1);
+        if !last {
+            return Ok(None);
+        }
+
+        self.m_next = 0;
+        let bytes = std::mem::take(&mut self.m_bytes);
+        BinaryCodec.decode(&bytes).map(Some)
+    }
+
+    /// Drops the incomplete Ido and skips to the next one.
+    fn fail(&mut self, err: CodecError) -> CodecError {
+        self.m_bytes.clear();
+        self.m_next = 0;
+        self.m_skipping = true;
+        err
+    }
+}
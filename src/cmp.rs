@@ -0,0 +1,164 @@
+//! Equality and ordering of items and Idos.
+//!
+//! Items compare by value; keys, versions, timestamps and flags are ignored.
+//! Values of the same type compare naturally, and INTEGER, INTEGER128 and
+//! FLOAT values compare with each other exactly, without rounding the
+//! integer to a float. Values of unrelated types, and NaN, are unordered.
+//!
+//! Two Idos are equal when they hold the same keys with equal values,
+//! regardless of insertion order. ARRAY items compare element by element
+//! like slices, but as Idos have no order of their own, arrays whose first
+//! differing elements are both present are unordered.
+
+use std::cmp::Ordering;
+
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+/// 2^127, the first float above every `i128`.
+const I128_LIMIT: f64 = 170_141_183_460_469_231_731_687_303_715_884_105_728.0;
+
+fn integer_value(item: &IdoItem) -> Option<i128> {
+    match item.m_type {
+        IdoItemType::INTEGER => Some(i128::from(item.m_integer)),
+        IdoItemType::INTEGER128 => Some(item.m_integer128),
+        _ => None,
+    }
+}
+
+/// Compares an integer with a float exactly.
+fn cmp_integer_float(integer: i128, float: f64) -> Option<Ordering> {
+    if float.is_nan() {
+        return None;
+    }
+    if float >= I128_LIMIT {
+        return Some(Ordering::Less);
+    }
+    if float < -I128_LIMIT {
+        return Some(Ordering::Greater);
+    }
+
+    let whole = float.trunc();
+    match integer.cmp(&(whole as i128)) {
+        Ordering::Equal => 0.0.partial_cmp(&(float - whole)),
+        ordering => Some(ordering),
+    }
+}
+
+fn cmp_arrays(left: &[Ido], right: &[Ido]) -> Option<Ordering> {
+    for (left, right) in left.iter().zip(right) {
+        if left != right {
+            return None;
+        }
+    }
+    Some(left.len().cmp(&right.len()))
+}
+
+impl PartialEq for IdoItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+///
+/// let mut ido = Ido::new();
+/// ido.set_integer(&1, 3);
+/// ido.set_f64(&2, 2.5);
+/// ido.set_integer(&3, 7);
+///
+/// let items: Vec<_> = ido.into_iter().map(|(_, item)| item).collect();
+/// let max = items.iter().max_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
+/// assert_eq!(max.m_integer, 7);
+/// assert!(items[1] < items[0]);
+/// ```
+impl PartialOrd for IdoItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.m_type, other.m_type) {
+            (IdoItemType::STRING, IdoItemType::STRING) => Some(self.m_string.cmp(&other.m_string)),
+            (IdoItemType::FLOAT, IdoItemType::FLOAT) => self.m_float.partial_cmp(&other.m_float),
+            #[cfg(feature = "chrono")]
+            (IdoItemType::DATETIME, IdoItemType::DATETIME) => Some(self.m_datetime.cmp(&other.m_datetime)),
+            (IdoItemType::BOOL, IdoItemType::BOOL) => Some(self.m_bool.cmp(&other.m_bool)),
+            (IdoItemType::ARRAY, IdoItemType::ARRAY) => cmp_arrays(&self.m_array, &other.m_array),
+            (IdoItemType::FLOAT, _) => cmp_integer_float(integer_value(other)?, self.m_float).map(Ordering::reverse),
+            (_, IdoItemType::FLOAT) => cmp_integer_float(integer_value(self)?, other.m_float),
+            _ => Some(integer_value(self)?.cmp(&integer_value(other)?)),
+        }
+    }
+}
+
+impl PartialEq for Ido {
+    fn eq(&self, other: &Self) -> bool {
+        let live = |ido: &Ido| ido.ordered_items().filter(|(key, _)| ido.live_item(key).is_some()).count();
+
+        live(self) == live(other)
+            && self
+                .ordered_items()
+                .filter_map(|(key, _)| Some((key, self.live_item(&key)?)))
+                .all(|(key, item)| other.live_item(&key) == Some(item))
+    }
+}
+
+impl Ido {
+    /// Compares only the values under `keys`, as `==` would compare them.
+    ///
+    /// A key missing from both Idos counts as equal, and a key missing from
+    /// one of them as different. Arrays are compared whole; use
+    /// `eq_on_deep` to restrict their elements to `keys` as well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ours = Ido::new();
+    /// ours.set_string(&1, "ORDER-1".to_string());
+    /// ours.set_integer(&2, 100);
+    /// ours.set_integer(&52, 1_700_000_000);
+    ///
+    /// let mut theirs = ours.clone();
+    /// theirs.set_integer(&52, 1_700_000_005);
+    ///
+    /// assert!(ours != theirs);
+    /// assert!(ours.eq_on(&theirs, &[1, 2]));
+    /// ```
+    pub fn eq_on(&self, other: &Ido, keys: &[IdoKeyT]) -> bool {
+        keys.iter().all(|key| self.live_item(key) == other.live_item(key))
+    }
+
+    /// Compares only the values under `keys` like `eq_on`, but compares
+    /// arrays element by element with `eq_on_deep` on the same keys, so
+    /// fields left out are ignored at every depth.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut fill = Ido::new();
+    /// fill.set_integer(&2, 100);
+    /// fill.set_integer(&52, 1_700_000_000);
+    ///
+    /// let mut ours = Ido::new();
+    /// ours.append_array(&10, fill.clone());
+    ///
+    /// fill.set_integer(&52, 1_700_000_005);
+    /// let mut theirs = Ido::new();
+    /// theirs.append_array(&10, fill);
+    ///
+    /// assert!(!ours.eq_on(&theirs, &[2, 10]));
+    /// assert!(ours.eq_on_deep(&theirs, &[2, 10]));
+    /// ```
+    pub fn eq_on_deep(&self, other: &Ido, keys: &[IdoKeyT]) -> bool {
+        keys.iter().all(|key| match (self.live_item(key), other.live_item(key)) {
+            (Some(left), Some(right)) if left.m_type == IdoItemType::ARRAY && right.m_type == IdoItemType::ARRAY => {
+                left.m_array.len() == right.m_array.len()
+                    && left.m_array.iter().zip(&right.m_array).all(|(left, right)| left.eq_on_deep(right, keys))
+            }
+            (left, right) => left == right,
+        })
+    }
+}
@@ -0,0 +1,1131 @@
+//! Binary encoding for Ido objects.
+//!
+//! Items are written in insertion order so that a decoded Ido iterates the
+//! same way as the one it was encoded from; expired items are left out.
+//! All integers are little-endian, except keys: they are zigzag-encoded
+//! LEB128 varints, so keys from -64 to 63 take one byte and wider keys only
+//! pay for the bytes they need.
+//!
+//! ```text
+//! ido    := count:u32 item*
+//! item   := key:varint tag:u8 value
+//! value  := STRING   len:u32 utf8*
+//!         | INTEGER  i64
+//!         | FLOAT    f64
+//!         | DATETIME secs:i64 nanos:u32
+//!         | ARRAY    len:u32 count:u32 ido*
+//!         | INTEGER128 i128
+//!         | BOOL     u8
+//! frame  := len:u32 ido
+//! chunk  := len:u32 seq:u32 flags:u8 byte*
+//! ```
+//!
+//! An Ido too large for a single frame can be split into chunks, see the
+//! [`chunked`](crate::chunked) module. The bytes of its chunks, in order,
+//! make up the encoded Ido.
+//!
+//! The `len` of an ARRAY covers everything after it, so a reader can skip a
+//! nested array without walking its elements.
+//!
+//! Without the `chrono` feature there is no DATETIME type, and decoding a
+//! DATETIME value fails with `CodecError::Unsupported`.
+//!
+//! With the `tracing` feature enabled, every encode and decode runs inside an
+//! `ido.encode` or `ido.decode` span at trace level that records the number
+//! of items and the size in bytes.
+
+#[cfg(feature = "chrono")]
+use chrono::DateTime;
+use std::fmt;
+use std::io::{self, IoSlice, Read, Write};
+use std::time::SystemTime;
+
+use crate::key::{key_from_i64, key_to_i64};
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT, IdoLimits};
+
+/// Largest frame body accepted by [`read_frame`].
+pub const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// Deepest array nesting accepted when decoding without a `max_depth`
+/// limit. Nested arrays are decoded recursively, so without a bound a
+/// small hostile frame could overflow the stack.
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+pub(crate) const TAG_STRING: u8 = 1;
+const TAG_INTEGER: u8 = 2;
+const TAG_FLOAT: u8 = 3;
+const TAG_DATETIME: u8 = 4;
+const TAG_ARRAY: u8 = 5;
+const TAG_INTEGER128: u8 = 6;
+const TAG_BOOL: u8 = 7;
+
+/// Errors raised while encoding or decoding an Ido.
+#[derive(Debug)]
+pub enum CodecError {
+    /// The buffer ended in the middle of a value.
+    UnexpectedEof,
+    /// A key varint that is malformed or does not fit in `IdoKeyT`.
+    InvalidKey,
+    /// A type tag that does not map to an `IdoItemType`.
+    UnknownTypeTag(u8),
+    /// A string value that is not valid UTF-8.
+    InvalidUtf8,
+    /// A datetime value outside the range chrono can represent.
+    InvalidDateTime,
+    /// Bytes left over after a complete Ido was decoded.
+    TrailingBytes(usize),
+    /// A frame whose declared length exceeds [`MAX_FRAME_LEN`].
+    FrameTooLarge(usize),
+    /// The value under this key cannot be represented in the target format.
+    Unsupported(IdoKeyT),
+    /// The value under this key is malformed for its type.
+    InvalidValue(IdoKeyT),
+    /// The text field starting at this byte offset is malformed.
+    InvalidField(usize),
+    /// The array under this key nests deeper than the decoding limits allow.
+    DepthExceeded(IdoKeyT),
+    /// The array under this key is longer than the decoding limits allow.
+    ArrayTooLong(IdoKeyT),
+    /// The value under this key takes the Ido beyond the memory budget of
+    /// the decoding limits.
+    MemoryExceeded(IdoKeyT),
+    /// The output buffer is smaller than the given encoded length.
+    BufferTooSmall(usize),
+    /// A chunk too short to hold its sequence number and flags.
+    InvalidChunk,
+    /// A chunk whose sequence number, given here, does not follow the
+    /// previous chunk of the same Ido.
+    ChunkOutOfOrder(u32),
+    /// The underlying reader or writer failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::UnexpectedEof => write!(f, "unexpected end of buffer"),
+            CodecError::InvalidKey => write!(f, "key is malformed or out of range"),
+            CodecError::UnknownTypeTag(tag) => write!(f, "unknown type tag {}", tag),
+            CodecError::InvalidUtf8 => write!(f, "string value is not valid utf-8"),
+            CodecError::InvalidDateTime => write!(f, "datetime value out of range"),
+            CodecError::TrailingBytes(n) => write!(f, "{} trailing bytes after ido", n),
+            CodecError::FrameTooLarge(n) => write!(f, "frame of {} bytes exceeds limit", n),
+            CodecError::Unsupported(key) => write!(f, "value of key {} is not supported by the format", key),
+            CodecError::InvalidValue(key) => write!(f, "value of key {} is malformed", key),
+            CodecError::InvalidField(offset) => write!(f, "invalid field at byte {}", offset),
+            CodecError::DepthExceeded(key) => write!(f, "array of key {} nests too deep", key),
+            CodecError::ArrayTooLong(key) => write!(f, "array of key {} has too many elements", key),
+            CodecError::MemoryExceeded(key) => write!(f, "value of key {} exceeds the memory limit", key),
+            CodecError::BufferTooSmall(n) => write!(f, "buffer too small, {} bytes needed", n),
+            CodecError::InvalidChunk => write!(f, "chunk is too short"),
+            CodecError::ChunkOutOfOrder(seq) => write!(f, "chunk {} is out of order", seq),
+            CodecError::Io(err) => write!(f, "io error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CodecError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CodecError {
+    fn from(err: io::Error) -> Self {
+        CodecError::Io(err)
+    }
+}
+
+/// A codec that can turn an Ido into bytes.
+pub trait Encode {
+    /// Appends the encoded form of `ido` to `out`.
+    fn encode(&self, ido: &Ido, out: &mut Vec<u8>) -> Result<(), CodecError>;
+}
+
+/// A codec that can rebuild an Ido from bytes.
+pub trait Decode {
+    /// Decodes a single Ido occupying the whole of `buf`.
+    fn decode(&self, buf: &[u8]) -> Result<Ido, CodecError>;
+}
+
+/// The native binary format described in the module documentation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BinaryCodec;
+
+impl Encode for BinaryCodec {
+    fn encode(&self, ido: &Ido, out: &mut Vec<u8>) -> Result<(), CodecError> {
+        #[cfg(feature = "tracing")]
+        let span = tracing::trace_span!("ido.encode", items = ido.size(), bytes = tracing::field::Empty).entered();
+        #[cfg(feature = "tracing")]
+        let start = out.len();
+
+        write_ido(ido, out, SystemTime::now());
+
+        #[cfg(feature = "tracing")]
+        span.record("bytes", out.len() - start);
+        Ok(())
+    }
+}
+
+impl Decode for BinaryCodec {
+    fn decode(&self, buf: &[u8]) -> Result<Ido, CodecError> {
+        decode_limited(buf, &IdoLimits::default())
+    }
+}
+
+fn decode_limited(buf: &[u8], limits: &IdoLimits) -> Result<Ido, CodecError> {
+    #[cfg(feature = "tracing")]
+    let span = tracing::trace_span!("ido.decode", bytes = buf.len(), items = tracing::field::Empty).entered();
+
+    let mut reader = Reader::new(buf);
+    let ido = read_ido(&mut reader, limits, 0)?;
+
+    #[cfg(feature = "tracing")]
+    span.record("items", ido.size());
+
+    match reader.remaining() {
+        0 => Ok(ido),
+        n => Err(CodecError::TrailingBytes(n)),
+    }
+}
+
+/// Destination of the binary writer.
+///
+/// Bytes passed to `put_ref` live as long as the Ido being written, which
+/// lets a sink keep a reference instead of copying them. Array lengths are
+/// only known once their elements are written, so sinks that can go back
+/// write a placeholder in `array_len` and fill it in with `patch`.
+trait Sink<'a> {
+    fn put(&mut self, bytes: &[u8]);
+
+    fn put_ref(&mut self, bytes: &'a [u8]) {
+        self.put(bytes);
+    }
+
+    /// Number of bytes written so far.
+    fn written(&self) -> usize;
+
+    /// Writes the length of an array holding `elements`, leaving out items
+    /// expired by `now`.
+    ///
+    /// # Returns
+    ///
+    /// A handle to pass to `patch` once the length is known, or `None` if
+    /// the length was written as is.
+    fn array_len(&mut self, _elements: &[Ido], _now: SystemTime) -> Option<usize> {
+        let handle = self.written();
+        self.put(&[0; 4]);
+        Some(handle)
+    }
+
+    fn patch(&mut self, handle: usize, value: u32);
+}
+
+impl<'a> Sink<'a> for Vec<u8> {
+    fn put(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+
+    fn written(&self) -> usize {
+        self.len()
+    }
+
+    fn patch(&mut self, handle: usize, value: u32) {
+        self[handle..handle + 4].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// A caller's buffer, already checked to be large enough.
+struct SliceSink<'b> {
+    m_buf: &'b mut [u8],
+    m_pos: usize,
+}
+
+impl<'a> Sink<'a> for SliceSink<'_> {
+    fn put(&mut self, bytes: &[u8]) {
+        self.m_buf[self.m_pos..self.m_pos + bytes.len()].copy_from_slice(bytes);
+        self.m_pos += bytes.len();
+    }
+
+    fn written(&self) -> usize {
+        self.m_pos
+    }
+
+    fn patch(&mut self, handle: usize, value: u32) {
+        self.m_buf[handle..handle + 4].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// A writer that cannot go back, so array lengths are computed up front.
+/// The first error is kept and later bytes are dropped.
+struct WriteSink<'w, W: Write> {
+    m_writer: &'w mut W,
+    m_written: usize,
+    m_error: Option<io::Error>,
+}
+
+impl<'a, W: Write> Sink<'a> for WriteSink<'_, W> {
+    fn put(&mut self, bytes: &[u8]) {
+        if self.m_error.is_none() {
+            self.m_error = self.m_writer.write_all(bytes).err();
+            self.m_written += bytes.len();
+        }
+    }
+
+    fn written(&self) -> usize {
+        self.m_written
+    }
+
+    fn array_len(&mut self, elements: &[Ido], now: SystemTime) -> Option<usize> {
+        let len = 4 + elements.iter().map(|element| encoded_len(element, now)).sum::<usize>();
+        self.put(&(len as u32).to_le_bytes());
+        None
+    }
+
+    fn patch(&mut self, _handle: usize, _value: u32) {
+        unreachable!("array lengths are written up front");
+    }
+}
+
+enum Part<'a> {
+    /// A range of the header buffer.
+    Header(usize, usize),
+    Borrowed(&'a [u8]),
+}
+
+impl<'a> Sink<'a> for VectoredIdo<'a> {
+    fn put(&mut self, bytes: &[u8]) {
+        let start = self.m_headers.len();
+        self.m_headers.extend_from_slice(bytes);
+        self.m_len += bytes.len();
+        match self.m_parts.last_mut() {
+            Some(Part::Header(_, end)) if *end == start => *end += bytes.len(),
+            _ => self.m_parts.push(Part::Header(start, start + bytes.len())),
+        }
+    }
+
+    fn put_ref(&mut self, bytes: &'a [u8]) {
+        if !bytes.is_empty() {
+            self.m_parts.push(Part::Borrowed(bytes));
+            self.m_len += bytes.len();
+        }
+    }
+
+    fn written(&self) -> usize {
+        self.m_len
+    }
+
+    fn array_len(&mut self, _elements: &[Ido], _now: SystemTime) -> Option<usize> {
+        let handle = self.m_headers.len();
+        self.put(&[0; 4]);
+        Some(handle)
+    }
+
+    fn patch(&mut self, handle: usize, value: u32) {
+        self.m_headers[handle..handle + 4].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// An encoded Ido split into slices for a vectored write.
+///
+/// String values are not copied: their slices point into the Ido the
+/// encoding was made from. Everything else is written to a small header
+/// buffer owned by this value. Created by [`Ido::encode_vectored`].
+pub struct VectoredIdo<'a> {
+    m_headers: Vec<u8>,
+    m_parts: Vec<Part<'a>>,
+    m_len: usize,
+}
+
+impl VectoredIdo<'_> {
+    /// Returns the slices that make up the encoding, in order.
+    pub fn io_slices(&self) -> Vec<IoSlice<'_>> {
+        self.m_parts
+            .iter()
+            .map(|part| match part {
+                Part::Header(start, end) => IoSlice::new(&self.m_headers[*start..*end]),
+                Part::Borrowed(bytes) => IoSlice::new(bytes),
+            })
+            .collect()
+    }
+
+    /// Returns the total length of the encoding in bytes.
+    pub fn len(&self) -> usize {
+        self.m_len
+    }
+
+    /// Returns `true` if the encoding is empty, which it never is.
+    pub fn is_empty(&self) -> bool {
+        self.m_len == 0
+    }
+
+    /// Writes the whole encoding to `writer` with vectored writes,
+    /// retrying until every slice has been written.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the writer, or `io::ErrorKind::WriteZero` if it
+    /// stops accepting bytes.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut slices = self.io_slices();
+        let mut remaining = &mut slices[..];
+
+        while !remaining.is_empty() {
+            match writer.write_vectored(remaining) {
+                Ok(0) => return Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write whole ido")),
+                Ok(n) => IoSlice::advance_slices(&mut remaining, n),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the items that have not expired by `now`. The same `now` is used
+/// for nested Idos and by `encoded_len`, so lengths written up front match.
+fn write_ido<'a, S: Sink<'a>>(ido: &'a Ido, out: &mut S, now: SystemTime) {
+    out.put(&(ido.live_items_at(now).count() as u32).to_le_bytes());
+    for (key, item) in ido.live_items_at(now) {
+        write_item(key, item, out, write_ido, now);
+    }
+}
+
+/// Writes the live items in key order, with every number that has an
+/// integer value written as the narrowest integer type holding it.
+fn write_canonical(ido: &Ido, out: &mut Vec<u8>, now: SystemTime) {
+    let mut items: Vec<(IdoKeyT, &IdoItem)> = ido.live_items_at(now).collect();
+    items.sort_unstable_by_key(|(key, _)| *key);
+
+    out.put(&(items.len() as u32).to_le_bytes());
+    for (key, item) in items {
+        let integer = match item.m_type {
+            IdoItemType::INTEGER => Some(i128::from(item.m_integer)),
+            IdoItemType::INTEGER128 => Some(item.m_integer128),
+            // Exact for every integral float below 2^127 in magnitude.
+            IdoItemType::FLOAT if item.m_float.fract() == 0.0 && item.m_float.abs() < 2f64.powi(127) => {
+                Some(item.m_float as i128)
+            }
+            _ => None,
+        };
+
+        let mut number = IdoItem::new();
+        let item = match integer {
+            Some(value) => {
+                match i64::try_from(value) {
+                    Ok(value) => {
+                        number.m_type = IdoItemType::INTEGER;
+                        number.m_integer = value;
+                    }
+                    Err(_) => {
+                        number.m_type = IdoItemType::INTEGER128;
+                        number.m_integer128 = value;
+                    }
+                }
+                &number
+            }
+            None if item.m_type == IdoItemType::FLOAT && item.m_float.is_nan() => {
+                number.m_type = IdoItemType::FLOAT;
+                number.m_float = f64::NAN;
+                &number
+            }
+            None => item,
+        };
+        write_item(key, item, out, write_canonical, now);
+    }
+}
+
+/// Writes `key` as a zigzag-encoded varint.
+fn write_key<'a, S: Sink<'a>>(key: IdoKeyT, out: &mut S) {
+    let value = key_to_i64(key);
+    let mut zigzag = ((value << 1) ^ (value >> 63)) as u64;
+    let mut bytes = [0u8; 10];
+    let mut len = 0;
+    loop {
+        let byte = (zigzag & 0x7f) as u8;
+        zigzag >>= 7;
+        if zigzag == 0 {
+            bytes[len] = byte;
+            len += 1;
+            break;
+        }
+        bytes[len] = byte | 0x80;
+        len += 1;
+    }
+    out.put(&bytes[..len]);
+}
+
+/// Number of bytes `write_key` writes for `key`.
+fn key_len(key: IdoKeyT) -> usize {
+    let value = key_to_i64(key);
+    let zigzag = ((value << 1) ^ (value >> 63)) as u64;
+    (64 - zigzag.leading_zeros() as usize).max(1).div_ceil(7)
+}
+
+fn write_item<'a, S: Sink<'a>>(
+    key: IdoKeyT,
+    item: &'a IdoItem,
+    out: &mut S,
+    write_nested: fn(&'a Ido, &mut S, SystemTime),
+    now: SystemTime,
+) {
+    write_key(key, out);
+
+    match item.m_type {
+        IdoItemType::STRING => {
+            out.put(&[TAG_STRING]);
+            out.put(&(item.m_string.len() as u32).to_le_bytes());
+            out.put_ref(item.m_string.as_bytes());
+        }
+        IdoItemType::INTEGER => {
+            out.put(&[TAG_INTEGER]);
+            out.put(&item.m_integer.to_le_bytes());
+        }
+        IdoItemType::FLOAT => {
+            out.put(&[TAG_FLOAT]);
+            out.put(&item.m_float.to_le_bytes());
+        }
+        #[cfg(feature = "chrono")]
+        IdoItemType::DATETIME => {
+            out.put(&[TAG_DATETIME]);
+            out.put(&item.m_datetime.timestamp().to_le_bytes());
+            out.put(&item.m_datetime.timestamp_subsec_nanos().to_le_bytes());
+        }
+        IdoItemType::ARRAY => {
+            out.put(&[TAG_ARRAY]);
+            let handle = out.array_len(&item.m_array, now);
+            let start = out.written();
+            out.put(&(item.m_array.len() as u32).to_le_bytes());
+            for element in &item.m_array {
+                write_nested(element, out, now);
+            }
+            if let Some(handle) = handle {
+                out.patch(handle, (out.written() - start) as u32);
+            }
+        }
+        IdoItemType::INTEGER128 => {
+            out.put(&[TAG_INTEGER128]);
+            out.put(&item.m_integer128.to_le_bytes());
+        }
+        IdoItemType::BOOL => {
+            out.put(&[TAG_BOOL, u8::from(item.m_bool)]);
+        }
+    }
+}
+
+/// Length in bytes of the native encoding of `ido`, leaving out items
+/// expired by `now`.
+fn encoded_len(ido: &Ido, now: SystemTime) -> usize {
+    4 + ido
+        .live_items_at(now)
+        .map(|(key, item)| {
+            key_len(key) + 1 + match item.m_type {
+                IdoItemType::STRING => 4 + item.m_string.len(),
+                IdoItemType::INTEGER | IdoItemType::FLOAT => 8,
+                #[cfg(feature = "chrono")]
+                IdoItemType::DATETIME => 12,
+                IdoItemType::INTEGER128 => 16,
+                IdoItemType::BOOL => 1,
+                IdoItemType::ARRAY => 8 + item.m_array.iter().map(|element| encoded_len(element, now)).sum::<usize>(),
+            }
+        })
+        .sum::<usize>()
+}
+
+pub(crate) struct Reader<'a> {
+    m_buf: &'a [u8],
+    m_pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Self {
+        Reader { m_buf: buf, m_pos: 0 }
+    }
+
+    pub(crate) fn position(&self) -> usize {
+        self.m_pos
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.m_buf.len() - self.m_pos
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], CodecError> {
+        if self.remaining() < n {
+            return Err(CodecError::UnexpectedEof);
+        }
+        let bytes = &self.m_buf[self.m_pos..self.m_pos + n];
+        self.m_pos += n;
+        Ok(bytes)
+    }
+
+    pub(crate) fn array<const N: usize>(&mut self) -> Result<[u8; N], CodecError> {
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(self.take(N)?);
+        Ok(bytes)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, CodecError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, CodecError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    /// Reads a key written by `write_key`.
+    pub(crate) fn key(&mut self) -> Result<IdoKeyT, CodecError> {
+        let mut zigzag = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            zigzag |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                let value = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+                return key_from_i64(value).ok_or(CodecError::InvalidKey);
+            }
+        }
+        Err(CodecError::InvalidKey)
+    }
+
+    fn i64(&mut self) -> Result<i64, CodecError> {
+        Ok(i64::from_le_bytes(self.array()?))
+    }
+
+    fn i128(&mut self) -> Result<i128, CodecError> {
+        Ok(i128::from_le_bytes(self.array()?))
+    }
+
+    fn f64(&mut self) -> Result<f64, CodecError> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+}
+
+/// Reads an Ido nested `depth` levels deep.
+fn read_ido(reader: &mut Reader<'_>, limits: &IdoLimits, depth: usize) -> Result<Ido, CodecError> {
+    let count = reader.u32()?;
+    let mut ido = Ido::new();
+
+    for _ in 0..count {
+        let key = reader.key()?;
+        let item = read_value(reader, key, limits, depth)?;
+        if limits.max_bytes.is_some_and(|max| ido.memory_used() + item.approx_bytes() > max) {
+            return Err(CodecError::MemoryExceeded(key));
+        }
+        ido.set_item(&key, item);
+    }
+    Ok(ido)
+}
+
+pub(crate) fn read_item(reader: &mut Reader<'_>) -> Result<IdoItem, CodecError> {
+    read_value(reader, 0, &IdoLimits::default(), 0)
+}
+
+fn read_value(reader: &mut Reader<'_>, key: IdoKeyT, limits: &IdoLimits, depth: usize) -> Result<IdoItem, CodecError> {
+    let mut item = IdoItem::new();
+
+    match reader.u8()? {
+        TAG_STRING => {
+            let len = reader.u32()? as usize;
+            let bytes = reader.take(len)?;
+            item.m_type = IdoItemType::STRING;
+            item.m_string = std::str::from_utf8(bytes)
+                .map_err(|_| CodecError::InvalidUtf8)?
+                .to_string();
+        }
+        TAG_INTEGER => {
+            item.m_type = IdoItemType::INTEGER;
+            item.m_integer = reader.i64()?;
+        }
+        TAG_FLOAT => {
+            item.m_type = IdoItemType::FLOAT;
+            item.m_float = reader.f64()?;
+        }
+        #[cfg(feature = "chrono")]
+        TAG_DATETIME => {
+            let secs = reader.i64()?;
+            let nanos = reader.u32()?;
+            item.m_type = IdoItemType::DATETIME;
+            item.m_datetime = DateTime::from_timestamp(secs, nanos)
+                .ok_or(CodecError::InvalidDateTime)?;
+        }
+        #[cfg(not(feature = "chrono"))]
+        TAG_DATETIME => return Err(CodecError::Unsupported(key)),
+        TAG_ARRAY => {
+            let len = reader.u32()? as usize;
+            let mut nested = Reader::new(reader.take(len)?);
+            let count = nested.u32()? as usize;
+            if limits.max_array_len.is_some_and(|max| count > max) {
+                return Err(CodecError::ArrayTooLong(key));
+            }
+            if count > 0 && depth + 1 > limits.max_depth.unwrap_or(DEFAULT_MAX_DEPTH) {
+                return Err(CodecError::DepthExceeded(key));
+            }
+            item.m_type = IdoItemType::ARRAY;
+            for _ in 0..count {
+                item.m_array.push(read_ido(&mut nested, limits, depth + 1)?);
+            }
+            if nested.remaining() != 0 {
+                return Err(CodecError::TrailingBytes(nested.remaining()));
+            }
+        }
+        TAG_INTEGER128 => {
+            item.m_type = IdoItemType::INTEGER128;
+            item.m_integer128 = reader.i128()?;
+        }
+        TAG_BOOL => {
+            item.m_type = IdoItemType::BOOL;
+            item.m_bool = match reader.u8()? {
+                0 => false,
+                1 => true,
+                _ => return Err(CodecError::InvalidValue(key)),
+            };
+        }
+        tag => return Err(CodecError::UnknownTypeTag(tag)),
+    }
+    Ok(item)
+}
+
+/// Moves past an item value, checking only that it is complete.
+pub(crate) fn skip_item(reader: &mut Reader<'_>) -> Result<(), CodecError> {
+    let len = match reader.u8()? {
+        TAG_STRING | TAG_ARRAY => reader.u32()? as usize,
+        TAG_INTEGER | TAG_FLOAT => 8,
+        TAG_DATETIME => 12,
+        TAG_INTEGER128 => 16,
+        TAG_BOOL => 1,
+        tag => return Err(CodecError::UnknownTypeTag(tag)),
+    };
+    reader.take(len)?;
+    Ok(())
+}
+
+/// Writes `ido` to `writer` as a length-prefixed frame.
+///
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+/// use ido::codec::{read_frame, write_frame};
+///
+/// let mut ido = Ido::new();
+/// ido.set_integer(&1, 42);
+///
+/// let mut wire = Vec::new();
+/// write_frame(&mut wire, &ido).unwrap();
+///
+/// let decoded = read_frame(&mut wire.as_slice()).unwrap();
+/// assert_eq!(decoded.get_i64(&1), Some(42));
+/// ```
+pub fn write_frame<W: Write>(writer: &mut W, ido: &Ido) -> Result<(), CodecError> {
+    let mut buf = vec![0u8; 4];
+    BinaryCodec.encode(ido, &mut buf)?;
+
+    let len = buf.len() - 4;
+    if len > MAX_FRAME_LEN {
+        return Err(CodecError::FrameTooLarge(len));
+    }
+    buf[..4].copy_from_slice(&(len as u32).to_le_bytes());
+
+    writer.write_all(&buf)?;
+    Ok(())
+}
+
+/// Decodes length-prefixed frames from bytes that arrive in pieces.
+///
+/// Bytes are fed in as they are received, for instance from a non-blocking
+/// socket, and complete frames are taken out with `next_frame`. Only the
+/// bytes of frames that are not complete yet are buffered.
+///
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+/// use ido::codec::FrameDecoder;
+///
+/// let mut ido = Ido::new();
+/// ido.set_integer(&1, 42);
+/// let mut wire = Vec::new();
+/// ido.encode_to(&mut wire).unwrap();
+///
+/// let mut decoder = FrameDecoder::new();
+/// decoder.feed(&wire[..5]);
+/// assert!(decoder.next_frame().unwrap().is_none());
+///
+/// decoder.feed(&wire[5..]);
+/// let decoded = decoder.next_frame().unwrap().unwrap();
+/// assert_eq!(decoded.get_i64(&1), Some(42));
+/// ```
+#[derive(Debug, Default)]
+pub struct FrameDecoder {
+    m_buf: Vec<u8>,
+    m_start: usize,
+}
+
+impl FrameDecoder {
+    /// Creates a decoder with nothing buffered.
+    pub fn new() -> Self {
+        FrameDecoder::default()
+    }
+
+    /// Appends received bytes.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        if self.m_start == self.m_buf.len() {
+            self.m_buf.clear();
+            self.m_start = 0;
+        }
+        self.m_buf.extend_from_slice(bytes);
+    }
+
+    /// Reads whatever `reader` has available with a single `read` call.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes read; 0 means the reader has reached its end.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::Io` with the error of the reader, which includes
+    /// `io::ErrorKind::WouldBlock` for a non-blocking reader with nothing
+    /// to read.
+    pub fn read_from<R: Read>(&mut self, reader: &mut R) -> Result<usize, CodecError> {
+        let mut chunk = [0u8; 8192];
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(n) => {
+                    self.feed(&chunk[..n]);
+                    return Ok(n);
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Returns the number of bytes received but not decoded yet.
+    pub fn buffered(&self) -> usize {
+        self.m_buf.len() - self.m_start
+    }
+
+    /// Decodes the next frame if all of it has been received.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::FrameTooLarge` as soon as a length prefix
+    /// exceeds [`MAX_FRAME_LEN`], or the error of decoding a complete frame.
+    /// A frame that fails to decode is dropped.
+    pub fn next_frame(&mut self) -> Result<Option<Ido>, CodecError> {
+        match self.next_body()? {
+            Some(body) => BinaryCodec.decode(body).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Takes the body of the next frame out of the buffer if all of it has
+    /// been received.
+    pub(crate) fn next_body(&mut self) -> Result<Option<&[u8]>, CodecError> {
+        let pending = &self.m_buf[self.m_start..];
+        let Some(prefix) = pending.first_chunk::<4>() else {
+            return Ok(None);
+        };
+        let len = u32::from_le_bytes(*prefix) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(CodecError::FrameTooLarge(len));
+        }
+        if pending.len() < 4 + len {
+            return Ok(None);
+        }
+
+        let start = self.m_start + 4;
+        self.m_start = start + len;
+        Ok(Some(&self.m_buf[start..start + len]))
+    }
+}
+
+/// Reads a single length-prefixed frame from `reader`.
+///
+/// Blocks until the whole frame has arrived. A reader that is closed before
+/// the length prefix is read surfaces as `CodecError::Io` with
+/// `io::ErrorKind::UnexpectedEof`. Arrays nested deeper than
+/// [`DEFAULT_MAX_DEPTH`] fail with `CodecError::DepthExceeded`.
+pub fn read_frame<R: Read>(reader: &mut R) -> Result<Ido, CodecError> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(CodecError::FrameTooLarge(len));
+    }
+
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body)?;
+    BinaryCodec.decode(&body)
+}
+
+impl Ido {
+    /// Encodes the Ido using the native binary format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "value1".to_string());
+    ///
+    /// let bytes = ido.encode();
+    /// let decoded = Ido::decode(&bytes).unwrap();
+    /// assert_eq!(decoded.get_string(&1), Some("value1".to_string()));
+    /// ```
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        // Writing to a Vec cannot fail.
+        let _ = BinaryCodec.encode(self, &mut out);
+        out
+    }
+
+    /// Returns the length in bytes of [`Ido::encode`]'s output, without
+    /// encoding.
+    pub fn encoded_len(&self) -> usize {
+        encoded_len(self, SystemTime::now())
+    }
+
+    /// Writes the Ido to `writer` as a length-prefixed frame, without
+    /// building the frame in memory first. The output is the same as that
+    /// of [`write_frame`] and can be read back with `decode_from`.
+    ///
+    /// Values are written one by one, so unbuffered writers such as a
+    /// `TcpStream` should be wrapped in a `BufWriter`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::FrameTooLarge` before writing anything if the
+    /// frame would exceed [`MAX_FRAME_LEN`], or `CodecError::Io` if the
+    /// writer fails, in which case part of the frame may have been written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "value1".to_string());
+    ///
+    /// let mut file = Vec::new();
+    /// ido.encode_to(&mut file).unwrap();
+    /// ido.encode_to(&mut file).unwrap();
+    ///
+    /// let mut reader = file.as_slice();
+    /// assert_eq!(Ido::decode_from(&mut reader).unwrap().get_string(&1), Some("value1".to_string()));
+    /// assert!(Ido::decode_from(&mut reader).is_ok());
+    /// assert!(reader.is_empty());
+    /// ```
+    pub fn encode_to<W: Write>(&self, writer: &mut W) -> Result<(), CodecError> {
+        let now = SystemTime::now();
+        let len = encoded_len(self, now);
+        if len > MAX_FRAME_LEN {
+            return Err(CodecError::FrameTooLarge(len));
+        }
+
+        let mut sink = WriteSink { m_writer: writer, m_written: 0, m_error: None };
+        sink.put(&(len as u32).to_le_bytes());
+        write_ido(self, &mut sink, now);
+        match sink.m_error {
+            Some(err) => Err(err.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads one length-prefixed frame from `reader`, as written by
+    /// `encode_to` or [`write_frame`]. Short reads are retried until the
+    /// frame is complete; see [`read_frame`].
+    ///
+    /// # Errors
+    ///
+    /// See [`read_frame`].
+    pub fn decode_from<R: Read>(reader: &mut R) -> Result<Ido, CodecError> {
+        read_frame(reader)
+    }
+
+    /// Encodes the Ido into the start of `buf` without allocating.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written, which is `encoded_len`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::BufferTooSmall` with the required length if
+    /// `buf` is shorter. Nothing is written in that case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "value1".to_string());
+    ///
+    /// let mut buf = [0u8; 64];
+    /// let n = ido.encode_into(&mut buf).unwrap();
+    /// assert_eq!(&buf[..n], ido.encode().as_slice());
+    /// ```
+    pub fn encode_into(&self, buf: &mut [u8]) -> Result<usize, CodecError> {
+        let now = SystemTime::now();
+        let len = encoded_len(self, now);
+        if buf.len() < len {
+            return Err(CodecError::BufferTooSmall(len));
+        }
+        write_ido(self, &mut SliceSink { m_buf: buf, m_pos: 0 }, now);
+        Ok(len)
+    }
+
+    /// Encodes the Ido as slices for a vectored write, borrowing string
+    /// values from the Ido instead of copying them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "a long string value".to_string());
+    /// ido.set_integer(&2, 42);
+    ///
+    /// let vectored = ido.encode_vectored();
+    /// let mut wire = Vec::new();
+    /// vectored.write_to(&mut wire).unwrap();
+    /// assert_eq!(wire, ido.encode());
+    /// assert_eq!(vectored.io_slices().len(), 3);
+    /// ```
+    pub fn encode_vectored(&self) -> VectoredIdo<'_> {
+        let mut vectored = VectoredIdo { m_headers: Vec::new(), m_parts: Vec::new(), m_len: 0 };
+        write_ido(self, &mut vectored, SystemTime::now());
+        vectored
+    }
+
+    /// Encodes the Ido into a deterministic byte string, so that two
+    /// processes can compare hashes of their content instead of the content
+    /// itself.
+    ///
+    /// Idos that are equal under `==` produce the same bytes: items are
+    /// written in key order whatever order they were set in, expired items
+    /// are left out, and numbers are normalized so that INTEGER 1,
+    /// INTEGER128 1 and FLOAT 1.0 are written alike. Every NaN is written
+    /// the same way. The output uses the native binary layout and can be
+    /// read back with [`Ido::decode`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut a = Ido::new();
+    /// a.set_integer(&1, 100);
+    /// a.set_string(&2, "NYSE".to_string());
+    ///
+    /// let mut b = Ido::new();
+    /// b.set_string(&2, "NYSE".to_string());
+    /// b.set_f64(&1, 100.0);
+    ///
+    /// assert_eq!(a.canonical_bytes(), b.canonical_bytes());
+    /// ```
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_canonical(self, &mut out, SystemTime::now());
+        out
+    }
+
+    /// Decodes an Ido previously produced by [`Ido::encode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CodecError` if the buffer is truncated, malformed or has
+    /// bytes left over after the Ido, and `CodecError::DepthExceeded` if
+    /// arrays nest deeper than [`DEFAULT_MAX_DEPTH`].
+    pub fn decode(buf: &[u8]) -> Result<Ido, CodecError> {
+        BinaryCodec.decode(buf)
+    }
+
+    /// Decodes only the items under `keys`, skipping over the others
+    /// without decoding them. Arrays under a selected key are decoded in
+    /// full.
+    ///
+    /// Skipped values are only checked to be complete, so a malformed value
+    /// under a key that was not asked for goes unnoticed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CodecError` if the buffer is truncated, holds an unknown
+    /// type tag, has bytes left over after the Ido, or a selected value is
+    /// malformed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// for key in 0..500 {
+    ///     ido.set_integer(&key, i64::from(key) * 10);
+    /// }
+    ///
+    /// let decoded = Ido::decode_keys(&ido.encode(), &[7, 42, 1000]).unwrap();
+    /// assert_eq!(decoded.size(), 2);
+    /// assert_eq!(decoded.get_i64(&42), Some(420));
+    /// ```
+    pub fn decode_keys(buf: &[u8], keys: &[IdoKeyT]) -> Result<Ido, CodecError> {
+        let mut reader = Reader::new(buf);
+        let count = reader.u32()?;
+        let mut ido = Ido::new();
+
+        for _ in 0..count {
+            let key = reader.key()?;
+            if keys.contains(&key) {
+                ido.set_item(&key, read_item(&mut reader)?);
+            } else {
+                skip_item(&mut reader)?;
+            }
+        }
+
+        match reader.remaining() {
+            0 => Ok(ido),
+            n => Err(CodecError::TrailingBytes(n)),
+        }
+    }
+
+    /// Decodes an Ido like [`Ido::decode`], rejecting arrays that nest
+    /// deeper or hold more elements than `limits` allow before decoding
+    /// their elements. The decoded Ido keeps `limits` for later changes.
+    ///
+    /// Leaving `max_depth` unset still bounds the nesting at
+    /// [`DEFAULT_MAX_DEPTH`]; set it to accept deeper arrays.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CodecError::DepthExceeded` or `CodecError::ArrayTooLong`
+    /// with the key of the offending array, `CodecError::MemoryExceeded`
+    /// with the key of the first value beyond `max_bytes`, or any error of
+    /// `decode`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoLimits};
+    ///
+    /// let mut inner = Ido::new();
+    /// inner.append_array(&2, Ido::new());
+    /// let mut ido = Ido::new();
+    /// ido.append_array(&1, inner);
+    ///
+    /// let limits = IdoLimits { max_depth: Some(1), ..IdoLimits::default() };
+    /// assert!(Ido::decode_with_limits(&ido.encode(), limits).is_err());
+    /// ```
+    pub fn decode_with_limits(buf: &[u8], limits: IdoLimits) -> Result<Ido, CodecError> {
+        let mut ido = decode_limited(buf, &limits)?;
+        ido.set_limits(limits);
+        Ok(ido)
+    }
+}
@@ -0,0 +1,309 @@
+//! Generating typed Rust code from a JSON message catalogue.
+//!
+//! The catalogue lists every message type with its fields:
+//!
+//! ```json
+//! {
+//!   "messages": [
+//!     {
+//!       "name": "Order",
+//!       "fields": [
+//!         { "name": "order_id", "key": 1, "type": "STRING", "required": true },
+//!         { "name": "price", "key": 2, "type": "FLOAT" },
+//!         { "name": "legs", "key": 3, "type": "ARRAY", "of": "Leg" }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! For each message [`generate`] emits a wrapper struct around an Ido with
+//! an associated key constant, a getter and a setter per field, and a
+//! `schema()` function returning the matching [`IdoSchema`](crate::schema::IdoSchema).
+//! ARRAY fields name the message type of their elements with `of` and get
+//! a getter returning a `Vec` of wrappers plus a `push_` method. DATETIME
+//! fields use `chrono::DateTime<Utc>`, so the generated code needs `chrono`
+//! as a dependency and the `chrono` feature of this crate.
+//!
+//! The intended use is from a build script:
+//!
+//! ```no_run
+//! // build.rs
+//! let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("messages.rs");
+//! ido::codegen::generate_file("messages.json", out).unwrap();
+//! ```
+//!
+//! followed by `include!(concat!(env!("OUT_DIR"), "/messages.rs"));` in the
+//! crate.
+
+use std::fmt::{self, Write};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::{IdoItemType, IdoKeyT};
+
+/// Errors raised while generating code.
+#[derive(Debug)]
+pub enum CodegenError {
+    /// Reading the catalogue or writing the output failed.
+    Io(io::Error),
+    /// The catalogue is not valid JSON or does not have the expected shape.
+    Parse(serde_json::Error),
+    /// The catalogue is well formed but describes invalid messages.
+    Invalid(String),
+}
+
+impl fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodegenError::Io(err) => write!(f, "io error: {}", err),
+            CodegenError::Parse(err) => write!(f, "invalid catalogue: {}", err),
+            CodegenError::Invalid(reason) => write!(f, "invalid catalogue: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for CodegenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CodegenError::Io(err) => Some(err),
+            CodegenError::Parse(err) => Some(err),
+            CodegenError::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for CodegenError {
+    fn from(err: io::Error) -> Self {
+        CodegenError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for CodegenError {
+    fn from(err: serde_json::Error) -> Self {
+        CodegenError::Parse(err)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Catalogue {
+    messages: Vec<MessageDef>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MessageDef {
+    name: String,
+    fields: Vec<FieldDef>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldDef {
+    name: String,
+    key: IdoKeyT,
+    #[serde(rename = "type")]
+    ty: String,
+    #[serde(default)]
+    required: bool,
+    of: Option<String>,
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn item_type(field: &FieldDef) -> Result<IdoItemType, CodegenError> {
+    match field.ty.as_str() {
+        "STRING" => Ok(IdoItemType::STRING),
+        "INTEGER" => Ok(IdoItemType::INTEGER),
+        "INTEGER128" => Ok(IdoItemType::INTEGER128),
+        "FLOAT" => Ok(IdoItemType::FLOAT),
+        #[cfg(feature = "chrono")]
+        "DATETIME" => Ok(IdoItemType::DATETIME),
+        "ARRAY" => Ok(IdoItemType::ARRAY),
+        "BOOL" => Ok(IdoItemType::BOOL),
+        other => Err(CodegenError::Invalid(format!("field {} has unknown type {}", field.name, other))),
+    }
+}
+
+fn rust_type(ty: IdoItemType) -> &'static str {
+    match ty {
+        IdoItemType::STRING => "String",
+        IdoItemType::INTEGER => "i64",
+        IdoItemType::INTEGER128 => "i128",
+        IdoItemType::FLOAT => "f64",
+        #[cfg(feature = "chrono")]
+        IdoItemType::DATETIME => "::chrono::DateTime<::chrono::Utc>",
+        IdoItemType::BOOL => "bool",
+        IdoItemType::ARRAY => unreachable!("arrays are generated separately"),
+    }
+}
+
+fn check(catalogue: &Catalogue) -> Result<(), CodegenError> {
+    let names: Vec<&str> = catalogue.messages.iter().map(|message| message.name.as_str()).collect();
+
+    for message in &catalogue.messages {
+        if !is_identifier(&message.name) {
+            return Err(CodegenError::Invalid(format!("message name {:?} is not an identifier", message.name)));
+        }
+        for (index, field) in message.fields.iter().enumerate() {
+            if !is_identifier(&field.name) {
+                return Err(CodegenError::Invalid(format!("field name {:?} is not an identifier", field.name)));
+            }
+            if message.fields[..index].iter().any(|other| other.key == field.key || other.name == field.name) {
+                return Err(CodegenError::Invalid(format!(
+                    "field {} of {} reuses a key or name",
+                    field.name, message.name
+                )));
+            }
+
+            let ty = item_type(field)?;
+            match (&field.of, ty) {
+                (Some(of), IdoItemType::ARRAY) if names.contains(&of.as_str()) => {}
+                (None, IdoItemType::ARRAY) => {
+                    return Err(CodegenError::Invalid(format!("array field {} needs \"of\"", field.name)))
+                }
+                (Some(of), IdoItemType::ARRAY) => {
+                    return Err(CodegenError::Invalid(format!("field {} refers to unknown message {}", field.name, of)))
+                }
+                (Some(_), _) => {
+                    return Err(CodegenError::Invalid(format!("only array fields take \"of\", not {}", field.name)))
+                }
+                (None, _) => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_message(out: &mut String, message: &MessageDef) -> fmt::Result {
+    let name = &message.name;
+
+    writeln!(out, "/// Typed wrapper around an Ido for the `{}` message.", name)?;
+    writeln!(out, "#[derive(Clone, Default)]")?;
+    writeln!(out, "pub struct {} {{", name)?;
+    writeln!(out, "    m_ido: ::ido::Ido,")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "impl {} {{", name)?;
+    for field in &message.fields {
+        writeln!(out, "    pub const {}: ::ido::IdoKeyT = {};", field.name.to_uppercase(), field.key)?;
+    }
+    writeln!(out)?;
+    writeln!(out, "    /// Creates an empty message.")?;
+    writeln!(out, "    pub fn new() -> Self {{")?;
+    writeln!(out, "        Self::default()")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+    writeln!(out, "    /// Wraps an existing Ido without checking it.")?;
+    writeln!(out, "    pub fn from_ido(ido: ::ido::Ido) -> Self {{")?;
+    writeln!(out, "        {} {{ m_ido: ido }}", name)?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+    writeln!(out, "    /// Returns the wrapped Ido.")?;
+    writeln!(out, "    pub fn as_ido(&self) -> &::ido::Ido {{")?;
+    writeln!(out, "        &self.m_ido")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+    writeln!(out, "    /// Unwraps the Ido.")?;
+    writeln!(out, "    pub fn into_ido(self) -> ::ido::Ido {{")?;
+    writeln!(out, "        self.m_ido")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+    writeln!(out, "    /// Returns the schema of the message.")?;
+    writeln!(out, "    pub fn schema() -> ::ido::schema::IdoSchema {{")?;
+    writeln!(out, "        ::ido::schema::IdoSchema::new()")?;
+    for field in &message.fields {
+        let constant = field.name.to_uppercase();
+        match &field.of {
+            Some(of) => writeln!(out, "            .array_of(Self::{}, {}, {}::schema())", constant, field.required, of)?,
+            None => {
+                let method = if field.required { "required" } else { "optional" };
+                writeln!(out, "            .{}(Self::{}, ::ido::IdoItemType::{})", method, constant, field.ty)?;
+            }
+        }
+    }
+    writeln!(out, "    }}")?;
+
+    for field in &message.fields {
+        let constant = field.name.to_uppercase();
+        let field_name = &field.name;
+        writeln!(out)?;
+
+        match &field.of {
+            Some(of) => {
+                writeln!(out, "    pub fn {}(&self) -> Vec<{}> {{", field_name, of)?;
+                writeln!(out, "        self.m_ido")?;
+                writeln!(out, "            .get_item(&Self::{})", constant)?;
+                writeln!(out, "            .map(|item| item.m_array.into_iter().map({}::from_ido).collect())", of)?;
+                writeln!(out, "            .unwrap_or_default()")?;
+                writeln!(out, "    }}")?;
+                writeln!(out)?;
+                writeln!(out, "    pub fn push_{}(&mut self, value: {}) {{", field_name, of)?;
+                writeln!(out, "        self.m_ido.append_array(&Self::{}, value.into_ido());", constant)?;
+                writeln!(out, "    }}")?;
+            }
+            None => {
+                let ty = rust_type(item_type(field).map_err(|_| fmt::Error)?);
+                writeln!(out, "    pub fn {}(&self) -> Option<{}> {{", field_name, ty)?;
+                writeln!(out, "        self.m_ido.get_value(&Self::{}).ok()", constant)?;
+                writeln!(out, "    }}")?;
+                writeln!(out)?;
+                writeln!(out, "    pub fn set_{}(&mut self, value: {}) {{", field_name, ty)?;
+                writeln!(out, "        self.m_ido.set_value(&Self::{}, value);", constant)?;
+                writeln!(out, "    }}")?;
+            }
+        }
+    }
+    writeln!(out, "}}")
+}
+
+/// Generates Rust code for every message in a JSON catalogue.
+///
+/// # Errors
+///
+/// Returns a `CodegenError` if the catalogue cannot be parsed, uses an
+/// unknown type, reuses a key or name within a message, or refers to an
+/// unknown message in `of`.
+///
+/// # Example
+///
+/// ```
+/// let code = ido::codegen::generate(r#"{
+///     "messages": [
+///         { "name": "Quote", "fields": [{ "name": "bid", "key": 1, "type": "FLOAT" }] }
+///     ]
+/// }"#).unwrap();
+///
+/// assert!(code.contains("pub struct Quote"));
+/// assert!(code.contains("pub fn set_bid(&mut self, value: f64)"));
+/// ```
+pub fn generate(catalogue: &str) -> Result<String, CodegenError> {
+    let catalogue: Catalogue = serde_json::from_str(catalogue)?;
+    check(&catalogue)?;
+
+    let mut out = String::from("// @generated by ido::codegen. Do not edit.\n");
+    for message in &catalogue.messages {
+        out.push('\n');
+        write_message(&mut out, message).map_err(|_| CodegenError::Invalid("formatting failed".to_string()))?;
+    }
+    Ok(out)
+}
+
+/// Reads a catalogue from `input` and writes the generated code to `output`.
+///
+/// Also prints a `cargo:rerun-if-changed` line for `input`, so the code is
+/// regenerated when the catalogue changes.
+pub fn generate_file<P: AsRef<Path>, Q: AsRef<Path>>(input: P, output: Q) -> Result<(), CodegenError> {
+    let code = generate(&fs::read_to_string(&input)?)?;
+    fs::write(output, code)?;
+    println!("cargo:rerun-if-changed={}", input.as_ref().display());
+    Ok(())
+}
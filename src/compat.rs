@@ -0,0 +1,257 @@
+//! Reading and writing the binary layout of the legacy C++ Ido class.
+//!
+//! The C++ implementation serialises its objects with a simpler layout than
+//! the native [`BinaryCodec`](crate::codec::BinaryCodec):
+//!
+//! ```text
+//! ido    := count:u32 item*
+//! item   := key:i32 tag:u8 value
+//! value  := STRING   len:u32 utf8*
+//!         | INTEGER  i64
+//!         | FLOAT    f64
+//!         | DATETIME micros:i64
+//!         | ARRAY    count:u32 ido*
+//! ```
+//!
+//! Datetimes are microseconds since the Unix epoch, so sub-microsecond
+//! precision is lost when encoding. The C++ class has no 128-bit integers:
+//! INTEGER128 items that fit in 64 bits are written as INTEGER, others
+//! cannot be encoded, and neither can keys outside the `i32` range when the
+//! `wide-keys` feature is enabled. Arrays carry no byte length. The byte
+//! order and the value of each type tag depend on how the C++ side was
+//! built, so both are configurable on [`CppCodec`]; the defaults match the
+//! x86 builds, which write little-endian values and number the tags from 0
+//! in the order STRING, INTEGER, FLOAT, DATETIME, ARRAY.
+
+#[cfg(feature = "chrono")]
+use chrono::DateTime;
+
+use crate::codec::{CodecError, Decode, Encode, Reader};
+use crate::key::{key_from_i64, key_to_i64};
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+/// Byte order of multi-byte values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endianness {
+    /// Least significant byte first.
+    #[default]
+    Little,
+    /// Most significant byte first.
+    Big,
+}
+
+/// The tag byte written before each value type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeTags {
+    pub string: u8,
+    pub integer: u8,
+    pub float: u8,
+    pub datetime: u8,
+    pub array: u8,
+}
+
+impl TypeTags {
+    fn tag(&self, ty: IdoItemType) -> u8 {
+        match ty {
+            IdoItemType::STRING => self.string,
+            IdoItemType::INTEGER | IdoItemType::INTEGER128 | IdoItemType::BOOL => self.integer,
+            IdoItemType::FLOAT => self.float,
+            #[cfg(feature = "chrono")]
+            IdoItemType::DATETIME => self.datetime,
+            IdoItemType::ARRAY => self.array,
+        }
+    }
+
+    fn item_type(&self, tag: u8) -> Option<IdoItemType> {
+        [
+            IdoItemType::STRING,
+            IdoItemType::INTEGER,
+            IdoItemType::FLOAT,
+            #[cfg(feature = "chrono")]
+            IdoItemType::DATETIME,
+            IdoItemType::ARRAY,
+        ]
+        .into_iter()
+        .find(|ty| self.tag(*ty) == tag)
+    }
+
+    fn is_distinct(&self) -> bool {
+        let tags = [self.string, self.integer, self.float, self.datetime, self.array];
+        tags.iter().enumerate().all(|(i, tag)| !tags[..i].contains(tag))
+    }
+}
+
+impl Default for TypeTags {
+    fn default() -> Self {
+        TypeTags { string: 0, integer: 1, float: 2, datetime: 3, array: 4 }
+    }
+}
+
+/// Codec for the layout described in the module documentation.
+///
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+/// use ido::codec::{Decode, Encode};
+/// use ido::compat::{CppCodec, Endianness};
+///
+/// let codec = CppCodec::new().endianness(Endianness::Big);
+///
+/// let mut ido = Ido::new();
+/// ido.set_integer(&1, 42);
+///
+/// let mut bytes = Vec::new();
+/// codec.encode(&ido, &mut bytes).unwrap();
+/// assert_eq!(bytes, [0, 0, 0, 1, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 42]);
+///
+/// let decoded = codec.decode(&bytes).unwrap();
+/// assert_eq!(decoded.get_i64(&1), Some(42));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CppCodec {
+    m_endianness: Endianness,
+    m_tags: TypeTags,
+}
+
+macro_rules! to_bytes {
+    ($codec:expr, $value:expr) => {
+        match $codec.m_endianness {
+            Endianness::Little => $value.to_le_bytes(),
+            Endianness::Big => $value.to_be_bytes(),
+        }
+    };
+}
+
+macro_rules! read_value {
+    ($codec:expr, $reader:expr, $ty:ty) => {{
+        let bytes = $reader.array()?;
+        match $codec.m_endianness {
+            Endianness::Little => <$ty>::from_le_bytes(bytes),
+            Endianness::Big => <$ty>::from_be_bytes(bytes),
+        }
+    }};
+}
+
+impl CppCodec {
+    /// Creates a codec with little-endian values and the default tags.
+    pub fn new() -> Self {
+        CppCodec::default()
+    }
+
+    /// Sets the byte order.
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.m_endianness = endianness;
+        self
+    }
+
+    /// Sets the type tags.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two types share a tag, as the layout could not be decoded.
+    pub fn tags(mut self, tags: TypeTags) -> Self {
+        assert!(tags.is_distinct(), "type tags must be distinct: {:?}", tags);
+        self.m_tags = tags;
+        self
+    }
+
+    fn write_ido(&self, ido: &Ido, out: &mut Vec<u8>) -> Result<(), CodecError> {
+        let items: Vec<_> = ido.live_items().collect();
+        out.extend_from_slice(&to_bytes!(self, items.len() as u32));
+        for (key, item) in items {
+            self.write_item(key, item, out)?;
+        }
+        Ok(())
+    }
+
+    fn write_item(&self, key: IdoKeyT, item: &IdoItem, out: &mut Vec<u8>) -> Result<(), CodecError> {
+        let tag = i32::try_from(key_to_i64(key)).map_err(|_| CodecError::Unsupported(key))?;
+        out.extend_from_slice(&to_bytes!(self, tag));
+        out.push(self.m_tags.tag(item.m_type));
+
+        match item.m_type {
+            IdoItemType::STRING => {
+                out.extend_from_slice(&to_bytes!(self, item.m_string.len() as u32));
+                out.extend_from_slice(item.m_string.as_bytes());
+            }
+            IdoItemType::INTEGER => out.extend_from_slice(&to_bytes!(self, item.m_integer)),
+            IdoItemType::FLOAT => out.extend_from_slice(&to_bytes!(self, item.m_float)),
+            #[cfg(feature = "chrono")]
+            IdoItemType::DATETIME => out.extend_from_slice(&to_bytes!(self, item.m_datetime.timestamp_micros())),
+            IdoItemType::ARRAY => {
+                out.extend_from_slice(&to_bytes!(self, item.m_array.len() as u32));
+                for element in &item.m_array {
+                    self.write_ido(element, out)?;
+                }
+            }
+            IdoItemType::INTEGER128 => {
+                let value = i64::try_from(item.m_integer128).map_err(|_| CodecError::Unsupported(key))?;
+                out.extend_from_slice(&to_bytes!(self, value));
+            }
+            IdoItemType::BOOL => out.extend_from_slice(&to_bytes!(self, i64::from(item.m_bool))),
+        }
+        Ok(())
+    }
+
+    fn read_ido(&self, reader: &mut Reader<'_>) -> Result<Ido, CodecError> {
+        let count = read_value!(self, reader, u32);
+        let mut ido = Ido::new();
+
+        for _ in 0..count {
+            let key = key_from_i64(read_value!(self, reader, i32).into()).ok_or(CodecError::InvalidKey)?;
+            let item = self.read_item(reader)?;
+            ido.set_item(&key, item);
+        }
+        Ok(ido)
+    }
+
+    fn read_item(&self, reader: &mut Reader<'_>) -> Result<IdoItem, CodecError> {
+        let tag = reader.take(1)?[0];
+        let mut item = IdoItem::new();
+        item.m_type = self.m_tags.item_type(tag).ok_or(CodecError::UnknownTypeTag(tag))?;
+
+        match item.m_type {
+            IdoItemType::STRING => {
+                let len = read_value!(self, reader, u32) as usize;
+                item.m_string = std::str::from_utf8(reader.take(len)?)
+                    .map_err(|_| CodecError::InvalidUtf8)?
+                    .to_string();
+            }
+            IdoItemType::INTEGER => item.m_integer = read_value!(self, reader, i64),
+            IdoItemType::FLOAT => item.m_float = read_value!(self, reader, f64),
+            #[cfg(feature = "chrono")]
+            IdoItemType::DATETIME => {
+                item.m_datetime = DateTime::from_timestamp_micros(read_value!(self, reader, i64))
+                    .ok_or(CodecError::InvalidDateTime)?;
+            }
+            IdoItemType::ARRAY => {
+                let count = read_value!(self, reader, u32);
+                for _ in 0..count {
+                    item.m_array.push(self.read_ido(reader)?);
+                }
+            }
+            IdoItemType::INTEGER128 | IdoItemType::BOOL => unreachable!("no tag maps to {:?}", item.m_type),
+        }
+        Ok(item)
+    }
+}
+
+impl Encode for CppCodec {
+    fn encode(&self, ido: &Ido, out: &mut Vec<u8>) -> Result<(), CodecError> {
+        let start = out.len();
+        self.write_ido(ido, out).inspect_err(|_| out.truncate(start))
+    }
+}
+
+impl Decode for CppCodec {
+    fn decode(&self, buf: &[u8]) -> Result<Ido, CodecError> {
+        let mut reader = Reader::new(buf);
+        let ido = self.read_ido(&mut reader)?;
+
+        match reader.remaining() {
+            0 => Ok(ido),
+            n => Err(CodecError::TrailingBytes(n)),
+        }
+    }
+}
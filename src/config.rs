@@ -0,0 +1,690 @@
+//! Loading service configuration into an Ido.
+//!
+//! Configuration can come from environment variables named through an
+//! [`IdoDictionary`] or from a file of `key = value` lines. Both infer the
+//! type of each value from its text:
+//!
+//! | text                          | item                        |
+//! |-------------------------------|-----------------------------|
+//! | `"quoted"`                    | STRING without the quotes   |
+//! | `42`, `-7`                    | INTEGER                     |
+//! | beyond 64 bits                | INTEGER128                  |
+//! | `1.5`, `2e-3`                 | FLOAT                       |
+//! | `2024-01-02T03:04:05Z`        | DATETIME (RFC 3339)         |
+//! | anything else                 | STRING                      |
+//!
+//! Quote a value to keep it a string, e.g. `"007"`. Without the `chrono`
+//! feature, RFC 3339 timestamps are read as strings.
+//!
+//! [`Ido::from_toml`] reads TOML documents instead, whose values are typed
+//! already, with tables and arrays becoming nested Idos.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use crate::dictionary::IdoDictionary;
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+/// Errors raised while loading configuration.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Reading the file failed.
+    Io(io::Error),
+    /// A line of the file is not `key = value` with an integer key.
+    Parse { line: usize, reason: String },
+    /// An environment variable holds a value that is not valid unicode.
+    InvalidEnv(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "io error: {}", err),
+            ConfigError::Parse { line, reason } => write!(f, "line {}: {}", line, reason),
+            ConfigError::InvalidEnv(name) => write!(f, "environment variable {} is not valid unicode", name),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(err: io::Error) -> Self {
+        ConfigError::Io(err)
+    }
+}
+
+pub(crate) fn is_number(text: &str) -> bool {
+    text.chars().any(|c| c.is_ascii_digit())
+        && text.chars().all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | 'e' | 'E'))
+}
+
+fn parse_value(text: &str) -> IdoItem {
+    let mut item = IdoItem::new();
+
+    if let Some(quoted) = text.strip_prefix('"').and_then(|text| text.strip_suffix('"')) {
+        item.m_type = IdoItemType::STRING;
+        item.m_string = quoted.to_string();
+    } else if let Ok(value) = text.parse::<i64>() {
+        item.m_type = IdoItemType::INTEGER;
+        item.m_integer = value;
+    } else if let Ok(value) = text.parse::<i128>() {
+        item.m_type = IdoItemType::INTEGER128;
+        item.m_integer128 = value;
+    } else if let Some(value) = text.parse::<f64>().ok().filter(|_| is_number(text)) {
+        item.m_type = IdoItemType::FLOAT;
+        item.m_float = value;
+    } else if !parse_datetime(text, &mut item) {
+        item.m_type = IdoItemType::STRING;
+        item.m_string = text.to_string();
+    }
+    item
+}
+
+/// Makes `item` a DATETIME if `text` is an RFC 3339 timestamp.
+#[cfg(feature = "chrono")]
+fn parse_datetime(text: &str, item: &mut IdoItem) -> bool {
+    match DateTime::parse_from_rfc3339(text) {
+        Ok(value) => {
+            item.m_type = IdoItemType::DATETIME;
+            item.m_datetime = value.with_timezone(&Utc);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Makes `item` a DATETIME if `text` is an RFC 3339 timestamp.
+#[cfg(not(feature = "chrono"))]
+fn parse_datetime(_text: &str, _item: &mut IdoItem) -> bool {
+    false
+}
+
+fn parse_config(text: &str) -> Result<Ido, ConfigError> {
+    let mut ido = Ido::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let parse_error = |reason: &str| ConfigError::Parse { line: index + 1, reason: reason.to_string() };
+        let (key, value) = line.split_once('=').ok_or_else(|| parse_error("expected key = value"))?;
+        let key = key
+            .trim()
+            .parse::<IdoKeyT>()
+            .map_err(|_| parse_error("key is not an integer"))?;
+        ido.set_item(&key, parse_value(value.trim()));
+    }
+    Ok(ido)
+}
+
+/// A TOML value before its keys are mapped.
+enum TomlValue {
+    Item(IdoItem),
+    Table(TomlTable),
+    /// Tables from `[[header]]`s, which later headers can extend.
+    Tables(Vec<TomlTable>),
+    Array(Vec<TomlValue>),
+}
+
+#[derive(Default)]
+struct TomlTable {
+    entries: Vec<TomlEntry>,
+    /// Whether a `[header]` has named the table, rather than only a longer
+    /// header or a dotted key.
+    defined: bool,
+}
+
+struct TomlEntry {
+    name: String,
+    line: usize,
+    value: TomlValue,
+}
+
+impl TomlTable {
+    fn position(&self, name: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.name == name)
+    }
+
+    /// Returns the table at `path` below this one, creating missing tables
+    /// on the way. An array of tables leads to its last table.
+    fn walk(&mut self, path: &[String], line: usize) -> Result<&mut TomlTable, ConfigError> {
+        let mut table = self;
+        for name in path {
+            let index = match table.position(name) {
+                Some(index) => index,
+                None => {
+                    let value = TomlValue::Table(TomlTable::default());
+                    table.entries.push(TomlEntry { name: name.clone(), line, value });
+                    table.entries.len() - 1
+                }
+            };
+            table = match &mut table.entries[index].value {
+                TomlValue::Table(nested) => nested,
+                TomlValue::Tables(nested) => nested.last_mut().expect("arrays of tables are never empty"),
+                _ => return Err(toml_error(line, format!("{} is not a table", name))),
+            };
+        }
+        Ok(table)
+    }
+
+    /// Adds the value of a `key = value` line, where `key` may be dotted.
+    fn insert(&mut self, key: &[String], value: TomlValue, line: usize) -> Result<(), ConfigError> {
+        let (name, parents) = key.split_last().expect("keys have at least one part");
+        let table = self.walk(parents, line)?;
+        if table.position(name).is_some() {
+            return Err(toml_error(line, format!("duplicate key {}", name)));
+        }
+        table.entries.push(TomlEntry { name: name.clone(), line, value });
+        Ok(())
+    }
+
+    fn into_ido(self, key_map: &IdoDictionary) -> Result<Ido, ConfigError> {
+        let mut ido = Ido::new();
+        for entry in self.entries {
+            let key = key_map
+                .key(&entry.name)
+                .or_else(|| entry.name.parse().ok())
+                .ok_or_else(|| toml_error(entry.line, format!("unknown key {}", entry.name)))?;
+            ido.set_item(&key, entry.value.into_item(key_map)?);
+        }
+        Ok(ido)
+    }
+}
+
+impl TomlValue {
+    fn into_item(self, key_map: &IdoDictionary) -> Result<IdoItem, ConfigError> {
+        let elements = match self {
+            TomlValue::Item(item) => return Ok(item),
+            TomlValue::Table(table) => vec![table.into_ido(key_map)?],
+            TomlValue::Tables(tables) => tables
+                .into_iter()
+                .map(|table| table.into_ido(key_map))
+                .collect::<Result<_, _>>()?,
+            TomlValue::Array(values) => values
+                .into_iter()
+                .map(|value| match value {
+                    TomlValue::Table(table) => table.into_ido(key_map),
+                    value => {
+                        let mut element = Ido::new();
+                        element.set_item(&0, value.into_item(key_map)?);
+                        Ok(element)
+                    }
+                })
+                .collect::<Result<_, _>>()?,
+        };
+
+        let mut item = IdoItem::new();
+        item.m_type = IdoItemType::ARRAY;
+        item.m_array = elements;
+        Ok(item)
+    }
+}
+
+/// Whether `text` starts with `digits` digits followed by `separator`, as
+/// dates and times do.
+fn starts_with_field(text: &str, digits: usize, separator: char) -> bool {
+    text.get(..digits).is_some_and(|field| field.bytes().all(|b| b.is_ascii_digit()))
+        && text[digits..].starts_with(separator)
+}
+
+fn toml_error(line: usize, reason: String) -> ConfigError {
+    ConfigError::Parse { line, reason }
+}
+
+struct TomlParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> TomlParser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn line(&self) -> usize {
+        self.text[..self.pos].matches('\n').count() + 1
+    }
+
+    fn error(&self, reason: impl Into<String>) -> ConfigError {
+        toml_error(self.line(), reason.into())
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), ConfigError> {
+        if !self.eat(token) {
+            return Err(self.error(format!("expected {}", token)));
+        }
+        Ok(())
+    }
+
+    /// Moves past spaces, tabs and a comment, but not a line break.
+    fn skip_space(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        if self.rest().starts_with('#') {
+            self.pos += self.rest().find('\n').unwrap_or(self.rest().len());
+        }
+    }
+
+    /// Moves past whitespace, comments and line breaks.
+    fn skip_lines(&mut self) {
+        loop {
+            self.skip_space();
+            if !self.eat("\n") && !self.eat("\r\n") {
+                return;
+            }
+        }
+    }
+
+    /// Checks that nothing but a comment follows on the line.
+    fn end_line(&mut self) -> Result<(), ConfigError> {
+        self.skip_space();
+        if self.rest().is_empty() || self.eat("\n") || self.eat("\r\n") {
+            return Ok(());
+        }
+        Err(self.error("expected the end of the line"))
+    }
+
+    /// Reads a possibly dotted key.
+    fn key(&mut self) -> Result<Vec<String>, ConfigError> {
+        let mut parts = Vec::new();
+        loop {
+            self.skip_space();
+            let part = if self.rest().starts_with('"') {
+                self.basic_string()?
+            } else if self.rest().starts_with('\'') {
+                self.literal_string()?
+            } else {
+                let rest = self.rest();
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                    .unwrap_or(rest.len());
+                if len == 0 {
+                    return Err(self.error("expected a key"));
+                }
+                self.pos += len;
+                rest[..len].to_string()
+            };
+            parts.push(part);
+            self.skip_space();
+            if !self.eat(".") {
+                return Ok(parts);
+            }
+        }
+    }
+
+    fn escape(&mut self, out: &mut String) -> Result<(), ConfigError> {
+        let mut chars = self.rest().chars();
+        let c = chars.next().ok_or_else(|| self.error("unterminated string"))?;
+        self.pos += c.len_utf8();
+        let escaped = match c {
+            'b' => '\u{8}',
+            't' => '\t',
+            'n' => '\n',
+            'f' => '\u{c}',
+            'r' => '\r',
+            'e' => '\u{1b}',
+            '"' => '"',
+            '\\' => '\\',
+            'u' | 'U' => {
+                let len = if c == 'u' { 4 } else { 8 };
+                let hex = self.rest().get(..len).unwrap_or_default();
+                let escaped = u32::from_str_radix(hex, 16)
+                    .ok()
+                    .filter(|_| hex.len() == len)
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error("invalid unicode escape"))?;
+                self.pos += len;
+                escaped
+            }
+            _ => return Err(self.error(format!("invalid escape \\{}", c))),
+        };
+        out.push(escaped);
+        Ok(())
+    }
+
+    fn basic_string(&mut self) -> Result<String, ConfigError> {
+        let multiline = self.eat("\"\"\"");
+        if multiline {
+            // A line break straight after the delimiter is not part of the string.
+            let _ = self.eat("\n") || self.eat("\r\n");
+        } else {
+            self.expect("\"")?;
+        }
+
+        let mut out = String::new();
+        loop {
+            let c = self.rest().chars().next().ok_or_else(|| self.error("unterminated string"))?;
+            if multiline && self.eat("\"\"\"") {
+                return Ok(out);
+            }
+            if c == '\n' && !multiline {
+                return Err(self.error("unterminated string"));
+            }
+            self.pos += c.len_utf8();
+            match c {
+                '"' if !multiline => return Ok(out),
+                '\\' if multiline && self.rest().trim_start_matches([' ', '\t']).starts_with(['\n', '\r']) => {
+                    // A line-ending backslash trims the break and the
+                    // whitespace after it.
+                    let rest = self.rest();
+                    self.pos += rest.len() - rest.trim_start().len();
+                }
+                '\\' => self.escape(&mut out)?,
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn literal_string(&mut self) -> Result<String, ConfigError> {
+        let (delimiter, multiline) = if self.eat("'''") { ("'''", true) } else { ("'", false) };
+        if multiline {
+            let _ = self.eat("\n") || self.eat("\r\n");
+        } else {
+            self.expect("'")?;
+        }
+
+        let rest = self.rest();
+        let len = rest.find(delimiter).ok_or_else(|| self.error("unterminated string"))?;
+        if !multiline && rest[..len].contains('\n') {
+            return Err(self.error("unterminated string"));
+        }
+        self.pos += len + delimiter.len();
+        Ok(rest[..len].to_string())
+    }
+
+    fn value(&mut self) -> Result<TomlValue, ConfigError> {
+        let mut item = IdoItem::new();
+        let rest = self.rest();
+
+        if rest.starts_with('"') || rest.starts_with('\'') {
+            item.m_type = IdoItemType::STRING;
+            item.m_string = if rest.starts_with('"') { self.basic_string()? } else { self.literal_string()? };
+        } else if self.eat("[") {
+            let mut values = Vec::new();
+            loop {
+                self.skip_lines();
+                if self.eat("]") {
+                    return Ok(TomlValue::Array(values));
+                }
+                values.push(self.value()?);
+                self.skip_lines();
+                if !self.eat(",") {
+                    self.skip_lines();
+                    self.expect("]")?;
+                    return Ok(TomlValue::Array(values));
+                }
+            }
+        } else if self.eat("{") {
+            let mut table = TomlTable::default();
+            self.skip_space();
+            if self.eat("}") {
+                return Ok(TomlValue::Table(table));
+            }
+            loop {
+                let line = self.line();
+                let key = self.key()?;
+                self.expect("=")?;
+                self.skip_space();
+                let value = self.value()?;
+                table.insert(&key, value, line)?;
+                self.skip_space();
+                if self.eat("}") {
+                    return Ok(TomlValue::Table(table));
+                }
+                self.expect(",")?;
+            }
+        } else {
+            let mut len = rest
+                .find(|c: char| c.is_whitespace() || matches!(c, ',' | ']' | '}' | '#'))
+                .unwrap_or(rest.len());
+            // A space may separate the date and time of a datetime.
+            let time = rest[len..].strip_prefix(' ').filter(|_| len == 10 && starts_with_field(rest, 4, '-'));
+            if let Some(time) = time.filter(|time| starts_with_field(time, 2, ':')) {
+                len += 1 + time.find(|c: char| c.is_whitespace() || matches!(c, ',' | ']' | '}' | '#')).unwrap_or(time.len());
+            }
+            let token = &rest[..len];
+            if token.is_empty() {
+                return Err(self.error("expected a value"));
+            }
+            item = self.bare_value(token)?;
+            self.pos += len;
+        }
+        Ok(TomlValue::Item(item))
+    }
+
+    /// Reads a boolean, number or datetime.
+    fn bare_value(&self, token: &str) -> Result<IdoItem, ConfigError> {
+        let mut item = IdoItem::new();
+        let number = token.replace('_', "");
+        let (sign, digits) = match number.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", number.strip_prefix('+').unwrap_or(&number)),
+        };
+        let radix = match digits.get(..2) {
+            Some("0x") => 16,
+            Some("0o") => 8,
+            Some("0b") => 2,
+            _ => 10,
+        };
+
+        if token == "true" || token == "false" {
+            item.m_type = IdoItemType::BOOL;
+            item.m_bool = token == "true";
+        } else if matches!(digits, "inf" | "nan") {
+            item.m_type = IdoItemType::FLOAT;
+            item.m_float = if digits == "nan" { f64::NAN } else { format!("{}inf", sign).parse().unwrap_or_default() };
+        } else if radix != 10 {
+            item.m_type = IdoItemType::INTEGER;
+            item.m_integer = i64::from_str_radix(&digits[2..], radix)
+                .map_err(|_| self.error(format!("invalid integer {}", token)))?;
+        } else if starts_with_field(token, 4, '-') || starts_with_field(token, 2, ':') {
+            if !parse_datetime(&token.replacen(' ', "T", 1), &mut item) {
+                // Dates and times without an offset name no instant.
+                item.m_type = IdoItemType::STRING;
+                item.m_string = token.to_string();
+            }
+        } else if let Ok(value) = number.parse::<i64>() {
+            item.m_type = IdoItemType::INTEGER;
+            item.m_integer = value;
+        } else if let Some(value) = number.parse::<f64>().ok().filter(|_| is_number(&number)) {
+            if !number.contains(['.', 'e', 'E']) {
+                return Err(self.error(format!("integer {} does not fit in 64 bits", token)));
+            }
+            item.m_type = IdoItemType::FLOAT;
+            item.m_float = value;
+        } else {
+            return Err(self.error(format!("invalid value {}", token)));
+        }
+        Ok(item)
+    }
+
+    fn document(&mut self) -> Result<TomlTable, ConfigError> {
+        let mut root = TomlTable::default();
+        let mut current: Vec<String> = Vec::new();
+
+        loop {
+            self.skip_lines();
+            if self.rest().is_empty() {
+                return Ok(root);
+            }
+            let line = self.line();
+
+            if self.eat("[[") {
+                let path = self.key()?;
+                self.expect("]]")?;
+                let (name, parents) = path.split_last().expect("keys have at least one part");
+                let parent = root.walk(parents, line)?;
+                match parent.position(name) {
+                    None => {
+                        let value = TomlValue::Tables(vec![TomlTable::default()]);
+                        parent.entries.push(TomlEntry { name: name.clone(), line, value });
+                    }
+                    Some(index) => match &mut parent.entries[index].value {
+                        TomlValue::Tables(tables) => tables.push(TomlTable::default()),
+                        _ => return Err(toml_error(line, format!("{} is not an array of tables", name))),
+                    },
+                }
+                current = path;
+            } else if self.eat("[") {
+                let path = self.key()?;
+                self.expect("]")?;
+                let table = root.walk(&path, line)?;
+                if table.defined {
+                    return Err(toml_error(line, format!("table {} is defined twice", path.join("."))));
+                }
+                table.defined = true;
+                current = path;
+            } else {
+                let key = self.key()?;
+                self.expect("=")?;
+                self.skip_space();
+                let value = self.value()?;
+                root.walk(&current, line)?.insert(&key, value, line)?;
+            }
+            self.end_line()?;
+        }
+    }
+}
+
+impl Ido {
+    /// Builds an Ido from environment variables.
+    ///
+    /// For every entry of `key_map`, the variable named `prefix` followed by
+    /// the upper-cased name is read and, if set, stored under the key. The
+    /// type of each value is inferred as described in the module
+    /// documentation.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidEnv` if a variable is not valid unicode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    /// use ido::dictionary::IdoDictionary;
+    ///
+    /// std::env::set_var("GATEWAY_PORT", "9000");
+    /// std::env::set_var("GATEWAY_HOST", "localhost");
+    ///
+    /// let key_map: IdoDictionary = [(1, "host"), (2, "port"), (3, "timeout")].into_iter().collect();
+    /// let config = Ido::from_env("GATEWAY_", &key_map).unwrap();
+    ///
+    /// assert_eq!(config.get_string(&1), Some("localhost".to_string()));
+    /// assert_eq!(config.get_i64(&2), Some(9000));
+    /// assert!(!config.contains(&3));
+    /// ```
+    pub fn from_env(prefix: &str, key_map: &IdoDictionary) -> Result<Ido, ConfigError> {
+        let mut ido = Ido::new();
+
+        for (key, name) in key_map.iter() {
+            let variable = format!("{}{}", prefix, name.to_uppercase());
+            match env::var(&variable) {
+                Ok(value) => ido.set_item(&key, parse_value(&value)),
+                Err(env::VarError::NotPresent) => {}
+                Err(env::VarError::NotUnicode(_)) => return Err(ConfigError::InvalidEnv(variable)),
+            }
+        }
+        Ok(ido)
+    }
+
+    /// Builds an Ido from a configuration file.
+    ///
+    /// Each line holds `key = value` with an integer key. Blank lines and
+    /// lines starting with `#` are ignored, and later lines override earlier
+    /// ones. The type of each value is inferred as described in the module
+    /// documentation.
+    ///
+    /// ```text
+    /// # gateway.conf
+    /// 1 = localhost
+    /// 2 = 9000
+    /// 3 = "007"
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::Io` if the file cannot be read and
+    /// `ConfigError::Parse` for a malformed line.
+    pub fn from_config_file<P: AsRef<Path>>(path: P) -> Result<Ido, ConfigError> {
+        parse_config(&fs::read_to_string(path)?)
+    }
+
+    /// Builds an Ido from a TOML document.
+    ///
+    /// Every key is looked up in `key_map`, or else read as an integer key,
+    /// at any depth. Values keep their TOML types:
+    ///
+    /// | TOML                         | item                                         |
+    /// |------------------------------|----------------------------------------------|
+    /// | string                       | STRING                                       |
+    /// | integer                      | INTEGER                                      |
+    /// | float, `inf`, `nan`          | FLOAT                                        |
+    /// | boolean                      | BOOL                                         |
+    /// | offset datetime              | DATETIME                                     |
+    /// | local datetime, date or time | STRING, as written                           |
+    /// | table or inline table        | ARRAY of one Ido                             |
+    /// | array of tables              | ARRAY of one Ido per table                   |
+    /// | array of values              | ARRAY of Idos holding each value under key 0 |
+    ///
+    /// Without the `chrono` feature, offset datetimes are read as strings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::Parse` for malformed TOML, a key that is
+    /// neither in `key_map` nor an integer, or a key or table defined twice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    /// use ido::dictionary::IdoDictionary;
+    ///
+    /// let toml = r#"
+    /// host = "localhost"
+    /// ports = [9000, 9001]
+    ///
+    /// [limits]
+    /// timeout = 2.5
+    ///
+    /// [[route]]
+    /// host = "primary"
+    /// "#;
+    ///
+    /// let key_map: IdoDictionary =
+    ///     [(1, "host"), (2, "ports"), (3, "limits"), (4, "timeout"), (5, "route")].into_iter().collect();
+    /// let config = Ido::from_toml(toml, &key_map).unwrap();
+    ///
+    /// assert_eq!(config.get_string(&1), Some("localhost".to_string()));
+    /// assert_eq!(config.get_item(&2).unwrap().m_array[1].get_i64(&0), Some(9001));
+    /// assert_eq!(config.get_item(&3).unwrap().m_array[0].get_f64(&4), Some(2.5));
+    /// assert_eq!(config.get_item(&5).unwrap().m_array[0].get_string(&1), Some("primary".to_string()));
+    /// ```
+    pub fn from_toml(toml: &str, key_map: &IdoDictionary) -> Result<Ido, ConfigError> {
+        TomlParser { text: toml, pos: 0 }.document()?.into_ido(key_map)
+    }
+}
@@ -0,0 +1,122 @@
+//! Coalescing pending updates for slow consumers.
+//!
+//! A consumer that cannot keep up with a feed usually only needs the latest
+//! state of every entity, not each intermediate update. [`IdoConflator`]
+//! queues updates per entity and merges an update into the one already
+//! waiting for the same entity, so the queue never grows beyond one entry
+//! per entity.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::state::entity;
+use crate::{ArrayMerge, Ido, IdoKeyT, MissingKeys};
+
+/// A queue holding at most one merged update per entity.
+///
+/// Entities are identified like in [`IdoStateMachine`](crate::state::IdoStateMachine).
+/// Entities are delivered in the order their first pending update arrived;
+/// merging a later update does not move the entity back in the queue.
+///
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+/// use ido::conflate::IdoConflator;
+///
+/// let mut queue = IdoConflator::new(1);
+/// for (symbol, price) in [("AAPL", 101.0), ("MSFT", 402.5), ("AAPL", 101.25)] {
+///     let mut tick = Ido::new();
+///     tick.set_string(&1, symbol.to_string());
+///     tick.set_f64(&2, price);
+///     queue.push(tick).unwrap();
+/// }
+///
+/// assert_eq!(queue.len(), 2);
+/// let first = queue.pop().unwrap();
+/// assert_eq!(first.get_string(&1), Some("AAPL".to_string()));
+/// assert_eq!(first.get_f64(&2), Some(101.25));
+/// ```
+pub struct IdoConflator {
+    m_entity_key: IdoKeyT,
+    m_array_merge: ArrayMerge,
+    m_order: VecDeque<String>,
+    m_pending: HashMap<String, Ido>,
+    m_conflated: u64,
+}
+
+impl IdoConflator {
+    /// Creates an empty queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_key` - The key identifying the entity of every update.
+    pub fn new(entity_key: IdoKeyT) -> Self {
+        IdoConflator {
+            m_entity_key: entity_key,
+            m_array_merge: ArrayMerge::default(),
+            m_order: VecDeque::new(),
+            m_pending: HashMap::new(),
+            m_conflated: 0,
+        }
+    }
+
+    /// Sets how arrays of an update are combined with the arrays of the
+    /// pending update. Arrays are replaced by default.
+    pub fn array_merge(mut self, policy: ArrayMerge) -> Self {
+        self.m_array_merge = policy;
+        self
+    }
+
+    /// Queues an update, merging it into the pending update of its entity.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingKeys` with the entity key if the update has no value
+    /// under it.
+    pub fn push(&mut self, update: Ido) -> Result<(), MissingKeys> {
+        let entity = entity(&update, self.m_entity_key)?;
+
+        match self.m_pending.get_mut(&entity) {
+            Some(pending) => {
+                pending.update_with(&update, self.m_array_merge);
+                self.m_conflated += 1;
+            }
+            None => {
+                self.m_order.push_back(entity.clone());
+                self.m_pending.insert(entity, update);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the update of the entity that has been waiting longest.
+    pub fn pop(&mut self) -> Option<Ido> {
+        let entity = self.m_order.pop_front()?;
+        self.m_pending.remove(&entity)
+    }
+
+    /// Removes every pending update, in delivery order.
+    pub fn drain(&mut self) -> Vec<Ido> {
+        std::iter::from_fn(|| self.pop()).collect()
+    }
+
+    /// Returns the pending update of an entity without removing it.
+    pub fn peek(&self, entity: &str) -> Option<&Ido> {
+        self.m_pending.get(entity)
+    }
+
+    /// Returns the number of entities with a pending update.
+    pub fn len(&self) -> usize {
+        self.m_order.len()
+    }
+
+    /// Returns `true` if no update is pending.
+    pub fn is_empty(&self) -> bool {
+        self.m_order.is_empty()
+    }
+
+    /// Returns the number of updates merged into an already pending one.
+    pub fn conflated(&self) -> u64 {
+        self.m_conflated
+    }
+}
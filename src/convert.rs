@@ -0,0 +1,378 @@
+//! Converting between Idos and strongly typed Rust values.
+//!
+//! [`ToIdo`] and [`FromIdo`] convert a whole type to and from an Ido, and
+//! [`IdoValue`] converts a single field stored under one key. Generic APIs
+//! such as [`Ido::append_array_item`] and [`Ido::get_value`] accept any type
+//! implementing them, so application types can be stored without building
+//! Idos by hand. The traits are re-exported at the crate root.
+//!
+//! With the `derive` feature, `#[derive(IntoIdo, FromIdo)]` implements the
+//! first two for structs whose fields implement `IdoValue`:
+//!
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use ido::{FromIdo, IntoIdo, ToIdo};
+//!
+//! #[derive(IntoIdo, FromIdo, Debug, PartialEq)]
+//! struct Leg {
+//!     #[ido(key = 1)]
+//!     symbol: String,
+//!     #[ido(key = 2)]
+//!     qty: i64,
+//! }
+//!
+//! #[derive(IntoIdo, FromIdo, Debug, PartialEq)]
+//! struct Order {
+//!     #[ido(key = 1)]
+//!     id: i64,
+//!     #[ido(key = 2)]
+//!     price: Option<f64>,
+//!     #[ido(key = 3)]
+//!     legs: Vec<Leg>,
+//! }
+//!
+//! let order = Order {
+//!     id: 7,
+//!     price: None,
+//!     legs: vec![Leg { symbol: "EURUSD".to_string(), qty: 100 }],
+//! };
+//!
+//! let ido = order.to_ido();
+//! assert_eq!(ido.to_string(), "1=7,3=[{1=EURUSD,2=100}]");
+//! assert_eq!(Order::from_ido(&ido).unwrap(), order);
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+/// Errors raised when an Ido does not have the shape a type expects.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FromIdoError {
+    /// A required key is absent.
+    Missing(IdoKeyT),
+    /// A key holds a different type than the field expects.
+    WrongType { key: IdoKeyT, expected: IdoItemType, actual: IdoItemType },
+    /// A numeric value does not fit the field's type.
+    OutOfRange(IdoKeyT),
+    /// An element of the array under `key` could not be converted.
+    Element { key: IdoKeyT, index: usize, error: Box<FromIdoError> },
+}
+
+impl fmt::Display for FromIdoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FromIdoError::Missing(key) => write!(f, "key {} is missing", key),
+            FromIdoError::WrongType { key, expected, actual } => {
+                write!(f, "key {} is {:?}, expected {:?}", key, actual, expected)
+            }
+            FromIdoError::OutOfRange(key) => write!(f, "key {} is out of range", key),
+            FromIdoError::Element { key, index, error } => {
+                write!(f, "element {} of key {}: {}", index, key, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FromIdoError {}
+
+/// Returned by [`get_tuple!`](crate::get_tuple), listing the error of every
+/// key that could not be read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FromIdoErrors(pub Vec<FromIdoError>);
+
+impl FromIdoErrors {
+    /// Returns the errors in the order the keys were requested.
+    pub fn errors(&self) -> &[FromIdoError] {
+        &self.0
+    }
+}
+
+impl fmt::Display for FromIdoErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<String> = self.0.iter().map(|error| error.to_string()).collect();
+        write!(f, "{}", errors.join("; "))
+    }
+}
+
+impl std::error::Error for FromIdoErrors {}
+
+/// A type that can be written as an Ido.
+pub trait ToIdo {
+    /// Builds an Ido holding the value.
+    fn to_ido(&self) -> Ido;
+}
+
+/// A type that can be read back from an Ido.
+pub trait FromIdo: Sized {
+    /// Rebuilds the value from an Ido.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FromIdoError` if a key is missing or has the wrong type.
+    fn from_ido(ido: &Ido) -> Result<Self, FromIdoError>;
+}
+
+impl ToIdo for Ido {
+    fn to_ido(&self) -> Ido {
+        self.clone()
+    }
+}
+
+impl FromIdo for Ido {
+    fn from_ido(ido: &Ido) -> Result<Self, FromIdoError> {
+        Ok(ido.clone())
+    }
+}
+
+/// Maps store each entry under its key, in key order.
+impl<V: IdoValue> ToIdo for BTreeMap<IdoKeyT, V> {
+    fn to_ido(&self) -> Ido {
+        let mut ido = Ido::new();
+        for (key, value) in self {
+            value.write(&mut ido, *key);
+        }
+        ido
+    }
+}
+
+impl<V: IdoValue> FromIdo for BTreeMap<IdoKeyT, V> {
+    fn from_ido(ido: &Ido) -> Result<Self, FromIdoError> {
+        ido.live_items()
+            .map(|(key, _)| Ok((key, V::read(ido, key)?)))
+            .collect()
+    }
+}
+
+/// A value stored under a single key of an Ido.
+///
+/// Implemented for the scalar types Ido supports, for `Option<V>` (absent
+/// keys read as `None`) and for `Vec<T>` of [`ToIdo`]/[`FromIdo`] types,
+/// which maps to an ARRAY item (absent keys read as an empty vector).
+pub trait IdoValue: Sized {
+    /// Stores the value under `key`.
+    fn write(&self, ido: &mut Ido, key: IdoKeyT);
+
+    /// Reads the value stored under `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FromIdoError` if the key is missing or has the wrong type.
+    fn read(ido: &Ido, key: IdoKeyT) -> Result<Self, FromIdoError>;
+}
+
+fn typed_item(ido: &Ido, key: IdoKeyT, expected: IdoItemType) -> Result<&IdoItem, FromIdoError> {
+    let item = ido.read_item(&key).ok_or(FromIdoError::Missing(key))?;
+    if item.m_type != expected {
+        return Err(FromIdoError::WrongType { key, expected, actual: item.m_type });
+    }
+    Ok(item)
+}
+
+impl IdoValue for i64 {
+    fn write(&self, ido: &mut Ido, key: IdoKeyT) {
+        ido.set_integer(&key, *self);
+    }
+
+    fn read(ido: &Ido, key: IdoKeyT) -> Result<Self, FromIdoError> {
+        Ok(typed_item(ido, key, IdoItemType::INTEGER)?.m_integer)
+    }
+}
+
+macro_rules! narrow_integer_value {
+    ($($ty:ty),*) => {
+        $(
+            impl IdoValue for $ty {
+                fn write(&self, ido: &mut Ido, key: IdoKeyT) {
+                    ido.set_integer(&key, i64::from(*self));
+                }
+
+                fn read(ido: &Ido, key: IdoKeyT) -> Result<Self, FromIdoError> {
+                    let value = typed_item(ido, key, IdoItemType::INTEGER)?.m_integer;
+                    <$ty>::try_from(value).map_err(|_| FromIdoError::OutOfRange(key))
+                }
+            }
+        )*
+    };
+}
+
+narrow_integer_value!(i8, i16, i32, u8, u16, u32);
+
+impl IdoValue for i128 {
+    fn write(&self, ido: &mut Ido, key: IdoKeyT) {
+        ido.set_i128(&key, *self);
+    }
+
+    fn read(ido: &Ido, key: IdoKeyT) -> Result<Self, FromIdoError> {
+        if ido.live_item(&key).is_some_and(|item| item.m_type == IdoItemType::INTEGER) {
+            return i64::read(ido, key).map(i128::from);
+        }
+        Ok(typed_item(ido, key, IdoItemType::INTEGER128)?.m_integer128)
+    }
+}
+
+impl IdoValue for f64 {
+    fn write(&self, ido: &mut Ido, key: IdoKeyT) {
+        ido.set_f64(&key, *self);
+    }
+
+    fn read(ido: &Ido, key: IdoKeyT) -> Result<Self, FromIdoError> {
+        Ok(typed_item(ido, key, IdoItemType::FLOAT)?.m_float)
+    }
+}
+
+impl IdoValue for f32 {
+    fn write(&self, ido: &mut Ido, key: IdoKeyT) {
+        ido.set_f64(&key, f64::from(*self));
+    }
+
+    fn read(ido: &Ido, key: IdoKeyT) -> Result<Self, FromIdoError> {
+        Ok(typed_item(ido, key, IdoItemType::FLOAT)?.m_float as f32)
+    }
+}
+
+impl IdoValue for String {
+    fn write(&self, ido: &mut Ido, key: IdoKeyT) {
+        ido.set_string(&key, self.clone());
+    }
+
+    fn read(ido: &Ido, key: IdoKeyT) -> Result<Self, FromIdoError> {
+        Ok(typed_item(ido, key, IdoItemType::STRING)?.m_string.clone())
+    }
+}
+
+impl IdoValue for bool {
+    fn write(&self, ido: &mut Ido, key: IdoKeyT) {
+        ido.set_bool(&key, *self);
+    }
+
+    fn read(ido: &Ido, key: IdoKeyT) -> Result<Self, FromIdoError> {
+        Ok(typed_item(ido, key, IdoItemType::BOOL)?.m_bool)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl IdoValue for DateTime<Utc> {
+    fn write(&self, ido: &mut Ido, key: IdoKeyT) {
+        let mut item = IdoItem::new();
+        item.m_type = IdoItemType::DATETIME;
+        item.m_datetime = *self;
+        ido.set_item(&key, item);
+    }
+
+    fn read(ido: &Ido, key: IdoKeyT) -> Result<Self, FromIdoError> {
+        Ok(typed_item(ido, key, IdoItemType::DATETIME)?.m_datetime)
+    }
+}
+
+impl<V: IdoValue> IdoValue for Option<V> {
+    fn write(&self, ido: &mut Ido, key: IdoKeyT) {
+        if let Some(value) = self {
+            value.write(ido, key);
+        }
+    }
+
+    fn read(ido: &Ido, key: IdoKeyT) -> Result<Self, FromIdoError> {
+        if ido.contains(&key) {
+            V::read(ido, key).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<T: ToIdo + FromIdo> IdoValue for Vec<T> {
+    fn write(&self, ido: &mut Ido, key: IdoKeyT) {
+        for element in self {
+            ido.append_array_item(&key, element);
+        }
+    }
+
+    fn read(ido: &Ido, key: IdoKeyT) -> Result<Self, FromIdoError> {
+        read_array(ido, key)
+    }
+}
+
+fn read_array<T: FromIdo>(ido: &Ido, key: IdoKeyT) -> Result<Vec<T>, FromIdoError> {
+    if !ido.contains(&key) {
+        return Ok(Vec::new());
+    }
+
+    typed_item(ido, key, IdoItemType::ARRAY)?
+        .m_array
+        .iter()
+        .enumerate()
+        .map(|(index, element)| {
+            T::from_ido(element).map_err(|error| FromIdoError::Element { key, index, error: Box::new(error) })
+        })
+        .collect()
+}
+
+impl Ido {
+    /// Stores a typed value under the given key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_value(&1, 42u32);
+    /// ido.set_value(&2, Some("ACC-1".to_string()));
+    ///
+    /// assert_eq!(ido.get_value::<u32>(&1), Ok(42));
+    /// assert_eq!(ido.get_value::<Option<f64>>(&3), Ok(None));
+    /// ```
+    pub fn set_value<V: IdoValue>(&mut self, key: &IdoKeyT, value: V) {
+        value.write(self, *key);
+    }
+
+    /// Reads a typed value stored under the given key.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FromIdoError` if the key is missing or holds a value that
+    /// cannot be converted to `V`.
+    pub fn get_value<V: IdoValue>(&self, key: &IdoKeyT) -> Result<V, FromIdoError> {
+        V::read(self, *key)
+    }
+
+    /// Appends any [`ToIdo`] value to the array stored under the given key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use ido::{Ido, IdoKeyT};
+    ///
+    /// let mut leg = BTreeMap::new();
+    /// leg.insert(1, "EURUSD".to_string());
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.append_array_item(&5, &leg);
+    ///
+    /// let legs: Vec<BTreeMap<IdoKeyT, String>> = ido.get_array_items(&5).unwrap();
+    /// assert_eq!(legs, vec![leg]);
+    /// ```
+    pub fn append_array_item<T: ToIdo + ?Sized>(&mut self, key: &IdoKeyT, item: &T) {
+        self.append_array(key, item.to_ido());
+    }
+
+    /// Converts every element of the array stored under the given key.
+    ///
+    /// A missing key yields an empty vector.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FromIdoError` if the key is not an array or an element
+    /// cannot be converted to `T`.
+    pub fn get_array_items<T: FromIdo>(&self, key: &IdoKeyT) -> Result<Vec<T>, FromIdoError> {
+        read_array(self, *key)
+    }
+}
@@ -0,0 +1,260 @@
+//! Loading CSV records into an array of Idos.
+//!
+//! [`Ido::append_array_from_csv`] reads CSV as RFC 4180 describes it: a
+//! header row naming the columns, then one record per line, with quoted
+//! cells holding commas, quotes (doubled) and line breaks. Each record
+//! becomes an Ido appended to an ARRAY, with one item per mapped column.
+//! [`Ido::array_to_csv`] writes the same format.
+//!
+//! Every column gets a single type, the first of the following that all
+//! of its non-empty cells parse as:
+//!
+//! | cells                         | item                        |
+//! |-------------------------------|-----------------------------|
+//! | `42`, `-7`                    | INTEGER                     |
+//! | beyond 64 bits                | INTEGER128                  |
+//! | `1.5`, `2e-3`, and integers   | FLOAT                       |
+//! | `true`, `FALSE`               | BOOL                        |
+//! | `2024-01-02T03:04:05Z`        | DATETIME (RFC 3339)         |
+//! | anything else                 | STRING                      |
+//!
+//! Empty cells leave the item out. Without the `chrono` feature, columns
+//! of timestamps are read as strings.
+
+use std::fmt;
+use std::io::{self, Read};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use crate::config::is_number;
+use crate::dictionary::IdoDictionary;
+use crate::{Ido, IdoError, IdoItem, IdoItemType, IdoKey, IdoKeyT};
+
+/// Errors raised while loading CSV.
+#[derive(Debug)]
+pub enum CsvError {
+    /// Reading the input failed.
+    Io(io::Error),
+    /// The record starting on this line is malformed.
+    Parse { line: usize, reason: String },
+    /// Appending a record to the array failed.
+    Ido(IdoError),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(err) => write!(f, "io error: {}", err),
+            CsvError::Parse { line, reason } => write!(f, "line {}: {}", line, reason),
+            CsvError::Ido(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsvError::Io(err) => Some(err),
+            CsvError::Ido(err) => Some(err),
+            CsvError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(err: io::Error) -> Self {
+        CsvError::Io(err)
+    }
+}
+
+impl From<IdoError> for CsvError {
+    fn from(err: IdoError) -> Self {
+        CsvError::Ido(err)
+    }
+}
+
+/// A record and the line it starts on.
+struct Record {
+    line: usize,
+    cells: Vec<String>,
+}
+
+/// Splits `text` into records, skipping blank lines.
+fn parse_records(text: &str) -> Result<Vec<Record>, CsvError> {
+    let mut records = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+
+    while chars.peek().is_some() {
+        let start = line;
+        let mut cells = Vec::new();
+        let mut cell = String::new();
+        let mut quoted = false;
+
+        loop {
+            match chars.next() {
+                Some('"') if quoted => {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        cell.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                }
+                Some('"') if cell.is_empty() => quoted = true,
+                Some(c) if quoted => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    cell.push(c);
+                }
+                Some(',') => cells.push(std::mem::take(&mut cell)),
+                Some('\r') if chars.peek() == Some(&'\n') => {}
+                Some('\n') | None => {
+                    line += 1;
+                    break;
+                }
+                Some(c) => cell.push(c),
+            }
+            if quoted && chars.peek().is_none() {
+                return Err(CsvError::Parse { line: start, reason: "unterminated quoted cell".to_string() });
+            }
+        }
+        cells.push(cell);
+
+        if cells.len() > 1 || !cells[0].is_empty() {
+            records.push(Record { line: start, cells });
+        }
+    }
+    Ok(records)
+}
+
+/// Reads `text` as `kind`, or returns `None` if it is not one.
+fn parse_cell(text: &str, kind: IdoItemType) -> Option<IdoItem> {
+    let mut item = IdoItem::new();
+    item.m_type = kind;
+
+    match kind {
+        IdoItemType::INTEGER => item.m_integer = text.parse().ok()?,
+        IdoItemType::INTEGER128 => item.m_integer128 = text.parse().ok()?,
+        IdoItemType::FLOAT => item.m_float = text.parse().ok().filter(|_| is_number(text))?,
+        IdoItemType::BOOL if text.eq_ignore_ascii_case("true") => item.m_bool = true,
+        IdoItemType::BOOL if text.eq_ignore_ascii_case("false") => item.m_bool = false,
+        #[cfg(feature = "chrono")]
+        IdoItemType::DATETIME => item.m_datetime = DateTime::parse_from_rfc3339(text).ok()?.with_timezone(&Utc),
+        IdoItemType::STRING => item.m_string = text.to_string(),
+        _ => return None,
+    }
+    Some(item)
+}
+
+/// Picks the type of the column at `index`, as the module documentation
+/// describes.
+fn column_type(records: &[Record], index: usize) -> IdoItemType {
+    let kinds = [
+        IdoItemType::INTEGER,
+        IdoItemType::INTEGER128,
+        IdoItemType::FLOAT,
+        IdoItemType::BOOL,
+        #[cfg(feature = "chrono")]
+        IdoItemType::DATETIME,
+    ];
+
+    kinds
+        .into_iter()
+        .find(|kind| {
+            records
+                .iter()
+                .map(|record| record.cells[index].as_str())
+                .filter(|cell| !cell.is_empty())
+                .all(|cell| parse_cell(cell, *kind).is_some())
+        })
+        .unwrap_or(IdoItemType::STRING)
+}
+
+impl Ido {
+    /// Reads CSV from `reader` and appends one Ido per record to the array
+    /// under `key`; see the [`csv`](crate::csv) module for the format and
+    /// how columns are typed.
+    ///
+    /// Each header is looked up in `column_key_map`, or else read as an
+    /// integer key; columns with other headers are skipped. The whole input
+    /// is read and typed before the first record is appended.
+    ///
+    /// # Returns
+    ///
+    /// The number of records appended.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CsvError::Io` if reading fails, `CsvError::Parse` for an
+    /// unterminated quoted cell or a record whose cell count differs from
+    /// the header's, and `CsvError::Ido` if `try_append_array` refuses a
+    /// record. Records appended before a refused one are kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::dictionary::IdoDictionary;
+    /// use ido::Ido;
+    ///
+    /// let csv = "symbol,lot size,tick,comment\n\
+    ///            EURUSD,1000,0.0001,\"major, liquid\"\n\
+    ///            USDJPY,1000,0.01,\n";
+    /// let column_key_map: IdoDictionary = [(1, "symbol"), (2, "lot size"), (3, "tick")].into_iter().collect();
+    ///
+    /// let mut ido = Ido::new();
+    /// assert_eq!(ido.append_array_from_csv(&5, csv.as_bytes(), &column_key_map).unwrap(), 2);
+    ///
+    /// let rows = ido.get_item(&5).unwrap().m_array;
+    /// assert_eq!(rows[1].get_string(&1), Some("USDJPY".to_string()));
+    /// assert_eq!(rows[1].get_i64(&2), Some(1000));
+    /// assert_eq!(rows[1].get_f64(&3), Some(0.01));
+    /// assert_eq!(rows[1].size(), 3);
+    /// ```
+    pub fn append_array_from_csv<R: Read>(
+        &mut self,
+        key: &impl IdoKey,
+        mut reader: R,
+        column_key_map: &IdoDictionary,
+    ) -> Result<usize, CsvError> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+
+        let mut records = parse_records(&text)?.into_iter();
+        let Some(header) = records.next() else {
+            return Ok(0);
+        };
+        let records: Vec<Record> = records.collect();
+        if let Some(record) = records.iter().find(|record| record.cells.len() != header.cells.len()) {
+            return Err(CsvError::Parse {
+                line: record.line,
+                reason: format!("expected {} cells, found {}", header.cells.len(), record.cells.len()),
+            });
+        }
+
+        let columns: Vec<(usize, IdoKeyT, IdoItemType)> = header
+            .cells
+            .iter()
+            .enumerate()
+            .filter_map(|(index, name)| {
+                let column = column_key_map.key(name).or_else(|| name.trim().parse().ok())?;
+                Some((index, column, column_type(&records, index)))
+            })
+            .collect();
+
+        let key = &key.ido_key();
+        for record in &records {
+            let mut row = Ido::new();
+            for (index, column, kind) in &columns {
+                let cell = &record.cells[*index];
+                if let Some(item) = parse_cell(cell, *kind).filter(|_| !cell.is_empty()) {
+                    row.set_item(column, item);
+                }
+            }
+            self.try_append_array(key, row)?;
+        }
+        Ok(records.len())
+    }
+}
@@ -0,0 +1,228 @@
+//! Walking an Ido in insertion order from a remembered position or in
+//! fixed-size chunks, and scanning it with predicates.
+
+use crate::{Ido, IdoItem, IdoKeyT};
+
+/// A position in the insertion order of an Ido.
+///
+/// The cursor starts before the first item. `next` returns the item at the
+/// cursor and moves past it, `peek` returns it without moving, and
+/// `seek_key` moves the cursor to a given key, so parsers can resume or
+/// skip ahead without iterating from the start again. Expired items are
+/// skipped.
+///
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+///
+/// let mut ido = Ido::new();
+/// ido.set_string(&10, "ORDER-1".to_string());
+/// ido.set_integer(&20, 100);
+/// ido.set_f64(&30, 101.25);
+///
+/// let mut cursor = ido.cursor();
+/// assert_eq!(cursor.next().map(|(key, _)| key), Some(10));
+/// assert_eq!(cursor.peek().map(|(key, _)| key), Some(20));
+///
+/// assert!(cursor.seek_key(&30));
+/// let (key, item) = cursor.next().unwrap();
+/// assert_eq!((key, item.m_float), (30, 101.25));
+/// assert!(cursor.next().is_none());
+/// ```
+pub struct IdoCursor<'a> {
+    m_ido: &'a Ido,
+    m_position: u64,
+}
+
+impl<'a> IdoCursor<'a> {
+    /// Returns the item at the cursor without moving it.
+    pub fn peek(&self) -> Option<(IdoKeyT, &'a IdoItem)> {
+        self.current().map(|(_, key, item)| (key, item))
+    }
+
+    /// Moves the cursor to `key`, so the next call to `next` returns it.
+    ///
+    /// # Returns
+    ///
+    /// `false`, leaving the cursor where it was, if no live item is stored
+    /// under `key`.
+    pub fn seek_key(&mut self, key: &IdoKeyT) -> bool {
+        match self.m_ido.live_item(key) {
+            Some(item) => {
+                self.m_position = item.m_index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the cursor back before the first item.
+    pub fn rewind(&mut self) {
+        self.m_position = 0;
+    }
+
+    /// The first live item at or after the cursor and its index.
+    fn current(&self) -> Option<(u64, IdoKeyT, &'a IdoItem)> {
+        let ido = self.m_ido;
+        ido.m_ordered
+            .range(self.m_position..)
+            .find_map(|(index, key)| ido.live_item(key).map(|item| (*index, *key, item)))
+    }
+}
+
+impl<'a> Iterator for IdoCursor<'a> {
+    type Item = (IdoKeyT, &'a IdoItem);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, key, item) = self.current()?;
+        self.m_position = index + 1;
+        Some((key, item))
+    }
+}
+
+/// Iterator over the items of an Ido in insertion order, `n` at a time.
+///
+/// Created by [`Ido::chunks`].
+pub struct IdoChunks<'a> {
+    m_cursor: IdoCursor<'a>,
+    m_size: usize,
+}
+
+impl<'a> Iterator for IdoChunks<'a> {
+    type Item = Vec<(IdoKeyT, &'a IdoItem)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk: Vec<_> = self.m_cursor.by_ref().take(self.m_size).collect();
+        (!chunk.is_empty()).then_some(chunk)
+    }
+}
+
+impl Ido {
+    /// Returns a cursor before the first item in insertion order.
+    pub fn cursor(&self) -> IdoCursor<'_> {
+        IdoCursor { m_ido: self, m_position: 0 }
+    }
+
+    /// Returns the position of `key` in insertion order, counting from 0.
+    ///
+    /// The position is the one the item has when iterating the Ido, so
+    /// expired items are not counted. Finding it takes time linear in the
+    /// position.
+    ///
+    /// # Returns
+    ///
+    /// `None` if no live item is stored under `key`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&35, "D".to_string());
+    /// ido.set_string(&11, "ORDER-1".to_string());
+    /// ido.set_integer(&38, 100);
+    ///
+    /// assert_eq!(ido.position_of(&11), Some(1));
+    /// ido.delete_item(&35);
+    /// assert_eq!(ido.position_of(&11), Some(0));
+    /// assert_eq!(ido.position_of(&35), None);
+    /// ```
+    pub fn position_of(&self, key: &IdoKeyT) -> Option<usize> {
+        let index = self.live_item(key)?.m_index;
+        Some(self.m_ordered.range(..index).filter(|(_, key)| self.live_item(key).is_some()).count())
+    }
+
+    /// Iterates over the items in insertion order in chunks of `n`.
+    ///
+    /// Every chunk but the last holds exactly `n` items, and only one chunk
+    /// is held at a time, so very large Idos can be processed in fixed-size
+    /// batches. Expired items are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of items per chunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// for key in 0..5 {
+    ///     ido.set_integer(&key, 0);
+    /// }
+    ///
+    /// let sizes: Vec<usize> = ido.chunks(2).map(|chunk| chunk.len()).collect();
+    /// assert_eq!(sizes, [2, 2, 1]);
+    /// ```
+    pub fn chunks(&self, n: usize) -> IdoChunks<'_> {
+        assert!(n > 0, "chunk size must be non-zero");
+        IdoChunks { m_cursor: self.cursor(), m_size: n }
+    }
+
+    /// Returns the first item in insertion order for which `predicate`
+    /// returns `true`. Expired items are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoItemType};
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "ORDER-1".to_string());
+    /// ido.set_integer(&2, 100);
+    /// ido.set_integer(&3, 60);
+    ///
+    /// let (key, item) = ido.find(|_, item| item.m_type == IdoItemType::INTEGER).unwrap();
+    /// assert_eq!((key, item.m_integer), (2, 100));
+    /// assert!(ido.find(|key, _| key > 10).is_none());
+    /// ```
+    pub fn find<F: FnMut(IdoKeyT, &IdoItem) -> bool>(&self, mut predicate: F) -> Option<(IdoKeyT, &IdoItem)> {
+        self.cursor().find(|(key, item)| predicate(*key, item))
+    }
+
+    /// Returns `true` if `predicate` returns `true` for any item, checking
+    /// them in insertion order and stopping at the first match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 100);
+    /// ido.set_integer(&2, -5);
+    ///
+    /// assert!(ido.any(|_, item| item.m_integer < 0));
+    /// assert!(!Ido::new().any(|_, _| true));
+    /// ```
+    pub fn any<F: FnMut(IdoKeyT, &IdoItem) -> bool>(&self, mut predicate: F) -> bool {
+        self.cursor().any(|(key, item)| predicate(key, item))
+    }
+
+    /// Returns `true` if `predicate` returns `true` for every item, checking
+    /// them in insertion order and stopping at the first mismatch. An empty
+    /// Ido returns `true`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 100);
+    /// ido.set_integer(&2, -5);
+    ///
+    /// assert!(ido.all(|key, _| key > 0));
+    /// assert!(!ido.all(|_, item| item.m_integer > 0));
+    /// ```
+    pub fn all<F: FnMut(IdoKeyT, &IdoItem) -> bool>(&self, mut predicate: F) -> bool {
+        self.cursor().all(|(key, item)| predicate(key, item))
+    }
+}
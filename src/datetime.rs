@@ -0,0 +1,134 @@
+//! How DATETIME values are rendered as text.
+//!
+//! [`Ido::to_string`](crate::Ido) renders datetimes with the format set by
+//! [`Ido::set_datetime_format`](crate::Ido::set_datetime_format), and
+//! [`Ido::to_string_with_format`](crate::Ido::to_string_with_format) picks
+//! one for a single call. Nested Idos are rendered with the format of the
+//! Ido being displayed.
+//!
+//! Without the `chrono` feature there are no DATETIME values; formats can
+//! still be chosen, but neither parsed from patterns nor applied.
+
+use std::fmt;
+
+#[cfg(feature = "chrono")]
+use chrono::format::{Item, StrftimeItems};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, SecondsFormat, Utc};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+enum Kind {
+    #[default]
+    Standard,
+    Rfc3339,
+    EpochSeconds,
+    EpochMillis,
+    EpochMicros,
+    EpochNanos,
+    #[cfg(feature = "chrono")]
+    Pattern(String),
+}
+
+/// A way of rendering DATETIME values.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "chrono")]
+/// # {
+/// use chrono::{TimeZone, Utc};
+/// use ido::datetime::DateTimeFormat;
+///
+/// let stamp = Utc.with_ymd_and_hms(2023, 7, 1, 12, 30, 0).unwrap();
+///
+/// assert_eq!(DateTimeFormat::standard().format(&stamp), "2023-07-01 12:30:00.0000");
+/// assert_eq!(DateTimeFormat::rfc3339().format(&stamp), "2023-07-01T12:30:00Z");
+/// assert_eq!(DateTimeFormat::epoch_millis().format(&stamp), "1688214600000");
+/// assert_eq!(DateTimeFormat::pattern("%d/%m/%Y").unwrap().format(&stamp), "01/07/2023");
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DateTimeFormat(Kind);
+
+impl DateTimeFormat {
+    /// `2023-07-01 12:30:00.0000`, with a space and a tenth of a millisecond.
+    /// This is the default.
+    pub fn standard() -> Self {
+        DateTimeFormat(Kind::Standard)
+    }
+
+    /// ISO-8601 as profiled by RFC 3339, e.g. `2023-07-01T12:30:00.25Z`.
+    /// Fractional seconds are only written when present.
+    pub fn rfc3339() -> Self {
+        DateTimeFormat(Kind::Rfc3339)
+    }
+
+    /// Whole seconds since the Unix epoch.
+    pub fn epoch_seconds() -> Self {
+        DateTimeFormat(Kind::EpochSeconds)
+    }
+
+    /// Milliseconds since the Unix epoch.
+    pub fn epoch_millis() -> Self {
+        DateTimeFormat(Kind::EpochMillis)
+    }
+
+    /// Microseconds since the Unix epoch.
+    pub fn epoch_micros() -> Self {
+        DateTimeFormat(Kind::EpochMicros)
+    }
+
+    /// Nanoseconds since the Unix epoch.
+    pub fn epoch_nanos() -> Self {
+        DateTimeFormat(Kind::EpochNanos)
+    }
+
+    /// A `chrono` strftime pattern such as `%Y-%m-%dT%H:%M:%S%.3f`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidPattern` if the pattern contains an unknown or
+    /// malformed specifier.
+    #[cfg(feature = "chrono")]
+    pub fn pattern(pattern: &str) -> Result<Self, InvalidPattern> {
+        if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) {
+            return Err(InvalidPattern(pattern.to_string()));
+        }
+        Ok(DateTimeFormat(Kind::Pattern(pattern.to_string())))
+    }
+
+    /// Renders `datetime` in this format.
+    #[cfg(feature = "chrono")]
+    pub fn format(&self, datetime: &DateTime<Utc>) -> String {
+        let epoch = |per_second: i128| {
+            let fraction = i128::from(datetime.timestamp_subsec_nanos()) * per_second / 1_000_000_000;
+            (i128::from(datetime.timestamp()) * per_second + fraction).to_string()
+        };
+
+        match &self.0 {
+            Kind::Standard => format!(
+                "{}.{:04}",
+                datetime.format("%Y-%m-%d %H:%M:%S"),
+                (datetime.timestamp_subsec_nanos() / 100_000).min(9999)
+            ),
+            Kind::Rfc3339 => datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            Kind::EpochSeconds => datetime.timestamp().to_string(),
+            Kind::EpochMillis => epoch(1_000),
+            Kind::EpochMicros => epoch(1_000_000),
+            Kind::EpochNanos => epoch(1_000_000_000),
+            Kind::Pattern(pattern) => datetime.format(pattern).to_string(),
+        }
+    }
+}
+
+/// Returned by [`DateTimeFormat::pattern`] for a pattern chrono cannot use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidPattern(pub String);
+
+impl fmt::Display for InvalidPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid datetime pattern {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidPattern {}
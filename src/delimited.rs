@@ -0,0 +1,183 @@
+//! A delimited text format used by legacy middleware.
+//!
+//! Every item is written as its key, a value separator and its value, and
+//! items are joined by a field separator. With the default separators a
+//! message reads `1|ORDER-1^2|100^3|101.25`. Both separators and the escape
+//! character are configurable on [`DelimitedCodec`].
+//!
+//! A separator or escape character inside a value is preceded by the escape
+//! character. Empty fields, such as a trailing field separator, are
+//! ignored when decoding. The format carries no types, so values are
+//! written as `to_string` renders them and decoded as STRING items. Arrays
+//! cannot be written.
+
+use crate::codec::{CodecError, Decode, Encode};
+use crate::{Ido, IdoItemType, IdoKeyT};
+
+/// Codec for the layout described in the module documentation.
+///
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+/// use ido::codec::{Decode, Encode};
+/// use ido::delimited::DelimitedCodec;
+///
+/// let codec = DelimitedCodec::new();
+///
+/// let mut ido = Ido::new();
+/// ido.set_string(&1, "A|B".to_string());
+/// ido.set_integer(&2, 100);
+///
+/// let mut bytes = Vec::new();
+/// codec.encode(&ido, &mut bytes).unwrap();
+/// assert_eq!(bytes, b"1|A\\|B^2|100");
+///
+/// let decoded = codec.decode(b"1|A\\|B^2|100^").unwrap();
+/// assert_eq!(decoded.get_string(&1), Some("A|B".to_string()));
+/// assert_eq!(decoded.get_string(&2), Some("100".to_string()));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DelimitedCodec {
+    m_field_separator: char,
+    m_value_separator: char,
+    m_escape: Option<char>,
+}
+
+impl Default for DelimitedCodec {
+    fn default() -> Self {
+        DelimitedCodec { m_field_separator: '^', m_value_separator: '|', m_escape: Some('\\') }
+    }
+}
+
+impl DelimitedCodec {
+    /// Creates a codec separating fields with `^`, keys from values with
+    /// `|` and escaping with `\`.
+    pub fn new() -> Self {
+        DelimitedCodec::default()
+    }
+
+    /// Sets the character between fields.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it equals the value separator or the escape character.
+    pub fn field_separator(mut self, separator: char) -> Self {
+        self.m_field_separator = separator;
+        self.check();
+        self
+    }
+
+    /// Sets the character between a key and its value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it equals the field separator or the escape character.
+    pub fn value_separator(mut self, separator: char) -> Self {
+        self.m_value_separator = separator;
+        self.check();
+        self
+    }
+
+    /// Sets the escape character, or disables escaping with `None`. Without
+    /// escaping, values containing a separator cannot be written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it equals one of the separators.
+    pub fn escape(mut self, escape: Option<char>) -> Self {
+        self.m_escape = escape;
+        self.check();
+        self
+    }
+
+    fn check(&self) {
+        let distinct = self.m_field_separator != self.m_value_separator
+            && self.m_escape != Some(self.m_field_separator)
+            && self.m_escape != Some(self.m_value_separator);
+        assert!(distinct, "separators and escape must be distinct: {:?}", self);
+    }
+
+    fn is_special(&self, c: char) -> bool {
+        c == self.m_field_separator || c == self.m_value_separator || Some(c) == self.m_escape
+    }
+
+    fn write_ido(&self, ido: &Ido, out: &mut String) -> Result<(), CodecError> {
+        for (index, (key, item)) in ido.live_items().enumerate() {
+            if item.m_type == IdoItemType::ARRAY {
+                return Err(CodecError::Unsupported(key));
+            }
+            let value = item.as_string_with(ido.datetime_format()).unwrap_or_default();
+
+            if index > 0 {
+                out.push(self.m_field_separator);
+            }
+            out.push_str(&key.to_string());
+            out.push(self.m_value_separator);
+            for c in value.chars() {
+                if self.is_special(c) {
+                    out.push(self.m_escape.ok_or(CodecError::Unsupported(key))?);
+                }
+                out.push(c);
+            }
+        }
+        Ok(())
+    }
+
+    /// Splits `text` on unescaped field separators into the byte offset,
+    /// the unescaped text and the position of the value separator in that
+    /// text of every field.
+    fn fields(&self, text: &str) -> Result<Vec<(usize, String, Option<usize>)>, CodecError> {
+        let mut fields = Vec::new();
+        let mut start = 0;
+        let mut current = String::new();
+        let mut value_at = None;
+        let mut chars = text.char_indices();
+
+        while let Some((offset, c)) = chars.next() {
+            if Some(c) == self.m_escape {
+                let (_, escaped) = chars.next().ok_or(CodecError::InvalidField(start))?;
+                current.push(escaped);
+            } else if c == self.m_field_separator {
+                fields.push((start, std::mem::take(&mut current), value_at.take()));
+                start = offset + c.len_utf8();
+            } else if c == self.m_value_separator && value_at.is_none() {
+                value_at = Some(current.len());
+            } else {
+                current.push(c);
+            }
+        }
+        fields.push((start, current, value_at));
+        Ok(fields)
+    }
+}
+
+impl Encode for DelimitedCodec {
+    fn encode(&self, ido: &Ido, out: &mut Vec<u8>) -> Result<(), CodecError> {
+        let mut text = String::new();
+        self.write_ido(ido, &mut text)?;
+        out.extend_from_slice(text.as_bytes());
+        Ok(())
+    }
+}
+
+impl Decode for DelimitedCodec {
+    fn decode(&self, buf: &[u8]) -> Result<Ido, CodecError> {
+        let text = std::str::from_utf8(buf).map_err(|_| CodecError::InvalidUtf8)?;
+        let mut ido = Ido::new();
+
+        for (offset, field, value_at) in self.fields(text)? {
+            let Some(value_at) = value_at else {
+                if field.is_empty() {
+                    continue;
+                }
+                return Err(CodecError::InvalidField(offset));
+            };
+
+            let (key, value) = field.split_at(value_at);
+            let key = key.parse::<IdoKeyT>().map_err(|_| CodecError::InvalidField(offset))?;
+            ido.set_string(&key, value.to_string());
+        }
+        Ok(ido)
+    }
+}
@@ -0,0 +1,264 @@
+//! Human-readable names for numeric keys.
+//!
+//! An [`IdoDictionary`] maps keys to names and back. It is not needed to
+//! build or exchange Idos, but makes dumps readable: [`Ido::with_names`]
+//! renders `ORDER_ID=ORDER-1,PRICE=101.25` instead of `1=ORDER-1,2=101.25`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::diff;
+use crate::html;
+use crate::markdown;
+#[cfg(feature = "proto")]
+use crate::proto;
+use crate::table::Table;
+use crate::xml::{self, XmlKeys};
+use crate::yaml;
+use crate::{Ido, IdoKeyT};
+
+/// A two-way mapping between keys and their names.
+///
+/// # Example
+///
+/// ```
+/// use ido::dictionary::IdoDictionary;
+///
+/// let mut dictionary = IdoDictionary::new();
+/// dictionary.insert(1, "ORDER_ID");
+/// dictionary.insert(2, "PRICE");
+///
+/// assert_eq!(dictionary.name(&1), Some("ORDER_ID"));
+/// assert_eq!(dictionary.key("PRICE"), Some(2));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IdoDictionary {
+    m_names: BTreeMap<IdoKeyT, String>,
+    m_keys: HashMap<String, IdoKeyT>,
+}
+
+impl IdoDictionary {
+    /// Creates an empty dictionary.
+    pub fn new() -> Self {
+        IdoDictionary::default()
+    }
+
+    /// Names `key`, replacing any previous name of the key and any previous
+    /// key with the same name.
+    pub fn insert<S: Into<String>>(&mut self, key: IdoKeyT, name: S) {
+        let name = name.into();
+
+        if let Some(old_name) = self.m_names.remove(&key) {
+            self.m_keys.remove(&old_name);
+        }
+        if let Some(old_key) = self.m_keys.insert(name.clone(), key) {
+            self.m_names.remove(&old_key);
+        }
+        self.m_names.insert(key, name);
+    }
+
+    /// Removes the name of `key`, returning it.
+    pub fn remove(&mut self, key: &IdoKeyT) -> Option<String> {
+        let name = self.m_names.remove(key)?;
+        self.m_keys.remove(&name);
+        Some(name)
+    }
+
+    /// Returns the name of `key`.
+    pub fn name(&self, key: &IdoKeyT) -> Option<&str> {
+        self.m_names.get(key).map(String::as_str)
+    }
+
+    /// Returns the key with the given name.
+    pub fn key(&self, name: &str) -> Option<IdoKeyT> {
+        self.m_keys.get(name).copied()
+    }
+
+    /// Returns the number of named keys.
+    pub fn len(&self) -> usize {
+        self.m_names.len()
+    }
+
+    /// Returns `true` if no key is named.
+    pub fn is_empty(&self) -> bool {
+        self.m_names.is_empty()
+    }
+
+    /// Iterates the entries in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (IdoKeyT, &str)> + '_ {
+        self.m_names.iter().map(|(key, name)| (*key, name.as_str()))
+    }
+}
+
+impl<S: Into<String>> FromIterator<(IdoKeyT, S)> for IdoDictionary {
+    fn from_iter<I: IntoIterator<Item = (IdoKeyT, S)>>(iter: I) -> Self {
+        let mut dictionary = IdoDictionary::new();
+        for (key, name) in iter {
+            dictionary.insert(key, name);
+        }
+        dictionary
+    }
+}
+
+/// Displays an Ido with keys replaced by their names; see [`Ido::with_names`].
+pub struct NamedIdo<'a> {
+    m_ido: &'a Ido,
+    m_names: &'a IdoDictionary,
+}
+
+impl NamedIdo<'_> {
+    /// Describes how `other` differs from the named Ido like
+    /// [`Ido::diff_report`], with every key found in the dictionary written
+    /// as its name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    /// use ido::dictionary::IdoDictionary;
+    ///
+    /// let names: IdoDictionary = [(1, "LEGS"), (10, "PRICE")].into_iter().collect();
+    ///
+    /// let mut leg = Ido::new();
+    /// leg.set_f64(&10, 1.25);
+    /// let mut before = Ido::new();
+    /// before.append_array(&1, leg.clone());
+    ///
+    /// leg.set_f64(&10, 1.5);
+    /// let mut after = Ido::new();
+    /// after.append_array(&1, leg);
+    ///
+    /// assert_eq!(before.with_names(&names).diff_report(&after), "~ LEGS[0].PRICE: 1.25 -> 1.5\n");
+    /// ```
+    pub fn diff_report(&self, other: &Ido) -> String {
+        diff::report(self.m_ido, other, Some(self.m_names))
+    }
+
+    /// Summarizes how the named Ido changed into `new` like
+    /// [`Ido::change_log`], with every key found in the dictionary written
+    /// as its name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    /// use ido::dictionary::IdoDictionary;
+    ///
+    /// let names: IdoDictionary = [(1, "PRICE")].into_iter().collect();
+    ///
+    /// let mut before = Ido::new();
+    /// before.set_f64(&1, 101.2);
+    /// let mut after = Ido::new();
+    /// after.set_f64(&1, 101.3);
+    ///
+    /// assert_eq!(before.with_names(&names).change_log(&after), "PRICE: 101.2 → 101.3\n");
+    /// ```
+    pub fn change_log(&self, new: &Ido) -> String {
+        diff::change_log(self.m_ido, new, Some(self.m_names))
+    }
+
+    /// Renders the array under `array_key` as a table like
+    /// [`Ido::to_table`], with every key found in the dictionary written as
+    /// its name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    /// use ido::dictionary::IdoDictionary;
+    ///
+    /// let names: IdoDictionary = [(1, "ORDER_ID"), (2, "QTY")].into_iter().collect();
+    ///
+    /// let mut order = Ido::new();
+    /// order.set_string(&1, "ORDER-1".to_string());
+    /// order.set_integer(&2, 100);
+    /// let mut ido = Ido::new();
+    /// ido.append_array(&5, order);
+    ///
+    /// assert_eq!(ido.with_names(&names).to_table(&5).unwrap(), "ORDER_ID  QTY\n--------  ---\nORDER-1   100\n");
+    /// ```
+    pub fn to_table(&self, array_key: &IdoKeyT) -> Option<String> {
+        Table::from_array(self.m_ido, array_key, Some(self.m_names)).map(|table| table.to_text())
+    }
+
+    /// Writes the array under `array_key` as CSV like
+    /// [`Ido::array_to_csv`], with every key found in the dictionary
+    /// written as its name in the header.
+    pub fn array_to_csv<W: Write>(&self, array_key: &IdoKeyT, writer: &mut W) -> io::Result<usize> {
+        match Table::from_array(self.m_ido, array_key, Some(self.m_names)) {
+            Some(table) => table.write_csv(writer),
+            None => Ok(0),
+        }
+    }
+
+    /// Renders the named Ido as HTML like [`Ido::to_html`], with every key
+    /// found in the dictionary written as its name.
+    pub fn to_html(&self) -> String {
+        html::render(self.m_ido, Some(self.m_names))
+    }
+
+    /// Renders the named Ido as Markdown like [`Ido::to_markdown`], with
+    /// every key found in the dictionary written as its name.
+    pub fn to_markdown(&self) -> String {
+        markdown::render(self.m_ido, Some(self.m_names))
+    }
+
+    /// Renders the named Ido as XML like [`Ido::to_xml`], with every key
+    /// found in the dictionary written as its name.
+    pub fn to_xml(&self, keys: XmlKeys) -> String {
+        let mut out = String::new();
+        xml::write_ido(&mut out, self.m_ido, keys, Some(self.m_names));
+        out
+    }
+
+    /// Renders the named Ido as YAML like [`Ido::to_yaml`], with every key
+    /// found in the dictionary written as its name.
+    pub fn to_yaml(&self) -> String {
+        let mut out = String::new();
+        yaml::write_mapping(&mut out, self.m_ido, 0, Some(self.m_names), false);
+        if out.is_empty() {
+            out.push_str("{}\n");
+        }
+        out
+    }
+
+    /// Maps the named Ido onto a protobuf `Struct` like
+    /// [`Ido::to_struct`], with every key found in the dictionary used as
+    /// the field name.
+    #[cfg(feature = "proto")]
+    pub fn to_struct(&self) -> prost_types::Struct {
+        proto::struct_from_ido(self.m_ido, Some(self.m_names))
+    }
+}
+
+impl fmt::Display for NamedIdo<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.m_ido.write_items(f, Some(self.m_names), self.m_ido.datetime_format())
+    }
+}
+
+impl Ido {
+    /// Returns a value that displays the Ido like `to_string`, but with
+    /// every key found in `names` written as its name.
+    ///
+    /// Keys without a name are written as numbers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    /// use ido::dictionary::IdoDictionary;
+    ///
+    /// let names: IdoDictionary = [(1, "ORDER_ID")].into_iter().collect();
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "ORDER-1".to_string());
+    /// ido.set_integer(&2, 100);
+    ///
+    /// assert_eq!(ido.with_names(&names).to_string(), "ORDER_ID=ORDER-1,2=100");
+    /// ```
+    pub fn with_names<'a>(&'a self, names: &'a IdoDictionary) -> NamedIdo<'a> {
+        NamedIdo { m_ido: self, m_names: names }
+    }
+}
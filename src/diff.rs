@@ -0,0 +1,217 @@
+//! Field-by-field reports of how two Idos differ.
+//!
+//! A report has one line per difference, in key order:
+//!
+//! ```text
+//! - QTY: 10
+//! ~ SIDE: "BUY" -> "SELL"
+//! ~ LEGS[1].PRICE: 1.25 -> 1.5
+//! + LEGS[2]: [SYMBOL="GBPUSD",PRICE=1.27]
+//! ```
+//!
+//! `-` marks a field only present in the first Ido, `+` one only present in
+//! the second and `~` a field whose value changed. Strings are quoted so a
+//! string `"10"` is distinguishable from an integer `10`, and the type is
+//! spelled out when a value changes type without changing its text.
+//! Arrays are compared element by element.
+//!
+//! [`Ido::change_log`] builds on the same comparison for audit logs, with
+//! one `field: before → after` line per difference and strings unquoted:
+//!
+//! ```text
+//! QTY: 10 → (removed)
+//! SIDE: BUY → SELL
+//! LEGS[1].PRICE: 1.25 → 1.5
+//! LEGS[2]: (none) → [SYMBOL=GBPUSD,PRICE=1.27]
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use crate::dictionary::IdoDictionary;
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Style {
+    Report,
+    ChangeLog,
+}
+
+struct Report<'a> {
+    m_names: Option<&'a IdoDictionary>,
+    m_style: Style,
+    m_lines: String,
+}
+
+impl Report<'_> {
+    fn field(&self, path: &str, key: &IdoKeyT) -> String {
+        let name = match self.m_names.and_then(|names| names.name(key)) {
+            Some(name) => name.to_string(),
+            None => key.to_string(),
+        };
+        if path.is_empty() {
+            name
+        } else {
+            format!("{}.{}", path, name)
+        }
+    }
+
+    fn value(&self, ido: &Ido, item: &IdoItem) -> String {
+        match item.m_type {
+            IdoItemType::STRING if self.m_style == Style::Report => format!("{:?}", item.m_string),
+            IdoItemType::ARRAY => item.m_array.iter().map(|element| self.element(element)).collect(),
+            _ => item.as_string_with(ido.datetime_format()).unwrap_or_default(),
+        }
+    }
+
+    fn element(&self, ido: &Ido) -> String {
+        let mut fields = Vec::new();
+        for (key, item) in ido.live_items() {
+            fields.push(format!("{}={}", self.field("", &key), self.value(ido, item)));
+        }
+        format!("[{}]", fields.join(","))
+    }
+
+    fn compare(&mut self, path: &str, before: &Ido, after: &Ido) {
+        let keys: BTreeSet<_> = before.ordered_items().chain(after.ordered_items()).map(|(key, _)| key).collect();
+
+        for key in keys {
+            let field = self.field(path, &key);
+            match (before.live_item(&key), after.live_item(&key)) {
+                (Some(old), Some(new)) => self.compare_items(&field, (before, old), (after, new)),
+                (Some(old), None) => self.removed(&field, self.value(before, old)),
+                (None, Some(new)) => self.added(&field, self.value(after, new)),
+                (None, None) => {}
+            }
+        }
+    }
+
+    fn compare_items(&mut self, field: &str, (before, old): (&Ido, &IdoItem), (after, new): (&Ido, &IdoItem)) {
+        if old.m_type == IdoItemType::ARRAY && new.m_type == IdoItemType::ARRAY {
+            let length = old.m_array.len().max(new.m_array.len());
+            for index in 0..length {
+                let path = format!("{}[{}]", field, index);
+                match (old.m_array.get(index), new.m_array.get(index)) {
+                    (Some(old), Some(new)) => self.compare(&path, old, new),
+                    (Some(old), None) => self.removed(&path, self.element(old)),
+                    (None, Some(new)) => self.added(&path, self.element(new)),
+                    (None, None) => {}
+                }
+            }
+            return;
+        }
+
+        let both_nan = old.m_type == IdoItemType::FLOAT
+            && new.m_type == IdoItemType::FLOAT
+            && old.m_float.is_nan()
+            && new.m_float.is_nan();
+        if old.m_type == new.m_type && (old == new || both_nan) {
+            return;
+        }
+
+        let (mut old_value, mut new_value) = (self.value(before, old), self.value(after, new));
+        if old_value == new_value {
+            old_value = format!("{} ({:?})", old_value, old.m_type);
+            new_value = format!("{} ({:?})", new_value, new.m_type);
+        }
+        let _ = match self.m_style {
+            Style::Report => writeln!(self.m_lines, "~ {}: {} -> {}", field, old_value, new_value),
+            Style::ChangeLog => writeln!(self.m_lines, "{}: {} → {}", field, old_value, new_value),
+        };
+    }
+
+    fn removed(&mut self, field: &str, value: String) {
+        let _ = match self.m_style {
+            Style::Report => writeln!(self.m_lines, "- {}: {}", field, value),
+            Style::ChangeLog => writeln!(self.m_lines, "{}: {} → (removed)", field, value),
+        };
+    }
+
+    fn added(&mut self, field: &str, value: String) {
+        let _ = match self.m_style {
+            Style::Report => writeln!(self.m_lines, "+ {}: {}", field, value),
+            Style::ChangeLog => writeln!(self.m_lines, "{}: (none) → {}", field, value),
+        };
+    }
+}
+
+/// Builds the report of how `after` differs from `before`.
+pub(crate) fn report(before: &Ido, after: &Ido, names: Option<&IdoDictionary>) -> String {
+    let mut report = Report { m_names: names, m_style: Style::Report, m_lines: String::new() };
+    report.compare("", before, after);
+    report.m_lines
+}
+
+/// Builds the change log from `before` to `after`.
+pub(crate) fn change_log(before: &Ido, after: &Ido, names: Option<&IdoDictionary>) -> String {
+    let mut report = Report { m_names: names, m_style: Style::ChangeLog, m_lines: String::new() };
+    report.compare("", before, after);
+    report.m_lines
+}
+
+impl Ido {
+    /// Describes how `other` differs from this Ido, one line per added,
+    /// removed or changed field, recursing into arrays.
+    ///
+    /// Keys are written as numbers, or as names if a dictionary is attached
+    /// with [`set_names`](Ido::set_names); use
+    /// [`NamedIdo::diff_report`](crate::dictionary::NamedIdo::diff_report)
+    /// to name them for a single call. See the [`diff`](crate::diff) module for the
+    /// format.
+    ///
+    /// # Returns
+    ///
+    /// An empty string if both Idos hold the same fields and values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut before = Ido::new();
+    /// before.set_string(&1, "BUY".to_string());
+    /// before.set_integer(&2, 10);
+    ///
+    /// let mut after = Ido::new();
+    /// after.set_string(&1, "SELL".to_string());
+    /// after.set_f64(&3, 101.25);
+    ///
+    /// assert_eq!(before.diff_report(&after), "~ 1: \"BUY\" -> \"SELL\"\n- 2: 10\n+ 3: 101.25\n");
+    /// assert_eq!(before.diff_report(&before), "");
+    /// ```
+    pub fn diff_report(&self, other: &Ido) -> String {
+        report(self, other, self.names())
+    }
+
+    /// Summarizes how this Ido changed into `new` for audit logs, one
+    /// `field: before → after` line per added, removed or changed field.
+    ///
+    /// Uses the same comparison as [`Ido::diff_report`], but writes strings
+    /// unquoted and marks missing values as `(none)` and `(removed)`; see
+    /// the [`diff`](crate::diff) module. Use
+    /// [`NamedIdo::change_log`](crate::dictionary::NamedIdo::change_log) to
+    /// write keys as names.
+    ///
+    /// # Returns
+    ///
+    /// An empty string if nothing changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut before = Ido::new();
+    /// before.set_f64(&1, 101.2);
+    /// before.set_string(&2, "NEW".to_string());
+    ///
+    /// let mut after = before.clone();
+    /// after.set_f64(&1, 101.3);
+    /// after.delete_item(&2);
+    ///
+    /// assert_eq!(before.change_log(&after), "1: 101.2 → 101.3\n2: NEW → (removed)\n");
+    /// ```
+    pub fn change_log(&self, new: &Ido) -> String {
+        change_log(self, new, self.names())
+    }
+}
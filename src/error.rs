@@ -0,0 +1,93 @@
+use std::fmt;
+
+use crate::{IdoItemType, IdoKeyT};
+
+/// Errors raised by fallible operations on an [`Ido`](crate::Ido).
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdoError {
+    /// `begin` was called while a transaction was already open.
+    TransactionActive,
+    /// `commit` or `rollback` was called without an open transaction.
+    NoTransaction,
+    /// `set_if_version` found a different version than expected; 0 means the
+    /// key did not exist.
+    VersionMismatch { key: IdoKeyT, expected: u64, actual: u64 },
+    /// Strict mode rejected storing a value of type `actual` under a key
+    /// holding `expected`.
+    TypeMismatch { key: IdoKeyT, expected: IdoItemType, actual: IdoItemType },
+    /// The float policy rejected a NaN or infinite value.
+    NonFinite { key: IdoKeyT, value: f64 },
+    /// Appending under `key` would nest Idos `depth` levels deep, beyond
+    /// the configured maximum.
+    DepthExceeded { key: IdoKeyT, depth: usize, max: usize },
+    /// Appending under `key` would leave an array of `len` elements, beyond
+    /// the configured maximum.
+    ArrayTooLong { key: IdoKeyT, len: usize, max: usize },
+    /// The overflow policy rejected narrowing the INTEGER `value` under
+    /// `key` to the `target` type.
+    OutOfRange { key: IdoKeyT, value: i64, target: &'static str },
+    /// The capacity policy refused a new key because the Ido already holds
+    /// `max` items.
+    CapacityExceeded { key: IdoKeyT, max: usize },
+    /// Storing under `key` would leave the Ido holding about `bytes` bytes,
+    /// beyond the configured maximum.
+    MemoryExceeded { key: IdoKeyT, bytes: usize, max: usize },
+    /// The key ranges reserve `key` for `owner`, not for the owner writing
+    /// the Ido.
+    ForeignKey { key: IdoKeyT, owner: String },
+}
+
+impl fmt::Display for IdoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdoError::TransactionActive => write!(f, "a transaction is already active"),
+            IdoError::NoTransaction => write!(f, "no transaction is active"),
+            IdoError::VersionMismatch { key, expected, actual } => {
+                write!(f, "key {} is at version {}, expected {}", key, actual, expected)
+            }
+            IdoError::TypeMismatch { key, expected, actual } => {
+                write!(f, "key {} holds {:?} and cannot be set to {:?}", key, expected, actual)
+            }
+            IdoError::NonFinite { key, value } => write!(f, "key {} cannot be set to {}", key, value),
+            IdoError::DepthExceeded { key, depth, max } => {
+                write!(f, "key {} would nest {} levels deep, limit is {}", key, depth, max)
+            }
+            IdoError::ArrayTooLong { key, len, max } => {
+                write!(f, "key {} would hold an array of {} elements, limit is {}", key, len, max)
+            }
+            IdoError::OutOfRange { key, value, target } => {
+                write!(f, "key {} holds {}, which does not fit in {}", key, value, target)
+            }
+            IdoError::CapacityExceeded { key, max } => {
+                write!(f, "key {} cannot be added, the Ido already holds {} items", key, max)
+            }
+            IdoError::MemoryExceeded { key, bytes, max } => {
+                write!(f, "key {} would grow the Ido to {} bytes, limit is {}", key, bytes, max)
+            }
+            IdoError::ForeignKey { key, owner } => write!(f, "key {} is reserved for {}", key, owner),
+        }
+    }
+}
+
+impl std::error::Error for IdoError {}
+
+/// Returned by [`Ido::require`](crate::Ido::require), listing every required
+/// key that was absent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingKeys(pub Vec<IdoKeyT>);
+
+impl MissingKeys {
+    /// Returns the missing keys in the order they were requested.
+    pub fn keys(&self) -> &[IdoKeyT] {
+        &self.0
+    }
+}
+
+impl fmt::Display for MissingKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<String> = self.0.iter().map(|key| key.to_string()).collect();
+        write!(f, "missing required keys: {}", keys.join(", "))
+    }
+}
+
+impl std::error::Error for MissingKeys {}
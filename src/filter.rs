@@ -0,0 +1,290 @@
+//! Filter expressions evaluated against Idos at runtime.
+//!
+//! An [`IdoFilter`] is parsed once from text, for instance from a routing
+//! configuration, and can then be matched against any number of Idos.
+//!
+//! ```text
+//! expr       := and ("||" and)*
+//! and        := unary ("&&" unary)*
+//! unary      := "!" unary | "(" expr ")" | key | comparison
+//! comparison := key ("==" | "!=" | "<" | "<=" | ">" | ">=") literal
+//! literal    := integer | float | 'string' | "string"
+//! ```
+//!
+//! A key on its own is true when the Ido holds it. Comparisons follow the
+//! ordering of items, so an integer key can be compared with a float
+//! literal. A comparison is false when the key is missing or its value
+//! cannot be compared with the literal, whatever the operator; use
+//! `!(key == literal)` to also match Idos without the key.
+//!
+//! Strings are quoted with `'` or `"`, and `\` escapes the next character.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+/// Errors raised while parsing a filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterError {
+    /// The expression ended where more was expected.
+    UnexpectedEnd,
+    /// The token at this byte offset is not valid here.
+    UnexpectedToken(usize),
+    /// The key or literal at this byte offset cannot be parsed.
+    InvalidLiteral(usize),
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterError::UnexpectedEnd => write!(f, "unexpected end of filter"),
+            FilterError::UnexpectedToken(offset) => write!(f, "unexpected token at byte {}", offset),
+            FilterError::InvalidLiteral(offset) => write!(f, "invalid literal at byte {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn test(self, ordering: Ordering) -> bool {
+        match self {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Ne => ordering != Ordering::Equal,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Node {
+    Or(Box<Node>, Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Exists(IdoKeyT),
+    Compare(IdoKeyT, Op, IdoItem),
+}
+
+impl Node {
+    fn matches(&self, ido: &Ido) -> bool {
+        match self {
+            Node::Or(left, right) => left.matches(ido) || right.matches(ido),
+            Node::And(left, right) => left.matches(ido) && right.matches(ido),
+            Node::Not(node) => !node.matches(ido),
+            Node::Exists(key) => ido.live_item(key).is_some(),
+            Node::Compare(key, op, literal) => ido
+                .live_item(key)
+                .and_then(|item| item.partial_cmp(literal))
+                .is_some_and(|ordering| op.test(ordering)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(String),
+    Text(String),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, FilterError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((offset, c)) = chars.next() {
+        let mut next_is = |expected: char| chars.next_if(|(_, c)| *c == expected).is_some();
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '&' if next_is('&') => Token::And,
+            '|' if next_is('|') => Token::Or,
+            '=' if next_is('=') => Token::Op(Op::Eq),
+            '!' if next_is('=') => Token::Op(Op::Ne),
+            '!' => Token::Not,
+            '<' if next_is('=') => Token::Op(Op::Le),
+            '<' => Token::Op(Op::Lt),
+            '>' if next_is('=') => Token::Op(Op::Ge),
+            '>' => Token::Op(Op::Gt),
+            '\'' | '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '\\')) => value.push(chars.next().ok_or(FilterError::UnexpectedEnd)?.1),
+                        Some((_, end)) if end == c => break,
+                        Some((_, other)) => value.push(other),
+                        None => return Err(FilterError::UnexpectedEnd),
+                    }
+                }
+                Token::Text(value)
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' => {
+                let mut value = c.to_string();
+                while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-')) {
+                    value.push(c);
+                }
+                Token::Number(value)
+            }
+            _ => return Err(FilterError::UnexpectedToken(offset)),
+        };
+        tokens.push((offset, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    m_tokens: Vec<(usize, Token)>,
+    m_pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.m_tokens.get(self.m_pos).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Result<(usize, Token), FilterError> {
+        let token = self.m_tokens.get(self.m_pos).cloned().ok_or(FilterError::UnexpectedEnd)?;
+        self.m_pos += 1;
+        Ok(token)
+    }
+
+    fn or(&mut self) -> Result<Node, FilterError> {
+        let mut node = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.m_pos += 1;
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node, FilterError> {
+        let mut node = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.m_pos += 1;
+            node = Node::And(Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node, FilterError> {
+        match self.next()? {
+            (_, Token::Not) => Ok(Node::Not(Box::new(self.unary()?))),
+            (_, Token::Open) => {
+                let node = self.or()?;
+                match self.next()? {
+                    (_, Token::Close) => Ok(node),
+                    (offset, _) => Err(FilterError::UnexpectedToken(offset)),
+                }
+            }
+            (offset, Token::Number(key)) => {
+                let key = key.parse::<IdoKeyT>().map_err(|_| FilterError::InvalidLiteral(offset))?;
+                let Some(Token::Op(op)) = self.peek().cloned() else {
+                    return Ok(Node::Exists(key));
+                };
+                self.m_pos += 1;
+                Ok(Node::Compare(key, op, self.literal()?))
+            }
+            (offset, _) => Err(FilterError::UnexpectedToken(offset)),
+        }
+    }
+
+    fn literal(&mut self) -> Result<IdoItem, FilterError> {
+        let mut item = IdoItem::new();
+        match self.next()? {
+            (_, Token::Text(value)) => {
+                item.m_type = IdoItemType::STRING;
+                item.m_string = value;
+            }
+            (offset, Token::Number(value)) => {
+                if let Ok(integer) = value.parse::<i64>() {
+                    item.m_type = IdoItemType::INTEGER;
+                    item.m_integer = integer;
+                } else if let Ok(integer) = value.parse::<i128>() {
+                    item.m_type = IdoItemType::INTEGER128;
+                    item.m_integer128 = integer;
+                } else {
+                    item.m_type = IdoItemType::FLOAT;
+                    item.m_float = value.parse().map_err(|_| FilterError::InvalidLiteral(offset))?;
+                }
+            }
+            (offset, _) => return Err(FilterError::UnexpectedToken(offset)),
+        }
+        Ok(item)
+    }
+}
+
+/// A parsed filter expression.
+///
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+/// use ido::filter::IdoFilter;
+///
+/// let filter = IdoFilter::parse("1 == 'NYSE' && (7 >= 100 || !8)").unwrap();
+///
+/// let mut order = Ido::new();
+/// order.set_string(&1, "NYSE".to_string());
+/// order.set_integer(&7, 250);
+/// assert!(filter.matches(&order));
+///
+/// order.set_integer(&7, 50);
+/// assert!(filter.matches(&order));
+///
+/// order.set_integer(&8, 1);
+/// assert!(!filter.matches(&order));
+/// ```
+#[derive(Clone)]
+pub struct IdoFilter {
+    m_root: Node,
+}
+
+impl IdoFilter {
+    /// Parses a filter expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FilterError` describing where the expression is malformed.
+    pub fn parse(text: &str) -> Result<IdoFilter, FilterError> {
+        let mut parser = Parser { m_tokens: tokenize(text)?, m_pos: 0 };
+        let root = parser.or()?;
+
+        match parser.m_tokens.get(parser.m_pos) {
+            None => Ok(IdoFilter { m_root: root }),
+            Some((offset, _)) => Err(FilterError::UnexpectedToken(*offset)),
+        }
+    }
+
+    /// Returns `true` if `ido` satisfies the filter.
+    pub fn matches(&self, ido: &Ido) -> bool {
+        self.m_root.matches(ido)
+    }
+}
+
+impl FromStr for IdoFilter {
+    type Err = FilterError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        IdoFilter::parse(text)
+    }
+}
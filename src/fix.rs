@@ -0,0 +1,316 @@
+//! Converting between FIX tag=value messages and Idos.
+//!
+//! Every field of a message becomes an item keyed by its tag. FIX carries
+//! no type information, so decoded values are STRING items. Repeating
+//! groups become an ARRAY item under their NumInGroup tag with one element
+//! per group instance; which tags form a group is described by a
+//! [`FixSpec`], as the wire format alone does not say where a group ends.
+//!
+//! BeginString (8) is kept as an item, while BodyLength (9) and CheckSum
+//! (10) are checked when decoding and dropped, then recomputed when
+//! encoding.
+//!
+//! ```
+//! use ido::fix::{self, FixSpec};
+//!
+//! let spec = FixSpec::new().group(453, &[448, 447, 452]);
+//! let message = b"8=FIX.4.4\x019=37\x0135=D\x01453=2\x01448=AB\x01452=1\x01448=CD\x01452=3\x0110=212\x01";
+//!
+//! let ido = fix::decode(message, &spec).unwrap();
+//! assert_eq!(ido.get_string(&35), Some("D".to_string()));
+//!
+//! let parties = ido.get_item(&453).unwrap().m_array;
+//! assert_eq!(parties.len(), 2);
+//! assert_eq!(parties[1].get_string(&448), Some("CD".to_string()));
+//! assert_eq!(fix::encode(&ido).unwrap(), message);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+/// The field separator of the tag=value encoding.
+pub const SOH: u8 = 0x01;
+
+const BEGIN_STRING: IdoKeyT = 8;
+const BODY_LENGTH: IdoKeyT = 9;
+const CHECK_SUM: IdoKeyT = 10;
+
+/// Errors raised while converting FIX messages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FixError {
+    /// The field starting at this byte offset is not `tag=value`.
+    InvalidField(usize),
+    /// BeginString, BodyLength or CheckSum is missing or out of place.
+    MissingHeader(IdoKeyT),
+    /// BodyLength does not match the number of bytes in the body.
+    BodyLength { declared: usize, actual: usize },
+    /// CheckSum does not match the bytes of the message.
+    Checksum { declared: u8, actual: u8 },
+    /// The repeating group under this tag has a bad count or fewer
+    /// instances than its count.
+    InvalidGroup(IdoKeyT),
+    /// The item under this key cannot be written as a FIX field.
+    InvalidValue(IdoKeyT),
+}
+
+impl fmt::Display for FixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixError::InvalidField(offset) => write!(f, "invalid field at byte {}", offset),
+            FixError::MissingHeader(tag) => write!(f, "tag {} is missing or out of place", tag),
+            FixError::BodyLength { declared, actual } => {
+                write!(f, "body length is {} but {} was declared", actual, declared)
+            }
+            FixError::Checksum { declared, actual } => {
+                write!(f, "checksum is {:03} but {:03} was declared", actual, declared)
+            }
+            FixError::InvalidGroup(tag) => write!(f, "invalid repeating group {}", tag),
+            FixError::InvalidValue(key) => write!(f, "key {} cannot be written as a fix field", key),
+        }
+    }
+}
+
+impl std::error::Error for FixError {}
+
+/// Describes the repeating groups of a FIX dialect.
+///
+/// # Example
+///
+/// ```
+/// use ido::fix::FixSpec;
+///
+/// // NoPartyIDs with PartyID as the delimiter and a nested NoPartySubIDs.
+/// let spec = FixSpec::new()
+///     .group(453, &[448, 447, 452, 802])
+///     .group(802, &[523, 803]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FixSpec {
+    m_groups: HashMap<IdoKeyT, Vec<IdoKeyT>>,
+}
+
+impl FixSpec {
+    /// Creates a spec without repeating groups.
+    pub fn new() -> Self {
+        FixSpec::default()
+    }
+
+    /// Declares a repeating group counted by `count_tag`.
+    ///
+    /// `members` lists the tags an instance may contain. The first one is
+    /// the delimiter that starts every instance. A member may itself be the
+    /// count tag of a nested group.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `members` is empty.
+    pub fn group(mut self, count_tag: IdoKeyT, members: &[IdoKeyT]) -> Self {
+        assert!(!members.is_empty(), "group {} needs a delimiter tag", count_tag);
+        self.m_groups.insert(count_tag, members.to_vec());
+        self
+    }
+}
+
+struct Field<'a> {
+    m_offset: usize,
+    m_tag: IdoKeyT,
+    m_value: &'a str,
+}
+
+fn split_fields(message: &[u8]) -> Result<Vec<Field<'_>>, FixError> {
+    let mut fields = Vec::new();
+    let mut offset = 0;
+
+    while offset < message.len() {
+        let end = message[offset..]
+            .iter()
+            .position(|byte| *byte == SOH)
+            .map(|len| offset + len)
+            .ok_or(FixError::InvalidField(offset))?;
+        let field = std::str::from_utf8(&message[offset..end]).map_err(|_| FixError::InvalidField(offset))?;
+        let (tag, value) = field.split_once('=').ok_or(FixError::InvalidField(offset))?;
+        let tag = tag
+            .parse::<IdoKeyT>()
+            .ok()
+            .filter(|tag| *tag > 0)
+            .ok_or(FixError::InvalidField(offset))?;
+
+        fields.push(Field { m_offset: offset, m_tag: tag, m_value: value });
+        offset = end + 1;
+    }
+    Ok(fields)
+}
+
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+}
+
+fn string_item(value: &str) -> IdoItem {
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::STRING;
+    item.m_string = value.to_string();
+    item
+}
+
+fn read_group(fields: &[Field<'_>], pos: &mut usize, spec: &FixSpec, count: &Field<'_>) -> Result<IdoItem, FixError> {
+    let count_tag = count.m_tag;
+    let members = &spec.m_groups[&count_tag];
+    let count = count
+        .m_value
+        .parse::<usize>()
+        .map_err(|_| FixError::InvalidGroup(count_tag))?;
+
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::ARRAY;
+
+    for _ in 0..count {
+        if fields.get(*pos).map(|field| field.m_tag) != Some(members[0]) {
+            return Err(FixError::InvalidGroup(count_tag));
+        }
+
+        let mut element = Ido::new();
+        while let Some(field) = fields.get(*pos) {
+            // A repeated tag, including the delimiter, starts the next instance.
+            if !members.contains(&field.m_tag) || element.contains(&field.m_tag) {
+                break;
+            }
+            *pos += 1;
+            read_field(fields, pos, spec, &mut element, field)?;
+        }
+        item.m_array.push(element);
+    }
+    Ok(item)
+}
+
+fn read_field(
+    fields: &[Field<'_>],
+    pos: &mut usize,
+    spec: &FixSpec,
+    ido: &mut Ido,
+    field: &Field<'_>,
+) -> Result<(), FixError> {
+    let item = if spec.m_groups.contains_key(&field.m_tag) {
+        read_group(fields, pos, spec, field)?
+    } else {
+        string_item(field.m_value)
+    };
+    ido.set_item(&field.m_tag, item);
+    Ok(())
+}
+
+/// Decodes a complete FIX message into an Ido.
+///
+/// The message must start with BeginString and BodyLength, end with
+/// CheckSum and terminate every field with [`SOH`].
+///
+/// # Errors
+///
+/// Returns a `FixError` if a field is malformed, the header or trailer is
+/// missing, BodyLength or CheckSum do not match, or a repeating group has
+/// fewer instances than its count.
+pub fn decode(message: &[u8], spec: &FixSpec) -> Result<Ido, FixError> {
+    let fields = split_fields(message)?;
+
+    for (index, tag) in [(0, BEGIN_STRING), (1, BODY_LENGTH)] {
+        if fields.get(index).map(|field| field.m_tag) != Some(tag) {
+            return Err(FixError::MissingHeader(tag));
+        }
+    }
+    let trailer = fields
+        .last()
+        .filter(|field| fields.len() > 2 && field.m_tag == CHECK_SUM)
+        .ok_or(FixError::MissingHeader(CHECK_SUM))?;
+
+    let body = &message[fields[2].m_offset.min(trailer.m_offset)..trailer.m_offset];
+    let declared = fields[1].m_value.parse::<usize>().map_err(|_| FixError::InvalidField(fields[1].m_offset))?;
+    if declared != body.len() {
+        return Err(FixError::BodyLength { declared, actual: body.len() });
+    }
+
+    let declared = trailer.m_value.parse::<u8>().map_err(|_| FixError::InvalidField(trailer.m_offset))?;
+    let actual = checksum(&message[..trailer.m_offset]);
+    if declared != actual {
+        return Err(FixError::Checksum { declared, actual });
+    }
+
+    let mut ido = Ido::new();
+    ido.set_item(&BEGIN_STRING, string_item(fields[0].m_value));
+
+    let body_fields = &fields[..fields.len() - 1];
+    let mut pos = 2;
+    while let Some(field) = body_fields.get(pos) {
+        pos += 1;
+        read_field(body_fields, &mut pos, spec, &mut ido, field)?;
+    }
+    Ok(ido)
+}
+
+fn write_field(out: &mut Vec<u8>, tag: IdoKeyT, value: &str) -> Result<(), FixError> {
+    if tag <= 0 || value.as_bytes().contains(&SOH) {
+        return Err(FixError::InvalidValue(tag));
+    }
+    out.extend_from_slice(format!("{}={}", tag, value).as_bytes());
+    out.push(SOH);
+    Ok(())
+}
+
+fn write_body(out: &mut Vec<u8>, ido: &Ido) -> Result<(), FixError> {
+    for (key, item) in ido.live_items() {
+        if matches!(key, BEGIN_STRING | BODY_LENGTH | CHECK_SUM) {
+            continue;
+        }
+
+        match item.m_type {
+            IdoItemType::STRING => write_field(out, key, &item.m_string)?,
+            IdoItemType::INTEGER => write_field(out, key, &item.m_integer.to_string())?,
+            IdoItemType::INTEGER128 => write_field(out, key, &item.m_integer128.to_string())?,
+            IdoItemType::BOOL => write_field(out, key, if item.m_bool { "Y" } else { "N" })?,
+            IdoItemType::FLOAT => write_field(out, key, &item.m_float.to_string())?,
+            #[cfg(feature = "chrono")]
+            IdoItemType::DATETIME => {
+                write_field(out, key, &item.m_datetime.format("%Y%m%d-%H:%M:%S%.3f").to_string())?
+            }
+            IdoItemType::ARRAY => {
+                write_field(out, key, &item.m_array.len().to_string())?;
+                for element in &item.m_array {
+                    write_body(out, element)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Encodes an Ido as a FIX message.
+///
+/// The Ido must hold BeginString (8) as a STRING. The remaining items are
+/// written in insertion order, so MsgType (35) should be inserted first.
+/// ARRAY items become repeating groups, integer and FLOAT items are written
+/// in decimal and DATETIME items as UTCTimestamp with milliseconds.
+/// BodyLength and CheckSum are computed; any stored values for them are
+/// ignored.
+///
+/// # Errors
+///
+/// Returns `FixError::MissingHeader(8)` without a BeginString and
+/// `FixError::InvalidValue` for a key that is not a positive tag or a
+/// value containing [`SOH`].
+pub fn encode(ido: &Ido) -> Result<Vec<u8>, FixError> {
+    let begin_string = ido
+        .get_item(&BEGIN_STRING)
+        .filter(|item| item.m_type == IdoItemType::STRING)
+        .ok_or(FixError::MissingHeader(BEGIN_STRING))?;
+
+    let mut body = Vec::new();
+    write_body(&mut body, ido)?;
+
+    let mut out = Vec::with_capacity(body.len() + 32);
+    write_field(&mut out, BEGIN_STRING, &begin_string.m_string)?;
+    write_field(&mut out, BODY_LENGTH, &body.len().to_string())?;
+    out.extend_from_slice(&body);
+    let sum = checksum(&out);
+    write_field(&mut out, CHECK_SUM, &format!("{:03}", sum))?;
+    Ok(out)
+}
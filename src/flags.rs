@@ -0,0 +1,109 @@
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitOrAssign, Not};
+
+/// A set of metadata flags attached to an item.
+///
+/// Flags describe how an item should be treated by generic processing, such
+/// as redacting sensitive fields before logging. They are not part of the
+/// item's value and are not written by the codecs.
+///
+/// # Example
+///
+/// ```
+/// use ido::IdoFlags;
+///
+/// let flags = IdoFlags::SENSITIVE | IdoFlags::MANDATORY;
+///
+/// assert!(flags.contains(IdoFlags::SENSITIVE));
+/// assert!(!flags.contains(IdoFlags::DERIVED));
+/// ```
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IdoFlags(u32);
+
+impl IdoFlags {
+    /// The item holds data that must not be logged or displayed.
+    pub const SENSITIVE: IdoFlags = IdoFlags(1);
+    /// The item is calculated from other items rather than supplied.
+    pub const DERIVED: IdoFlags = IdoFlags(1 << 1);
+    /// The item must be present for the Ido to be complete.
+    pub const MANDATORY: IdoFlags = IdoFlags(1 << 2);
+
+    /// Returns a set with no flags.
+    pub const fn empty() -> Self {
+        IdoFlags(0)
+    }
+
+    /// Builds a set from raw bits, for applications defining their own flags.
+    pub const fn from_bits(bits: u32) -> Self {
+        IdoFlags(bits)
+    }
+
+    /// Returns the raw bits of the set.
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns `true` if no flag is set.
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if every flag in `other` is also set in `self`.
+    pub const fn contains(&self, other: IdoFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for IdoFlags {
+    type Output = IdoFlags;
+
+    fn bitor(self, rhs: IdoFlags) -> IdoFlags {
+        IdoFlags(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for IdoFlags {
+    fn bitor_assign(&mut self, rhs: IdoFlags) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for IdoFlags {
+    type Output = IdoFlags;
+
+    fn bitand(self, rhs: IdoFlags) -> IdoFlags {
+        IdoFlags(self.0 & rhs.0)
+    }
+}
+
+impl Not for IdoFlags {
+    type Output = IdoFlags;
+
+    fn not(self) -> IdoFlags {
+        IdoFlags(!self.0)
+    }
+}
+
+impl fmt::Debug for IdoFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const NAMES: [(IdoFlags, &str); 3] = [
+            (IdoFlags::SENSITIVE, "SENSITIVE"),
+            (IdoFlags::DERIVED, "DERIVED"),
+            (IdoFlags::MANDATORY, "MANDATORY"),
+        ];
+
+        let mut rest = *self;
+        let mut names = Vec::new();
+        for (flag, name) in NAMES {
+            if self.contains(flag) {
+                names.push(name.to_string());
+                rest = rest & !flag;
+            }
+        }
+        if !rest.is_empty() {
+            names.push(format!("{:#x}", rest.0));
+        }
+
+        write!(f, "IdoFlags({})", names.join(" | "))
+    }
+}
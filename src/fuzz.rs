@@ -0,0 +1,75 @@
+//! Random Idos for fuzzing, generated with `arbitrary`.
+//!
+//! Generated Idos hold every item type, including NaN and infinite floats,
+//! and nest arrays up to [`MAX_DEPTH`] levels deep. Datetimes stay within
+//! the range of `i64` nanoseconds since the epoch, like real timestamps.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use chrono::DateTime;
+
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+/// How many levels of arrays a generated Ido can nest.
+const MAX_DEPTH: usize = 3;
+
+/// The most items generated in one Ido and elements in one array.
+const MAX_LEN: usize = 16;
+
+fn ido(u: &mut Unstructured<'_>, depth: usize) -> Result<Ido> {
+    let mut ido = Ido::new();
+    let len = u.arbitrary_len::<(IdoKeyT, u8)>()?.min(MAX_LEN);
+
+    for _ in 0..len {
+        let key: IdoKeyT = u.arbitrary()?;
+        ido.set_item(&key, item(u, depth)?);
+    }
+    Ok(ido)
+}
+
+fn item(u: &mut Unstructured<'_>, depth: usize) -> Result<IdoItem> {
+    let mut item = IdoItem::new();
+    let types = if depth < MAX_DEPTH { 6 } else { 5 };
+
+    match u.choose_index(types)? {
+        0 => {
+            item.m_type = IdoItemType::STRING;
+            item.m_string = u.arbitrary()?;
+        }
+        1 => {
+            item.m_type = IdoItemType::INTEGER;
+            item.m_integer = u.arbitrary()?;
+        }
+        2 => {
+            item.m_type = IdoItemType::INTEGER128;
+            item.m_integer128 = u.arbitrary()?;
+        }
+        3 => {
+            item.m_type = IdoItemType::FLOAT;
+            item.m_float = u.arbitrary()?;
+        }
+        4 => {
+            item.m_type = IdoItemType::DATETIME;
+            item.m_datetime = DateTime::from_timestamp_nanos(u.arbitrary()?);
+        }
+        _ => {
+            item.m_type = IdoItemType::ARRAY;
+            let len = u.arbitrary_len::<u8>()?.min(MAX_LEN);
+            for _ in 0..len {
+                item.m_array.push(ido(u, depth + 1)?);
+            }
+        }
+    }
+    Ok(item)
+}
+
+impl<'a> Arbitrary<'a> for Ido {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        ido(u, 0)
+    }
+}
+
+impl<'a> Arbitrary<'a> for IdoItem {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        item(u, 0)
+    }
+}
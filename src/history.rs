@@ -0,0 +1,131 @@
+//! Keeping the last versions of an Ido.
+//!
+//! [`IdoHistory`] is a ring buffer of timestamped snapshots. Once it is
+//! full, recording a snapshot drops the oldest one, so memory stays bounded
+//! while questions like "what did this order look like two seconds ago" can
+//! still be answered in process.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::Ido;
+
+/// The last `capacity` snapshots of an Ido, ordered by time.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use ido::Ido;
+/// use ido::history::IdoHistory;
+///
+/// let mut history = IdoHistory::new(2);
+/// let mut order = Ido::new();
+///
+/// for (second, quantity) in [(0, 100), (1, 60), (2, 0)] {
+///     order.set_integer(&1, quantity);
+///     history.record_at(Utc.timestamp_opt(second, 0).unwrap(), &order);
+/// }
+///
+/// assert_eq!(history.len(), 2);
+/// assert_eq!(history.nth_back(0).unwrap().get_i64(&1), Some(0));
+/// assert_eq!(history.nth_back(1).unwrap().get_i64(&1), Some(60));
+///
+/// let then = Utc.timestamp_opt(1, 500_000_000).unwrap();
+/// assert_eq!(history.at(then).unwrap().get_i64(&1), Some(60));
+/// assert!(history.at(Utc.timestamp_opt(0, 0).unwrap()).is_none());
+/// ```
+#[derive(Clone)]
+pub struct IdoHistory {
+    m_capacity: usize,
+    m_snapshots: VecDeque<(DateTime<Utc>, Ido)>,
+}
+
+impl IdoHistory {
+    /// Creates an empty history.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of snapshots to keep.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "history capacity must be at least 1");
+        IdoHistory { m_capacity: capacity, m_snapshots: VecDeque::with_capacity(capacity) }
+    }
+
+    /// Records a copy of `ido` taken now.
+    pub fn record(&mut self, ido: &Ido) {
+        self.record_at(Utc::now(), ido);
+    }
+
+    /// Records a copy of `ido` taken at `timestamp`.
+    ///
+    /// A snapshot older than the latest one is inserted in time order. When
+    /// the history is full, the oldest snapshot is dropped, which may be the
+    /// one just recorded.
+    pub fn record_at(&mut self, timestamp: DateTime<Utc>, ido: &Ido) {
+        let index = self.m_snapshots.partition_point(|(recorded, _)| *recorded <= timestamp);
+        self.m_snapshots.insert(index, (timestamp, ido.clone()));
+        if self.m_snapshots.len() > self.m_capacity {
+            self.m_snapshots.pop_front();
+        }
+    }
+
+    /// Returns the snapshot `n` versions back; `0` is the latest.
+    pub fn nth_back(&self, n: usize) -> Option<&Ido> {
+        let index = self.m_snapshots.len().checked_sub(n + 1)?;
+        self.m_snapshots.get(index).map(|(_, ido)| ido)
+    }
+
+    /// Returns the latest snapshot.
+    pub fn latest(&self) -> Option<&Ido> {
+        self.nth_back(0)
+    }
+
+    /// Returns the state at `timestamp`, which is the latest snapshot
+    /// recorded at or before it.
+    ///
+    /// # Returns
+    ///
+    /// `None` if every kept snapshot is newer than `timestamp`.
+    pub fn at(&self, timestamp: DateTime<Utc>) -> Option<&Ido> {
+        let index = self.m_snapshots.partition_point(|(recorded, _)| *recorded <= timestamp);
+        self.m_snapshots.get(index.checked_sub(1)?).map(|(_, ido)| ido)
+    }
+
+    /// Returns the state `duration` ago, like `at`.
+    pub fn ago(&self, duration: Duration) -> Option<&Ido> {
+        let duration = TimeDelta::from_std(duration).ok()?;
+        self.at(Utc::now().checked_sub_signed(duration)?)
+    }
+
+    /// Iterates over the snapshots and their timestamps, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = (DateTime<Utc>, &Ido)> {
+        self.m_snapshots.iter().map(|(timestamp, ido)| (*timestamp, ido))
+    }
+
+    /// Returns the number of snapshots kept.
+    pub fn len(&self) -> usize {
+        self.m_snapshots.len()
+    }
+
+    /// Returns `true` if no snapshot has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.m_snapshots.is_empty()
+    }
+
+    /// Returns the maximum number of snapshots kept.
+    pub fn capacity(&self) -> usize {
+        self.m_capacity
+    }
+
+    /// Drops every snapshot.
+    pub fn clear(&mut self) {
+        self.m_snapshots.clear();
+    }
+}
@@ -0,0 +1,103 @@
+//! Rendering an Ido as HTML.
+//!
+//! An Ido becomes a two-column `<table class="ido">` with one row per item:
+//! the key in a `<th>` and the value in a `<td>`. An ARRAY value becomes a
+//! `<details>` element whose summary gives the number of elements and whose
+//! body holds one nested table per element, so large arrays stay collapsed
+//! until opened. Keys and values are HTML-escaped, and the output needs no
+//! stylesheet or script.
+//!
+//! ```text
+//! <table class="ido">
+//! <tr><th>1</th><td>ORDER-1</td></tr>
+//! <tr><th>5</th><td><details><summary>array of 1</summary>
+//! <table class="ido">
+//! <tr><th>1</th><td>EURUSD</td></tr>
+//! </table>
+//! </details></td></tr>
+//! </table>
+//! ```
+
+use std::fmt::Write;
+
+use crate::datetime::DateTimeFormat;
+use crate::dictionary::IdoDictionary;
+use crate::{Ido, IdoItemType};
+
+/// Renders `ido` as HTML, naming keys found in `names`.
+pub(crate) fn render(ido: &Ido, names: Option<&IdoDictionary>) -> String {
+    let mut out = String::new();
+    write_table(&mut out, ido, names, ido.datetime_format());
+    out
+}
+
+fn write_table(out: &mut String, ido: &Ido, names: Option<&IdoDictionary>, format: &DateTimeFormat) {
+    out.push_str("<table class=\"ido\">\n");
+    for (key, item) in ido.live_items() {
+
+        out.push_str("<tr><th>");
+        match names.and_then(|names| names.name(&key)) {
+            Some(name) => escape(out, name),
+            None => {
+                let _ = write!(out, "{}", key);
+            }
+        }
+        out.push_str("</th><td>");
+
+        if item.m_type == IdoItemType::ARRAY {
+            let _ = writeln!(out, "<details><summary>array of {}</summary>", item.m_array.len());
+            for element in &item.m_array {
+                write_table(out, element, names, format);
+            }
+            out.push_str("</details>");
+        } else if let Some(value) = item.as_string_with(format) {
+            escape(out, &value);
+        }
+        out.push_str("</td></tr>\n");
+    }
+    out.push_str("</table>\n");
+}
+
+fn escape(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
+
+impl Ido {
+    /// Renders the Ido as a nested HTML table with collapsible arrays; see
+    /// the [`html`](crate::html) module for the markup.
+    ///
+    /// Keys are written as numbers, or as names if a dictionary is attached
+    /// with [`set_names`](Ido::set_names); use
+    /// [`NamedIdo::to_html`](crate::dictionary::NamedIdo::to_html) to name
+    /// them for a single call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "<b>".to_string());
+    /// ido.set_integer(&2, 100);
+    ///
+    /// assert_eq!(
+    ///     ido.to_html(),
+    ///     "<table class=\"ido\">\n\
+    ///      <tr><th>1</th><td>&lt;b&gt;</td></tr>\n\
+    ///      <tr><th>2</th><td>100</td></tr>\n\
+    ///      </table>\n"
+    /// );
+    /// ```
+    pub fn to_html(&self) -> String {
+        render(self, self.names())
+    }
+}
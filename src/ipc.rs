@@ -0,0 +1,372 @@
+//! Passing Idos between processes on the same host.
+//!
+//! On unix the transport is a Unix domain socket bound to a file path; on
+//! Windows it is a named pipe, addressed as `\\.\pipe\<name>`. Both carry
+//! the same length-prefixed frames as the [`net`](crate::net) module, so
+//! code can move between local and TCP transports without changing how
+//! messages are read.
+
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::codec::{self, FrameDecoder};
+use crate::net::NetError;
+use crate::Ido;
+
+/// Listens for local connections on a Unix domain socket or a named pipe.
+///
+/// On unix the socket file is removed when the listener is dropped.
+pub struct IdoIpcListener {
+    m_listener: sys::Listener,
+    m_path: PathBuf,
+}
+
+impl IdoIpcListener {
+    /// Binds a listener to the socket or pipe at `path`.
+    ///
+    /// On unix, a leftover socket file from a previous run that nobody is
+    /// listening on is replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NetError::Io` of kind `AddrInUse` if another listener owns
+    /// `path`, or of kind `InvalidInput` if `path` exists but is not a
+    /// socket. Such a file is left in place.
+    pub fn bind<P: AsRef<Path>>(path: P) -> Result<Self, NetError> {
+        let path = path.as_ref();
+
+        Ok(IdoIpcListener {
+            m_listener: sys::Listener::bind(path)?,
+            m_path: path.to_path_buf(),
+        })
+    }
+
+    /// Returns the path of the socket file or pipe.
+    pub fn path(&self) -> &Path {
+        &self.m_path
+    }
+
+    /// Blocks until a peer connects.
+    pub fn accept(&self) -> Result<IdoIpcStream, NetError> {
+        Ok(IdoIpcStream::from_stream(self.m_listener.accept()?))
+    }
+}
+
+/// A bidirectional connection that sends and receives framed Idos.
+pub struct IdoIpcStream {
+    m_stream: sys::Stream,
+    m_decoder: FrameDecoder,
+    m_read_timeout: Option<Duration>,
+}
+
+impl IdoIpcStream {
+    /// Connects to the listener at `path`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    /// use ido::ipc::{IdoIpcListener, IdoIpcStream};
+    ///
+    /// #[cfg(unix)]
+    /// let path = std::env::temp_dir().join(format!("ido-doc-{}.sock", std::process::id()));
+    /// #[cfg(windows)]
+    /// let path = format!(r"\\.\pipe\ido-doc-{}", std::process::id());
+    /// let listener = IdoIpcListener::bind(&path).unwrap();
+    ///
+    /// let mut client = IdoIpcStream::connect(&path).unwrap();
+    /// let mut server = listener.accept().unwrap();
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 42);
+    /// client.send(&ido).unwrap();
+    ///
+    /// assert_eq!(server.recv().unwrap().get_i64(&1), Some(42));
+    /// ```
+    pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self, NetError> {
+        Ok(Self::from_stream(sys::connect(path.as_ref())?))
+    }
+
+    fn from_stream(stream: sys::Stream) -> Self {
+        IdoIpcStream {
+            m_stream: stream,
+            m_decoder: FrameDecoder::new(),
+            m_read_timeout: None,
+        }
+    }
+
+    /// Sets a read timeout for `recv`, which then returns an `Io` error of
+    /// kind `WouldBlock` or `TimedOut` when no frame arrives in time. The
+    /// part of a frame received before the timeout is kept for the next
+    /// `recv`.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> Result<(), NetError> {
+        sys::set_read_timeout(&self.m_stream, timeout)?;
+        self.m_read_timeout = timeout;
+        Ok(())
+    }
+
+    /// Sends `ido` to the peer.
+    pub fn send(&mut self, ido: &Ido) -> Result<(), NetError> {
+        codec::write_frame(&mut self.m_stream, ido)?;
+        self.m_stream.flush()?;
+        Ok(())
+    }
+
+    /// Blocks until the next Ido arrives from the peer.
+    ///
+    /// A peer that closes the connection surfaces as an `Io` error of kind
+    /// `UnexpectedEof`.
+    pub fn recv(&mut self) -> Result<Ido, NetError> {
+        loop {
+            if let Some(ido) = self.m_decoder.next_frame()? {
+                return Ok(ido);
+            }
+
+            sys::wait_readable(&self.m_stream, self.m_read_timeout)?;
+            if self.m_decoder.read_from(&mut self.m_stream)? == 0 {
+                return Err(NetError::Io(ErrorKind::UnexpectedEof.into()));
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::fs;
+    use std::io::{self, ErrorKind};
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    pub(super) type Stream = UnixStream;
+
+    pub(super) struct Listener {
+        m_listener: UnixListener,
+        m_path: PathBuf,
+    }
+
+    impl Listener {
+        pub(super) fn bind(path: &Path) -> io::Result<Self> {
+            match fs::symlink_metadata(path) {
+                Ok(meta) if !meta.file_type().is_socket() => {
+                    return Err(io::Error::new(ErrorKind::InvalidInput, "path exists and is not a socket"));
+                }
+                Ok(_) if UnixStream::connect(path).is_ok() => return Err(ErrorKind::AddrInUse.into()),
+                Ok(_) => fs::remove_file(path)?,
+                Err(err) if err.kind() == ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+
+            Ok(Listener {
+                m_listener: UnixListener::bind(path)?,
+                m_path: path.to_path_buf(),
+            })
+        }
+
+        pub(super) fn accept(&self) -> io::Result<Stream> {
+            let (stream, _) = self.m_listener.accept()?;
+            Ok(stream)
+        }
+    }
+
+    impl Drop for Listener {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.m_path);
+        }
+    }
+
+    pub(super) fn connect(path: &Path) -> io::Result<Stream> {
+        UnixStream::connect(path)
+    }
+
+    pub(super) fn set_read_timeout(stream: &Stream, timeout: Option<Duration>) -> io::Result<()> {
+        stream.set_read_timeout(timeout)
+    }
+
+    /// The socket read itself times out, so there is nothing to wait for.
+    pub(super) fn wait_readable(_stream: &Stream, _timeout: Option<Duration>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::ffi::c_void;
+    use std::fs::{File, OpenOptions};
+    use std::io::{self, ErrorKind};
+    use std::iter;
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::{AsRawHandle, FromRawHandle, OwnedHandle, RawHandle};
+    use std::path::Path;
+    use std::ptr;
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    const PIPE_ACCESS_DUPLEX: u32 = 0x0000_0003;
+    const FILE_FLAG_FIRST_PIPE_INSTANCE: u32 = 0x0008_0000;
+    const PIPE_TYPE_BYTE: u32 = 0x0000_0000;
+    const PIPE_READMODE_BYTE: u32 = 0x0000_0000;
+    const PIPE_WAIT: u32 = 0x0000_0000;
+    const PIPE_REJECT_REMOTE_CLIENTS: u32 = 0x0000_0008;
+    const PIPE_UNLIMITED_INSTANCES: u32 = 255;
+    const NMPWAIT_USE_DEFAULT_WAIT: u32 = 0;
+    const ERROR_ACCESS_DENIED: i32 = 5;
+    const ERROR_PIPE_BUSY: i32 = 231;
+    const ERROR_PIPE_CONNECTED: i32 = 535;
+    const BUFFER_SIZE: u32 = 64 * 1024;
+    const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateNamedPipeW(
+            name: *const u16,
+            open_mode: u32,
+            pipe_mode: u32,
+            max_instances: u32,
+            out_buffer_size: u32,
+            in_buffer_size: u32,
+            default_timeout: u32,
+            security_attributes: *mut c_void,
+        ) -> RawHandle;
+        fn ConnectNamedPipe(pipe: RawHandle, overlapped: *mut c_void) -> i32;
+        fn WaitNamedPipeW(name: *const u16, timeout: u32) -> i32;
+        fn PeekNamedPipe(
+            pipe: RawHandle,
+            buffer: *mut c_void,
+            buffer_size: u32,
+            bytes_read: *mut u32,
+            bytes_available: *mut u32,
+            bytes_left: *mut u32,
+        ) -> i32;
+    }
+
+    pub(super) type Stream = File;
+
+    /// A named pipe server. Every accepted connection gets its own pipe
+    /// instance, and the next instance is created before `accept` returns
+    /// so clients never find the pipe missing.
+    pub(super) struct Listener {
+        m_name: Vec<u16>,
+        m_pending: Mutex<OwnedHandle>,
+    }
+
+    impl Listener {
+        pub(super) fn bind(path: &Path) -> io::Result<Self> {
+            let name = wide(path);
+            let pending = create_instance(&name, true)?;
+
+            Ok(Listener {
+                m_name: name,
+                m_pending: Mutex::new(pending),
+            })
+        }
+
+        pub(super) fn accept(&self) -> io::Result<Stream> {
+            let mut pending = self.m_pending.lock().unwrap_or_else(|err| err.into_inner());
+
+            // SAFETY: the handle is a pipe instance owned by the listener,
+            // and a null overlapped pointer makes the call synchronous.
+            if unsafe { ConnectNamedPipe(pending.as_raw_handle(), ptr::null_mut()) } == 0 {
+                let err = io::Error::last_os_error();
+                if err.raw_os_error() != Some(ERROR_PIPE_CONNECTED) {
+                    return Err(err);
+                }
+            }
+
+            let connected = std::mem::replace(&mut *pending, create_instance(&self.m_name, false)?);
+            Ok(File::from(connected))
+        }
+    }
+
+    fn wide(path: &Path) -> Vec<u16> {
+        path.as_os_str().encode_wide().chain(iter::once(0)).collect()
+    }
+
+    fn create_instance(name: &[u16], first: bool) -> io::Result<OwnedHandle> {
+        let mut open_mode = PIPE_ACCESS_DUPLEX;
+        if first {
+            open_mode |= FILE_FLAG_FIRST_PIPE_INSTANCE;
+        }
+
+        // SAFETY: `name` is NUL-terminated and outlives the call, and a null
+        // security attributes pointer selects the default descriptor.
+        let handle = unsafe {
+            CreateNamedPipeW(
+                name.as_ptr(),
+                open_mode,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                BUFFER_SIZE,
+                BUFFER_SIZE,
+                0,
+                ptr::null_mut(),
+            )
+        };
+        if handle as isize == -1 {
+            let err = io::Error::last_os_error();
+            if first && err.raw_os_error() == Some(ERROR_ACCESS_DENIED) {
+                return Err(ErrorKind::AddrInUse.into());
+            }
+            return Err(err);
+        }
+
+        // SAFETY: the handle was just created and nothing else owns it.
+        Ok(unsafe { OwnedHandle::from_raw_handle(handle) })
+    }
+
+    pub(super) fn connect(path: &Path) -> io::Result<Stream> {
+        loop {
+            match OpenOptions::new().read(true).write(true).open(path) {
+                Err(err) if err.raw_os_error() == Some(ERROR_PIPE_BUSY) => {
+                    let name = wide(path);
+                    // SAFETY: `name` is NUL-terminated and outlives the call.
+                    if unsafe { WaitNamedPipeW(name.as_ptr(), NMPWAIT_USE_DEFAULT_WAIT) } == 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Pipe reads cannot time out, so `wait_readable` polls instead.
+    pub(super) fn set_read_timeout(_stream: &Stream, _timeout: Option<Duration>) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Waits until the pipe has bytes to read or the peer has gone, or
+    /// fails with `TimedOut` once `timeout` has passed.
+    pub(super) fn wait_readable(stream: &Stream, timeout: Option<Duration>) -> io::Result<()> {
+        let Some(timeout) = timeout else {
+            return Ok(());
+        };
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let mut available = 0u32;
+            // SAFETY: the handle stays open while `stream` is borrowed, and
+            // with a null buffer nothing is copied.
+            let ok = unsafe {
+                PeekNamedPipe(
+                    stream.as_raw_handle(),
+                    ptr::null_mut(),
+                    0,
+                    ptr::null_mut(),
+                    &mut available,
+                    ptr::null_mut(),
+                )
+            };
+            // A closed pipe is reported by the read that follows.
+            if ok == 0 || available > 0 {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(ErrorKind::TimedOut.into());
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
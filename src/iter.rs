@@ -0,0 +1,142 @@
+//! Combinators for iterators over Ido items.
+//!
+//! [`IdoIteratorExt`] adds `map_values`, `filter_keys` and `map_into_ido`
+//! to every iterator of `(IdoKeyT, IdoItem)` pairs, such as
+//! [`Ido::into_ordered_iterator`] and the consuming iterator of an Ido.
+//! Collecting such an iterator builds a new Ido holding the items in
+//! iteration order, so a transformation reads as one chain:
+//!
+//! ```
+//! use ido::iter::IdoIteratorExt;
+//! use ido::{Ido, IdoItemType};
+//!
+//! let mut ido = Ido::new();
+//! ido.set_string(&1, "eurusd".to_string());
+//! ido.set_integer(&2, 100);
+//! ido.set_string(&1001, "internal".to_string());
+//!
+//! let upper: Ido = ido
+//!     .into_ordered_iterator()
+//!     .filter_keys(|key| *key <= 1000)
+//!     .map_values(|mut item| {
+//!         if item.m_type == IdoItemType::STRING {
+//!             item.m_string = item.m_string.to_uppercase();
+//!         }
+//!         item
+//!     })
+//!     .collect();
+//!
+//! assert_eq!(upper.get_string(&1), Some("EURUSD".to_string()));
+//! assert_eq!(upper.get_i64(&2), Some(100));
+//! assert!(!upper.contains(&1001));
+//! ```
+
+use crate::{Ido, IdoItem, IdoKeyT};
+
+/// Combinators for iterators over `(IdoKeyT, IdoItem)` pairs.
+pub trait IdoIteratorExt: Iterator<Item = (IdoKeyT, IdoItem)> + Sized {
+    /// Replaces every item with the result of `f`, keeping its key.
+    fn map_values<F>(self, f: F) -> MapValues<Self, F>
+    where
+        F: FnMut(IdoItem) -> IdoItem,
+    {
+        MapValues { m_iter: self, m_map: f }
+    }
+
+    /// Keeps only the items whose key satisfies `predicate`.
+    fn filter_keys<P>(self, predicate: P) -> FilterKeys<Self, P>
+    where
+        P: FnMut(&IdoKeyT) -> bool,
+    {
+        FilterKeys { m_iter: self, m_predicate: predicate }
+    }
+
+    /// Builds a new Ido from the pairs returned by `f`, in iteration order.
+    ///
+    /// `f` may change the key as well as the item, and drops the item by
+    /// returning `None`. A key returned twice holds the last item.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::iter::IdoIteratorExt;
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 5);
+    /// ido.set_integer(&2, 6);
+    ///
+    /// let shifted = ido
+    ///     .into_ordered_iterator()
+    ///     .map_into_ido(|key, item| (key != 2).then_some((key + 100, item)));
+    ///
+    /// assert_eq!(shifted.get_i64(&101), Some(5));
+    /// assert_eq!(shifted.size(), 1);
+    /// ```
+    fn map_into_ido<F>(self, mut f: F) -> Ido
+    where
+        F: FnMut(IdoKeyT, IdoItem) -> Option<(IdoKeyT, IdoItem)>,
+    {
+        self.filter_map(|(key, item)| f(key, item)).collect()
+    }
+}
+
+impl<I: Iterator<Item = (IdoKeyT, IdoItem)>> IdoIteratorExt for I {}
+
+/// Iterator returned by [`IdoIteratorExt::map_values`].
+pub struct MapValues<I, F> {
+    m_iter: I,
+    m_map: F,
+}
+
+impl<I, F> Iterator for MapValues<I, F>
+where
+    I: Iterator<Item = (IdoKeyT, IdoItem)>,
+    F: FnMut(IdoItem) -> IdoItem,
+{
+    type Item = (IdoKeyT, IdoItem);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, item) = self.m_iter.next()?;
+        Some((key, (self.m_map)(item)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.m_iter.size_hint()
+    }
+}
+
+/// Iterator returned by [`IdoIteratorExt::filter_keys`].
+pub struct FilterKeys<I, P> {
+    m_iter: I,
+    m_predicate: P,
+}
+
+impl<I, P> Iterator for FilterKeys<I, P>
+where
+    I: Iterator<Item = (IdoKeyT, IdoItem)>,
+    P: FnMut(&IdoKeyT) -> bool,
+{
+    type Item = (IdoKeyT, IdoItem);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let predicate = &mut self.m_predicate;
+        self.m_iter.find(|(key, _)| predicate(key))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.m_iter.size_hint().1)
+    }
+}
+
+/// Builds an Ido holding the items in iteration order. A key that occurs
+/// twice holds the last item.
+impl FromIterator<(IdoKeyT, IdoItem)> for Ido {
+    fn from_iter<T: IntoIterator<Item = (IdoKeyT, IdoItem)>>(iter: T) -> Self {
+        let mut ido = Ido::new();
+        for (key, item) in iter {
+            ido.set_item(&key, item);
+        }
+        ido
+    }
+}
@@ -0,0 +1,168 @@
+//! Recording every mutation of an Ido.
+//!
+//! When the journal is enabled with [`Ido::enable_journal`], each change is
+//! appended as a [`JournalEntry`] holding the item before and after the
+//! change, so the evolution of an object can be reconstructed later. The
+//! same entries drive [`Ido::undo`] and [`Ido::redo`].
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use crate::{Ido, IdoItem, IdoKeyT};
+
+/// A single recorded mutation.
+#[derive(Clone)]
+pub struct JournalEntry {
+    /// When the change was applied.
+    #[cfg(feature = "chrono")]
+    pub timestamp: DateTime<Utc>,
+    /// The key that changed.
+    pub key: IdoKeyT,
+    /// The item before the change, or `None` if the key was absent.
+    pub old: Option<IdoItem>,
+    /// The item after the change, or `None` if the key was removed.
+    pub new: Option<IdoItem>,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Journal {
+    m_entries: Vec<JournalEntry>,
+    m_undone: Vec<JournalEntry>,
+}
+
+impl Journal {
+    pub(crate) fn record(&mut self, key: IdoKeyT, old: Option<&IdoItem>, new: Option<&IdoItem>) {
+        self.m_undone.clear();
+        self.m_entries.push(JournalEntry {
+            #[cfg(feature = "chrono")]
+            timestamp: Utc::now(),
+            key,
+            old: old.cloned(),
+            new: new.cloned(),
+        });
+    }
+}
+
+impl Ido {
+    /// Starts recording every mutation of the Ido.
+    ///
+    /// Enabling an already enabled journal keeps the recorded history.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.enable_journal();
+    ///
+    /// ido.set_integer(&1, 10);
+    /// ido.set_integer(&1, 11);
+    ///
+    /// for entry in ido.history() {
+    ///     let old = entry.old.as_ref().and_then(|item| item.as_string());
+    ///     let new = entry.new.as_ref().and_then(|item| item.as_string());
+    ///     println!("{}: {:?} -> {:?}", entry.key, old, new);
+    /// }
+    /// ```
+    pub fn enable_journal(&mut self) {
+        if self.m_journal.is_none() {
+            self.m_journal = Some(Journal::default());
+        }
+    }
+
+    /// Stops recording mutations and discards the recorded history.
+    pub fn disable_journal(&mut self) {
+        self.m_journal = None;
+    }
+
+    /// Checks whether the journal is enabled.
+    pub fn is_journal_enabled(&self) -> bool {
+        self.m_journal.is_some()
+    }
+
+    /// Returns the recorded mutations, oldest first.
+    ///
+    /// The slice is empty when the journal is disabled.
+    pub fn history(&self) -> &[JournalEntry] {
+        match &self.m_journal {
+            Some(journal) => &journal.m_entries,
+            None => &[],
+        }
+    }
+
+    /// Discards the recorded history while keeping the journal enabled.
+    ///
+    /// Mutations that were undone can no longer be redone afterwards.
+    pub fn clear_history(&mut self) {
+        if let Some(journal) = &mut self.m_journal {
+            journal.m_entries.clear();
+            journal.m_undone.clear();
+        }
+    }
+
+    /// Reverts the most recent recorded mutation.
+    ///
+    /// The reverted entry is removed from `history` and can be reapplied with
+    /// `redo` until another mutation is made. Undoing an overwrite or a delete
+    /// also restores the item's position in the insertion order.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a mutation was reverted, `false` if the journal is disabled or empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.enable_journal();
+    ///
+    /// ido.set_integer(&1, 10);
+    /// ido.set_integer(&1, 11);
+    ///
+    /// ido.undo();
+    /// assert_eq!(ido.get_i64(&1), Some(10));
+    ///
+    /// ido.redo();
+    /// assert_eq!(ido.get_i64(&1), Some(11));
+    /// ```
+    pub fn undo(&mut self) -> bool {
+        let Some(mut journal) = self.m_journal.take() else {
+            return false;
+        };
+
+        let undone = journal.m_entries.pop();
+        if let Some(entry) = &undone {
+            self.revert_to(entry.key, entry.old.clone());
+        }
+        let changed = undone.is_some();
+        journal.m_undone.extend(undone);
+
+        self.m_journal = Some(journal);
+        changed
+    }
+
+    /// Reapplies the most recently undone mutation.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a mutation was reapplied, `false` if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(mut journal) = self.m_journal.take() else {
+            return false;
+        };
+
+        let redone = journal.m_undone.pop();
+        if let Some(entry) = &redone {
+            self.revert_to(entry.key, entry.new.clone());
+        }
+        let changed = redone.is_some();
+        journal.m_entries.extend(redone);
+
+        self.m_journal = Some(journal);
+        changed
+    }
+
+}
@@ -0,0 +1,153 @@
+//! Rendering Idos as JSON, and arrays of Idos as JSON Lines.
+//!
+//! An Ido becomes a JSON object with one member per item, in insertion
+//! order. Members are named by the key, or by its name with a dictionary
+//! attached by [`Ido::set_names`]. Values map as follows:
+//!
+//! | Ido type      | JSON                                              |
+//! |---------------|---------------------------------------------------|
+//! | STRING        | string                                            |
+//! | INTEGER       | number                                            |
+//! | INTEGER128    | number                                            |
+//! | FLOAT         | number, or `null` for NaN and infinite values     |
+//! | BOOL          | `true` or `false`                                 |
+//! | DATETIME      | string, in the datetime format of the Ido         |
+//! | ARRAY         | array of objects                                  |
+//!
+//! [`Ido::write_array_as_jsonl`] streams the elements of an array as
+//! [JSON Lines](https://jsonlines.org): one object per line, each written
+//! as soon as it is rendered.
+//!
+//! ```text
+//! {"1":"EURUSD","2":100}
+//! {"1":"GBPUSD","2":60,"3":[{"1":true}]}
+//! ```
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::datetime::DateTimeFormat;
+use crate::dictionary::IdoDictionary;
+use crate::{Ido, IdoItemType, IdoKeyT};
+
+/// Writes `ido` as a JSON object, naming keys found in `names`.
+#[cfg_attr(not(feature = "chrono"), allow(clippy::only_used_in_recursion))]
+pub(crate) fn write_object(out: &mut String, ido: &Ido, names: Option<&IdoDictionary>, format: &DateTimeFormat) {
+    out.push('{');
+    let mut first = true;
+    for (key, item) in ido.live_items() {
+        if !first {
+            out.push(',');
+        }
+        first = false;
+
+        match names.and_then(|names| names.name(&key)) {
+            Some(name) => write_string(out, name),
+            None => {
+                let _ = write!(out, "\"{}\"", key);
+            }
+        }
+        out.push(':');
+
+        match item.m_type {
+            IdoItemType::STRING => write_string(out, &item.m_string),
+            IdoItemType::INTEGER => {
+                let _ = write!(out, "{}", item.m_integer);
+            }
+            IdoItemType::INTEGER128 => {
+                let _ = write!(out, "{}", item.m_integer128);
+            }
+            IdoItemType::FLOAT if item.m_float.is_finite() => {
+                let _ = write!(out, "{}", item.m_float);
+            }
+            IdoItemType::FLOAT => out.push_str("null"),
+            IdoItemType::BOOL => out.push_str(if item.m_bool { "true" } else { "false" }),
+            #[cfg(feature = "chrono")]
+            IdoItemType::DATETIME => write_string(out, &format.format(&item.m_datetime)),
+            IdoItemType::ARRAY => {
+                out.push('[');
+                for (index, element) in item.m_array.iter().enumerate() {
+                    if index != 0 {
+                        out.push(',');
+                    }
+                    write_object(out, element, names, format);
+                }
+                out.push(']');
+            }
+        }
+    }
+    out.push('}');
+}
+
+/// Writes `text` as a quoted JSON string.
+pub(crate) fn write_string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+impl Ido {
+    /// Writes each Ido of the array under `array_key` to `writer` as a JSON
+    /// object on its own line; see the [`json`](crate::json) module for the
+    /// mapping.
+    ///
+    /// Each line is written as soon as its element is rendered, so an array
+    /// of any length is streamed without building the whole document.
+    /// Wrap `writer` in a `BufWriter` when it is unbuffered.
+    ///
+    /// # Returns
+    ///
+    /// The number of lines written: 0 if `array_key` does not hold an
+    /// ARRAY.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the writer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// for (symbol, quantity) in [("EURUSD", 100), ("GBPUSD", 60)] {
+    ///     let mut row = Ido::new();
+    ///     row.set_string(&1, symbol.to_string());
+    ///     row.set_integer(&2, quantity);
+    ///     ido.append_array(&5, row);
+    /// }
+    ///
+    /// let mut out = Vec::new();
+    /// assert_eq!(ido.write_array_as_jsonl(&5, &mut out).unwrap(), 2);
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     "{\"1\":\"EURUSD\",\"2\":100}\n{\"1\":\"GBPUSD\",\"2\":60}\n"
+    /// );
+    /// ```
+    pub fn write_array_as_jsonl<W: Write>(&self, array_key: &IdoKeyT, writer: &mut W) -> io::Result<usize> {
+        let Some(item) = self.live_item(array_key).filter(|item| item.m_type == IdoItemType::ARRAY) else {
+            return Ok(0);
+        };
+
+        let mut line = String::new();
+        for element in &item.m_array {
+            line.clear();
+            write_object(&mut line, element, self.names(), self.datetime_format());
+            line.push('\n');
+            writer.write_all(line.as_bytes())?;
+        }
+        Ok(item.m_array.len())
+    }
+}
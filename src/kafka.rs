@@ -0,0 +1,213 @@
+//! Producing and consuming Idos on Kafka topics with `rdkafka`.
+//!
+//! Payloads use the native binary format of [`Ido::encode`]. When a
+//! producer knows which message type it sends, it also attaches two
+//! headers so consumers can tell the layout apart without peeking into the
+//! payload:
+//!
+//! | header         | value                                  |
+//! |----------------|----------------------------------------|
+//! | `ido.schema`   | message type, as registered in a [`SchemaRegistry`] |
+//! | `ido.version`  | schema version in decimal              |
+//!
+//! A consumer with a registry upgrades older messages to the latest version
+//! of their type as they are deserialized.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use ido::Ido;
+//! use ido::kafka::IdoSerializer;
+//! use rdkafka::producer::{FutureProducer, FutureRecord};
+//!
+//! # async fn send(producer: FutureProducer, ido: Ido) {
+//! let serializer = IdoSerializer::new().schema("order", 2);
+//! let payload = serializer.serialize(&ido);
+//!
+//! let record = FutureRecord::<(), _>::to("orders")
+//!     .payload(&payload)
+//!     .headers(serializer.headers());
+//! producer.send(record, Duration::from_secs(1)).await.unwrap();
+//! # }
+//! ```
+
+use std::fmt;
+
+use rdkafka::message::{Header, Headers, Message, OwnedHeaders};
+
+use crate::codec::CodecError;
+use crate::registry::{RegistryError, SchemaRegistry};
+use crate::Ido;
+
+/// Header carrying the message type.
+pub const SCHEMA_HEADER: &str = "ido.schema";
+
+/// Header carrying the schema version.
+pub const VERSION_HEADER: &str = "ido.version";
+
+/// Errors raised while deserializing a Kafka message.
+#[derive(Debug)]
+pub enum IdoKafkaError {
+    /// The message has no payload.
+    EmptyPayload,
+    /// The payload is not a valid encoded Ido.
+    Codec(CodecError),
+    /// A schema header is missing its partner, is not UTF-8 or the version
+    /// is not a number.
+    InvalidHeader(String),
+    /// Upgrading the message through the registry failed.
+    Registry(RegistryError),
+}
+
+impl fmt::Display for IdoKafkaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdoKafkaError::EmptyPayload => write!(f, "message has no payload"),
+            IdoKafkaError::Codec(err) => write!(f, "invalid payload: {}", err),
+            IdoKafkaError::InvalidHeader(header) => write!(f, "invalid header {}", header),
+            IdoKafkaError::Registry(err) => write!(f, "cannot upgrade message: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for IdoKafkaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IdoKafkaError::Codec(err) => Some(err),
+            IdoKafkaError::Registry(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<CodecError> for IdoKafkaError {
+    fn from(err: CodecError) -> Self {
+        IdoKafkaError::Codec(err)
+    }
+}
+
+impl From<RegistryError> for IdoKafkaError {
+    fn from(err: RegistryError) -> Self {
+        IdoKafkaError::Registry(err)
+    }
+}
+
+/// The message type and version carried in the headers of a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaRef {
+    pub message_type: String,
+    pub version: u32,
+}
+
+/// Turns Idos into Kafka payloads and headers.
+#[derive(Debug, Clone, Default)]
+pub struct IdoSerializer {
+    m_schema: Option<SchemaRef>,
+}
+
+impl IdoSerializer {
+    /// Creates a serializer that attaches no schema headers.
+    pub fn new() -> Self {
+        IdoSerializer::default()
+    }
+
+    /// Attaches `ido.schema` and `ido.version` headers to every message.
+    pub fn schema(mut self, message_type: &str, version: u32) -> Self {
+        self.m_schema = Some(SchemaRef { message_type: message_type.to_string(), version });
+        self
+    }
+
+    /// Encodes the payload of a message.
+    pub fn serialize(&self, ido: &Ido) -> Vec<u8> {
+        ido.encode()
+    }
+
+    /// Returns the headers to send with every message.
+    pub fn headers(&self) -> OwnedHeaders {
+        match &self.m_schema {
+            Some(schema) => OwnedHeaders::new_with_capacity(2)
+                .insert(Header { key: SCHEMA_HEADER, value: Some(&schema.message_type) })
+                .insert(Header { key: VERSION_HEADER, value: Some(&schema.version.to_string()) }),
+            None => OwnedHeaders::new(),
+        }
+    }
+}
+
+/// Turns Kafka messages back into Idos.
+#[derive(Debug, Clone, Default)]
+pub struct IdoDeserializer {
+    m_registry: Option<SchemaRegistry>,
+}
+
+impl IdoDeserializer {
+    /// Creates a deserializer that decodes payloads as they are.
+    pub fn new() -> Self {
+        IdoDeserializer::default()
+    }
+
+    /// Upgrades messages carrying schema headers through `registry`.
+    pub fn registry(mut self, registry: SchemaRegistry) -> Self {
+        self.m_registry = Some(registry);
+        self
+    }
+
+    /// Reads the schema headers of a message.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the message carries neither header.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IdoKafkaError::InvalidHeader` if only one header is present
+    /// or a value cannot be parsed.
+    pub fn schema_of<M: Message>(&self, message: &M) -> Result<Option<SchemaRef>, IdoKafkaError> {
+        let mut message_type = None;
+        let mut version = None;
+
+        for header in message.headers().into_iter().flat_map(|headers| headers.iter()) {
+            let slot = match header.key {
+                SCHEMA_HEADER => &mut message_type,
+                VERSION_HEADER => &mut version,
+                _ => continue,
+            };
+            let value = header
+                .value
+                .and_then(|value| std::str::from_utf8(value).ok())
+                .ok_or_else(|| IdoKafkaError::InvalidHeader(header.key.to_string()))?;
+            *slot = Some(value);
+        }
+
+        match (message_type, version) {
+            (None, None) => Ok(None),
+            (Some(message_type), Some(version)) => {
+                let version = version
+                    .parse()
+                    .map_err(|_| IdoKafkaError::InvalidHeader(VERSION_HEADER.to_string()))?;
+                Ok(Some(SchemaRef { message_type: message_type.to_string(), version }))
+            }
+            (None, Some(_)) => Err(IdoKafkaError::InvalidHeader(SCHEMA_HEADER.to_string())),
+            (Some(_), None) => Err(IdoKafkaError::InvalidHeader(VERSION_HEADER.to_string())),
+        }
+    }
+
+    /// Decodes the payload of a message.
+    ///
+    /// With a registry set and schema headers present, the Ido is upgraded
+    /// to the latest version of its type and validated against it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IdoKafkaError` if the payload is missing or malformed,
+    /// the headers are invalid or the upgrade fails.
+    pub fn deserialize<M: Message>(&self, message: &M) -> Result<Ido, IdoKafkaError> {
+        let payload = message.payload().ok_or(IdoKafkaError::EmptyPayload)?;
+        let mut ido = Ido::decode(payload)?;
+
+        if let Some(registry) = &self.m_registry {
+            if let Some(schema) = self.schema_of(message)? {
+                registry.upgrade(&schema.message_type, schema.version, &mut ido)?;
+            }
+        }
+        Ok(ido)
+    }
+}
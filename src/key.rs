@@ -0,0 +1,65 @@
+//! Types that can be used as Ido keys.
+//!
+//! The getters and setters of [`Ido`](crate::Ido) take a reference to any
+//! [`IdoKey`]: a plain `IdoKeyT` or an enum naming the fields of a message.
+//! With the `derive` feature, `#[derive(IdoKey)]` implements the trait for
+//! a `#[repr(i32)]` enum of unit variants, each variant standing for its
+//! discriminant. The key stored and put on the wire is always the
+//! number, so enum keys and plain keys read the same items.
+//!
+//! ```
+//! use ido::{Ido, IdoKey, IdoKeyT};
+//!
+//! #[derive(Clone, Copy)]
+//! #[repr(i32)]
+//! enum OrderField {
+//!     Symbol = 1,
+//!     Price = 3,
+//! }
+//!
+//! impl IdoKey for OrderField {
+//!     fn ido_key(&self) -> IdoKeyT {
+//!         *self as IdoKeyT
+//!     }
+//! }
+//!
+//! let mut ido = Ido::new();
+//! ido.set_string(&OrderField::Symbol, "EURUSD".to_string());
+//! ido.set_integer(&OrderField::Price, 100);
+//!
+//! assert_eq!(ido.get_i64(&3), Some(100));
+//! assert_eq!(ido.get_string(&OrderField::Symbol), Some("EURUSD".to_string()));
+//! ```
+
+use crate::IdoKeyT;
+
+/// A value naming an Ido key.
+pub trait IdoKey {
+    /// Returns the numeric key.
+    fn ido_key(&self) -> IdoKeyT;
+}
+
+impl IdoKey for IdoKeyT {
+    fn ido_key(&self) -> IdoKeyT {
+        *self
+    }
+}
+
+impl<K: IdoKey + ?Sized> IdoKey for &K {
+    fn ido_key(&self) -> IdoKeyT {
+        (**self).ido_key()
+    }
+}
+
+/// Widens a key to `i64`, whatever the width of `IdoKeyT`.
+// A no-op with the `wide-keys` feature.
+#[allow(clippy::useless_conversion)]
+pub(crate) fn key_to_i64(key: IdoKeyT) -> i64 {
+    i64::from(key)
+}
+
+/// Narrows an `i64` to a key, or returns `None` if it does not fit.
+#[allow(clippy::useless_conversion)]
+pub(crate) fn key_from_i64(value: i64) -> Option<IdoKeyT> {
+    IdoKeyT::try_from(value).ok()
+}
@@ -0,0 +1,131 @@
+//! Reading single fields out of an encoded Ido without decoding the rest.
+//!
+//! [`LazyIdo`] scans a buffer produced by [`Ido::encode`] once to find where
+//! each item starts, skipping over the values themselves. An item is only
+//! decoded the first time its key is read, and kept for later reads. This
+//! pays off when consumers read a handful of fields from large messages.
+//!
+//! The scan checks that the buffer is complete and every type tag is known.
+//! Errors within a value, such as invalid UTF-8 or a malformed nested array,
+//! are only reported when that value is read.
+
+use std::cell::OnceCell;
+use std::collections::HashMap;
+
+use crate::codec::{self, CodecError, Reader};
+use crate::{Ido, IdoItem, IdoKeyT};
+
+struct LazyEntry {
+    m_offset: usize,
+    m_item: OnceCell<IdoItem>,
+}
+
+/// An encoded Ido whose items are decoded on first access.
+///
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+/// use ido::lazy::LazyIdo;
+///
+/// let mut ido = Ido::new();
+/// ido.set_string(&1, "ORDER-1".to_string());
+/// ido.set_f64(&2, 101.25);
+/// let bytes = ido.encode();
+///
+/// let lazy = LazyIdo::new(&bytes).unwrap();
+/// assert_eq!(lazy.size(), 2);
+///
+/// let price = lazy.get_item(&2).unwrap().unwrap();
+/// assert_eq!(price.m_float, 101.25);
+/// assert!(!lazy.is_decoded(&1));
+/// ```
+pub struct LazyIdo<'a> {
+    m_buf: &'a [u8],
+    m_keys: Vec<IdoKeyT>,
+    m_entries: HashMap<IdoKeyT, LazyEntry>,
+}
+
+impl<'a> LazyIdo<'a> {
+    /// Indexes an Ido encoded with [`Ido::encode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CodecError` if the buffer is truncated, holds an unknown
+    /// type tag or has bytes left over after the Ido.
+    pub fn new(buf: &'a [u8]) -> Result<Self, CodecError> {
+        let mut reader = Reader::new(buf);
+        let count = reader.u32()?;
+        let mut keys = Vec::new();
+        let mut entries = HashMap::new();
+
+        for _ in 0..count {
+            let key = reader.key()?;
+            let entry = LazyEntry { m_offset: reader.position(), m_item: OnceCell::new() };
+            codec::skip_item(&mut reader)?;
+
+            // A repeated key replaces the earlier item and moves to the end,
+            // as it does when the buffer is decoded.
+            if entries.insert(key, entry).is_some() {
+                keys.retain(|other| *other != key);
+            }
+            keys.push(key);
+        }
+
+        match reader.remaining() {
+            0 => Ok(LazyIdo { m_buf: buf, m_keys: keys, m_entries: entries }),
+            n => Err(CodecError::TrailingBytes(n)),
+        }
+    }
+
+    /// Returns the number of items.
+    pub fn size(&self) -> usize {
+        self.m_keys.len()
+    }
+
+    /// Returns `true` if an item is stored under `key`.
+    pub fn contains(&self, key: &IdoKeyT) -> bool {
+        self.m_entries.contains_key(key)
+    }
+
+    /// Returns the keys in the order they were encoded.
+    pub fn keys(&self) -> &[IdoKeyT] {
+        &self.m_keys
+    }
+
+    /// Returns `true` if the item under `key` has already been decoded.
+    pub fn is_decoded(&self, key: &IdoKeyT) -> bool {
+        self.m_entries.get(key).is_some_and(|entry| entry.m_item.get().is_some())
+    }
+
+    /// Returns the item stored under `key`, decoding it on first access.
+    ///
+    /// # Returns
+    ///
+    /// `None` if no item is stored under `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CodecError` if the value is malformed.
+    pub fn get_item(&self, key: &IdoKeyT) -> Result<Option<&IdoItem>, CodecError> {
+        let Some(entry) = self.m_entries.get(key) else {
+            return Ok(None);
+        };
+        if let Some(item) = entry.m_item.get() {
+            return Ok(Some(item));
+        }
+
+        let mut item = codec::read_item(&mut Reader::new(&self.m_buf[entry.m_offset..]))?;
+        item.m_key = *key;
+        Ok(Some(entry.m_item.get_or_init(|| item)))
+    }
+
+    /// Decodes every item into a regular Ido.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CodecError` if any value is malformed.
+    pub fn to_ido(&self) -> Result<Ido, CodecError> {
+        Ido::decode(self.m_buf)
+    }
+}
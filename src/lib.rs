@@ -1,9 +1,100 @@
+#[cfg(feature = "chrono")]
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
-use std::collections::hash_map::IntoIter;
-use std::fmt::Write;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::btree_map::{IntoValues, Values};
+use std::fmt;
+use std::ops::RangeBounds;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
+pub use convert::{FromIdo, ToIdo};
+pub use error::{IdoError, MissingKeys};
+pub use flags::IdoFlags;
+#[cfg(feature = "derive")]
+pub use ido_derive::{FromIdo, IdoKey, IntoIdo};
+pub use key::IdoKey;
+pub use view::IdoView;
+use datetime::DateTimeFormat;
+use dictionary::IdoDictionary;
+use journal::Journal;
+use ranges::RangeGuard;
+use stats::OpCounters;
+use transaction::Transaction;
+use watch::Watcher;
+
+#[cfg(feature = "tokio")]
+pub mod async_io;
+pub mod audit;
+pub mod chunked;
+mod cmp;
+pub mod codec;
+#[cfg(feature = "codegen")]
+pub mod codegen;
+pub mod compat;
+pub mod config;
+pub mod conflate;
+pub mod convert;
+pub mod csv;
+pub mod cursor;
+pub mod datetime;
+pub mod delimited;
+pub mod diff;
+pub mod dictionary;
+mod error;
+pub mod filter;
+pub mod fix;
+mod flags;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+#[cfg(feature = "chrono")]
+pub mod history;
+pub mod html;
+#[cfg(any(unix, windows))]
+pub mod ipc;
+pub mod iter;
+pub mod journal;
+pub mod json;
+pub mod lazy;
+#[cfg(feature = "ido-kafka")]
+pub mod kafka;
+mod key;
+mod macros;
+pub mod markdown;
+pub mod net;
+pub mod persistent;
+#[cfg(feature = "proto")]
+pub mod proto;
+pub mod ranges;
+pub mod registry;
+pub mod sbe;
+pub mod schema;
+pub mod shared;
+#[cfg(feature = "shm")]
+pub mod shm;
+pub mod state;
+pub mod stats;
+pub mod table;
+pub mod tlv;
+mod transaction;
+mod view;
+mod watch;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod xml;
+pub mod yaml;
+
+/// The type of Ido keys: `i32`, or `i64` with the `wide-keys` feature.
+///
+/// The binary codec writes keys as varints, so small keys take the same
+/// space with either width.
+#[cfg(not(feature = "wide-keys"))]
 pub type IdoKeyT = i32;
+/// The type of Ido keys: `i32`, or `i64` with the `wide-keys` feature.
+///
+/// The binary codec writes keys as varints, so small keys take the same
+/// space with either width.
+#[cfg(feature = "wide-keys")]
+pub type IdoKeyT = i64;
 type ItemMap = HashMap<IdoKeyT, IdoItem>;
 type IdoArray = Vec<Ido>; 
 
@@ -11,11 +102,18 @@ type IdoArray = Vec<Ido>;
 pub struct IdoItem
 {
     m_index: u64,
+    m_version: u64,
+    m_modified: Option<SystemTime>,
+    m_expires: Option<SystemTime>,
+    m_flags: IdoFlags,
     pub m_key: IdoKeyT,
     pub m_string: String,
     pub m_type: IdoItemType,
     pub m_integer: i64,
+    pub m_integer128: i128,
+    pub m_bool: bool,
     pub m_float: f64,
+    #[cfg(feature = "chrono")]
     pub m_datetime: DateTime<Utc>,
     pub m_array: IdoArray
 }
@@ -25,10 +123,17 @@ impl IdoItem {
         IdoItem { 
             m_key: (0),
             m_index: (0),
+            m_version: (0),
+            m_modified: (None),
+            m_expires: (None),
+            m_flags: (IdoFlags::empty()),
             m_string: (String::new()),
             m_type: (IdoItemType::STRING),
             m_integer: (0),
+            m_integer128: (0),
+            m_bool: (false),
             m_float: (0.0),
+            #[cfg(feature = "chrono")]
             m_datetime: (DateTime::<Utc>::MIN_UTC),
             m_array: (IdoArray::new())
         }
@@ -38,6 +143,45 @@ impl IdoItem {
     pub fn get_type(&self) -> IdoItemType {
         self.m_type
     }
+
+    /// Returns the version of the item within its Ido.
+    ///
+    /// The version starts at 1 when a key is first set and is incremented on
+    /// every change to that key. Items that were never stored report 0.
+    pub fn version(&self) -> u64 {
+        self.m_version
+    }
+
+    /// Returns when the item was last changed within its Ido.
+    ///
+    /// Items that were never stored report `DateTime::<Utc>::MIN_UTC`.
+    #[cfg(feature = "chrono")]
+    pub fn modified_at(&self) -> DateTime<Utc> {
+        self.m_modified.map_or(DateTime::<Utc>::MIN_UTC, DateTime::from)
+    }
+
+    /// Returns the deadline after which the item expires, if it has one.
+    #[cfg(feature = "chrono")]
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.m_expires.map(DateTime::from)
+    }
+
+    /// Returns the metadata flags attached to the item.
+    pub fn flags(&self) -> IdoFlags {
+        self.m_flags
+    }
+
+    fn is_expired(&self, now: SystemTime) -> bool {
+        self.m_expires.is_some_and(|deadline| deadline <= now)
+    }
+
+    /// Returns the approximate number of bytes the item holds: its own
+    /// size, the length of its string and every Ido in its array.
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<IdoItem>()
+            + self.m_string.len()
+            + self.m_array.iter().map(Ido::memory_used).sum::<usize>()
+    }
     
     /// Returns the item value as a string, if possible.
     ///
@@ -46,61 +190,345 @@ impl IdoItem {
     /// - `Some(String)` if the item can be converted to a string.
     /// - `None` if the item type is not convertible to a string.
     pub fn as_string(&self) -> Option<String> {
+        self.as_string_with(&DateTimeFormat::standard())
+    }
+
+    /// Returns the item value as a string, rendering datetimes in `format`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "chrono")]
+    /// # {
+    /// use chrono::{TimeZone, Utc};
+    /// use ido::{IdoItem, IdoItemType};
+    /// use ido::datetime::DateTimeFormat;
+    ///
+    /// let mut item = IdoItem::new();
+    /// item.m_type = IdoItemType::DATETIME;
+    /// item.m_datetime = Utc.with_ymd_and_hms(2023, 7, 1, 12, 30, 0).unwrap();
+    ///
+    /// assert_eq!(item.as_string(), Some("2023-07-01 12:30:00.0000".to_string()));
+    /// assert_eq!(item.as_string_with(&DateTimeFormat::epoch_seconds()), Some("1688214600".to_string()));
+    /// # }
+    /// ```
+    #[cfg_attr(not(feature = "chrono"), allow(unused_variables))]
+    pub fn as_string_with(&self, format: &DateTimeFormat) -> Option<String> {
         match self.m_type {
             IdoItemType::STRING => Some(self.m_string.clone()),
             IdoItemType::FLOAT => Some(self.m_float.to_string()),
             IdoItemType::INTEGER => Some(self.m_integer.to_string()),
-            IdoItemType::DATETIME => Some(self.m_datetime.format("%Y-%m-%d %H:%M:%S%.4f").to_string()),
-            IdoItemType::ARRAY => Some(format!("<array of {}>", self.m_array.len()))
+            #[cfg(feature = "chrono")]
+            IdoItemType::DATETIME => Some(format.format(&self.m_datetime)),
+            IdoItemType::ARRAY => Some(format!("<array of {}>", self.m_array.len())),
+            IdoItemType::INTEGER128 => Some(self.m_integer128.to_string()),
+            IdoItemType::BOOL => Some(self.m_bool.to_string())
         }
     }
 }
 
+impl Default for IdoItem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug,Copy,Clone,PartialEq)]
 pub enum IdoItemType
 {
     STRING,
     INTEGER,
     FLOAT,
+    #[cfg(feature = "chrono")]
     DATETIME,
-    ARRAY
+    ARRAY,
+    INTEGER128,
+    BOOL
+}
+
+/// What an Ido does when a NaN or infinite FLOAT value is set.
+///
+/// See [`Ido::set_float_policy`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum FloatPolicy {
+    /// Store the value as is.
+    #[default]
+    Allow,
+    /// Refuse the value with `IdoError::NonFinite`.
+    Reject,
+    /// Leave the key without a value, removing any previous one.
+    Null,
+}
+
+/// What the narrowing getters such as `get_i8` and `get_u16` do with an
+/// INTEGER that does not fit in the requested type.
+///
+/// See [`Ido::set_overflow_policy`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Keep the low bits, like an `as` cast.
+    #[default]
+    Wrap,
+    /// Clamp to the nearest bound of the type.
+    Saturate,
+    /// Refuse the value: `try_get_int` returns `IdoError::OutOfRange` and
+    /// the plain getters return `None`.
+    Reject,
+    /// Return `None`, as if the key held no value.
+    Null,
+}
+
+/// An integer type an INTEGER value can be narrowed to; see
+/// [`Ido::try_get_int`].
+pub trait NarrowInt: Sized {
+    /// Converts `value` if it fits.
+    fn from_exact(value: i64) -> Option<Self>;
+    /// Converts `value` keeping its low bits.
+    fn from_wrapped(value: i64) -> Self;
+    /// Converts `value` clamped to the range of the type.
+    fn from_saturated(value: i64) -> Self;
+}
+
+macro_rules! narrow_int {
+    ($($t:ty),*) => {
+        $(impl NarrowInt for $t {
+            fn from_exact(value: i64) -> Option<Self> {
+                <$t>::try_from(value).ok()
+            }
+
+            fn from_wrapped(value: i64) -> Self {
+                value as $t
+            }
+
+            fn from_saturated(value: i64) -> Self {
+                match <$t>::try_from(value) {
+                    Ok(value) => value,
+                    Err(_) if value < 0 => <$t>::MIN,
+                    Err(_) => <$t>::MAX,
+                }
+            }
+        })*
+    };
+}
+
+narrow_int!(i8, i16, i32, u8, u16, u32, u64);
+
+/// How [`Ido::update_with`] combines two ARRAY values under the same key.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ArrayMerge {
+    /// The incoming array replaces the existing one, as `update` does.
+    #[default]
+    Replace,
+    /// The incoming elements are appended to the existing ones.
+    Append,
+    /// Elements are matched on the value under the given key. A matching
+    /// existing element is updated with the incoming one, using the same
+    /// policy for nested arrays, and unmatched elements are appended.
+    Keyed(IdoKeyT),
+}
+
+/// Which occurrence [`Ido::dedup_array`] keeps of elements sharing an
+/// identity.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Keep {
+    /// Keep the earliest element.
+    #[default]
+    First,
+    /// Keep the latest element.
+    Last,
+}
+
+/// How many items an Ido holds at most, and what happens to an insert
+/// beyond that.
+///
+/// See [`Ido::set_capacity`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum CapacityPolicy {
+    /// Hold any number of items.
+    #[default]
+    Unbounded,
+    /// Hold at most this many items and refuse new keys beyond it with
+    /// `IdoError::CapacityExceeded`.
+    Reject(usize),
+    /// Hold at most this many items, deleting the oldest in insertion order
+    /// to make room for a new key.
+    EvictOldest(usize),
+}
+
+/// Bounds on the shape of an Ido, protecting services from messages that
+/// nest or grow without limit. `None` leaves a dimension unbounded.
+///
+/// See [`Ido::set_limits`] and [`Ido::decode_with_limits`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct IdoLimits {
+    /// Deepest level of array nesting, as returned by [`Ido::depth`].
+    /// Decoding treats `None` as
+    /// [`DEFAULT_MAX_DEPTH`](codec::DEFAULT_MAX_DEPTH).
+    pub max_depth: Option<usize>,
+    /// Largest number of elements in any array, at any depth.
+    pub max_array_len: Option<usize>,
+    /// Largest approximate number of bytes held, as returned by
+    /// [`Ido::memory_used`].
+    pub max_bytes: Option<usize>,
+}
+
+impl IdoLimits {
+    /// Checks `ido` when it is placed at element `len` of the array under
+    /// `key`, which is itself `depth` levels deep.
+    fn check_element(&self, key: IdoKeyT, depth: usize, len: usize, ido: &Ido) -> Result<(), IdoError> {
+        if let Some(max) = self.max_array_len.filter(|max| len > *max) {
+            return Err(IdoError::ArrayTooLong { key, len, max });
+        }
+        let nested = depth + 1 + ido.depth();
+        if let Some(max) = self.max_depth.filter(|max| nested > *max) {
+            return Err(IdoError::DepthExceeded { key, depth: nested, max });
+        }
+        if let Some(max) = self.max_array_len {
+            let len = ido.longest_array();
+            if len > max {
+                return Err(IdoError::ArrayTooLong { key, len, max });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that an Ido holding `used` bytes may grow to `bytes` through
+    /// a change of `key`. Changes that do not grow the Ido always pass.
+    fn check_bytes(&self, key: IdoKeyT, used: usize, bytes: usize) -> Result<(), IdoError> {
+        match self.max_bytes {
+            Some(max) if bytes > max && bytes > used => Err(IdoError::MemoryExceeded { key, bytes, max }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Callback registered with [`Ido::on_change`].
+///
+/// Receives the key, the item before the change and the item after it;
+/// `None` stands for "not present".
+pub type ChangeListener = dyn Fn(IdoKeyT, Option<&IdoItem>, Option<&IdoItem>) + Send + Sync;
+
+/// Handle returned by [`Ido::on_change`], used to remove the listener again.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ListenerId(u64);
+
+#[derive(Default)]
+struct Listeners {
+    m_next_id: u64,
+    m_callbacks: Vec<(ListenerId, Box<ChangeListener>)>,
+    m_watchers: Vec<Watcher>
+}
+
+impl Listeners {
+    fn is_empty(&self) -> bool {
+        self.m_callbacks.is_empty()
+    }
+
+    fn notify(&mut self, key: IdoKeyT, old: Option<&IdoItem>, new: Option<&IdoItem>) {
+        for (_, callback) in &self.m_callbacks {
+            callback(key, old, new);
+        }
+        self.m_watchers.retain(|watcher| watcher.notify(key, new));
+    }
+}
+
+/// Listeners belong to the Ido they were registered on; clones start without any.
+impl Clone for Listeners {
+    fn clone(&self) -> Self {
+        Listeners::default()
+    }
 }
 
 #[derive(Clone)]
 pub struct Ido {
     m_items: ItemMap,
     m_idx: u64,
-    m_ordered: HashMap<u64, IdoKeyT>
+    m_ordered: BTreeMap<u64, IdoKeyT>,
+    m_listeners: Listeners,
+    m_dirty: HashSet<IdoKeyT>,
+    m_journal: Option<Journal>,
+    m_transaction: Option<Transaction>,
+    m_strict: bool,
+    m_float_policy: FloatPolicy,
+    m_overflow_policy: OverflowPolicy,
+    m_limits: IdoLimits,
+    m_capacity: CapacityPolicy,
+    m_bytes: usize,
+    m_ops: OpCounters,
+    m_names: Option<Arc<IdoDictionary>>,
+    m_key_ranges: Option<RangeGuard>,
+    m_datetime_format: DateTimeFormat,
+    m_rejected: Option<IdoError>
 }
 
 pub struct OrderedIdoIterator<'a> {
     m_ido: &'a Ido,
-    m_curr: usize
+    m_keys: Values<'a, u64, IdoKeyT>,
+    m_now: SystemTime
 }
 
 impl Iterator for OrderedIdoIterator<'_> {
     type Item = (IdoKeyT, IdoItem);
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.m_ido.m_ordered.get(&(self.m_curr as u64)) {
-            Some(key) => {
-                let item = self.m_ido.get_item(&key).unwrap();
-                self.m_curr += 1;
-                Some((*key, item))
-            }
-            None => {
-                None
-            }
-        }
+        let items = &self.m_ido.m_items;
+        let now = self.m_now;
+        self.m_keys.find_map(|key| items.get(key).filter(|item| !item.is_expired(now)).map(|item| (*key, item.clone())))
+    }
+}
+
+impl Default for Ido {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Consuming iterator over the items of an Ido in the order they were
+/// populated, leaving out those that had expired when it was created.
+pub struct IdoIntoIterator {
+    m_items: ItemMap,
+    m_keys: IntoValues<u64, IdoKeyT>
+}
+
+impl Iterator for IdoIntoIterator {
+    type Item = (IdoKeyT, IdoItem);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let items = &mut self.m_items;
+        self.m_keys.find_map(|key| items.remove(&key).map(|item| (key, item)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.m_items.len(), Some(self.m_items.len()))
     }
 }
 
+impl ExactSizeIterator for IdoIntoIterator {}
+
+/// Consumes the Ido, yielding its items in the order they were populated.
+/// Items whose time to live has passed are left out, as in
+/// [`into_ordered_iterator`](Ido::into_ordered_iterator).
+///
+/// # Example
+///
+/// ```
+/// use ido::{Ido, IdoKeyT};
+///
+/// let mut ido = Ido::new();
+/// ido.set_integer(&3, 1);
+/// ido.set_integer(&1, 2);
+///
+/// let keys: Vec<IdoKeyT> = ido.into_iter().map(|(key, _)| key).collect();
+/// assert_eq!(keys, vec![3, 1]);
+/// ```
 impl IntoIterator for Ido {
     type Item = (IdoKeyT, IdoItem);
-    type IntoIter = IntoIter<IdoKeyT, IdoItem>;
+    type IntoIter = IdoIntoIterator;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.m_items.into_iter()
+    fn into_iter(mut self) -> Self::IntoIter {
+        let now = SystemTime::now();
+        self.m_items.retain(|_, item| !item.is_expired(now));
+        IdoIntoIterator { m_items: self.m_items, m_keys: self.m_ordered.into_values() }
     }
 }
 
@@ -109,12 +537,48 @@ impl Ido {
         Ido {
             m_items: ItemMap::new(),
             m_idx: 0,
-            m_ordered: HashMap::new(),
+            m_ordered: BTreeMap::new(),
+            m_listeners: Listeners::default(),
+            m_dirty: HashSet::new(),
+            m_journal: None,
+            m_transaction: None,
+            m_strict: false,
+            m_float_policy: FloatPolicy::Allow,
+            m_overflow_policy: OverflowPolicy::Wrap,
+            m_limits: IdoLimits::default(),
+            m_capacity: CapacityPolicy::Unbounded,
+            m_bytes: 0,
+            m_ops: OpCounters::default(),
+            m_names: None,
+            m_key_ranges: None,
+            m_datetime_format: DateTimeFormat::standard(),
+            m_rejected: None,
         }
     }
 
+    /// Iterates the items by reference in the order they were populated.
+    fn ordered_items(&self) -> impl Iterator<Item = (IdoKeyT, &IdoItem)> + '_ {
+        self.m_ordered.values().map(move |key| (*key, &self.m_items[key]))
+    }
+
+    /// Iterates the items that have not expired by `now`, in the order they
+    /// were populated. Every serializer reads the Ido through this, so
+    /// expired items are left out of all output formats alike.
+    fn live_items_at(&self, now: SystemTime) -> impl Iterator<Item = (IdoKeyT, &IdoItem)> + '_ {
+        self.ordered_items().filter(move |(_, item)| !item.is_expired(now))
+    }
+
+    /// Iterates the items that have not expired yet, in the order they
+    /// were populated.
+    fn live_items(&self) -> impl Iterator<Item = (IdoKeyT, &IdoItem)> + '_ {
+        self.live_items_at(SystemTime::now())
+    }
+
     /// Ordered iterator that will iterate the Ido in the order it was populated
     ///
+    /// Items that have expired by the time the iterator is created are
+    /// skipped.
+    ///
     /// This function should only be used for debug purposes as it will be less
     /// performant than an unordered sort.
     /// 
@@ -133,8 +597,93 @@ impl Ido {
     ///         None => println!("Key: {}, Val: None", key),
     ///     }
     /// }
-    pub fn into_ordered_iterator(&self) -> OrderedIdoIterator {
-        OrderedIdoIterator { m_ido: self, m_curr: 0 }
+    pub fn into_ordered_iterator(&self) -> OrderedIdoIterator<'_> {
+        OrderedIdoIterator { m_ido: self, m_keys: self.m_ordered.values(), m_now: SystemTime::now() }
+    }
+
+    /// Copies the items into a vector of key and item pairs, in the order
+    /// they were populated. Expired items are left out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&3, 1);
+    /// ido.set_string(&1, "EURUSD".to_string());
+    ///
+    /// let pairs = ido.to_vec();
+    /// assert_eq!(pairs.len(), 2);
+    /// assert_eq!(pairs[0].0, 3);
+    /// assert_eq!(pairs[1].1.m_string, "EURUSD");
+    /// ```
+    pub fn to_vec(&self) -> Vec<(IdoKeyT, IdoItem)> {
+        self.as_pairs().into_iter().map(|(key, item)| (key, item.clone())).collect()
+    }
+
+    /// Borrows the items as a vector of key and item pairs, in the order
+    /// they were populated. Expired items are left out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&3, 1);
+    /// ido.set_integer(&1, 2);
+    ///
+    /// let keys: Vec<_> = ido.as_pairs().iter().map(|(key, item)| (*key, item.m_integer)).collect();
+    /// assert_eq!(keys, vec![(3, 1), (1, 2)]);
+    /// ```
+    pub fn as_pairs(&self) -> Vec<(IdoKeyT, &IdoItem)> {
+        self.live_items().collect()
+    }
+
+    /// Rearranges the insertion order to follow `keys`.
+    ///
+    /// Items under the keys in `keys` come first, in that order; keys
+    /// without an item and repeated keys are ignored. The other items
+    /// follow in their current order. Values, versions and dirty flags are
+    /// left untouched and no change is reported to listeners.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The template key sequence, e.g. the field layout of a
+    ///   fixed-layout encoder.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&38, 100);
+    /// ido.set_string(&11, "ORDER-1".to_string());
+    /// ido.set_string(&58, "note".to_string());
+    /// ido.set_string(&35, "D".to_string());
+    ///
+    /// ido.order_by_keys(&[35, 11, 99, 38]);
+    /// assert_eq!(ido.to_string(), "35=D,11=ORDER-1,38=100,58=note");
+    /// ```
+    pub fn order_by_keys(&mut self, keys: &[IdoKeyT]) {
+        let mut placed = HashSet::new();
+        let mut order: Vec<IdoKeyT> = keys
+            .iter()
+            .copied()
+            .filter(|key| self.m_items.contains_key(key) && placed.insert(*key))
+            .collect();
+        let rest = std::mem::take(&mut self.m_ordered);
+        order.extend(rest.into_values().filter(|key| !placed.contains(key)));
+
+        for key in order {
+            if let Some(item) = self.m_items.get_mut(&key) {
+                item.m_index = self.m_idx;
+            }
+            self.m_ordered.insert(self.m_idx, key);
+            self.m_idx += 1;
+        }
     }
 
     /// Clears the Ido object, removing all key-value pairs and resetting internal state.
@@ -155,9 +704,16 @@ impl Ido {
     /// ido.clear();
     /// ```
     pub fn clear(&mut self) {
-        self.m_items.clear();
-        self.m_ordered.clear();
+        let mut items = std::mem::take(&mut self.m_items);
+        let ordered = std::mem::take(&mut self.m_ordered);
         self.m_idx = 0;
+        self.m_bytes = 0;
+
+        for key in ordered.values() {
+            let old = items.remove(key);
+            self.record_change(*key, old.as_ref());
+            self.m_ops.count_delete();
+        }
     }
 
     /// Returns the number of key-value pairs in the Ido object.
@@ -184,7 +740,8 @@ impl Ido {
     /// This function iterates through the key-value pairs of the `other` Ido object
     /// and inserts or updates the corresponding entries in the current Ido object.
     /// Existing values are replaced with the new values, and new key-value pairs
-    /// are added to the current object.
+    /// are added to the current object. Arrays are replaced as a whole; use
+    /// `update_with` to append or merge their elements instead.
     ///
     /// # Arguments
     ///
@@ -205,175 +762,1479 @@ impl Ido {
     ///
     /// ido1.update(&ido2);
     /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", name = "ido.update", skip_all, fields(items = other.size()))
+    )]
     pub fn update(&mut self, other: &Ido) {
-        for (key, value) in &other.m_items {
-            self.m_items.insert(*key, value.clone());
+        for (key, value) in other.live_items() {
+            self.set_item(&key, value.clone());
         }
     }
 
-    /// Checks if the `Ido` object contains the specified key.
+    /// Updates the Ido with the values from another Ido like `update`, but
+    /// combines arrays present on both sides according to `policy`.
     ///
     /// # Arguments
     ///
-    /// * `key` - A reference to the key (`IdoKeyT`) to be checked.
-    ///
-    /// # Returns
-    ///
-    /// Returns `true` if the `Ido` object contains the specified key, `false` otherwise.
+    /// * `other` - Another Ido object to update from.
+    /// * `policy` - How to combine two ARRAY values under the same key.
     ///
     /// # Example
     ///
     /// ```
-    /// use ido::Ido;
-    ///
-    /// let mut ido = Ido::new();
-    /// ido.set_integer(&1, 25);
-    /// if ido.contains(&1) {
-    ///     println!("contains key");
-    /// }
-    /// ```
-    pub fn contains(&self, key: &IdoKeyT) -> bool {
-        self.m_items.contains_key(key)
-    }
-
-    /// Checks if the value associated with the given key has the specified type.
+    /// use ido::{ArrayMerge, Ido};
     ///
-    /// # Arguments
+    /// let leg = |id: i64, qty: i64| {
+    ///     let mut leg = Ido::new();
+    ///     leg.set_integer(&1, id);
+    ///     leg.set_integer(&2, qty);
+    ///     leg
+    /// };
     ///
-    /// * `key` - A reference to the key for which the type needs to be checked.
-    /// * `ty` - A reference to the `IdoItemType` enum representing the expected type.
+    /// let mut book = Ido::new();
+    /// book.append_array(&5, leg(1, 100));
+    /// book.append_array(&5, leg(2, 200));
     ///
-    /// # Returns
+    /// let mut delta = Ido::new();
+    /// delta.append_array(&5, leg(2, 250));
+    /// delta.append_array(&5, leg(3, 300));
     ///
-    /// A boolean value indicating whether the value associated with the key has the expected type.
-    /// - `true` if the value has the expected type.
-    /// - `false` if the value does not exist or has a different type.
-    pub fn is_type(&self, key: &IdoKeyT, ty: &IdoItemType) -> bool
-    {
-        if let Some(value) = self.m_items.get(key) {
-            return value.m_type == *ty;
-        } else {
-            false       
+    /// book.update_with(&delta, ArrayMerge::Keyed(1));
+    /// assert_eq!(book.to_string(), "5=[{1=1,2=100},{1=2,2=250},{1=3,2=300}]");
+    /// ```
+    pub fn update_with(&mut self, other: &Ido, policy: ArrayMerge) {
+        for (key, value) in other.live_items() {
+            let existing = self.live_item(&key).filter(|item| item.m_type == IdoItemType::ARRAY);
+            match existing {
+                Some(existing) if value.m_type == IdoItemType::ARRAY && policy != ArrayMerge::Replace => {
+                    let mut merged = existing.clone();
+                    merge_arrays(&mut merged.m_array, &value.m_array, policy);
+                    self.set_item(&key, merged);
+                }
+                _ => self.set_item(&key, value.clone()),
+            }
         }
     }
 
-    /// Sets an item with the specified key in the internal storage.
-    ///
-    /// # Arguments
+    /// Creates a new Ido pre-populated with the items of a template.
     ///
-    /// * `key` - A reference to the key associated with the item.
-    /// * `item` - The item to be set.
+    /// Unlike `clone`, only the items are copied: the new Ido starts without
+    /// listeners, journal, transaction or dirty keys.
     ///
-    /// # Remarks
+    /// # Arguments
     ///
-    /// This function assigns the given item to the specified key in the internal storage.
-    /// It updates the item's key, assigns an index, and inserts the item into the storage map and ordered set.
-    /// The index is incremented to maintain ordering.
+    /// * `template` - The Ido holding the default values.
     ///
     /// # Example
     ///
     /// ```
-    /// use ido::{Ido, IdoKeyT, IdoItem};
+    /// use ido::Ido;
     ///
-    /// let mut ido = Ido::new();
-    /// let item = IdoItem::new();
+    /// let mut template = Ido::new();
+    /// template.set_string(&1, "NEW".to_string());
+    /// template.set_string(&2, "GTC".to_string());
     ///
-    /// ido.set_item(&42, item);
+    /// let mut order = Ido::from_template(&template);
+    /// order.set_integer(&3, 100);
+    ///
+    /// assert_eq!(order.to_string(), "1=NEW,2=GTC,3=100");
     /// ```
-    pub fn set_item(&mut self, key: &IdoKeyT, mut item: IdoItem)
-    {
-        item.m_key = *key;
-        item.m_index = self.m_idx;
-
-        if let Some(value) = self.m_items.get(key) {
-            self.m_ordered.remove(&value.m_index);
-        }
-
-        self.m_items.insert(*key, item);
-        self.m_ordered.insert(self.m_idx, *key);
-        
-        self.m_idx += 1;
+    pub fn from_template(template: &Ido) -> Ido {
+        let mut ido = Ido::new();
+        ido.update(template);
+        ido.clear_dirty();
+        ido
     }
 
-    /// Sets a string value associated with the given key.
+    /// Creates a new Ido holding deep copies of only the listed keys.
+    ///
+    /// Keys that are not present are skipped, and the copied items keep the
+    /// order in which they were populated here. Like `from_template`, the
+    /// new Ido starts without listeners, journal, transaction or dirty keys.
     ///
     /// # Arguments
     ///
-    /// * `key` - The key associated with the value.
-    /// * `val` - The string value to be set.
+    /// * `keys` - The keys to copy.
     ///
     /// # Example
     ///
     /// ```
-    /// use ido::{Ido};
-    /// let mut ido = Ido::new();
-    /// ido.set_string(&1, "John Doe".to_string());
+    /// use ido::Ido;
+    ///
+    /// let mut order = Ido::new();
+    /// order.set_string(&1, "ORDER-1".to_string());
+    /// order.set_integer(&2, 100);
+    /// order.set_f64(&3, 101.25);
+    ///
+    /// let forward = order.clone_subset(&[3, 1, 4]);
+    /// assert_eq!(forward.to_string(), "1=ORDER-1,3=101.25");
     /// ```
-    pub fn set_string (&mut self, key: &IdoKeyT, val: String)
-    {
-        let mut item: IdoItem = IdoItem::new();
-        item.m_type = IdoItemType::STRING;
-        item.m_string = val;
+    pub fn clone_subset(&self, keys: &[IdoKeyT]) -> Ido {
+        let mut items: Vec<&IdoItem> = keys.iter().filter_map(|key| self.live_item(key)).collect();
+        items.sort_by_key(|item| item.m_index);
+        items.dedup_by_key(|item| item.m_index);
 
-        self.set_item(key, item);
+        let mut ido = Ido::new();
+        for item in items {
+            ido.set_item(&item.m_key, item.clone());
+        }
+        ido.clear_dirty();
+        ido
     }
 
-    /// Sets an integer value associated with the given key.
+    /// Creates a new Ido holding deep copies of the items whose keys fall in
+    /// a range.
+    ///
+    /// The copied items keep the order in which they were populated here.
+    /// Like `clone_subset`, the new Ido starts without listeners, journal,
+    /// transaction or dirty keys.
     ///
     /// # Arguments
     ///
-    /// * `key` - The key associated with the value.
-    /// * `val` - The integer value to be set.
+    /// * `range` - The range of key values to copy, such as `100..200`.
     ///
     /// # Example
     ///
     /// ```
-    /// use ido::{Ido};
-    /// let mut ido = Ido::new();
-    /// ido.set_integer(&1, 42);
+    /// use ido::Ido;
+    ///
+    /// let mut order = Ido::new();
+    /// order.set_string(&1, "ORDER-1".to_string());
+    /// order.set_string(&101, "EURUSD".to_string());
+    /// order.set_integer(&100, 5);
+    /// order.set_integer(&200, 7);
+    ///
+    /// let instrument = order.get_range(100..200);
+    /// assert_eq!(instrument.to_string(), "101=EURUSD,100=5");
     /// ```
-    pub fn set_integer (&mut self, key: &IdoKeyT, val: i64)
-    {
-        let mut item: IdoItem = IdoItem::new();
-        item.m_type = IdoItemType::INTEGER;
-        item.m_integer = val;
-
-        self.set_item(key, item);
+    pub fn get_range<R: RangeBounds<IdoKeyT>>(&self, range: R) -> Ido {
+        let mut ido = Ido::new();
+        for (key, item) in self.ordered_items() {
+            if range.contains(&key) && self.live_item(&key).is_some() {
+                ido.set_item(&key, item.clone());
+            }
+        }
+        ido.clear_dirty();
+        ido
     }
 
-    /// Sets a floating-point value associated with the given key.
+    /// Moves the items matching a predicate into a new Ido.
+    ///
+    /// Both Idos keep the order in which the items were populated. Removing
+    /// the items counts as deleting them here, so listeners, the journal and
+    /// dirty tracking see one deletion per moved key. The new Ido starts
+    /// without listeners, journal, transaction or dirty keys.
     ///
     /// # Arguments
     ///
-    /// * `key` - The key associated with the value.
-    /// * `val` - The floating-point value to be set.
+    /// * `predicate` - Called with every key and item; `true` moves the item.
+    ///
+    /// # Returns
+    ///
+    /// A new Ido holding the matching items.
     ///
     /// # Example
     ///
     /// ```
-    /// use ido::{Ido};
-    /// let mut ido = Ido::new();
-    /// ido.set_f64(&1, 3.14159);
+    /// use ido::Ido;
+    ///
+    /// let mut message = Ido::new();
+    /// message.set_string(&1, "gateway-1".to_string());
+    /// message.set_integer(&100, 5);
+    /// message.set_string(&2, "orders".to_string());
+    ///
+    /// let headers = message.split_off(|key, _| key < 100);
+    /// assert_eq!(headers.to_string(), "1=gateway-1,2=orders");
+    /// assert_eq!(message.to_string(), "100=5");
     /// ```
-    pub fn set_f64 (&mut self, key: &IdoKeyT, val: f64)
+    pub fn split_off<F>(&mut self, mut predicate: F) -> Ido
+    where
+        F: FnMut(IdoKeyT, &IdoItem) -> bool
     {
-        let mut item: IdoItem = IdoItem::new();
-        item.m_type = IdoItemType::FLOAT;
-        item.m_float = val;
+        let keys: Vec<IdoKeyT> = self
+            .ordered_items()
+            .filter(|(key, item)| self.live_item(key).is_some() && predicate(*key, item))
+            .map(|(key, _)| key)
+            .collect();
 
-        self.set_item(key, item);
+        let mut ido = Ido::new();
+        for key in keys {
+            if let Some(value) = self.m_items.remove(&key) {
+                self.m_ordered.remove(&value.m_index);
+                self.m_bytes -= value.approx_bytes();
+                self.record_change(key, Some(&value));
+                self.m_ops.count_delete();
+                ido.set_item(&key, value);
+            }
+        }
+        ido.clear_dirty();
+        ido
     }
 
-    /// Retrieves an item from the collection using the specified key.
+    /// Sets every key of the template that is not present yet.
     ///
-    /// If an item is found in the collection associated with the provided key, a clone of the item is returned within a `Some` variant.
-    /// If no item is found, `None` is returned.
+    /// Existing values are kept, which makes this the counterpart of
+    /// `update`, where the other Ido wins.
     ///
     /// # Arguments
     ///
-    /// * `key`: A reference to a key of type `IdoKeyT` used for item lookup.
+    /// * `template` - The Ido holding the default values.
     ///
-    /// # Example
+    /// # Returns
+    ///
+    /// The number of keys that were filled in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut template = Ido::new();
+    /// template.set_string(&1, "NEW".to_string());
+    /// template.set_string(&2, "GTC".to_string());
+    ///
+    /// let mut order = Ido::new();
+    /// order.set_string(&2, "IOC".to_string());
+    ///
+    /// assert_eq!(order.apply_defaults(&template), 1);
+    /// assert_eq!(order.to_string(), "2=IOC,1=NEW");
+    /// ```
+    pub fn apply_defaults(&mut self, template: &Ido) -> usize {
+        let mut applied = 0;
+
+        for (key, value) in template.ordered_items() {
+            if !self.contains(&key) && template.contains(&key) {
+                self.set_item(&key, value.clone());
+                applied += 1;
+            }
+        }
+        applied
+    }
+
+    /// Checks if the `Ido` object contains the specified key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A reference to the key (`IdoKeyT`) to be checked.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the `Ido` object contains the specified key, `false` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 25);
+    /// if ido.contains(&1) {
+    ///     println!("contains key");
+    /// }
+    /// ```
+    pub fn contains(&self, key: &impl IdoKey) -> bool {
+        let key = &key.ido_key();
+        self.live_item(key).is_some()
+    }
+
+    /// Checks that every key in `keys` is present.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys that must be present.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingKeys` listing all absent keys, not just the first one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "ORDER-1".to_string());
+    ///
+    /// let err = ido.require(&[1, 2, 3]).unwrap_err();
+    /// assert_eq!(err.keys(), &[2, 3]);
+    /// assert_eq!(err.to_string(), "missing required keys: 2, 3");
+    /// ```
+    pub fn require(&self, keys: &[IdoKeyT]) -> Result<(), MissingKeys> {
+        let missing: Vec<IdoKeyT> = keys.iter().copied().filter(|key| !self.contains(key)).collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(MissingKeys(missing))
+        }
+    }
+
+    /// Checks if the value associated with the given key has the specified type.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A reference to the key for which the type needs to be checked.
+    /// * `ty` - A reference to the `IdoItemType` enum representing the expected type.
+    ///
+    /// # Returns
+    ///
+    /// A boolean value indicating whether the value associated with the key has the expected type.
+    /// - `true` if the value has the expected type.
+    /// - `false` if the value does not exist or has a different type.
+    pub fn is_type(&self, key: &impl IdoKey, ty: &IdoItemType) -> bool
+    {
+        let key = &key.ido_key();
+        if let Some(value) = self.live_item(key) {
+            value.m_type == *ty
+        } else {
+            false       
+        }
+    }
+
+    /// Sets an item with the specified key in the internal storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A reference to the key associated with the item.
+    /// * `item` - The item to be set.
+    ///
+    /// # Remarks
+    ///
+    /// This function assigns the given item to the specified key in the internal storage.
+    /// It updates the item's key, assigns an index, and inserts the item into the storage map and ordered set.
+    /// The index is incremented to maintain ordering.
+    ///
+    /// # Rejected writes
+    ///
+    /// When strict mode, the float, capacity or key range policy or the
+    /// memory budget refuses the item, nothing is written and the error is
+    /// kept for [`take_rejected`](Ido::take_rejected); use
+    /// [`try_set_item`](Ido::try_set_item) to handle it at the call site.
+    /// The same applies to every setter built on this one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoKeyT, IdoItem};
+    ///
+    /// let mut ido = Ido::new();
+    /// let item = IdoItem::new();
+    ///
+    /// ido.set_item(&42, item);
+    /// ```
+    pub fn set_item(&mut self, key: &impl IdoKey, item: IdoItem)
+    {
+        let key = &key.ido_key();
+        if let Err(err) = self.try_set_item(key, item) {
+            self.reject(err);
+        }
+    }
+
+    /// Sets an item, returning the error when strict mode or a policy
+    /// rejects it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IdoError::TypeMismatch` if strict mode is enabled and the key
+    /// already holds a different type, `IdoError::NonFinite` if the float
+    /// policy rejects the value, `IdoError::CapacityExceeded` if the
+    /// capacity policy refuses a new key, `IdoError::MemoryExceeded` if the
+    /// item would take the Ido beyond [`IdoLimits::max_bytes`], and
+    /// `IdoError::ForeignKey` if the key is reserved for another owner (see
+    /// [`set_key_ranges`](Ido::set_key_ranges)). The Ido is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoItem};
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_strict(true);
+    /// ido.set_integer(&1, 10);
+    ///
+    /// assert!(ido.try_set_item(&1, IdoItem::new()).is_err());
+    /// assert_eq!(ido.get_i64(&1), Some(10));
+    /// ```
+    pub fn try_set_item(&mut self, key: &impl IdoKey, mut item: IdoItem) -> Result<(), IdoError> {
+        let key = &key.ido_key();
+        self.check_type(key, item.m_type)?;
+        if !self.check_float(key, &item)? {
+            self.delete_item(key);
+            return Ok(());
+        }
+        self.check_range(key)?;
+        self.check_bytes(key, item.approx_bytes())?;
+        self.make_room(key)?;
+        item.m_expires = None;
+        self.store_item(key, item);
+        Ok(())
+    }
+
+    /// Enables or disables strict typing.
+    ///
+    /// In strict mode a key keeps the type it was first set with: storing a
+    /// value of another type, or appending to a key that is not an array, is
+    /// rejected with `IdoError::TypeMismatch`. Deleting the key lifts the
+    /// restriction. Strict mode is off by default.
+    ///
+    /// The `try_` setters ([`try_set_item`](Ido::try_set_item),
+    /// [`try_append_array`](Ido::try_append_array) and
+    /// [`try_append_value`](Ido::try_append_value)) return the error. The
+    /// other setters skip the write and keep the error for
+    /// [`take_rejected`](Ido::take_rejected).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoError, IdoItemType};
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_strict(true);
+    /// ido.set_integer(&1, 10);
+    /// ido.set_string(&1, "ten".to_string());
+    ///
+    /// assert_eq!(ido.get_i64(&1), Some(10));
+    /// assert_eq!(
+    ///     ido.take_rejected(),
+    ///     Some(IdoError::TypeMismatch { key: 1, expected: IdoItemType::INTEGER, actual: IdoItemType::STRING })
+    /// );
+    /// ```
+    pub fn set_strict(&mut self, strict: bool) {
+        self.m_strict = strict;
+    }
+
+    /// Returns `true` if strict typing is enabled.
+    pub fn is_strict(&self) -> bool {
+        self.m_strict
+    }
+
+    /// Returns the error of the last write the setters without a `try_`
+    /// counterpart refused, and clears it.
+    ///
+    /// Those setters never panic on a refused value: they leave the Ido
+    /// unchanged and keep the error here, so a caller can check it after a
+    /// batch of writes.
+    pub fn take_rejected(&mut self) -> Option<IdoError> {
+        self.m_rejected.take()
+    }
+
+    /// Skips a refused write, keeping its error for `take_rejected`.
+    fn reject(&mut self, err: IdoError) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(error = %err, "write rejected");
+        self.m_rejected = Some(err);
+    }
+
+    /// Sets how NaN and infinite FLOAT values are handled.
+    ///
+    /// The policy applies to every setter of this Ido, but not to the Idos
+    /// nested in its arrays. The default, [`FloatPolicy::Allow`], stores
+    /// them like any other value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{FloatPolicy, Ido};
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_float_policy(FloatPolicy::Null);
+    /// ido.set_f64(&1, 1.5);
+    /// ido.set_f64(&1, f64::NAN);
+    ///
+    /// assert!(!ido.contains(&1));
+    /// ```
+    pub fn set_float_policy(&mut self, policy: FloatPolicy) {
+        self.m_float_policy = policy;
+    }
+
+    /// Returns how NaN and infinite FLOAT values are handled.
+    pub fn float_policy(&self) -> FloatPolicy {
+        self.m_float_policy
+    }
+
+    /// Sets what the narrowing getters (`get_i32`, `get_i16`, `get_i8`,
+    /// `get_u64`, `get_u32`, `get_u16`, `get_u8` and `try_get_int`) do with
+    /// an INTEGER that does not fit in the requested type.
+    ///
+    /// The policy applies to this Ido, but not to the Idos nested in its
+    /// arrays. The default, [`OverflowPolicy::Wrap`], keeps the low bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, OverflowPolicy};
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 300);
+    /// assert_eq!(ido.get_u8(&1), Some(44));
+    ///
+    /// ido.set_overflow_policy(OverflowPolicy::Saturate);
+    /// assert_eq!(ido.get_u8(&1), Some(255));
+    ///
+    /// ido.set_overflow_policy(OverflowPolicy::Null);
+    /// assert_eq!(ido.get_u8(&1), None);
+    /// assert_eq!(ido.get_u16(&1), Some(300));
+    /// ```
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.m_overflow_policy = policy;
+    }
+
+    /// Returns what the narrowing getters do with out-of-range values.
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.m_overflow_policy
+    }
+
+    /// Sets the limits enforced by `append_array` and `try_append_array`.
+    /// The memory budget, `max_bytes`, is enforced by every setter.
+    ///
+    /// Limits only apply to later changes; the current content is not
+    /// checked, and a change that does not grow the Ido always succeeds.
+    /// Idos decoded with `decode_with_limits` keep the limits they were
+    /// decoded with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoLimits};
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_limits(IdoLimits { max_array_len: Some(2), ..IdoLimits::default() });
+    ///
+    /// ido.append_array(&1, Ido::new());
+    /// ido.append_array(&1, Ido::new());
+    /// assert!(ido.try_append_array(&1, Ido::new()).is_err());
+    /// ```
+    pub fn set_limits(&mut self, limits: IdoLimits) {
+        self.m_limits = limits;
+    }
+
+    /// Returns the limits enforced by `append_array`.
+    pub fn limits(&self) -> IdoLimits {
+        self.m_limits
+    }
+
+    /// Bounds the number of items, protecting caches from an upstream that
+    /// keeps sending new keys.
+    ///
+    /// Only new keys are affected: replacing or appending to an existing
+    /// key always succeeds. Items still held but expired count towards the
+    /// capacity until they are purged. Setting a capacity below the current
+    /// size removes nothing until the next new key. Evictions are reported
+    /// to listeners, the journal and any open transaction like deletions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{CapacityPolicy, Ido};
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_capacity(CapacityPolicy::EvictOldest(2));
+    /// ido.set_integer(&1, 10);
+    /// ido.set_integer(&2, 20);
+    /// ido.set_integer(&3, 30);
+    /// assert_eq!(ido.to_string(), "2=20,3=30");
+    ///
+    /// ido.set_capacity(CapacityPolicy::Reject(2));
+    /// assert!(ido.try_set_item(&4, ido.get_item(&3).unwrap()).is_err());
+    /// ido.set_integer(&3, 31);
+    /// assert_eq!(ido.to_string(), "2=20,3=31");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics on `CapacityPolicy::EvictOldest(0)`, which could never store
+    /// anything.
+    pub fn set_capacity(&mut self, policy: CapacityPolicy) {
+        assert!(policy != CapacityPolicy::EvictOldest(0), "evicting capacity must be at least 1");
+        self.m_capacity = policy;
+    }
+
+    /// Returns the capacity policy.
+    pub fn capacity(&self) -> CapacityPolicy {
+        self.m_capacity
+    }
+
+    /// Returns the approximate number of bytes held by the items, including
+    /// those of nested Idos.
+    ///
+    /// The estimate counts the fixed size of every item plus the length of
+    /// its string; it is kept up to date as the Ido changes, so reading it
+    /// is cheap. See [`IdoLimits::max_bytes`] to bound it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 10);
+    /// let one = ido.memory_used();
+    ///
+    /// ido.set_string(&2, "EURUSD".to_string());
+    /// assert_eq!(ido.memory_used(), 2 * one + 6);
+    ///
+    /// ido.delete_item(&2);
+    /// assert_eq!(ido.memory_used(), one);
+    /// ```
+    pub fn memory_used(&self) -> usize {
+        self.m_bytes
+    }
+
+    /// Checks the memory budget for storing an item of `bytes` under `key`.
+    fn check_bytes(&self, key: &IdoKeyT, bytes: usize) -> Result<(), IdoError> {
+        let replaced = self.m_items.get(key).map_or(0, IdoItem::approx_bytes);
+        self.m_limits.check_bytes(*key, self.m_bytes, self.m_bytes - replaced + bytes)
+    }
+
+    /// Makes room for an item under `key` according to the capacity policy.
+    fn make_room(&mut self, key: &IdoKeyT) -> Result<(), IdoError> {
+        if self.m_items.contains_key(key) {
+            return Ok(());
+        }
+        match self.m_capacity {
+            CapacityPolicy::Unbounded => Ok(()),
+            CapacityPolicy::Reject(max) if self.m_items.len() >= max => {
+                Err(IdoError::CapacityExceeded { key: *key, max })
+            }
+            CapacityPolicy::Reject(_) => Ok(()),
+            CapacityPolicy::EvictOldest(max) => {
+                while self.m_items.len() >= max {
+                    let Some((_, oldest)) = self.m_ordered.first_key_value() else { break };
+                    let oldest = *oldest;
+                    self.delete_item(&oldest);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// The length of the longest array at any depth.
+    fn longest_array(&self) -> usize {
+        self.ordered_items()
+            .map(|(_, item)| item.m_array.iter().map(Ido::longest_array).fold(item.m_array.len(), usize::max))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Sets how `to_string` renders DATETIME values, including those of
+    /// nested Idos.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "chrono")]
+    /// # {
+    /// use chrono::{TimeZone, Utc};
+    /// use ido::{Ido, IdoItem, IdoItemType};
+    /// use ido::datetime::DateTimeFormat;
+    ///
+    /// let mut item = IdoItem::new();
+    /// item.m_type = IdoItemType::DATETIME;
+    /// item.m_datetime = Utc.with_ymd_and_hms(2023, 7, 1, 12, 30, 0).unwrap();
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_item(&1, item);
+    /// ido.set_datetime_format(DateTimeFormat::rfc3339());
+    ///
+    /// assert_eq!(ido.to_string(), "1=2023-07-01T12:30:00Z");
+    /// # }
+    /// ```
+    pub fn set_datetime_format(&mut self, format: DateTimeFormat) {
+        self.m_datetime_format = format;
+    }
+
+    /// Returns how `to_string` renders DATETIME values.
+    pub fn datetime_format(&self) -> &DateTimeFormat {
+        &self.m_datetime_format
+    }
+
+    /// Attaches a dictionary used to name keys in textual output.
+    ///
+    /// `to_string`, `write_to`, `to_table`, `to_html`, `to_markdown`,
+    /// `diff_report` and `change_log` then write every key found in `names`
+    /// as its name, including the keys of nested Idos. Keys stay numeric
+    /// everywhere else: the dictionary is not encoded and does not take
+    /// part in comparisons. Clones share the dictionary.
+    ///
+    /// [`with_names`](Ido::with_names) names keys for a single call instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use ido::Ido;
+    /// use ido::dictionary::IdoDictionary;
+    ///
+    /// let names: IdoDictionary = [(7, "price")].into_iter().collect();
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_f64(&7, 101.3);
+    /// ido.set_integer(&8, 100);
+    /// ido.set_names(Arc::new(names));
+    ///
+    /// assert_eq!(ido.to_string(), "price=101.3,8=100");
+    /// assert_eq!(ido.get_f64(&7), Some(101.3));
+    ///
+    /// ido.clear_names();
+    /// assert_eq!(ido.to_string(), "7=101.3,8=100");
+    /// ```
+    pub fn set_names(&mut self, names: Arc<IdoDictionary>) {
+        self.m_names = Some(names);
+    }
+
+    /// Detaches the dictionary set with `set_names`.
+    pub fn clear_names(&mut self) {
+        self.m_names = None;
+    }
+
+    /// Returns the dictionary set with `set_names`.
+    pub fn names(&self) -> Option<&IdoDictionary> {
+        self.m_names.as_deref()
+    }
+
+    /// Renders the Ido like `to_string`, but with datetimes in `format`
+    /// instead of the format set on the Ido.
+    pub fn to_string_with_format(&self, format: &DateTimeFormat) -> String {
+        let mut out = String::with_capacity(self.text_len_hint());
+        // Writing to a String cannot fail.
+        let _ = self.write_items(&mut out, self.names(), format);
+        out
+    }
+
+    /// Renders the Ido like `to_string`, letting `custom` override how
+    /// individual values are written.
+    ///
+    /// `custom` is called with every key and item, including those of Idos
+    /// nested in arrays. When it returns `Some`, that text is written as the
+    /// value; when it returns `None`, the item is rendered as usual. A
+    /// replaced ARRAY is written as `key=text`, without its elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoKeyT};
+    ///
+    /// const PASSWORD: IdoKeyT = 554;
+    /// const PRICE: IdoKeyT = 44;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "ORDER-1".to_string());
+    /// ido.set_string(&PASSWORD, "secret".to_string());
+    /// ido.set_f64(&PRICE, 101.25);
+    ///
+    /// let text = ido.to_string_with(|key, item| match key {
+    ///     PASSWORD => Some("***".to_string()),
+    ///     PRICE => Some(format!("{} USD", item.m_float)),
+    ///     _ => None,
+    /// });
+    /// assert_eq!(text, "1=ORDER-1,554=***,44=101.25 USD");
+    /// ```
+    pub fn to_string_with<F>(&self, mut custom: F) -> String
+    where
+        F: FnMut(IdoKeyT, &IdoItem) -> Option<String>,
+    {
+        let mut out = String::with_capacity(self.text_len_hint());
+        // Writing to a String cannot fail.
+        let _ = self.write_items_with(&mut out, self.names(), &self.m_datetime_format, false, &mut custom);
+        out
+    }
+
+    /// Renders the Ido like `to_string`, with each array element prefixed
+    /// by its index.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut leg = Ido::new();
+    /// leg.set_string(&1, "EURUSD".to_string());
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.append_array(&3, leg.clone());
+    /// ido.append_array(&3, leg);
+    ///
+    /// assert_eq!(ido.to_string(), "3=[{1=EURUSD},{1=EURUSD}]");
+    /// assert_eq!(ido.to_string_indexed(), "3=[0:{1=EURUSD},1:{1=EURUSD}]");
+    /// ```
+    pub fn to_string_indexed(&self) -> String {
+        let mut out = String::with_capacity(self.text_len_hint());
+        // Writing to a String cannot fail.
+        let _ = self.write_items_with(&mut out, self.names(), &self.m_datetime_format, true, &mut |_, _| None);
+        out
+    }
+
+    /// Renders the Ido in its `key=value` form into `out`, without building
+    /// an intermediate String.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer to append to, e.g. a reused String or a log
+    ///   buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `out`, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "ORDER-1".to_string());
+    /// ido.set_integer(&2, 100);
+    ///
+    /// let mut line = String::from("order: ");
+    /// ido.write_to(&mut line).unwrap();
+    /// assert_eq!(line, "order: 1=ORDER-1,2=100");
+    /// ```
+    pub fn write_to<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        self.write_items(out, self.names(), &self.m_datetime_format)
+    }
+
+    /// Converts the Ido object to a string representation.
+    ///
+    /// Returns a string that represents the Ido object, with key-value pairs separated by commas.
+    /// The key-value pairs are sorted based on the order of insertion. An ARRAY is written once,
+    /// with its elements in braces, as in `3=[{1=EURUSD},{1=GBPUSD}]`.
+    ///
+    /// Renders the same text as `Display`, but allocates the String once
+    /// with an estimate of the rendered length instead of growing it as
+    /// items are written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, String::from("John"));
+    /// ido.set_integer(&2, 30);
+    /// ido.set_string(&3, String::from("New York"));
+    ///
+    /// let result = ido.to_string();
+    /// ```
+    #[allow(clippy::inherent_to_string_shadow_display)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", name = "ido.to_string", skip_all, fields(items = self.size()))
+    )]
+    pub fn to_string(&self) -> String {
+        self.to_string_with_format(&self.m_datetime_format)
+    }
+
+    /// An estimate of the length of the `key=value` form.
+    fn text_len_hint(&self) -> usize {
+        self.live_items()
+            .map(|(_, item)| {
+                let value = match item.m_type {
+                    IdoItemType::STRING => item.m_string.len(),
+                    IdoItemType::INTEGER | IdoItemType::FLOAT => 12,
+                    IdoItemType::INTEGER128 => 24,
+                    IdoItemType::BOOL => 5,
+                    #[cfg(feature = "chrono")]
+                    IdoItemType::DATETIME => 30,
+                    IdoItemType::ARRAY => {
+                        return item.m_array.iter().map(|element| element.text_len_hint() + 8).sum::<usize>();
+                    }
+                };
+                value + 6
+            })
+            .sum()
+    }
+
+    /// Applies the float policy to `item`, returning `false` if it should
+    /// be dropped instead of stored.
+    fn check_float(&self, key: &IdoKeyT, item: &IdoItem) -> Result<bool, IdoError> {
+        if item.m_type != IdoItemType::FLOAT || item.m_float.is_finite() {
+            return Ok(true);
+        }
+        match self.m_float_policy {
+            FloatPolicy::Allow => Ok(true),
+            FloatPolicy::Reject => Err(IdoError::NonFinite { key: *key, value: item.m_float }),
+            FloatPolicy::Null => Ok(false),
+        }
+    }
+
+    /// Returns whether the FLOAT value stored under the given key is finite.
+    ///
+    /// # Returns
+    ///
+    /// `Some(false)` for NaN and infinities, `Some(true)` for other FLOAT
+    /// values and `None` if the key is missing or not a FLOAT.
+    pub fn is_finite(&self, key: &impl IdoKey) -> Option<bool> {
+        let key = &key.ido_key();
+        self.live_item(key)
+            .filter(|value| value.m_type == IdoItemType::FLOAT)
+            .map(|value| value.m_float.is_finite())
+    }
+
+    /// In strict mode, rejects storing `ty` under a key holding another type.
+    fn check_type(&self, key: &IdoKeyT, ty: IdoItemType) -> Result<(), IdoError> {
+        match self.live_item(key) {
+            Some(value) if self.m_strict && value.m_type != ty => Err(IdoError::TypeMismatch {
+                key: *key,
+                expected: value.m_type,
+                actual: ty,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Assigns the key, index, version and timestamp of `item` and stores it.
+    fn store_item(&mut self, key: &IdoKeyT, mut item: IdoItem) {
+        item.m_key = *key;
+        item.m_index = self.m_idx;
+        item.m_version = self.next_version(key, 0);
+        item.m_modified = Some(SystemTime::now());
+        if let Some(value) = self.m_items.get(key) {
+            item.m_flags |= value.m_flags;
+        }
+
+        self.m_bytes += item.approx_bytes();
+        let old = self.m_items.insert(*key, item);
+        if let Some(value) = &old {
+            self.m_ordered.remove(&value.m_index);
+            self.m_bytes -= value.approx_bytes();
+        }
+        self.m_ordered.insert(self.m_idx, *key);
+
+        self.m_idx += 1;
+        self.record_change(*key, old.as_ref());
+        self.m_ops.count_set();
+        audit::record_write(*key);
+    }
+
+    /// Returns the item stored under `key` unless it has expired.
+    fn live_item(&self, key: &IdoKeyT) -> Option<&IdoItem> {
+        self.m_items.get(key).filter(|value| !value.is_expired(SystemTime::now()))
+    }
+
+    /// Returns the item stored under `key` for changing it in place, unless
+    /// it has expired.
+    fn live_item_mut(&mut self, key: &IdoKeyT) -> Option<&mut IdoItem> {
+        self.m_items.get_mut(key).filter(|value| !value.is_expired(SystemTime::now()))
+    }
+
+    /// Puts an item back at the insertion position recorded in its index.
+    ///
+    /// Falls back to storing it as a new item if that position has since
+    /// been reused. Restoring is never subject to strict mode.
+    pub(crate) fn restore_item(&mut self, key: IdoKeyT, mut item: IdoItem) {
+        let index = item.m_index;
+        if self.m_ordered.get(&index).is_some_and(|owner| *owner != key) {
+            return self.store_item(&key, item);
+        }
+        item.m_version = self.next_version(&key, item.m_version);
+        item.m_modified = Some(SystemTime::now());
+
+        self.m_bytes += item.approx_bytes();
+        let old = self.m_items.insert(key, item);
+        if let Some(value) = &old {
+            self.m_ordered.remove(&value.m_index);
+            self.m_bytes -= value.approx_bytes();
+        }
+        self.m_ordered.insert(index, key);
+        self.record_change(key, old.as_ref());
+    }
+
+    /// Returns the version for the next change of `key`, which is never lower
+    /// than `floor + 1`.
+    fn next_version(&self, key: &IdoKeyT, floor: u64) -> u64 {
+        let current = self.m_items.get(key).map_or(0, |value| value.m_version);
+        current.max(floor) + 1
+    }
+
+    /// Puts `key` back into the given state, deleting it for `None`.
+    pub(crate) fn revert_to(&mut self, key: IdoKeyT, item: Option<IdoItem>) {
+        match item {
+            Some(item) => self.restore_item(key, item),
+            None => self.delete_item(&key),
+        }
+    }
+
+    /// Bookkeeping shared by every mutation, run after the change has been applied.
+    fn record_change(&mut self, key: IdoKeyT, old: Option<&IdoItem>) {
+        let new = self.m_items.get(&key);
+
+        self.m_dirty.insert(key);
+        if let Some(transaction) = &mut self.m_transaction {
+            transaction.record(key, old);
+        }
+        if let Some(journal) = &mut self.m_journal {
+            journal.record(key, old, new);
+        }
+        self.m_listeners.notify(key, old, new);
+    }
+
+    /// Whether any bookkeeping needs a copy of an item before it is changed in place.
+    fn needs_old_items(&self) -> bool {
+        !self.m_listeners.is_empty() || self.m_journal.is_some() || self.m_transaction.is_some()
+    }
+
+    /// Returns the version of the item stored under the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the item.
+    ///
+    /// # Returns
+    ///
+    /// `Some(version)` if the key exists, `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 10);
+    /// ido.set_integer(&1, 11);
+    ///
+    /// assert_eq!(ido.item_version(&1), Some(2));
+    /// ```
+    pub fn item_version(&self, key: &impl IdoKey) -> Option<u64> {
+        let key = &key.ido_key();
+        self.live_item(key).map(|value| value.m_version)
+    }
+
+    /// Returns when the item stored under the given key was last changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the item.
+    ///
+    /// # Returns
+    ///
+    /// `Some(timestamp)` if the key exists, `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::{Duration, Utc};
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_f64(&1, 101.25);
+    ///
+    /// let age = Utc::now() - ido.modified_at(&1).unwrap();
+    /// assert!(age < Duration::seconds(5));
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn modified_at(&self, key: &impl IdoKey) -> Option<DateTime<Utc>> {
+        let key = &key.ido_key();
+        self.live_item(key).map(IdoItem::modified_at)
+    }
+
+    /// Returns the keys of all items changed at or after the given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - The earliest modification time to include.
+    ///
+    /// # Returns
+    ///
+    /// The matching keys in the order the items were populated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::Utc;
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_f64(&1, 101.25);
+    ///
+    /// let checkpoint = Utc::now();
+    /// ido.set_f64(&2, 101.50);
+    ///
+    /// assert_eq!(ido.items_modified_since(checkpoint), vec![2]);
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn items_modified_since(&self, since: DateTime<Utc>) -> Vec<IdoKeyT> {
+        let since = SystemTime::from(since);
+        self.ordered_items()
+            .filter(|(_, value)| value.m_modified.is_some_and(|modified| modified >= since))
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Attaches a metadata flag to the item stored under the given key.
+    ///
+    /// Flags stay attached when the key is overwritten, until they are
+    /// removed with [`clear_flag`](Ido::clear_flag) or the key is deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the item.
+    /// * `flag` - The flag, or combination of flags, to set.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the key exists and the flag was set, `false` otherwise,
+    /// including when the item has expired.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoFlags};
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "4111111111111111".to_string());
+    /// ido.set_flag(&1, IdoFlags::SENSITIVE);
+    ///
+    /// ido.set_string(&1, "5500000000000004".to_string());
+    /// assert!(ido.has_flag(&1, IdoFlags::SENSITIVE));
+    /// ```
+    pub fn set_flag(&mut self, key: &impl IdoKey, flag: IdoFlags) -> bool {
+        let key = &key.ido_key();
+        match self.live_item_mut(key) {
+            Some(value) => {
+                value.m_flags |= flag;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes a metadata flag from the item stored under the given key.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the key exists, `false` otherwise, including when the item
+    /// has expired.
+    pub fn clear_flag(&mut self, key: &impl IdoKey, flag: IdoFlags) -> bool {
+        let key = &key.ido_key();
+        match self.live_item_mut(key) {
+            Some(value) => {
+                value.m_flags = value.m_flags & !flag;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Checks whether the item stored under the given key carries a flag.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the key exists and has every flag in `flag` set.
+    pub fn has_flag(&self, key: &impl IdoKey, flag: IdoFlags) -> bool {
+        let key = &key.ido_key();
+        self.live_item(key).is_some_and(|value| value.m_flags.contains(flag))
+    }
+
+    /// Returns the keys of all items carrying the given flag, in the order
+    /// they were populated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoFlags};
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "ACC-1".to_string());
+    /// ido.set_string(&2, "secret".to_string());
+    /// ido.set_flag(&2, IdoFlags::SENSITIVE);
+    ///
+    /// for key in ido.keys_with_flag(IdoFlags::SENSITIVE) {
+    ///     ido.set_string(&key, "***".to_string());
+    /// }
+    /// assert_eq!(ido.get_string(&2), Some("***".to_string()));
+    /// ```
+    pub fn keys_with_flag(&self, flag: IdoFlags) -> Vec<IdoKeyT> {
+        let now = SystemTime::now();
+        self.ordered_items()
+            .filter(|(_, value)| !value.is_expired(now) && value.m_flags.contains(flag))
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Sets an item that expires once the given time to live has passed.
+    ///
+    /// Expired items are no longer returned by the getters, `contains` or
+    /// `is_type`, and are skipped by iteration, `update` and every output
+    /// format, but stay in the Ido until [`expire`](Ido::expire) removes
+    /// them. Setting the key again without a TTL clears the deadline.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the item.
+    /// * `item` - The item to store.
+    /// * `ttl` - How long the item stays valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoItem, IdoItemType};
+    /// use std::time::Duration;
+    ///
+    /// let mut item = IdoItem::new();
+    /// item.m_type = IdoItemType::FLOAT;
+    /// item.m_float = 101.25;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_with_ttl(&1, item, Duration::from_secs(5));
+    ///
+    /// assert_eq!(ido.get_f64(&1), Some(101.25));
+    /// # #[cfg(feature = "chrono")]
+    /// assert!(ido.get_item(&1).unwrap().expires_at().is_some());
+    /// ```
+    ///
+    /// # Rejected writes
+    ///
+    /// The item is refused like one passed to [`set_item`](Ido::set_item):
+    /// nothing is written and the error is kept for
+    /// [`take_rejected`](Ido::take_rejected). Use
+    /// [`try_set_with_ttl`](Ido::try_set_with_ttl) to handle it at the call
+    /// site.
+    pub fn set_with_ttl(&mut self, key: &impl IdoKey, item: IdoItem, ttl: Duration) {
+        let key = &key.ido_key();
+        if let Err(err) = self.try_set_with_ttl(key, item, ttl) {
+            self.reject(err);
+        }
+    }
+
+    /// Sets an item that expires after `ttl`, returning the error when
+    /// strict mode, a policy or the memory budget rejects it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`try_set_item`](Ido::try_set_item), and
+    /// leaves the Ido unchanged.
+    pub fn try_set_with_ttl(&mut self, key: &impl IdoKey, mut item: IdoItem, ttl: Duration) -> Result<(), IdoError> {
+        let key = &key.ido_key();
+        self.check_type(key, item.m_type)?;
+        if !self.check_float(key, &item)? {
+            self.delete_item(key);
+            return Ok(());
+        }
+        self.check_range(key)?;
+        self.check_bytes(key, item.approx_bytes())?;
+        self.make_room(key)?;
+        item.m_expires = SystemTime::now().checked_add(ttl);
+        self.store_item(key, item);
+        Ok(())
+    }
+
+    /// Removes every item whose time to live has passed.
+    ///
+    /// The removals are reported to listeners, the journal and any open
+    /// transaction like regular deletes.
+    ///
+    /// # Returns
+    ///
+    /// The number of items removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoItem};
+    /// use std::time::Duration;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_with_ttl(&1, IdoItem::new(), Duration::ZERO);
+    ///
+    /// assert!(!ido.contains(&1));
+    /// assert_eq!(ido.size(), 1);
+    /// assert_eq!(ido.expire(), 1);
+    /// assert_eq!(ido.size(), 0);
+    /// ```
+    pub fn expire(&mut self) -> usize {
+        let now = SystemTime::now();
+        let expired: Vec<IdoKeyT> = self
+            .ordered_items()
+            .filter(|(_, value)| value.is_expired(now))
+            .map(|(key, _)| key)
+            .collect();
+
+        for key in &expired {
+            self.delete_item(key);
+        }
+        expired.len()
+    }
+
+    /// Sets an item only if the current version of the key matches `expected`.
+    ///
+    /// This allows optimistic concurrency: read the version, compute the new
+    /// value, and write it back only if nobody changed the key in between.
+    /// An `expected` version of 0 means the key must not exist yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the item.
+    /// * `expected` - The version the caller last observed.
+    /// * `item` - The item to store.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IdoError::VersionMismatch` if the key has a different version,
+    /// or `IdoError::TypeMismatch` if strict mode rejects the item. The Ido is
+    /// left unchanged in both cases.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoItem};
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 10);
+    /// let version = ido.item_version(&1).unwrap();
+    ///
+    /// let mut item = ido.get_item(&1).unwrap();
+    /// item.m_integer += 1;
+    ///
+    /// assert!(ido.set_if_version(&1, version, item.clone()).is_ok());
+    /// assert!(ido.set_if_version(&1, version, item).is_err());
+    /// ```
+    pub fn set_if_version(&mut self, key: &impl IdoKey, expected: u64, item: IdoItem) -> Result<(), IdoError> {
+        let key = &key.ido_key();
+        let actual = self.item_version(key).unwrap_or(0);
+        if actual != expected {
+            return Err(IdoError::VersionMismatch { key: *key, expected, actual });
+        }
+
+        self.try_set_item(key, item)
+    }
+
+    /// Sets a string value associated with the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key associated with the value.
+    /// * `val` - The string value to be set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido};
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "John Doe".to_string());
+    /// ```
+    pub fn set_string (&mut self, key: &impl IdoKey, val: String)
+    {
+        let key = &key.ido_key();
+        let mut item: IdoItem = IdoItem::new();
+        item.m_type = IdoItemType::STRING;
+        item.m_string = val;
+
+        self.set_item(key, item);
+    }
+
+    /// Sets an integer value associated with the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key associated with the value.
+    /// * `val` - The integer value to be set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido};
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 42);
+    /// ```
+    pub fn set_integer (&mut self, key: &impl IdoKey, val: i64)
+    {
+        let key = &key.ido_key();
+        let mut item: IdoItem = IdoItem::new();
+        item.m_type = IdoItemType::INTEGER;
+        item.m_integer = val;
+
+        self.set_item(key, item);
+    }
+
+    /// Sets a 128-bit integer value associated with the given key.
+    ///
+    /// The value is stored exactly as an `INTEGER128` item, so quantities
+    /// and nanosecond timestamps that overflow `i64` arithmetic keep their
+    /// precision.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key associated with the value.
+    /// * `val` - The integer value to be set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido};
+    /// let mut ido = Ido::new();
+    /// ido.set_i128(&1, 1_700_000_000_000_000_000_000);
+    /// assert_eq!(ido.get_i128(&1), Some(1_700_000_000_000_000_000_000));
+    /// ```
+    pub fn set_i128 (&mut self, key: &impl IdoKey, val: i128)
+    {
+        let key = &key.ido_key();
+        let mut item: IdoItem = IdoItem::new();
+        item.m_type = IdoItemType::INTEGER128;
+        item.m_integer128 = val;
+
+        self.set_item(key, item);
+    }
+
+    /// Sets a boolean value associated with the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key associated with the value.
+    /// * `val` - The boolean value to be set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido};
+    /// let mut ido = Ido::new();
+    /// ido.set_bool(&1, true);
+    /// assert_eq!(ido.get_bool(&1), Some(true));
+    /// ```
+    pub fn set_bool (&mut self, key: &impl IdoKey, val: bool)
+    {
+        let key = &key.ido_key();
+        let mut item: IdoItem = IdoItem::new();
+        item.m_type = IdoItemType::BOOL;
+        item.m_bool = val;
+
+        self.set_item(key, item);
+    }
+
+    /// Sets a floating-point value associated with the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key associated with the value.
+    /// * `val` - The floating-point value to be set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido};
+    /// let mut ido = Ido::new();
+    /// ido.set_f64(&1, 3.14159);
+    /// ```
+    pub fn set_f64 (&mut self, key: &impl IdoKey, val: f64)
+    {
+        let key = &key.ido_key();
+        let mut item: IdoItem = IdoItem::new();
+        item.m_type = IdoItemType::FLOAT;
+        item.m_float = val;
+
+        self.set_item(key, item);
+    }
+
+    /// Retrieves an item from the collection using the specified key.
+    ///
+    /// If an item is found in the collection associated with the provided key, a clone of the item is returned within a `Some` variant.
+    /// If no item is found, `None` is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: A reference to a key of type `IdoKeyT` used for item lookup.
+    ///
+    /// # Example
     ///
     /// ```
     /// use ido::{Ido};
@@ -394,12 +2255,9 @@ impl Ido {
     ///
     /// This function returns a clone of the found item to ensure the original collection remains unchanged.
     ///
-    pub fn get_item(&self, key: &IdoKeyT) -> Option<IdoItem> {
-        if let Some(value) = self.m_items.get(key) {
-            Some(value.clone())
-        } else {
-            return None;
-        }
+    pub fn get_item(&self, key: &impl IdoKey) -> Option<IdoItem> {
+        let key = &key.ido_key();
+        self.read_item(key).cloned()
     }
 
     /// Retrieves a string value associated with the given key.
@@ -423,19 +2281,138 @@ impl Ido {
     ///     println!("Value not found.");
     /// }
     /// ```
-    pub fn get_string(&self, key: &IdoKeyT) -> Option<String> {
-        if let Some(value) = self.m_items.get(key) {
+    pub fn get_string(&self, key: &impl IdoKey) -> Option<String> {
+        let key = &key.ido_key();
+        if let Some(value) = self.read_item(key) {
             if value.m_type != IdoItemType::STRING {
-                return None;
+                None
             } else {
                 Some(value.m_string.clone())
             }
-        } else {
-            return None;
+        } else {
+            None
+        }
+    }
+
+    /// Retrieves a 64-bit signed integer value associated with the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key (integer) associated with the value.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Option` containing the 64-bit signed integer value if it exists and is of the correct type, or `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido};
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&42, 100);
+    /// if let Some(value) = ido.get_i64(&42) {
+    ///     println!("Value: {}", value);
+    /// } else {
+    ///     println!("Value not found or not an integer.");
+    /// }
+    /// ```
+    pub fn get_i64(&self, key: &impl IdoKey) -> Option<i64> {
+        let key = &key.ido_key();
+        if let Some(value) = self.read_item(key) {
+            if value.m_type != IdoItemType::INTEGER {
+                None
+            } else {
+                Some(value.m_integer)
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Retrieves a 128-bit signed integer value associated with the given key.
+    ///
+    /// Both `INTEGER128` and `INTEGER` items are returned, without loss.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key (integer) associated with the value.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Option` containing the 128-bit signed integer value if it exists and is of an integer type, or `None` otherwise.
+    pub fn get_i128(&self, key: &impl IdoKey) -> Option<i128> {
+        let key = &key.ido_key();
+        match self.read_item(key) {
+            Some(value) if value.m_type == IdoItemType::INTEGER128 => Some(value.m_integer128),
+            Some(value) if value.m_type == IdoItemType::INTEGER => Some(i128::from(value.m_integer)),
+            _ => None,
+        }
+    }
+
+    /// Retrieves a 128-bit unsigned integer value associated with the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key (integer) associated with the value.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Option` containing the value if it exists, is of an integer type and is not negative, or `None` otherwise.
+    pub fn get_u128(&self, key: &impl IdoKey) -> Option<u128> {
+        let key = &key.ido_key();
+        self.get_i128(key).and_then(|value| u128::try_from(value).ok())
+    }
+
+    /// Retrieves the INTEGER value associated with the given key, narrowed to
+    /// `T` according to the overflow policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key (integer) associated with the value.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(None)` if there is no INTEGER under `key`, or if it does not fit
+    /// and the policy is [`OverflowPolicy::Null`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `IdoError::OutOfRange` if the value does not fit in `T` and the
+    /// policy is [`OverflowPolicy::Reject`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoError, OverflowPolicy};
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, -1);
+    /// ido.set_overflow_policy(OverflowPolicy::Reject);
+    ///
+    /// assert_eq!(ido.try_get_int::<i8>(&1), Ok(Some(-1)));
+    /// assert_eq!(ido.try_get_int::<u32>(&1), Err(IdoError::OutOfRange { key: 1, value: -1, target: "u32" }));
+    /// assert_eq!(ido.try_get_int::<u32>(&2), Ok(None));
+    /// ```
+    pub fn try_get_int<T: NarrowInt>(&self, key: &impl IdoKey) -> Result<Option<T>, IdoError> {
+        let key = &key.ido_key();
+        let value = match self.read_item(key) {
+            Some(item) if item.m_type == IdoItemType::INTEGER => item.m_integer,
+            _ => return Ok(None),
+        };
+        if let Some(exact) = T::from_exact(value) {
+            return Ok(Some(exact));
+        }
+        match self.m_overflow_policy {
+            OverflowPolicy::Wrap => Ok(Some(T::from_wrapped(value))),
+            OverflowPolicy::Saturate => Ok(Some(T::from_saturated(value))),
+            OverflowPolicy::Reject => {
+                Err(IdoError::OutOfRange { key: *key, value, target: std::any::type_name::<T>() })
+            }
+            OverflowPolicy::Null => Ok(None),
         }
     }
 
-    /// Retrieves a 64-bit signed integer value associated with the given key.
+    /// Retrieves a boolean value associated with the given key.
     ///
     /// # Arguments
     ///
@@ -443,29 +2420,71 @@ impl Ido {
     ///
     /// # Returns
     ///
-    /// Returns an `Option` containing the 64-bit signed integer value if it exists and is of the correct type, or `None` otherwise.
+    /// Returns an `Option` containing the boolean value if it exists and is of the correct type, or `None` otherwise.
+    pub fn get_bool(&self, key: &impl IdoKey) -> Option<bool> {
+        let key = &key.ido_key();
+        match self.read_item(key) {
+            Some(value) if value.m_type == IdoItemType::BOOL => Some(value.m_bool),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the value associated with the given key as a boolean,
+    /// accepting the encodings upstream feeds use for flags.
+    ///
+    /// | Stored value                          | Result        |
+    /// |---------------------------------------|---------------|
+    /// | BOOL                                  | the value     |
+    /// | INTEGER or INTEGER128 `1` / `0`       | `true`/`false`|
+    /// | STRING `"true"` / `"false"`, any case | `true`/`false`|
+    /// | STRING `"Y"` / `"N"`, any case        | `true`/`false`|
+    /// | anything else                         | `None`        |
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key (integer) associated with the value.
     ///
     /// # Example
     ///
     /// ```
     /// use ido::{Ido};
     /// let mut ido = Ido::new();
-    /// ido.set_integer(&42, 100);
-    /// if let Some(value) = ido.get_i64(&42) {
-    ///     println!("Value: {}", value);
-    /// } else {
-    ///     println!("Value not found or not an integer.");
-    /// }
+    /// ido.set_integer(&1, 1);
+    /// ido.set_string(&2, "N".to_string());
+    /// ido.set_string(&3, "yes".to_string());
+    ///
+    /// assert_eq!(ido.get_bool_lossy(&1), Some(true));
+    /// assert_eq!(ido.get_bool_lossy(&2), Some(false));
+    /// assert_eq!(ido.get_bool_lossy(&3), None);
+    /// assert_eq!(ido.get_bool(&1), None);
     /// ```
-    pub fn get_i64(&self, key: &IdoKeyT) -> Option<i64> {
-        if let Some(value) = self.m_items.get(key) {
-            if value.m_type != IdoItemType::INTEGER {
-                return None;
-            } else {
-                Some(value.m_integer)
+    pub fn get_bool_lossy(&self, key: &impl IdoKey) -> Option<bool> {
+        let key = &key.ido_key();
+        let value = self.read_item(key)?;
+        match value.m_type {
+            IdoItemType::BOOL => Some(value.m_bool),
+            IdoItemType::INTEGER | IdoItemType::INTEGER128 => {
+                let number = match value.m_type {
+                    IdoItemType::INTEGER => i128::from(value.m_integer),
+                    _ => value.m_integer128,
+                };
+                match number {
+                    0 => Some(false),
+                    1 => Some(true),
+                    _ => None,
+                }
             }
-        } else {
-            None
+            IdoItemType::STRING => {
+                let text = value.m_string.as_str();
+                if text.eq_ignore_ascii_case("true") || text.eq_ignore_ascii_case("y") {
+                    Some(true)
+                } else if text.eq_ignore_ascii_case("false") || text.eq_ignore_ascii_case("n") {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            _ => None,
         }
     }
 
@@ -477,7 +2496,7 @@ impl Ido {
     ///
     /// # Returns
     ///
-    /// Returns an `Option` containing the 32-bit signed integer value if it exists and is of the correct type, or `None` otherwise.
+    /// Returns an `Option` containing the 32-bit signed integer value if it exists and is of the correct type, or `None` otherwise. Values out of range are handled according to the overflow policy.
     ///
     /// # Example
     ///
@@ -490,16 +2509,9 @@ impl Ido {
     ///     println!("Value not found or not an integer.");
     /// }
     /// ```
-    pub fn get_i32(&self, key: &IdoKeyT) -> Option<i32> {
-        if let Some(value) = self.m_items.get(key) {
-            if value.m_type != IdoItemType::INTEGER {
-                return None;
-            } else {
-                Some(value.m_integer as i32)
-            }
-        } else {
-            None
-        }
+    pub fn get_i32(&self, key: &impl IdoKey) -> Option<i32> {
+        let key = &key.ido_key();
+        self.try_get_int(key).ok().flatten()
     }
 
     /// Retrieves a 16-bit signed integer value associated with the given key.
@@ -510,7 +2522,7 @@ impl Ido {
     ///
     /// # Returns
     ///
-    /// Returns an `Option` containing the 16-bit signed integer value if it exists and is of the correct type, or `None` otherwise.
+    /// Returns an `Option` containing the 16-bit signed integer value if it exists and is of the correct type, or `None` otherwise. Values out of range are handled according to the overflow policy.
     ///
     /// # Example
     ///
@@ -523,16 +2535,9 @@ impl Ido {
     ///     println!("Value not found or not an integer.");
     /// }
     /// ```
-    pub fn get_i16(&self, key: &IdoKeyT) -> Option<i16> {
-        if let Some(value) = self.m_items.get(key) {
-            if value.m_type != IdoItemType::INTEGER {
-                return None;
-            } else {
-                Some(value.m_integer as i16)
-            }
-        } else {
-            None
-        }
+    pub fn get_i16(&self, key: &impl IdoKey) -> Option<i16> {
+        let key = &key.ido_key();
+        self.try_get_int(key).ok().flatten()
     }
 
     /// Retrieves an 8-bit signed integer value associated with the given key.
@@ -543,7 +2548,7 @@ impl Ido {
     ///
     /// # Returns
     ///
-    /// Returns an `Option` containing the 8-bit signed integer value if it exists and is of the correct type, or `None` otherwise.
+    /// Returns an `Option` containing the 8-bit signed integer value if it exists and is of the correct type, or `None` otherwise. Values out of range are handled according to the overflow policy.
     ///
     /// # Example
     ///
@@ -556,16 +2561,9 @@ impl Ido {
     ///     println!("Value not found or not an integer.");
     /// }
     /// ```
-    pub fn get_i8(&self, key: &IdoKeyT) -> Option<i8> {
-        if let Some(value) = self.m_items.get(key) {
-            if value.m_type != IdoItemType::INTEGER {
-                return None;
-            } else {
-                Some(value.m_integer as i8)
-            }
-        } else {
-            None
-        }
+    pub fn get_i8(&self, key: &impl IdoKey) -> Option<i8> {
+        let key = &key.ido_key();
+        self.try_get_int(key).ok().flatten()
     }
 
     /// Retrieves a 64-bit unsigned integer value associated with the given key.
@@ -576,7 +2574,7 @@ impl Ido {
     ///
     /// # Returns
     ///
-    /// Returns an `Option` containing the 64-bit unsigned integer value if it exists and is of the correct type, or `None` otherwise.
+    /// Returns an `Option` containing the 64-bit unsigned integer value if it exists and is of the correct type, or `None` otherwise. Values out of range are handled according to the overflow policy.
     ///
     /// # Example
     ///
@@ -589,16 +2587,9 @@ impl Ido {
     ///     println!("Value not found or not an integer.");
     /// }
     /// ```
-    pub fn get_u64(&self, key: &IdoKeyT) -> Option<u64> {
-        if let Some(value) = self.m_items.get(key) {
-            if value.m_type != IdoItemType::INTEGER {
-                return None;
-            } else {
-                Some(value.m_integer as u64)
-            }
-        } else {
-            None
-        }
+    pub fn get_u64(&self, key: &impl IdoKey) -> Option<u64> {
+        let key = &key.ido_key();
+        self.try_get_int(key).ok().flatten()
     }
 
     /// Retrieves a 32-bit unsigned integer value associated with the given key.
@@ -609,7 +2600,7 @@ impl Ido {
     ///
     /// # Returns
     ///
-    /// Returns an `Option` containing the 32-bit unsigned integer value if it exists and is of the correct type, or `None` otherwise.
+    /// Returns an `Option` containing the 32-bit unsigned integer value if it exists and is of the correct type, or `None` otherwise. Values out of range are handled according to the overflow policy.
     ///
     /// # Example
     ///
@@ -622,16 +2613,9 @@ impl Ido {
     ///     println!("Value not found or not an integer.");
     /// }
     /// ```
-    pub fn get_u32(&self, key: &IdoKeyT) -> Option<u32> {
-        if let Some(value) = self.m_items.get(key) {
-            if value.m_type != IdoItemType::INTEGER {
-                return None;
-            } else {
-                Some(value.m_integer as u32)
-            }
-        } else {
-            None
-        }
+    pub fn get_u32(&self, key: &impl IdoKey) -> Option<u32> {
+        let key = &key.ido_key();
+        self.try_get_int(key).ok().flatten()
     }
 
     /// Retrieves a 16-bit unsigned integer value associated with the given key.
@@ -642,7 +2626,7 @@ impl Ido {
     ///
     /// # Returns
     ///
-    /// Returns an `Option` containing the 16-bit unsigned integer value if it exists and is of the correct type, or `None` otherwise.
+    /// Returns an `Option` containing the 16-bit unsigned integer value if it exists and is of the correct type, or `None` otherwise. Values out of range are handled according to the overflow policy.
     ///
     /// # Example
     ///
@@ -655,16 +2639,9 @@ impl Ido {
     ///     println!("Value not found or not an integer.");
     /// }
     /// ```
-    pub fn get_u16(&self, key: &IdoKeyT) -> Option<u16> {
-        if let Some(value) = self.m_items.get(key) {
-            if value.m_type != IdoItemType::INTEGER {
-                return None;
-            } else {
-                Some(value.m_integer as u16)
-            }
-        } else {
-            None
-        }
+    pub fn get_u16(&self, key: &impl IdoKey) -> Option<u16> {
+        let key = &key.ido_key();
+        self.try_get_int(key).ok().flatten()
     }
 
     /// Retrieves an 8-bit unsigned integer value associated with the given key.
@@ -675,7 +2652,7 @@ impl Ido {
     ///
     /// # Returns
     ///
-    /// Returns an `Option` containing the 8-bit unsigned integer value if it exists and is of the correct type, or `None` otherwise.
+    /// Returns an `Option` containing the 8-bit unsigned integer value if it exists and is of the correct type, or `None` otherwise. Values out of range are handled according to the overflow policy.
     ///
     /// # Example
     ///
@@ -688,16 +2665,9 @@ impl Ido {
     ///     println!("Value not found or not an integer.");
     /// }
     /// ```
-    pub fn get_u8(&self, key: &IdoKeyT) -> Option<u8> {
-        if let Some(value) = self.m_items.get(key) {
-            if value.m_type != IdoItemType::INTEGER {
-                return None;
-            } else {
-                Some(value.m_integer as u8)
-            }
-        } else {
-            None
-        }
+    pub fn get_u8(&self, key: &impl IdoKey) -> Option<u8> {
+        let key = &key.ido_key();
+        self.try_get_int(key).ok().flatten()
     }
 
     /// Retrieves a 64-bit floating-point value associated with the given key.
@@ -721,10 +2691,11 @@ impl Ido {
     ///     println!("Value not found or not a floating-point number.");
     /// }
     /// ```
-    pub fn get_f64(&self, key: &IdoKeyT) -> Option<f64> {
-        if let Some(value) = self.m_items.get(key) {
+    pub fn get_f64(&self, key: &impl IdoKey) -> Option<f64> {
+        let key = &key.ido_key();
+        if let Some(value) = self.read_item(key) {
             if value.m_type != IdoItemType::FLOAT {
-                return None;
+                None
             } else {
                 Some(value.m_float)
             }
@@ -733,19 +2704,6 @@ impl Ido {
         }
     }
 
-    /// Empties the array associated with the given key, or creates a new empty array if the key doesn't exist.
-    ///
-    /// # Arguments
-    ///
-    /// * `key` - A reference to the key of the array in the Ido object.
-    fn empty_array(&mut self, key: &IdoKeyT)
-    {
-        let mut item: IdoItem = IdoItem::new();
-        item.m_type = IdoItemType::ARRAY;
-        item.m_array = IdoArray::new();
-        self.set_item(key, item);
-    }
-
     /// Appends an Ido object to an array within the Ido object.
     ///
     /// If the specified key does not exist or the value associated with the key is not an array,
@@ -770,21 +2728,290 @@ impl Ido {
     ///
     /// ido.append_array(&11, data);
     /// ```
-    pub fn append_array(&mut self, key: &IdoKeyT, data: Ido)
+    ///
+    /// # Rejected writes
+    ///
+    /// In strict mode, nothing is appended if the key holds a value that is
+    /// not an array. The same goes for an append that would exceed the
+    /// limits set with `set_limits` and a key refused by the capacity or key
+    /// range policy. The error is kept for
+    /// [`take_rejected`](Ido::take_rejected).
+    pub fn append_array(&mut self, key: &impl IdoKey, data: Ido)
     {
-        if let Some(value) = self.m_items.get(key) {
-            if value.m_type != IdoItemType::ARRAY {
-                self.empty_array(key);
+        let key = &key.ido_key();
+        if let Err(err) = self.try_append_array(key, data) {
+            self.reject(err);
+        }
+    }
+
+    /// Appends an Ido object to an array, returning the error when strict
+    /// mode or a limit rejects it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IdoError::TypeMismatch` if strict mode is enabled and the key
+    /// holds a value that is not an array, `IdoError::DepthExceeded` or
+    /// `IdoError::ArrayTooLong` if the append would exceed the limits set
+    /// with `set_limits`, `IdoError::CapacityExceeded` if the capacity
+    /// policy refuses a new key, and `IdoError::ForeignKey` if the key is
+    /// reserved for another owner. `IdoError::MemoryExceeded` is returned
+    /// if the Ido would outgrow its memory budget. The Ido is left unchanged.
+    pub fn try_append_array(&mut self, key: &impl IdoKey, data: Ido) -> Result<(), IdoError> {
+        let key = &key.ido_key();
+        self.check_type(key, IdoItemType::ARRAY)?;
+        let len = self
+            .live_item(key)
+            .filter(|value| value.m_type == IdoItemType::ARRAY)
+            .map_or(0, |value| value.m_array.len());
+        self.m_limits.check_element(*key, 0, len + 1, &data)?;
+        self.check_range(key)?;
+        let added = data.memory_used();
+        match self.live_item(key).filter(|value| value.m_type == IdoItemType::ARRAY) {
+            Some(_) => self.m_limits.check_bytes(*key, self.m_bytes, self.m_bytes + added)?,
+            None => self.check_bytes(key, std::mem::size_of::<IdoItem>() + added)?,
+        }
+        self.make_room(key)?;
+        let needs_old = self.needs_old_items();
+
+        match self.m_items.get_mut(key) {
+            Some(value) if value.m_type == IdoItemType::ARRAY && !value.is_expired(SystemTime::now()) => {
+                let old = needs_old.then(|| value.clone());
+                value.m_array.push(data);
+                self.m_bytes += added;
+                value.m_version += 1;
+                value.m_modified = Some(SystemTime::now());
+                self.record_change(*key, old.as_ref());
+                self.m_ops.count_set();
+                audit::record_write(*key);
+            }
+            _ => {
+                let mut item = IdoItem::new();
+                item.m_type = IdoItemType::ARRAY;
+                item.m_array.push(data);
+                self.store_item(key, item);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores a value under a key that may occur several times, keeping
+    /// every occurrence instead of overwriting the previous one.
+    ///
+    /// The first value is stored as is. Once the key repeats, it becomes an
+    /// ARRAY with one element per occurrence, each holding the value under
+    /// the same key, and later values are appended to it. An ARRAY value is
+    /// always stored that way, so it cannot be mistaken for the list of
+    /// occurrences. Use [`get_values`](Ido::get_values) to read them back.
+    ///
+    /// # Rejected writes
+    ///
+    /// In strict mode, nothing is stored if the key already holds a single
+    /// value that is not an array, and the error is kept for
+    /// [`take_rejected`](Ido::take_rejected); use
+    /// [`try_append_value`](Ido::try_append_value) to handle that case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoItem, IdoItemType};
+    ///
+    /// let mut ido = Ido::new();
+    /// for account in ["ACC-1", "ACC-2"] {
+    ///     let mut item = IdoItem::new();
+    ///     item.m_type = IdoItemType::STRING;
+    ///     item.m_string = account.to_string();
+    ///     ido.append_value(&79, item);
+    /// }
+    ///
+    /// let accounts: Vec<String> = ido.get_values(&79).into_iter().map(|item| item.m_string).collect();
+    /// assert_eq!(accounts, ["ACC-1", "ACC-2"]);
+    /// ```
+    pub fn append_value(&mut self, key: &impl IdoKey, item: IdoItem) {
+        let key = &key.ido_key();
+        if let Err(err) = self.try_append_value(key, item) {
+            self.reject(err);
+        }
+    }
+
+    /// Stores a repeated value, returning the error when strict mode
+    /// rejects it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IdoError::TypeMismatch` if strict mode is enabled and the key
+    /// holds a single value that is not an array. The Ido is left unchanged.
+    pub fn try_append_value(&mut self, key: &impl IdoKey, item: IdoItem) -> Result<(), IdoError> {
+        let key = &key.ido_key();
+        let existing = self.live_item(key).cloned();
+        if existing.is_none() && item.m_type != IdoItemType::ARRAY {
+            return self.try_set_item(key, item);
+        }
+
+        let mut occurrence = Ido::new();
+        occurrence.set_item(key, item);
+
+        match existing {
+            Some(first) if first.m_type != IdoItemType::ARRAY => {
+                self.check_type(key, IdoItemType::ARRAY)?;
+                self.check_range(key)?;
+                let mut wrapped = Ido::new();
+                wrapped.set_item(key, first);
+
+                let mut array = IdoItem::new();
+                array.m_type = IdoItemType::ARRAY;
+                array.m_array = vec![wrapped, occurrence];
+                self.check_bytes(key, array.approx_bytes())?;
+                self.store_item(key, array);
+                Ok(())
+            }
+            _ => self.try_append_array(key, occurrence),
+        }
+    }
+
+    /// Returns every occurrence of a value stored with
+    /// [`append_value`](Ido::append_value).
+    ///
+    /// A single value is returned on its own, and an ARRAY that does not
+    /// hold occurrences is returned as one value. A missing key yields an
+    /// empty vector.
+    pub fn get_values(&self, key: &impl IdoKey) -> Vec<IdoItem> {
+        let key = &key.ido_key();
+        match self.read_item(key) {
+            None => Vec::new(),
+            Some(value)
+                if value.m_type == IdoItemType::ARRAY
+                    && !value.m_array.is_empty()
+                    && value.m_array.iter().all(|element| element.size() == 1 && element.contains(key)) =>
+            {
+                value.m_array.iter().filter_map(|element| element.get_item(key)).collect()
+            }
+            Some(value) => vec![value.clone()],
+        }
+    }
+
+    /// Removes elements of an array that repeat the value of an identity
+    /// field.
+    ///
+    /// Elements compare equal on the identity field as items do, so an
+    /// INTEGER `1` and a FLOAT `1.0` are duplicates. Elements without the
+    /// field are always kept, and the kept elements stay in their order.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the ARRAY item.
+    /// * `identity` - The key within each element identifying duplicates.
+    /// * `keep` - Whether the first or the last duplicate survives.
+    ///
+    /// # Returns
+    ///
+    /// The number of elements removed; 0 if `key` holds no array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, Keep};
+    ///
+    /// let row = |id: i64, price: f64| {
+    ///     let mut row = Ido::new();
+    ///     row.set_integer(&1, id);
+    ///     row.set_f64(&2, price);
+    ///     row
+    /// };
+    ///
+    /// let mut feed = Ido::new();
+    /// feed.append_array(&5, row(1, 1.5));
+    /// feed.append_array(&5, row(2, 2.5));
+    /// feed.append_array(&5, row(1, 1.75));
+    ///
+    /// assert_eq!(feed.dedup_array(&5, &1, Keep::Last), 1);
+    /// assert_eq!(feed.to_string(), "5=[{1=2,2=2.5},{1=1,2=1.75}]");
+    /// ```
+    pub fn dedup_array(&mut self, key: &impl IdoKey, identity: &IdoKeyT, keep: Keep) -> usize {
+        let key = &key.ido_key();
+        let Some(mut item) = self.live_item(key).filter(|item| item.m_type == IdoItemType::ARRAY).cloned() else {
+            return 0;
+        };
+
+        let count = item.m_array.len();
+        if keep == Keep::Last {
+            item.m_array.reverse();
+        }
+
+        let mut kept: IdoArray = Vec::with_capacity(count);
+        for element in item.m_array {
+            let duplicate = element.live_item(identity).is_some_and(|value| {
+                kept.iter().any(|other| other.live_item(identity) == Some(value))
+            });
+            if !duplicate {
+                kept.push(element);
             }
         }
-        else {
-            self.empty_array(key);
+
+        if keep == Keep::Last {
+            kept.reverse();
         }
+        item.m_array = kept;
 
-        if let Some(value) = self.m_items.get_mut(key) {
-            (*value).m_type = IdoItemType::ARRAY;
-            (*value).m_array.push(data);
+        let removed = count - item.m_array.len();
+        if removed > 0 {
+            self.set_item(key, item);
         }
+        removed
+    }
+
+    /// Binary searches an array kept sorted by the value of a field.
+    ///
+    /// Values are compared as items are, so an INTEGER field can be searched
+    /// with a FLOAT target. Elements without the field, or with a value that
+    /// cannot be compared with `target`, are treated as sorting before it.
+    /// If the array is not sorted, the result is unspecified.
+    ///
+    /// # Arguments
+    ///
+    /// * `array_key` - The key of the ARRAY item.
+    /// * `field_key` - The key within each element the array is sorted by.
+    /// * `target` - The value to search for.
+    ///
+    /// # Returns
+    ///
+    /// `None` if `array_key` holds no array, otherwise the result of
+    /// `slice::binary_search`: `Ok` with the index of a matching element, or
+    /// `Err` with the index where it could be inserted to keep the order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::{Ido, IdoItem, IdoItemType};
+    ///
+    /// let mut book = Ido::new();
+    /// for price in [99.5, 100.0, 100.5] {
+    ///     let mut level = Ido::new();
+    ///     level.set_f64(&1, price);
+    ///     book.append_array(&5, level);
+    /// }
+    ///
+    /// let mut target = IdoItem::new();
+    /// target.m_type = IdoItemType::FLOAT;
+    /// target.m_float = 100.5;
+    /// assert_eq!(book.array_binary_search_by_key(&5, &1, &target), Some(Ok(2)));
+    ///
+    /// target.m_float = 99.75;
+    /// assert_eq!(book.array_binary_search_by_key(&5, &1, &target), Some(Err(1)));
+    /// ```
+    pub fn array_binary_search_by_key(
+        &self,
+        array_key: &IdoKeyT,
+        field_key: &IdoKeyT,
+        target: &IdoItem,
+    ) -> Option<Result<usize, usize>> {
+        let item = self.live_item(array_key).filter(|item| item.m_type == IdoItemType::ARRAY)?;
+
+        Some(item.m_array.binary_search_by(|element| {
+            element
+                .live_item(field_key)
+                .and_then(|value| value.partial_cmp(target))
+                .unwrap_or(std::cmp::Ordering::Less)
+        }))
     }
 
     /// Deletes an item from the Ido object based on the given key.
@@ -807,53 +3034,263 @@ impl Ido {
     ///
     /// ido.delete_item(&1);
     /// ```
-    pub fn delete_item(&mut self, key: &IdoKeyT) {
-        if let Some(value) = self.m_items.get(key) {
+    pub fn delete_item(&mut self, key: &impl IdoKey) {
+        let key = &key.ido_key();
+        if let Some(value) = self.m_items.remove(key) {
             self.m_ordered.remove(&value.m_index);
-            self.m_items.remove(key);
+            self.m_bytes -= value.approx_bytes();
+            self.record_change(*key, Some(&value));
+            self.m_ops.count_delete();
         }
     }
 
-    /// Converts the Ido object to a string representation.
+    /// Deletes every item whose key falls in a range.
     ///
-    /// Returns a string that represents the Ido object, with key-value pairs separated by commas.
-    /// The key-value pairs are sorted based on the order of insertion.
+    /// Each removed item counts as a deletion, so listeners, the journal and
+    /// dirty tracking see it like a call to `delete_item`.
     ///
-    /// # Examples
+    /// # Arguments
+    ///
+    /// * `range` - The range of key values to delete, such as `100..200`.
+    ///
+    /// # Returns
+    ///
+    /// The number of items deleted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut order = Ido::new();
+    /// order.set_string(&1, "ORDER-1".to_string());
+    /// order.set_string(&101, "EURUSD".to_string());
+    /// order.set_integer(&100, 5);
+    ///
+    /// assert_eq!(order.delete_range(100..200), 2);
+    /// assert_eq!(order.to_string(), "1=ORDER-1");
+    /// ```
+    pub fn delete_range<R: RangeBounds<IdoKeyT>>(&mut self, range: R) -> usize {
+        let keys: Vec<IdoKeyT> = self.ordered_items().map(|(key, _)| key).filter(|key| range.contains(key)).collect();
+        for key in &keys {
+            self.delete_item(key);
+        }
+        keys.len()
+    }
+
+    /// Returns the keys modified since the last `clear_dirty` or `take_dirty`.
+    ///
+    /// A key counts as modified when it was set, appended to or deleted. The
+    /// keys are returned in ascending order and include deleted keys.
+    ///
+    /// # Example
     ///
     /// ```
     /// use ido::Ido;
     ///
     /// let mut ido = Ido::new();
-    /// ido.set_string(&1, String::from("John"));
-    /// ido.set_integer(&2, 30);
-    /// ido.set_string(&3, String::from("New York"));
+    /// ido.set_integer(&2, 20);
+    /// ido.set_integer(&1, 10);
     ///
-    /// let result = ido.to_string();
+    /// assert_eq!(ido.dirty_keys(), vec![1, 2]);
     /// ```
-    pub fn to_string(&self) -> String {
-        let mut result = String::new();
-        let mut count = 0;
+    pub fn dirty_keys(&self) -> Vec<IdoKeyT> {
+        let mut keys: Vec<IdoKeyT> = self.m_dirty.iter().copied().collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Checks whether the given key was modified since the dirty set was last cleared.
+    pub fn is_dirty(&self, key: &impl IdoKey) -> bool {
+        let key = &key.ido_key();
+        self.m_dirty.contains(key)
+    }
+
+    /// Forgets all modifications recorded so far.
+    pub fn clear_dirty(&mut self) {
+        self.m_dirty.clear();
+    }
+
+    /// Returns a new Ido holding only the modified items and clears the dirty set.
+    ///
+    /// Items are copied in insertion order. Deleted keys have no item and are
+    /// therefore not part of the result; use `dirty_keys` beforehand if
+    /// deletions need to be propagated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 10);
+    /// ido.set_integer(&2, 20);
+    /// ido.clear_dirty();
+    ///
+    /// ido.set_integer(&2, 21);
+    ///
+    /// let delta = ido.take_dirty();
+    /// assert_eq!(delta.size(), 1);
+    /// assert_eq!(delta.get_i64(&2), Some(21));
+    /// assert!(ido.dirty_keys().is_empty());
+    /// ```
+    pub fn take_dirty(&mut self) -> Ido {
+        let mut delta = Ido::new();
+
+        for (key, item) in self.live_items() {
+            if self.m_dirty.contains(&key) {
+                delta.set_item(&key, item.clone());
+            }
+        }
+
+        self.m_dirty.clear();
+        delta
+    }
+
+    /// Registers a callback that is invoked after every mutation of the Ido.
+    ///
+    /// The callback receives the key, the previous item and the new item, with
+    /// `None` standing for "not present". It fires for `set_*`, `append_array`,
+    /// `delete_item`, `update` and once per removed key on `clear`.
+    ///
+    /// Listeners are not copied when the Ido is cloned.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The function to call on every change.
+    ///
+    /// # Returns
+    ///
+    /// A `ListenerId` that can be passed to `remove_listener`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.on_change(|key, old, new| {
+    ///     let old = old.and_then(|item| item.as_string());
+    ///     let new = new.and_then(|item| item.as_string());
+    ///     println!("{}: {:?} -> {:?}", key, old, new);
+    /// });
+    ///
+    /// ido.set_integer(&1, 42);
+    /// ```
+    pub fn on_change<F>(&mut self, callback: F) -> ListenerId
+    where
+        F: Fn(IdoKeyT, Option<&IdoItem>, Option<&IdoItem>) + Send + Sync + 'static
+    {
+        let id = ListenerId(self.m_listeners.m_next_id);
+        self.m_listeners.m_next_id += 1;
+        self.m_listeners.m_callbacks.push((id, Box::new(callback)));
+        id
+    }
+
+    /// Removes a listener registered with `on_change`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the listener was registered on this Ido.
+    pub fn remove_listener(&mut self, id: ListenerId) -> bool {
+        let before = self.m_listeners.m_callbacks.len();
+        self.m_listeners.m_callbacks.retain(|(listener, _)| *listener != id);
+        self.m_listeners.m_callbacks.len() != before
+    }
+}
+
+impl Ido {
+    /// Writes the `key=value` form used by `Display`, naming keys found in
+    /// `names` and rendering datetimes in `format`.
+    fn write_items(
+        &self,
+        out: &mut dyn fmt::Write,
+        names: Option<&IdoDictionary>,
+        format: &DateTimeFormat,
+    ) -> fmt::Result {
+        self.write_items_with(out, names, format, false, &mut |_, _| None)
+    }
 
-        for (key, value) in self.into_ordered_iterator() {
+    /// Like `write_items`, but writes the value returned by `custom` in
+    /// place of the default one whenever it returns `Some`, and prefixes
+    /// array elements with their index if `indices` is set.
+    #[cfg_attr(not(feature = "chrono"), allow(clippy::only_used_in_recursion))]
+    fn write_items_with(
+        &self,
+        out: &mut dyn fmt::Write,
+        names: Option<&IdoDictionary>,
+        format: &DateTimeFormat,
+        indices: bool,
+        custom: &mut dyn FnMut(IdoKeyT, &IdoItem) -> Option<String>,
+    ) -> fmt::Result {
+        for (count, (key, value)) in self.live_items().enumerate() {
             if count != 0 {
-                result.push(',');
+                out.write_str(",")?;
             }
 
-            if let Some(value_str) = value.as_string() {
-                if value.get_type() == IdoItemType::ARRAY {
-                    for array_item in &value.m_array {
-                        write!(result, "{}=[{}]", key, array_item.to_string()).unwrap();
+            let text = custom(key, value);
+            let name = names.and_then(|names| names.name(&key));
+            let key: &dyn fmt::Display = match &name {
+                Some(name) => name,
+                None => &key,
+            };
+
+            if let Some(text) = text {
+                write!(out, "{}={}", key, text)?;
+                continue;
+            }
+            match value.m_type {
+                IdoItemType::ARRAY => {
+                    write!(out, "{}=[", key)?;
+                    for (index, element) in value.m_array.iter().enumerate() {
+                        if index != 0 {
+                            out.write_str(",")?;
+                        }
+                        if indices {
+                            write!(out, "{}:", index)?;
+                        }
+                        out.write_str("{")?;
+                        element.write_items_with(out, names, format, indices, custom)?;
+                        out.write_str("}")?;
                     }
-                } else {
-                    write!(result, "{}={}", key, value_str).unwrap();
+                    out.write_str("]")?;
                 }
-            } else {
-                continue
+                IdoItemType::STRING => write!(out, "{}={}", key, value.m_string)?,
+                IdoItemType::INTEGER => write!(out, "{}={}", key, value.m_integer)?,
+                IdoItemType::FLOAT => write!(out, "{}={}", key, value.m_float)?,
+                IdoItemType::INTEGER128 => write!(out, "{}={}", key, value.m_integer128)?,
+                IdoItemType::BOOL => write!(out, "{}={}", key, value.m_bool)?,
+                #[cfg(feature = "chrono")]
+                IdoItemType::DATETIME => write!(out, "{}={}", key, format.format(&value.m_datetime))?,
             }
+        }
+        Ok(())
+    }
+}
+
+/// Combines the elements of `incoming` into `existing` for `update_with`.
+fn merge_arrays(existing: &mut IdoArray, incoming: &IdoArray, policy: ArrayMerge) {
+    for element in incoming {
+        let matched = match policy {
+            ArrayMerge::Keyed(id) => element
+                .live_item(&id)
+                .and_then(|value| existing.iter().position(|current| current.live_item(&id) == Some(value))),
+            _ => None,
+        };
 
-            count += 1;
+        match matched {
+            Some(index) => existing[index].update_with(element, policy),
+            None => existing.push(element.clone()),
         }
-        result
     }
-}
\ No newline at end of file
+}
+
+impl fmt::Display for Ido {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", name = "ido.to_string", skip_all, fields(items = self.size()))
+    )]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_items(f, self.names(), &self.m_datetime_format)
+    }
+}
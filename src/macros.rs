@@ -0,0 +1,105 @@
+/// Defines named constants for Ido keys.
+///
+/// Each entry becomes a `const` of type [`IdoKeyT`](crate::IdoKeyT), so
+/// application code can write `ido.get_f64(&PRICE)` instead of repeating
+/// magic numbers. Attributes and doc comments on an entry are kept, and an
+/// optional visibility applies to the constant.
+///
+/// Starting the list with `dictionary <vis> fn <name>;` also generates a
+/// function returning an [`IdoDictionary`](crate::dictionary::IdoDictionary)
+/// that names every key after its constant.
+///
+/// # Example
+///
+/// ```
+/// use ido::{define_ido_keys, Ido};
+///
+/// define_ido_keys! {
+///     dictionary pub fn order_keys;
+///
+///     /// Client order identifier.
+///     pub ORDER_ID = 1,
+///     pub PRICE = 2,
+///     QTY = 3,
+/// }
+///
+/// let mut ido = Ido::new();
+/// ido.set_string(&ORDER_ID, "ORDER-1".to_string());
+/// ido.set_integer(&QTY, 100);
+///
+/// assert_eq!(ido.with_names(&order_keys()).to_string(), "ORDER_ID=ORDER-1,QTY=100");
+/// ```
+#[macro_export]
+macro_rules! define_ido_keys {
+    (
+        dictionary $dict_vis:vis fn $dict:ident;
+        $( $(#[$meta:meta])* $vis:vis $name:ident = $value:expr ),* $(,)?
+    ) => {
+        $crate::define_ido_keys! { $( $(#[$meta])* $vis $name = $value ),* }
+
+        $dict_vis fn $dict() -> $crate::dictionary::IdoDictionary {
+            let mut dictionary = $crate::dictionary::IdoDictionary::new();
+            $( dictionary.insert($name, stringify!($name)); )*
+            dictionary
+        }
+    };
+    (
+        $( $(#[$meta:meta])* $vis:vis $name:ident = $value:expr ),* $(,)?
+    ) => {
+        $( $(#[$meta])* $vis const $name: $crate::IdoKeyT = $value; )*
+    };
+}
+
+/// Reads several typed values from an Ido at once.
+///
+/// Each `(key, type)` pair is read with [`Ido::get_value`](crate::Ido::get_value),
+/// so any type implementing [`IdoValue`](crate::convert::IdoValue) can be
+/// requested. The values are returned as a tuple in the order given. If
+/// any key is missing or cannot be converted, the error of every failing
+/// key is returned together as a
+/// [`FromIdoErrors`](crate::convert::FromIdoErrors), instead of stopping at
+/// the first one.
+///
+/// # Example
+///
+/// ```
+/// use ido::{get_tuple, Ido};
+///
+/// let mut ido = Ido::new();
+/// ido.set_integer(&1, 100);
+/// ido.set_string(&2, "EURUSD".to_string());
+/// ido.set_f64(&7, 1.25);
+///
+/// let (qty, symbol, price) = get_tuple!(ido, (1, i64), (2, String), (7, f64)).unwrap();
+/// assert_eq!((qty, symbol.as_str(), price), (100, "EURUSD", 1.25));
+///
+/// let errors = get_tuple!(ido, (1, String), (2, String), (3, f64)).unwrap_err();
+/// assert_eq!(errors.to_string(), "key 1 is INTEGER, expected STRING; key 3 is missing");
+/// ```
+#[macro_export]
+macro_rules! get_tuple {
+    ($ido:expr, $( ($key:expr, $ty:ty) ),+ $(,)?) => {{
+        let ido: &$crate::Ido = &$ido;
+        let mut errors = ::std::vec::Vec::new();
+        $crate::get_tuple!(@read ido, errors, [] $( ($key, $ty) )+)
+    }};
+    // Each step binds its value to `value`, which hygiene keeps distinct
+    // from the `value` of every other step.
+    (@read $ido:ident, $errors:ident, [$($value:ident)*] ($key:expr, $ty:ty) $($rest:tt)*) => {{
+        let value = match $ido.get_value::<$ty>(&$key) {
+            ::std::result::Result::Ok(value) => ::std::option::Option::Some(value),
+            ::std::result::Result::Err(error) => {
+                $errors.push(error);
+                ::std::option::Option::None
+            }
+        };
+        $crate::get_tuple!(@read $ido, $errors, [$($value)* value] $($rest)*)
+    }};
+    (@read $ido:ident, $errors:ident, [$($value:ident)*]) => {
+        match ($($value,)*) {
+            ($(::std::option::Option::Some($value),)*) => ::std::result::Result::Ok(($($value,)*)),
+            _ => ::std::result::Result::Err($crate::convert::FromIdoErrors($errors)),
+        }
+    };
+}
+
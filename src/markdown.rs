@@ -0,0 +1,105 @@
+//! Rendering an Ido as Markdown.
+//!
+//! Markdown tables cannot be nested, so an Ido is rendered as a `Key` /
+//! `Value` table of its items followed by one section per ARRAY. Each
+//! section is headed by the path of the array in bold and holds a table
+//! with one row per element and one column per key, like
+//! [`Ido::to_table`]. Arrays nested in elements get their own sections,
+//! with paths such as `5[0].7`.
+//!
+//! ```text
+//! | Key | Value |
+//! | --- | --- |
+//! | 1 | ORDER-1 |
+//! | 5 | array of 1 |
+//!
+//! **5**
+//!
+//! | 1 | 2 |
+//! | --- | --- |
+//! | EURUSD | 1.25 |
+//! ```
+
+use crate::dictionary::IdoDictionary;
+use crate::table::{self, Table};
+use crate::{Ido, IdoItemType, IdoKeyT};
+
+/// Renders `ido` as Markdown, naming keys found in `names`.
+pub(crate) fn render(ido: &Ido, names: Option<&IdoDictionary>) -> String {
+    let mut out = String::new();
+    table::write_markdown_row(&mut out, &["Key".to_string(), "Value".to_string()]);
+    table::write_markdown_row(&mut out, &["---".to_string(), "---".to_string()]);
+
+    let format = ido.datetime_format();
+    for (key, item) in ido.live_items() {
+        let value = match item.m_type {
+            IdoItemType::ARRAY => format!("array of {}", item.m_array.len()),
+            _ => item.as_string_with(format).unwrap_or_default(),
+        };
+        table::write_markdown_row(&mut out, &[name_of(key, names), value]);
+    }
+
+    write_arrays(&mut out, ido, "", names);
+    out
+}
+
+/// Writes a section for each ARRAY of `ido`, and recursively for the
+/// arrays of their elements.
+fn write_arrays(out: &mut String, ido: &Ido, prefix: &str, names: Option<&IdoDictionary>) {
+    for (key, item) in ido.live_items() {
+        if item.m_type != IdoItemType::ARRAY {
+            continue;
+        }
+        let path = format!("{}{}", prefix, name_of(key, names));
+        out.push_str("\n**");
+        out.push_str(&path);
+        out.push_str("**\n\n");
+
+        match Table::from_array(ido, &key, names).map(|table| table.to_markdown()) {
+            Some(rendered) if !rendered.is_empty() => out.push_str(&rendered),
+            _ => out.push_str("_empty_\n"),
+        }
+        for (index, element) in item.m_array.iter().enumerate() {
+            write_arrays(out, element, &format!("{}[{}].", path, index), names);
+        }
+    }
+}
+
+fn name_of(key: IdoKeyT, names: Option<&IdoDictionary>) -> String {
+    match names.and_then(|names| names.name(&key)) {
+        Some(name) => name.to_string(),
+        None => key.to_string(),
+    }
+}
+
+impl Ido {
+    /// Renders the Ido and its arrays as Markdown tables; see the
+    /// [`markdown`](crate::markdown) module for the layout.
+    ///
+    /// Keys are written as numbers, or as names if a dictionary is attached
+    /// with [`set_names`](Ido::set_names); use
+    /// [`NamedIdo::to_markdown`](crate::dictionary::NamedIdo::to_markdown)
+    /// to name them for a single call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut leg = Ido::new();
+    /// leg.set_string(&1, "EURUSD".to_string());
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&2, 100);
+    /// ido.append_array(&5, leg);
+    ///
+    /// assert_eq!(
+    ///     ido.to_markdown(),
+    ///     "| Key | Value |\n| --- | --- |\n| 2 | 100 |\n| 5 | array of 1 |\n\
+    ///      \n**5**\n\n| 1 |\n| --- |\n| EURUSD |\n"
+    /// );
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        render(self, self.names())
+    }
+}
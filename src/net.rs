@@ -0,0 +1,334 @@
+//! Publishing and subscribing to Idos over TCP.
+//!
+//! Every message is sent as a frame produced by [`codec::write_frame`], so a
+//! subscriber written in another language only has to understand the binary
+//! layout documented in [`codec`].
+//!
+//! [`codec`]: crate::codec
+//! [`codec::write_frame`]: crate::codec::write_frame
+
+use std::fmt;
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::Duration;
+
+use crate::codec::{self, CodecError, FrameDecoder};
+use crate::Ido;
+
+/// Errors raised by the TCP publisher and subscriber.
+#[derive(Debug)]
+pub enum NetError {
+    /// A socket operation failed.
+    Io(io::Error),
+    /// A frame could not be encoded or decoded.
+    Codec(CodecError),
+    /// The subscriber gave up reconnecting after the given number of attempts.
+    ReconnectFailed(u32),
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetError::Io(err) => write!(f, "io error: {}", err),
+            NetError::Codec(err) => write!(f, "codec error: {}", err),
+            NetError::ReconnectFailed(attempts) => {
+                write!(f, "failed to reconnect after {} attempts", attempts)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NetError::Io(err) => Some(err),
+            NetError::Codec(err) => Some(err),
+            NetError::ReconnectFailed(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for NetError {
+    fn from(err: io::Error) -> Self {
+        NetError::Io(err)
+    }
+}
+
+impl From<CodecError> for NetError {
+    fn from(err: CodecError) -> Self {
+        match err {
+            CodecError::Io(err) => NetError::Io(err),
+            err => NetError::Codec(err),
+        }
+    }
+}
+
+/// Controls how an [`IdoSubscriber`] reconnects after losing its connection.
+///
+/// The delay between attempts doubles after every failure, up to `max_delay`.
+#[derive(Debug, Clone, Copy)]
+pub struct ReconnectPolicy {
+    /// Maximum number of attempts before giving up, or `None` to retry forever.
+    pub max_attempts: Option<u32>,
+    /// Delay before the first attempt.
+    pub initial_delay: Duration,
+    /// Upper bound for the delay between attempts.
+    pub max_delay: Duration,
+}
+
+impl ReconnectPolicy {
+    /// A policy that never reconnects; a lost connection is returned as an error.
+    pub fn never() -> Self {
+        ReconnectPolicy {
+            max_attempts: Some(0),
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        }
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            max_attempts: None,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Write timeout given to subscriber connections unless
+/// [`IdoPublisher::set_write_timeout`] is called.
+pub const DEFAULT_WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Accepts subscriber connections and fans Idos out to all of them.
+///
+/// Connections are accepted lazily: every call to [`publish`] first picks up
+/// subscribers that have connected since the previous call. Subscribers whose
+/// connection fails, or that do not take a frame within the write timeout,
+/// are dropped, so a stalled subscriber cannot hold up the others for long.
+///
+/// [`publish`]: IdoPublisher::publish
+pub struct IdoPublisher {
+    m_listener: TcpListener,
+    m_subscribers: Vec<BufWriter<TcpStream>>,
+    m_write_timeout: Option<Duration>,
+}
+
+impl IdoPublisher {
+    /// Binds a publisher to the given address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::net::IdoPublisher;
+    ///
+    /// let publisher = IdoPublisher::bind("127.0.0.1:0").unwrap();
+    /// println!("publishing on {}", publisher.local_addr().unwrap());
+    /// ```
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self, NetError> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+
+        Ok(IdoPublisher {
+            m_listener: listener,
+            m_subscribers: Vec::new(),
+            m_write_timeout: Some(DEFAULT_WRITE_TIMEOUT),
+        })
+    }
+
+    /// Sets how long a write to a single subscriber may block before that
+    /// subscriber is dropped, for current and future subscribers. `None`
+    /// lets writes block indefinitely.
+    ///
+    /// Defaults to [`DEFAULT_WRITE_TIMEOUT`].
+    pub fn set_write_timeout(&mut self, timeout: Option<Duration>) -> Result<(), NetError> {
+        self.m_write_timeout = timeout;
+        for subscriber in &self.m_subscribers {
+            subscriber.get_ref().set_write_timeout(timeout)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the address the publisher is listening on.
+    pub fn local_addr(&self) -> Result<SocketAddr, NetError> {
+        Ok(self.m_listener.local_addr()?)
+    }
+
+    /// Returns the number of currently connected subscribers.
+    ///
+    /// Subscribers that connected since the last call to `publish` are not
+    /// counted until they have been accepted.
+    pub fn subscriber_count(&self) -> usize {
+        self.m_subscribers.len()
+    }
+
+    /// Accepts any subscribers waiting on the listener.
+    pub fn accept_pending(&mut self) -> Result<usize, NetError> {
+        let mut accepted = 0;
+
+        loop {
+            match self.m_listener.accept() {
+                Ok((stream, _)) => {
+                    stream.set_nonblocking(false)?;
+                    stream.set_nodelay(true)?;
+                    stream.set_write_timeout(self.m_write_timeout)?;
+                    self.m_subscribers.push(BufWriter::new(stream));
+                    accepted += 1;
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => return Ok(accepted),
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Sends `ido` to every connected subscriber.
+    ///
+    /// Subscribers are written to one after the other; one that does not
+    /// take the frame within the write timeout is dropped.
+    ///
+    /// # Returns
+    ///
+    /// The number of subscribers the Ido was delivered to.
+    pub fn publish(&mut self, ido: &Ido) -> Result<usize, NetError> {
+        self.accept_pending()?;
+
+        let mut frame = Vec::new();
+        codec::write_frame(&mut frame, ido)?;
+
+        self.m_subscribers
+            .retain_mut(|subscriber| subscriber.write_all(&frame).and_then(|_| subscriber.flush()).is_ok());
+
+        Ok(self.m_subscribers.len())
+    }
+}
+
+/// Receives Idos from an [`IdoPublisher`], reconnecting when the connection drops.
+pub struct IdoSubscriber {
+    m_addrs: Vec<SocketAddr>,
+    m_stream: Option<TcpStream>,
+    m_decoder: FrameDecoder,
+    m_policy: ReconnectPolicy,
+    m_read_timeout: Option<Duration>,
+}
+
+impl IdoSubscriber {
+    /// Connects to a publisher using the default [`ReconnectPolicy`].
+    ///
+    /// The initial connection is not retried; an unreachable publisher is
+    /// reported immediately.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    /// use ido::net::{IdoPublisher, IdoSubscriber};
+    ///
+    /// let mut publisher = IdoPublisher::bind("127.0.0.1:0").unwrap();
+    /// let mut subscriber = IdoSubscriber::connect(publisher.local_addr().unwrap()).unwrap();
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 42);
+    /// publisher.publish(&ido).unwrap();
+    ///
+    /// let received = subscriber.recv().unwrap();
+    /// assert_eq!(received.get_i64(&1), Some(42));
+    /// ```
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self, NetError> {
+        let mut subscriber = IdoSubscriber {
+            m_addrs: addr.to_socket_addrs()?.collect(),
+            m_stream: None,
+            m_decoder: FrameDecoder::new(),
+            m_policy: ReconnectPolicy::default(),
+            m_read_timeout: None,
+        };
+        subscriber.open()?;
+        Ok(subscriber)
+    }
+
+    /// Replaces the reconnect policy.
+    pub fn with_reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.m_policy = policy;
+        self
+    }
+
+    /// Sets a read timeout; `recv` returns an `Io` error of kind `WouldBlock`
+    /// or `TimedOut` when no frame arrives in time. The part of a frame
+    /// received before the timeout is kept for the next `recv`.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> Result<(), NetError> {
+        self.m_read_timeout = timeout;
+        if let Some(stream) = &self.m_stream {
+            stream.set_read_timeout(timeout)?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` while the subscriber holds an open connection.
+    pub fn is_connected(&self) -> bool {
+        self.m_stream.is_some()
+    }
+
+    fn open(&mut self) -> Result<(), NetError> {
+        let stream = TcpStream::connect(&self.m_addrs[..])?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(self.m_read_timeout)?;
+        self.m_stream = Some(stream);
+        self.m_decoder = FrameDecoder::new();
+        Ok(())
+    }
+
+    fn reconnect(&mut self) -> Result<(), NetError> {
+        let mut attempts = 0;
+        let mut delay = self.m_policy.initial_delay;
+
+        loop {
+            if self.m_policy.max_attempts.is_some_and(|max| attempts >= max) {
+                return Err(NetError::ReconnectFailed(attempts));
+            }
+
+            thread::sleep(delay);
+            attempts += 1;
+
+            if self.open().is_ok() {
+                return Ok(());
+            }
+            delay = (delay * 2).min(self.m_policy.max_delay);
+        }
+    }
+
+    /// Blocks until the next Ido arrives.
+    ///
+    /// If the connection is lost the subscriber reconnects according to its
+    /// [`ReconnectPolicy`] and keeps waiting. Idos published while the
+    /// subscriber was disconnected are not recovered.
+    pub fn recv(&mut self) -> Result<Ido, NetError> {
+        loop {
+            if self.m_stream.is_none() {
+                self.reconnect()?;
+            }
+
+            match self.m_decoder.next_frame() {
+                Ok(Some(ido)) => return Ok(ido),
+                Ok(None) => {}
+                Err(err) => {
+                    self.m_stream = None;
+                    return Err(err.into());
+                }
+            }
+
+            let stream = self.m_stream.as_mut().unwrap();
+            match self.m_decoder.read_from(stream) {
+                Ok(0) => self.m_stream = None,
+                Ok(_) => {}
+                Err(CodecError::Io(err))
+                    if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    return Err(NetError::Io(err));
+                }
+                Err(_) => self.m_stream = None,
+            }
+        }
+    }
+}
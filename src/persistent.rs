@@ -0,0 +1,286 @@
+//! An immutable Ido whose versions share unchanged items.
+//!
+//! Setting a value on a [`PersistentIdo`] leaves it untouched and returns a
+//! new version. Items live in a trie indexed by the bits of their key, five
+//! bits per level, and a new version only copies the nodes on the path to
+//! the changed key; everything else is shared with the original through
+//! reference counting. Keeping thousands of versions of a large Ido then
+//! costs a few small nodes per change instead of a full copy each.
+//!
+//! Items are kept in ascending key order rather than in insertion order.
+
+use std::sync::Arc;
+
+use crate::key::key_to_i64;
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+const BITS: u32 = 5;
+
+/// The trie index of `key` in its low `IdoKeyT::BITS` bits, ordered like
+/// the key itself.
+fn index_of(key: IdoKeyT) -> u64 {
+    let index = (key_to_i64(key) as u64) ^ (1 << (IdoKeyT::BITS - 1));
+    index & (u64::MAX >> (u64::BITS - IdoKeyT::BITS))
+}
+
+/// The child slot of `index` at `level`, taking the highest bits first.
+fn slot(index: u64, level: u32) -> u32 {
+    let used = BITS * (level + 1);
+    let slot = if used <= IdoKeyT::BITS {
+        index >> (IdoKeyT::BITS - used)
+    } else {
+        index << (used - IdoKeyT::BITS)
+    };
+    (slot & 0x1f) as u32
+}
+
+enum Node {
+    Leaf(IdoItem),
+    Branch { m_bitmap: u32, m_children: Vec<Arc<Node>> },
+}
+
+impl Node {
+    fn empty() -> Node {
+        Node::Branch { m_bitmap: 0, m_children: Vec::new() }
+    }
+
+    /// A branch at `level` holding two leaves with different keys.
+    fn pair(level: u32, first: Arc<Node>, second: Arc<Node>) -> Node {
+        let (Node::Leaf(a), Node::Leaf(b)) = (&*first, &*second) else {
+            unreachable!("pair is only built from leaves");
+        };
+        let (slot_a, slot_b) = (slot(index_of(a.m_key), level), slot(index_of(b.m_key), level));
+
+        if slot_a == slot_b {
+            let child = Node::pair(level + 1, first, second);
+            return Node::Branch { m_bitmap: 1 << slot_a, m_children: vec![Arc::new(child)] };
+        }
+        let children = if slot_a < slot_b { vec![first, second] } else { vec![second, first] };
+        Node::Branch { m_bitmap: (1 << slot_a) | (1 << slot_b), m_children: children }
+    }
+
+    fn get(&self, key: IdoKeyT, level: u32) -> Option<&IdoItem> {
+        match self {
+            Node::Leaf(item) => (item.m_key == key).then_some(item),
+            Node::Branch { m_bitmap, m_children } => {
+                let bit = 1 << slot(index_of(key), level);
+                if m_bitmap & bit == 0 {
+                    return None;
+                }
+                m_children[(m_bitmap & (bit - 1)).count_ones() as usize].get(key, level + 1)
+            }
+        }
+    }
+
+    /// Returns the node with `item` inserted, and whether its key is new.
+    fn insert(&self, item: IdoItem, level: u32) -> (Node, bool) {
+        let Node::Branch { m_bitmap, m_children } = self else {
+            unreachable!("insert is only called on branches");
+        };
+        let bit = 1 << slot(index_of(item.m_key), level);
+        let position = (m_bitmap & (bit - 1)).count_ones() as usize;
+        let mut children = m_children.clone();
+
+        if m_bitmap & bit == 0 {
+            children.insert(position, Arc::new(Node::Leaf(item)));
+            return (Node::Branch { m_bitmap: m_bitmap | bit, m_children: children }, true);
+        }
+
+        let (child, added) = match &*children[position] {
+            Node::Leaf(existing) if existing.m_key == item.m_key => (Node::Leaf(item), false),
+            Node::Leaf(_) => {
+                let existing = Arc::clone(&children[position]);
+                (Node::pair(level + 1, existing, Arc::new(Node::Leaf(item))), true)
+            }
+            branch => branch.insert(item, level + 1),
+        };
+        children[position] = Arc::new(child);
+        (Node::Branch { m_bitmap: *m_bitmap, m_children: children }, added)
+    }
+
+    /// Returns the node without `key`, or `None` if `key` is not present.
+    /// A branch left with a single leaf is replaced by that leaf.
+    fn remove(&self, key: IdoKeyT, level: u32) -> Option<Arc<Node>> {
+        let Node::Branch { m_bitmap, m_children } = self else {
+            unreachable!("remove is only called on branches");
+        };
+        let bit = 1 << slot(index_of(key), level);
+        if m_bitmap & bit == 0 {
+            return None;
+        }
+        let position = (m_bitmap & (bit - 1)).count_ones() as usize;
+        let mut children = m_children.clone();
+        let mut bitmap = *m_bitmap;
+
+        match &*children[position] {
+            Node::Leaf(existing) if existing.m_key == key => {
+                children.remove(position);
+                bitmap &= !bit;
+            }
+            Node::Leaf(_) => return None,
+            branch => {
+                let child = branch.remove(key, level + 1)?;
+                children[position] = child;
+            }
+        }
+
+        if level > 0 && children.len() == 1 && matches!(*children[0], Node::Leaf(_)) {
+            return children.pop();
+        }
+        Some(Arc::new(Node::Branch { m_bitmap: bitmap, m_children: children }))
+    }
+
+    fn collect<'a>(&'a self, items: &mut Vec<&'a IdoItem>) {
+        match self {
+            Node::Leaf(item) => items.push(item),
+            Node::Branch { m_children, .. } => {
+                for child in m_children {
+                    child.collect(items);
+                }
+            }
+        }
+    }
+}
+
+/// An immutable Ido whose versions share unchanged items.
+///
+/// Cloning is cheap, and every `set_*` and `delete_item` returns a new
+/// version instead of changing this one.
+///
+/// # Example
+///
+/// ```
+/// use ido::persistent::PersistentIdo;
+///
+/// let v1 = PersistentIdo::new().set_string(&1, "ORDER-1".to_string()).set_integer(&2, 100);
+/// let v2 = v1.set_integer(&2, 60);
+///
+/// assert_eq!(v1.get_i64(&2), Some(100));
+/// assert_eq!(v2.get_i64(&2), Some(60));
+/// assert!(std::ptr::eq(v1.get_item(&1).unwrap(), v2.get_item(&1).unwrap()));
+/// ```
+#[derive(Clone)]
+pub struct PersistentIdo {
+    m_root: Arc<Node>,
+    m_size: usize,
+}
+
+impl Default for PersistentIdo {
+    fn default() -> Self {
+        PersistentIdo { m_root: Arc::new(Node::empty()), m_size: 0 }
+    }
+}
+
+impl PersistentIdo {
+    /// Creates an empty Ido.
+    pub fn new() -> Self {
+        PersistentIdo::default()
+    }
+
+    /// Returns the number of items.
+    pub fn size(&self) -> usize {
+        self.m_size
+    }
+
+    /// Returns `true` if the Ido holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.m_size == 0
+    }
+
+    /// Returns `true` if an item is stored under `key`.
+    pub fn contains(&self, key: &IdoKeyT) -> bool {
+        self.get_item(key).is_some()
+    }
+
+    /// Returns the item stored under `key`.
+    pub fn get_item(&self, key: &IdoKeyT) -> Option<&IdoItem> {
+        self.m_root.get(*key, 0)
+    }
+
+    /// Returns the value under `key` rendered as a string.
+    pub fn get_string(&self, key: &IdoKeyT) -> Option<String> {
+        self.get_item(key).and_then(|item| item.as_string())
+    }
+
+    /// Returns the value under `key` if it is an INTEGER.
+    pub fn get_i64(&self, key: &IdoKeyT) -> Option<i64> {
+        self.get_item(key).filter(|item| item.m_type == IdoItemType::INTEGER).map(|item| item.m_integer)
+    }
+
+    /// Returns the value under `key` if it is a FLOAT.
+    pub fn get_f64(&self, key: &IdoKeyT) -> Option<f64> {
+        self.get_item(key).filter(|item| item.m_type == IdoItemType::FLOAT).map(|item| item.m_float)
+    }
+
+    /// Returns a new version with `item` stored under `key`.
+    pub fn set_item(&self, key: &IdoKeyT, mut item: IdoItem) -> Self {
+        item.m_key = *key;
+        let (root, added) = self.m_root.insert(item, 0);
+        PersistentIdo { m_root: Arc::new(root), m_size: self.m_size + usize::from(added) }
+    }
+
+    /// Returns a new version with a STRING stored under `key`.
+    pub fn set_string(&self, key: &IdoKeyT, val: String) -> Self {
+        let mut item = IdoItem::new();
+        item.m_type = IdoItemType::STRING;
+        item.m_string = val;
+        self.set_item(key, item)
+    }
+
+    /// Returns a new version with an INTEGER stored under `key`.
+    pub fn set_integer(&self, key: &IdoKeyT, val: i64) -> Self {
+        let mut item = IdoItem::new();
+        item.m_type = IdoItemType::INTEGER;
+        item.m_integer = val;
+        self.set_item(key, item)
+    }
+
+    /// Returns a new version with an INTEGER128 stored under `key`.
+    pub fn set_i128(&self, key: &IdoKeyT, val: i128) -> Self {
+        let mut item = IdoItem::new();
+        item.m_type = IdoItemType::INTEGER128;
+        item.m_integer128 = val;
+        self.set_item(key, item)
+    }
+
+    /// Returns a new version with a FLOAT stored under `key`.
+    pub fn set_f64(&self, key: &IdoKeyT, val: f64) -> Self {
+        let mut item = IdoItem::new();
+        item.m_type = IdoItemType::FLOAT;
+        item.m_float = val;
+        self.set_item(key, item)
+    }
+
+    /// Returns a new version without `key`. Deleting a missing key returns
+    /// a version sharing everything with this one.
+    pub fn delete_item(&self, key: &IdoKeyT) -> Self {
+        match self.m_root.remove(*key, 0) {
+            Some(root) => PersistentIdo { m_root: root, m_size: self.m_size - 1 },
+            None => self.clone(),
+        }
+    }
+
+    /// Returns the items in ascending key order.
+    pub fn items(&self) -> Vec<&IdoItem> {
+        let mut items = Vec::with_capacity(self.m_size);
+        self.m_root.collect(&mut items);
+        items
+    }
+
+    /// Copies the items into a regular Ido, in ascending key order.
+    pub fn to_ido(&self) -> Ido {
+        let mut ido = Ido::new();
+        for item in self.items() {
+            ido.set_item(&item.m_key, item.clone());
+        }
+        ido.clear_dirty();
+        ido
+    }
+}
+
+impl From<&Ido> for PersistentIdo {
+    fn from(ido: &Ido) -> Self {
+        ido.live_items()
+            .fold(PersistentIdo::new(), |persistent, (key, item)| persistent.set_item(&key, item.clone()))
+    }
+}
@@ -0,0 +1,395 @@
+//! Protobuf representation of Ido objects.
+//!
+//! The message types mirror `proto/ido.proto` and are what `prost-build`
+//! would generate from it, so services in other languages can compile that
+//! file and exchange Idos with Rust code over gRPC or any other protobuf
+//! transport.
+//!
+//! Idos can also travel inside existing APIs without a message of their
+//! own: [`Ido::to_any`] packs the message into a `google.protobuf.Any`, and
+//! [`Ido::to_struct`] maps the items onto a `google.protobuf.Struct` as the
+//! JSON mapping would, with keys named through the attached dictionary.
+
+use std::fmt;
+
+use chrono::DateTime;
+use prost::Name;
+use prost_types::value::Kind;
+use prost_types::{Any, ListValue, Struct};
+
+use crate::datetime::DateTimeFormat;
+use crate::dictionary::IdoDictionary;
+use crate::key::{key_from_i64, key_to_i64};
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+/// A whole Ido: its fields in insertion order.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct IdoMessage {
+    #[prost(message, repeated, tag = "1")]
+    pub fields: Vec<IdoField>,
+}
+
+impl Name for IdoMessage {
+    const NAME: &'static str = "IdoMessage";
+    const PACKAGE: &'static str = "ido";
+}
+
+/// A single key and its typed value.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct IdoField {
+    #[prost(int64, tag = "1")]
+    pub key: i64,
+    #[prost(oneof = "ido_field::Value", tags = "2, 3, 4, 5, 6, 7, 8")]
+    pub value: Option<ido_field::Value>,
+}
+
+/// The nested Idos of an ARRAY item.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct IdoArray {
+    #[prost(message, repeated, tag = "1")]
+    pub elements: Vec<IdoMessage>,
+}
+
+pub mod ido_field {
+    /// The value carried by an [`IdoField`](super::IdoField).
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Value {
+        #[prost(string, tag = "2")]
+        StringValue(String),
+        #[prost(int64, tag = "3")]
+        IntegerValue(i64),
+        #[prost(double, tag = "4")]
+        FloatValue(f64),
+        #[prost(message, tag = "5")]
+        DatetimeValue(prost_types::Timestamp),
+        #[prost(message, tag = "6")]
+        ArrayValue(super::IdoArray),
+        #[prost(string, tag = "7")]
+        Integer128Value(String),
+        #[prost(bool, tag = "8")]
+        BoolValue(bool),
+    }
+}
+
+/// Errors raised when converting a protobuf message into an Ido.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProtoError {
+    /// The key of a field does not fit in `IdoKeyT`.
+    InvalidKey(i64),
+    /// The field with this key has no value set.
+    MissingValue(IdoKeyT),
+    /// The timestamp of the field with this key is out of range.
+    InvalidTimestamp(IdoKeyT),
+    /// The 128-bit integer of the field with this key is not a decimal number.
+    InvalidInteger(IdoKeyT),
+    /// A `Struct` field name is neither in the dictionary nor a key.
+    UnknownName(String),
+    /// An `Any` does not hold an `ido.IdoMessage`, or its bytes do not
+    /// decode as one.
+    Decode(prost::DecodeError),
+}
+
+impl fmt::Display for ProtoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtoError::InvalidKey(key) => write!(f, "field key {} is out of range", key),
+            ProtoError::MissingValue(key) => write!(f, "field {} has no value", key),
+            ProtoError::InvalidTimestamp(key) => write!(f, "field {} has an invalid timestamp", key),
+            ProtoError::InvalidInteger(key) => write!(f, "field {} has an invalid integer", key),
+            ProtoError::UnknownName(name) => write!(f, "field {} is not a key or a known name", name),
+            ProtoError::Decode(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ProtoError {}
+
+impl Ido {
+    /// Converts the Ido into its protobuf message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    /// use prost::Message;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "value1".to_string());
+    ///
+    /// let bytes = ido.to_proto().encode_to_vec();
+    /// let message = ido::proto::IdoMessage::decode(bytes.as_slice()).unwrap();
+    ///
+    /// let decoded = Ido::from_proto(&message).unwrap();
+    /// assert_eq!(decoded.get_string(&1), Some("value1".to_string()));
+    /// ```
+    pub fn to_proto(&self) -> IdoMessage {
+        IdoMessage {
+            fields: self
+                .live_items()
+                .map(|(key, item)| IdoField { key: key_to_i64(key), value: Some(item_to_proto(item)) })
+                .collect(),
+        }
+    }
+
+    /// Builds an Ido from its protobuf message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ProtoError` if a field has no value or carries a key,
+    /// timestamp or 128-bit integer that cannot be represented.
+    pub fn from_proto(message: &IdoMessage) -> Result<Ido, ProtoError> {
+        let mut ido = Ido::new();
+
+        for field in &message.fields {
+            let key = key_from_i64(field.key).ok_or(ProtoError::InvalidKey(field.key))?;
+            let value = field.value.as_ref().ok_or(ProtoError::MissingValue(key))?;
+            ido.set_item(&key, item_from_proto(key, value)?);
+        }
+        Ok(ido)
+    }
+
+    /// Packs the protobuf message of the Ido into an `Any`, with the type
+    /// URL `/ido.IdoMessage`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 100);
+    ///
+    /// let any = ido.to_any();
+    /// assert_eq!(any.type_url, "/ido.IdoMessage");
+    /// assert_eq!(Ido::from_any(&any).unwrap().get_i64(&1), Some(100));
+    /// ```
+    pub fn to_any(&self) -> Any {
+        Any { type_url: IdoMessage::type_url(), value: prost::Message::encode_to_vec(&self.to_proto()) }
+    }
+
+    /// Unpacks an Ido from an `Any` built by [`to_any`](Ido::to_any). Type
+    /// URLs with a domain, such as `type.googleapis.com/ido.IdoMessage`,
+    /// are accepted as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProtoError::Decode` if the `Any` holds another message type
+    /// or malformed bytes, and the errors of
+    /// [`from_proto`](Ido::from_proto) otherwise.
+    pub fn from_any(any: &Any) -> Result<Ido, ProtoError> {
+        Ido::from_proto(&any.to_msg::<IdoMessage>().map_err(ProtoError::Decode)?)
+    }
+
+    /// Maps the Ido onto a `Struct`, with one field per item named by the
+    /// key, or by its name with a dictionary attached by
+    /// [`set_names`](Ido::set_names). Values map as the JSON mapping of
+    /// `Struct` expects:
+    ///
+    /// | Ido type      | `Value`                                         |
+    /// |---------------|-------------------------------------------------|
+    /// | STRING        | `string_value`                                  |
+    /// | INTEGER       | `number_value`, exact up to 2^53                |
+    /// | INTEGER128    | `string_value` in decimal                       |
+    /// | FLOAT         | `number_value`                                  |
+    /// | BOOL          | `bool_value`                                    |
+    /// | DATETIME      | `string_value` in RFC 3339                      |
+    /// | ARRAY         | `list_value` of `struct_value`s                 |
+    ///
+    /// A `Struct` keeps its fields sorted by name, so insertion order is
+    /// not preserved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use ido::dictionary::IdoDictionary;
+    /// use ido::Ido;
+    /// use prost_types::value::Kind;
+    ///
+    /// let names: IdoDictionary = [(1, "symbol")].into_iter().collect();
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "EURUSD".to_string());
+    /// ido.set_integer(&2, 100);
+    /// ido.set_names(Arc::new(names.clone()));
+    ///
+    /// let message = ido.to_struct();
+    /// assert_eq!(message.fields["symbol"].kind, Some(Kind::StringValue("EURUSD".to_string())));
+    /// assert_eq!(message.fields["2"].kind, Some(Kind::NumberValue(100.0)));
+    ///
+    /// let decoded = Ido::from_struct(&message, Some(&names)).unwrap();
+    /// assert_eq!(decoded.get_i64(&2), Some(100));
+    /// ```
+    pub fn to_struct(&self) -> Struct {
+        struct_from_ido(self, self.names())
+    }
+
+    /// Builds an Ido from a `Struct`, such as one made by
+    /// [`to_struct`](Ido::to_struct).
+    ///
+    /// Field names are looked up in `names` first, then read as keys.
+    /// Numbers become INTEGER items when they are whole and within 2^53,
+    /// and FLOAT items otherwise. A nested `struct_value` becomes an ARRAY
+    /// of one Ido, and a `list_value` an ARRAY whose elements are its
+    /// structs, or Idos holding any other value under key 0. Null values
+    /// are skipped, and strings stay strings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProtoError::UnknownName` for a field name that is neither
+    /// in `names` nor a key.
+    pub fn from_struct(message: &Struct, names: Option<&IdoDictionary>) -> Result<Ido, ProtoError> {
+        let mut ido = Ido::new();
+
+        for (name, value) in &message.fields {
+            let key = names
+                .and_then(|names| names.key(name))
+                .or_else(|| name.parse().ok())
+                .ok_or_else(|| ProtoError::UnknownName(name.clone()))?;
+            if let Some(item) = item_from_value(value.kind.as_ref(), names)? {
+                ido.set_item(&key, item);
+            }
+        }
+        Ok(ido)
+    }
+}
+
+pub(crate) fn struct_from_ido(ido: &Ido, names: Option<&IdoDictionary>) -> Struct {
+    let fields = ido
+        .live_items()
+        .map(|(key, item)| {
+            let name = match names.and_then(|names| names.name(&key)) {
+                Some(name) => name.to_string(),
+                None => key.to_string(),
+            };
+            (name, prost_types::Value { kind: Some(item_to_kind(item, names)) })
+        })
+        .collect();
+    Struct { fields }
+}
+
+fn item_to_kind(item: &IdoItem, names: Option<&IdoDictionary>) -> Kind {
+    match item.m_type {
+        IdoItemType::STRING => Kind::StringValue(item.m_string.clone()),
+        IdoItemType::INTEGER => Kind::NumberValue(item.m_integer as f64),
+        IdoItemType::INTEGER128 => Kind::StringValue(item.m_integer128.to_string()),
+        IdoItemType::FLOAT => Kind::NumberValue(item.m_float),
+        IdoItemType::BOOL => Kind::BoolValue(item.m_bool),
+        IdoItemType::DATETIME => Kind::StringValue(DateTimeFormat::rfc3339().format(&item.m_datetime)),
+        IdoItemType::ARRAY => Kind::ListValue(ListValue {
+            values: item
+                .m_array
+                .iter()
+                .map(|element| prost_types::Value { kind: Some(Kind::StructValue(struct_from_ido(element, names))) })
+                .collect(),
+        }),
+    }
+}
+
+/// Converts a `Struct` value, or returns `None` for a null or unset one.
+fn item_from_value(kind: Option<&Kind>, names: Option<&IdoDictionary>) -> Result<Option<IdoItem>, ProtoError> {
+    const EXACT: f64 = (1u64 << 53) as f64;
+
+    let mut item = IdoItem::new();
+    match kind {
+        None | Some(Kind::NullValue(_)) => return Ok(None),
+        Some(Kind::NumberValue(val)) if val.fract() == 0.0 && val.abs() <= EXACT => {
+            item.m_type = IdoItemType::INTEGER;
+            item.m_integer = *val as i64;
+        }
+        Some(Kind::NumberValue(val)) => {
+            item.m_type = IdoItemType::FLOAT;
+            item.m_float = *val;
+        }
+        Some(Kind::StringValue(val)) => {
+            item.m_type = IdoItemType::STRING;
+            item.m_string = val.clone();
+        }
+        Some(Kind::BoolValue(val)) => {
+            item.m_type = IdoItemType::BOOL;
+            item.m_bool = *val;
+        }
+        Some(Kind::StructValue(message)) => {
+            item.m_type = IdoItemType::ARRAY;
+            item.m_array.push(Ido::from_struct(message, names)?);
+        }
+        Some(Kind::ListValue(list)) => {
+            item.m_type = IdoItemType::ARRAY;
+            for value in &list.values {
+                let element = match &value.kind {
+                    Some(Kind::StructValue(message)) => Ido::from_struct(message, names)?,
+                    kind => {
+                        let mut element = Ido::new();
+                        if let Some(nested) = item_from_value(kind.as_ref(), names)? {
+                            element.set_item(&0, nested);
+                        }
+                        element
+                    }
+                };
+                item.m_array.push(element);
+            }
+        }
+    }
+    Ok(Some(item))
+}
+
+fn item_to_proto(item: &IdoItem) -> ido_field::Value {
+    use ido_field::Value;
+
+    match item.m_type {
+        IdoItemType::STRING => Value::StringValue(item.m_string.clone()),
+        IdoItemType::INTEGER => Value::IntegerValue(item.m_integer),
+        IdoItemType::FLOAT => Value::FloatValue(item.m_float),
+        IdoItemType::DATETIME => Value::DatetimeValue(prost_types::Timestamp {
+            seconds: item.m_datetime.timestamp(),
+            nanos: item.m_datetime.timestamp_subsec_nanos() as i32,
+        }),
+        IdoItemType::ARRAY => Value::ArrayValue(IdoArray {
+            elements: item.m_array.iter().map(Ido::to_proto).collect(),
+        }),
+        IdoItemType::INTEGER128 => Value::Integer128Value(item.m_integer128.to_string()),
+        IdoItemType::BOOL => Value::BoolValue(item.m_bool),
+    }
+}
+
+fn item_from_proto(key: IdoKeyT, value: &ido_field::Value) -> Result<IdoItem, ProtoError> {
+    use ido_field::Value;
+
+    let mut item = IdoItem::new();
+    match value {
+        Value::StringValue(val) => {
+            item.m_type = IdoItemType::STRING;
+            item.m_string = val.clone();
+        }
+        Value::IntegerValue(val) => {
+            item.m_type = IdoItemType::INTEGER;
+            item.m_integer = *val;
+        }
+        Value::FloatValue(val) => {
+            item.m_type = IdoItemType::FLOAT;
+            item.m_float = *val;
+        }
+        Value::DatetimeValue(ts) => {
+            let nanos = u32::try_from(ts.nanos).map_err(|_| ProtoError::InvalidTimestamp(key))?;
+            item.m_type = IdoItemType::DATETIME;
+            item.m_datetime = DateTime::from_timestamp(ts.seconds, nanos)
+                .ok_or(ProtoError::InvalidTimestamp(key))?;
+        }
+        Value::ArrayValue(array) => {
+            item.m_type = IdoItemType::ARRAY;
+            for element in &array.elements {
+                item.m_array.push(Ido::from_proto(element)?);
+            }
+        }
+        Value::Integer128Value(val) => {
+            item.m_type = IdoItemType::INTEGER128;
+            item.m_integer128 = val.parse().map_err(|_| ProtoError::InvalidInteger(key))?;
+        }
+        Value::BoolValue(val) => {
+            item.m_type = IdoItemType::BOOL;
+            item.m_bool = *val;
+        }
+    }
+    Ok(item)
+}
@@ -0,0 +1,187 @@
+//! Reserving ranges of keys for the parties writing a shared message.
+//!
+//! A [`KeyRanges`] registry assigns blocks of keys to owners, for instance
+//! 0 to 999 to the framework and 1000 upwards to the application. An Ido
+//! given the registry with [`Ido::set_key_ranges`] knows which owner is
+//! writing to it, and every write into a range reserved for another owner
+//! is refused or recorded according to the [`RangePolicy`]. Keys outside
+//! every range can be written by anyone.
+
+use std::collections::BTreeSet;
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+use std::sync::Arc;
+
+use crate::{Ido, IdoError, IdoKeyT};
+
+/// Ranges of keys and the owner each one is reserved for.
+///
+/// # Example
+///
+/// ```
+/// use ido::ranges::KeyRanges;
+///
+/// let ranges = KeyRanges::new()
+///     .reserve(0..1000, "framework")
+///     .reserve(1000.., "application");
+///
+/// assert_eq!(ranges.owner_of(&999), Some("framework"));
+/// assert_eq!(ranges.owner_of(&1000), Some("application"));
+/// assert_eq!(ranges.owner_of(&-1), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct KeyRanges {
+    m_ranges: Vec<(RangeInclusive<IdoKeyT>, String)>,
+}
+
+impl KeyRanges {
+    /// Creates a registry without any reserved range.
+    pub fn new() -> Self {
+        KeyRanges::default()
+    }
+
+    /// Reserves the keys in `range` for `owner`. An owner may hold several
+    /// ranges.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty or overlaps a range reserved before.
+    pub fn reserve<R: RangeBounds<IdoKeyT>>(mut self, range: R, owner: &str) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(start) => Some(*start),
+            Bound::Excluded(start) => start.checked_add(1),
+            Bound::Unbounded => Some(IdoKeyT::MIN),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => Some(*end),
+            Bound::Excluded(end) => end.checked_sub(1),
+            Bound::Unbounded => Some(IdoKeyT::MAX),
+        };
+        let range = match (start, end) {
+            (Some(start), Some(end)) if start <= end => start..=end,
+            _ => panic!("reserved key range for {} is empty", owner),
+        };
+
+        if let Some((other, other_owner)) = self
+            .m_ranges
+            .iter()
+            .find(|(other, _)| range.start() <= other.end() && other.start() <= range.end())
+        {
+            panic!("keys {:?} for {} overlap keys {:?} reserved for {}", range, owner, other, other_owner);
+        }
+        self.m_ranges.push((range, owner.to_string()));
+        self
+    }
+
+    /// Returns the owner `key` is reserved for, if any.
+    pub fn owner_of(&self, key: &IdoKeyT) -> Option<&str> {
+        self.m_ranges
+            .iter()
+            .find(|(range, _)| range.contains(key))
+            .map(|(_, owner)| owner.as_str())
+    }
+
+    /// Returns the ranges reserved for `owner`, in the order they were
+    /// reserved.
+    pub fn ranges_of<'a>(&'a self, owner: &'a str) -> impl Iterator<Item = &'a RangeInclusive<IdoKeyT>> + 'a {
+        self.m_ranges.iter().filter(move |(_, other)| other == owner).map(|(range, _)| range)
+    }
+}
+
+/// What an Ido does with a write into a range reserved for another owner.
+///
+/// See [`Ido::set_key_ranges`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum RangePolicy {
+    /// Store the value and record the key, see [`Ido::foreign_writes`].
+    /// With the `tracing` feature a warning event is emitted as well.
+    Warn,
+    /// Refuse the value with `IdoError::ForeignKey`.
+    #[default]
+    Reject,
+}
+
+/// The registry of an Ido and the owner writing to it.
+#[derive(Clone)]
+pub(crate) struct RangeGuard {
+    m_ranges: Arc<KeyRanges>,
+    m_owner: String,
+    m_policy: RangePolicy,
+    m_foreign: BTreeSet<IdoKeyT>,
+}
+
+impl Ido {
+    /// Checks writes to this Ido against reserved key ranges.
+    ///
+    /// From now on the Ido is written by `owner`: setting or appending to a
+    /// key reserved for another owner in `ranges` is handled according to
+    /// `policy`. Keys reserved for `owner` and keys outside every range are
+    /// written as usual. Deleting is always allowed, and items already held
+    /// are kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use ido::{Ido, IdoError};
+    /// use ido::ranges::{KeyRanges, RangePolicy};
+    ///
+    /// let ranges = Arc::new(KeyRanges::new().reserve(0..1000, "framework").reserve(1000.., "application"));
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_key_ranges(ranges, "application", RangePolicy::Reject);
+    /// ido.set_integer(&1000, 5);
+    ///
+    /// let err = ido.try_set_item(&1, ido.get_item(&1000).unwrap()).unwrap_err();
+    /// assert_eq!(err, IdoError::ForeignKey { key: 1, owner: "framework".to_string() });
+    /// assert!(!ido.contains(&1));
+    /// ```
+    pub fn set_key_ranges(&mut self, ranges: Arc<KeyRanges>, owner: &str, policy: RangePolicy) {
+        self.m_key_ranges = Some(RangeGuard {
+            m_ranges: ranges,
+            m_owner: owner.to_string(),
+            m_policy: policy,
+            m_foreign: BTreeSet::new(),
+        });
+    }
+
+    /// Stops checking writes against reserved key ranges.
+    pub fn clear_key_ranges(&mut self) {
+        self.m_key_ranges = None;
+    }
+
+    /// Returns the reserved key ranges and the owner writing this Ido, if
+    /// set with [`set_key_ranges`](Ido::set_key_ranges).
+    pub fn key_ranges(&self) -> Option<(&KeyRanges, &str)> {
+        self.m_key_ranges.as_ref().map(|guard| (&*guard.m_ranges, guard.m_owner.as_str()))
+    }
+
+    /// Returns the keys of other owners written under
+    /// [`RangePolicy::Warn`], in ascending order.
+    pub fn foreign_writes(&self) -> Vec<IdoKeyT> {
+        self.m_key_ranges
+            .as_ref()
+            .map(|guard| guard.m_foreign.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Applies the range policy to a write under `key`.
+    pub(crate) fn check_range(&mut self, key: &IdoKeyT) -> Result<(), IdoError> {
+        let Some(guard) = &mut self.m_key_ranges else {
+            return Ok(());
+        };
+        let owner = match guard.m_ranges.owner_of(key) {
+            Some(owner) if owner != guard.m_owner => owner,
+            _ => return Ok(()),
+        };
+
+        match guard.m_policy {
+            RangePolicy::Reject => Err(IdoError::ForeignKey { key: *key, owner: owner.to_string() }),
+            RangePolicy::Warn => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(key = *key, owner, writer = guard.m_owner.as_str(), "write to a key reserved for another owner");
+                guard.m_foreign.insert(*key);
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Keeping several versions of a message schema and upgrading old messages.
+//!
+//! A [`SchemaRegistry`] stores, for each message type, an ordered list of
+//! schema versions. Every version after the first carries a [`Migration`]
+//! describing how to turn a message of the previous version into one of
+//! this version, so a service can accept messages from peers that have not
+//! been upgraded yet and work with the latest layout only.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::convert::IdoValue;
+use crate::schema::{IdoSchema, Violation};
+use crate::{Ido, IdoKeyT};
+
+#[derive(Debug, Clone)]
+enum MigrationStep {
+    Rename(IdoKeyT, IdoKeyT),
+    Remove(IdoKeyT),
+    Default(IdoKeyT),
+}
+
+/// The changes needed to move a message from one schema version to the next.
+///
+/// Steps are applied in the order they were added.
+#[derive(Clone, Default)]
+pub struct Migration {
+    m_steps: Vec<MigrationStep>,
+    m_defaults: Ido,
+}
+
+impl Migration {
+    /// Creates a migration that changes nothing.
+    pub fn new() -> Self {
+        Migration::default()
+    }
+
+    /// Moves the value stored under `from` to `to`.
+    pub fn rename(mut self, from: IdoKeyT, to: IdoKeyT) -> Self {
+        self.m_steps.push(MigrationStep::Rename(from, to));
+        self
+    }
+
+    /// Drops the value stored under `key`.
+    pub fn remove(mut self, key: IdoKeyT) -> Self {
+        self.m_steps.push(MigrationStep::Remove(key));
+        self
+    }
+
+    /// Sets `key` to `value` if the message does not have it yet.
+    pub fn default_value<V: IdoValue>(mut self, key: IdoKeyT, value: V) -> Self {
+        value.write(&mut self.m_defaults, key);
+        self.m_steps.push(MigrationStep::Default(key));
+        self
+    }
+
+    /// Applies the migration to `ido` in place.
+    pub fn apply(&self, ido: &mut Ido) {
+        for step in &self.m_steps {
+            match step {
+                MigrationStep::Rename(from, to) => {
+                    if let Some(item) = ido.get_item(from) {
+                        ido.delete_item(from);
+                        ido.set_item(to, item);
+                    }
+                }
+                MigrationStep::Remove(key) => ido.delete_item(key),
+                MigrationStep::Default(key) => {
+                    if !ido.contains(key) {
+                        if let Some(item) = self.m_defaults.get_item(key) {
+                            ido.set_item(key, item);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Migration")
+            .field("m_steps", &self.m_steps)
+            .field("m_defaults", &self.m_defaults.to_string())
+            .finish()
+    }
+}
+
+/// Errors raised by the schema registry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    /// No schema is registered for the message type.
+    UnknownType(String),
+    /// The message type has no schema with this version.
+    UnknownVersion(String, u32),
+    /// A version was registered that is not newer than the latest one.
+    VersionNotNewer { message_type: String, version: u32, latest: u32 },
+    /// The upgraded message does not match the latest schema.
+    Invalid(Vec<Violation>),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::UnknownType(message_type) => write!(f, "unknown message type {}", message_type),
+            RegistryError::UnknownVersion(message_type, version) => {
+                write!(f, "message type {} has no version {}", message_type, version)
+            }
+            RegistryError::VersionNotNewer { message_type, version, latest } => write!(
+                f,
+                "version {} of message type {} is not newer than version {}",
+                version, message_type, latest
+            ),
+            RegistryError::Invalid(violations) => {
+                write!(f, "upgraded message has {} schema violations", violations.len())
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+#[derive(Debug, Clone)]
+struct SchemaVersion {
+    m_version: u32,
+    m_schema: IdoSchema,
+    m_migration: Migration,
+}
+
+/// Stores the schema versions of every message type.
+///
+/// # Example
+///
+/// ```
+/// use ido::{Ido, IdoItemType};
+/// use ido::registry::{Migration, SchemaRegistry};
+/// use ido::schema::IdoSchema;
+///
+/// let mut registry = SchemaRegistry::new();
+/// registry
+///     .register("order", 1, IdoSchema::new().required(1, IdoItemType::INTEGER), Migration::new())
+///     .unwrap();
+/// registry
+///     .register(
+///         "order",
+///         2,
+///         IdoSchema::new()
+///             .required(10, IdoItemType::INTEGER)
+///             .required(11, IdoItemType::STRING),
+///         Migration::new().rename(1, 10).default_value(11, "GTC".to_string()),
+///     )
+///     .unwrap();
+///
+/// let mut ido = Ido::new();
+/// ido.set_integer(&1, 100);
+///
+/// assert_eq!(registry.upgrade("order", 1, &mut ido), Ok(2));
+/// assert_eq!(ido.to_string(), "10=100,11=GTC");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    m_types: HashMap<String, Vec<SchemaVersion>>,
+}
+
+impl SchemaRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        SchemaRegistry::default()
+    }
+
+    /// Adds a new version of a message type.
+    ///
+    /// `migration` describes how to upgrade a message from the previous
+    /// version and is ignored for the first version of a type.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RegistryError::VersionNotNewer` if `version` is not greater
+    /// than the latest registered version of the type.
+    pub fn register(
+        &mut self,
+        message_type: &str,
+        version: u32,
+        schema: IdoSchema,
+        migration: Migration,
+    ) -> Result<(), RegistryError> {
+        let versions = self.m_types.entry(message_type.to_string()).or_default();
+        if let Some(latest) = versions.last() {
+            if version <= latest.m_version {
+                return Err(RegistryError::VersionNotNewer {
+                    message_type: message_type.to_string(),
+                    version,
+                    latest: latest.m_version,
+                });
+            }
+        }
+
+        versions.push(SchemaVersion { m_version: version, m_schema: schema, m_migration: migration });
+        Ok(())
+    }
+
+    /// Returns the schema of a specific version.
+    pub fn schema(&self, message_type: &str, version: u32) -> Option<&IdoSchema> {
+        self.m_types
+            .get(message_type)?
+            .iter()
+            .find(|entry| entry.m_version == version)
+            .map(|entry| &entry.m_schema)
+    }
+
+    /// Returns the latest version of a message type and its schema.
+    pub fn latest(&self, message_type: &str) -> Option<(u32, &IdoSchema)> {
+        self.m_types
+            .get(message_type)?
+            .last()
+            .map(|entry| (entry.m_version, &entry.m_schema))
+    }
+
+    /// Upgrades a message of version `from` to the latest version of its
+    /// type, then validates it against the latest schema.
+    ///
+    /// # Returns
+    ///
+    /// The version the message now conforms to.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RegistryError` if the type or version is unknown, or if
+    /// the upgraded message does not match the latest schema. The message
+    /// may be partially migrated in the latter case.
+    pub fn upgrade(&self, message_type: &str, from: u32, ido: &mut Ido) -> Result<u32, RegistryError> {
+        let versions = self
+            .m_types
+            .get(message_type)
+            .ok_or_else(|| RegistryError::UnknownType(message_type.to_string()))?;
+        let start = versions
+            .iter()
+            .position(|entry| entry.m_version == from)
+            .ok_or_else(|| RegistryError::UnknownVersion(message_type.to_string(), from))?;
+
+        for entry in &versions[start + 1..] {
+            entry.m_migration.apply(ido);
+        }
+
+        let latest = versions.last().unwrap();
+        ido.validate(&latest.m_schema).map_err(RegistryError::Invalid)?;
+        Ok(latest.m_version)
+    }
+}
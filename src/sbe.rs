@@ -0,0 +1,462 @@
+//! Mapping Simple Binary Encoding (SBE) messages to and from Idos.
+//!
+//! SBE messages are laid out by an XML schema shared with the exchange. An
+//! [`SbeMessage`] describes the parts of one message template needed to
+//! fill an Ido: where each fixed field sits in the root block and which key
+//! it maps to, the repeating groups that follow the block and the
+//! variable-length data after them. Fields not described are skipped.
+//!
+//! The standard framing is used throughout, in little-endian byte order:
+//!
+//! ```text
+//! message := header block group* var_data*
+//! header  := block_length:u16 template_id:u16 schema_id:u16 version:u16
+//! group   := block_length:u16 count:u16 (block group* var_data*)*
+//! var_data := len:u16 bytes
+//! ```
+//!
+//! Integers become INTEGER items, or INTEGER128 for `u64` values beyond
+//! `i64`, floats become FLOAT items, fixed-length character arrays become
+//! STRING items without their trailing NULs and nanosecond timestamps
+//! become DATETIME items, or INTEGER nanoseconds without the `chrono`
+//! feature. A repeating group becomes an ARRAY item with one
+//! element per entry. Optional fields holding the SBE null value of their
+//! type are left out of the Ido, and written as the null value when the
+//! Ido has no item for them.
+//!
+//! ```
+//! use ido::sbe::{SbeBlock, SbeMessage, SbeType};
+//! use ido::Ido;
+//!
+//! let levels = SbeBlock::new(12).field(270, 0, SbeType::Int64).field(271, 8, SbeType::Int32);
+//! let message = SbeMessage::new(46, SbeBlock::new(9).field(55, 0, SbeType::Char(8)).group(268, levels));
+//!
+//! let mut level = Ido::new();
+//! level.set_integer(&270, 101_250);
+//! level.set_integer(&271, 5);
+//!
+//! let mut ido = Ido::new();
+//! ido.set_string(&55, "ESZ3".to_string());
+//! ido.append_array(&268, level);
+//!
+//! let bytes = message.encode(&ido).unwrap();
+//! assert_eq!(bytes.len(), 8 + 9 + 4 + 12);
+//!
+//! let decoded = message.decode(&bytes).unwrap();
+//! assert_eq!(decoded.get_string(&55), Some("ESZ3".to_string()));
+//! assert_eq!(decoded.get_item(&268).unwrap().m_array[0].get_i64(&271), Some(5));
+//! ```
+
+use std::fmt;
+
+#[cfg(feature = "chrono")]
+use chrono::DateTime;
+
+use crate::codec::{CodecError, Reader};
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+const HEADER_LEN: usize = 8;
+
+/// Errors raised while converting SBE messages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SbeError {
+    /// The message ended in the middle of a header, block or value.
+    Truncated,
+    /// The header names a different message template.
+    TemplateMismatch { expected: u16, actual: u16 },
+    /// A required field is missing from the message or the Ido.
+    MissingField(IdoKeyT),
+    /// The value under this key does not fit its SBE type.
+    InvalidValue(IdoKeyT),
+    /// Bytes left over after the message.
+    TrailingBytes(usize),
+}
+
+impl fmt::Display for SbeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SbeError::Truncated => write!(f, "message is truncated"),
+            SbeError::TemplateMismatch { expected, actual } => {
+                write!(f, "template {} does not match expected template {}", actual, expected)
+            }
+            SbeError::MissingField(key) => write!(f, "required field {} is missing", key),
+            SbeError::InvalidValue(key) => write!(f, "value of key {} does not fit its sbe type", key),
+            SbeError::TrailingBytes(n) => write!(f, "{} trailing bytes after message", n),
+        }
+    }
+}
+
+impl std::error::Error for SbeError {}
+
+impl From<CodecError> for SbeError {
+    fn from(_: CodecError) -> Self {
+        SbeError::Truncated
+    }
+}
+
+/// The primitive type of a fixed field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbeType {
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Float,
+    Double,
+    /// A character array of the given length, padded with NULs.
+    Char(usize),
+    /// Nanoseconds since the Unix epoch as a `u64`.
+    TimestampNanos,
+}
+
+impl SbeType {
+    fn size(self) -> usize {
+        match self {
+            SbeType::Int8 | SbeType::UInt8 => 1,
+            SbeType::Int16 | SbeType::UInt16 => 2,
+            SbeType::Int32 | SbeType::UInt32 | SbeType::Float => 4,
+            SbeType::Int64 | SbeType::UInt64 | SbeType::Double | SbeType::TimestampNanos => 8,
+            SbeType::Char(len) => len,
+        }
+    }
+
+    fn null_value(self) -> Option<i128> {
+        match self {
+            SbeType::Int8 => Some(i8::MIN.into()),
+            SbeType::Int16 => Some(i16::MIN.into()),
+            SbeType::Int32 => Some(i32::MIN.into()),
+            SbeType::Int64 => Some(i64::MIN.into()),
+            SbeType::UInt8 => Some(u8::MAX.into()),
+            SbeType::UInt16 => Some(u16::MAX.into()),
+            SbeType::UInt32 => Some(u32::MAX.into()),
+            SbeType::UInt64 | SbeType::TimestampNanos => Some(u64::MAX.into()),
+            SbeType::Float | SbeType::Double | SbeType::Char(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SbeField {
+    m_key: IdoKeyT,
+    m_offset: usize,
+    m_type: SbeType,
+    m_optional: bool,
+}
+
+/// The fixed block of a message or group entry, with the groups and
+/// variable-length data that follow it.
+///
+/// Groups and variable-length data must be declared in the order they
+/// appear in the schema.
+#[derive(Debug, Clone)]
+pub struct SbeBlock {
+    m_block_length: u16,
+    m_fields: Vec<SbeField>,
+    m_groups: Vec<(IdoKeyT, SbeBlock)>,
+    m_var_data: Vec<IdoKeyT>,
+}
+
+impl SbeBlock {
+    /// Creates a block of `block_length` bytes without fields.
+    pub fn new(block_length: u16) -> Self {
+        SbeBlock { m_block_length: block_length, m_fields: Vec::new(), m_groups: Vec::new(), m_var_data: Vec::new() }
+    }
+
+    /// Maps the required field at `offset` to `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the field does not fit in the block.
+    pub fn field(self, key: IdoKeyT, offset: usize, ty: SbeType) -> Self {
+        self.add_field(key, offset, ty, false)
+    }
+
+    /// Maps the optional field at `offset` to `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the field does not fit in the block.
+    pub fn optional(self, key: IdoKeyT, offset: usize, ty: SbeType) -> Self {
+        self.add_field(key, offset, ty, true)
+    }
+
+    /// Maps the next repeating group to an ARRAY item under `key`.
+    pub fn group(mut self, key: IdoKeyT, entry: SbeBlock) -> Self {
+        self.m_groups.push((key, entry));
+        self
+    }
+
+    /// Maps the next variable-length data field to a STRING item under `key`.
+    /// Empty data leaves the key out of the Ido.
+    pub fn var_data(mut self, key: IdoKeyT) -> Self {
+        self.m_var_data.push(key);
+        self
+    }
+
+    fn add_field(mut self, key: IdoKeyT, offset: usize, ty: SbeType, optional: bool) -> Self {
+        assert!(
+            offset + ty.size() <= usize::from(self.m_block_length),
+            "field {} does not fit in a block of {} bytes",
+            key,
+            self.m_block_length
+        );
+        self.m_fields.push(SbeField { m_key: key, m_offset: offset, m_type: ty, m_optional: optional });
+        self
+    }
+
+    fn read(&self, reader: &mut Reader<'_>, block_length: usize) -> Result<Ido, SbeError> {
+        let block = reader.take(block_length)?;
+        let mut ido = Ido::new();
+
+        for field in &self.m_fields {
+            let value = block.get(field.m_offset..field.m_offset + field.m_type.size());
+            match value.map(|value| read_field(field, value)).transpose()?.flatten() {
+                Some(item) => ido.set_item(&field.m_key, item),
+                None if field.m_optional => {}
+                None => return Err(SbeError::MissingField(field.m_key)),
+            }
+        }
+
+        for (key, entry) in &self.m_groups {
+            let block_length = u16::from_le_bytes(reader.array()?);
+            let count = u16::from_le_bytes(reader.array()?);
+
+            let mut item = IdoItem::new();
+            item.m_type = IdoItemType::ARRAY;
+            for _ in 0..count {
+                item.m_array.push(entry.read(reader, usize::from(block_length))?);
+            }
+            ido.set_item(key, item);
+        }
+
+        for key in &self.m_var_data {
+            let len = u16::from_le_bytes(reader.array()?);
+            let bytes = reader.take(usize::from(len))?;
+            let value = std::str::from_utf8(bytes).map_err(|_| SbeError::InvalidValue(*key))?;
+            if !value.is_empty() {
+                ido.set_string(key, value.to_string());
+            }
+        }
+        Ok(ido)
+    }
+
+    fn write(&self, ido: &Ido, out: &mut Vec<u8>) -> Result<(), SbeError> {
+        let start = out.len();
+        out.resize(start + usize::from(self.m_block_length), 0);
+
+        for field in &self.m_fields {
+            let slot = &mut out[start + field.m_offset..start + field.m_offset + field.m_type.size()];
+            match ido.get_item(&field.m_key) {
+                Some(item) => write_field(field, &item, slot)?,
+                None if field.m_optional => write_null(field.m_type, slot),
+                None => return Err(SbeError::MissingField(field.m_key)),
+            }
+        }
+
+        for (key, entry) in &self.m_groups {
+            let elements = match ido.get_item(key) {
+                Some(item) if item.m_type == IdoItemType::ARRAY => item.m_array,
+                Some(_) => return Err(SbeError::InvalidValue(*key)),
+                None => Vec::new(),
+            };
+            let count = u16::try_from(elements.len()).map_err(|_| SbeError::InvalidValue(*key))?;
+            out.extend_from_slice(&entry.m_block_length.to_le_bytes());
+            out.extend_from_slice(&count.to_le_bytes());
+            for element in &elements {
+                entry.write(element, out)?;
+            }
+        }
+
+        for key in &self.m_var_data {
+            let value = match ido.get_item(key) {
+                Some(item) if item.m_type == IdoItemType::STRING => item.m_string,
+                Some(_) => return Err(SbeError::InvalidValue(*key)),
+                None => String::new(),
+            };
+            let len = u16::try_from(value.len()).map_err(|_| SbeError::InvalidValue(*key))?;
+            out.extend_from_slice(&len.to_le_bytes());
+            out.extend_from_slice(value.as_bytes());
+        }
+        Ok(())
+    }
+}
+
+fn read_field(field: &SbeField, value: &[u8]) -> Result<Option<IdoItem>, SbeError> {
+    let mut item = IdoItem::new();
+
+    let integer = match field.m_type {
+        SbeType::Int8 => i128::from(value[0] as i8),
+        SbeType::Int16 => i128::from(i16::from_le_bytes(value.try_into().unwrap())),
+        SbeType::Int32 => i128::from(i32::from_le_bytes(value.try_into().unwrap())),
+        SbeType::Int64 => i128::from(i64::from_le_bytes(value.try_into().unwrap())),
+        SbeType::UInt8 => i128::from(value[0]),
+        SbeType::UInt16 => i128::from(u16::from_le_bytes(value.try_into().unwrap())),
+        SbeType::UInt32 => i128::from(u32::from_le_bytes(value.try_into().unwrap())),
+        SbeType::UInt64 | SbeType::TimestampNanos => i128::from(u64::from_le_bytes(value.try_into().unwrap())),
+        SbeType::Float | SbeType::Double => {
+            item.m_type = IdoItemType::FLOAT;
+            item.m_float = match field.m_type {
+                SbeType::Float => f64::from(f32::from_le_bytes(value.try_into().unwrap())),
+                _ => f64::from_le_bytes(value.try_into().unwrap()),
+            };
+            let null = field.m_optional && item.m_float.is_nan();
+            return Ok((!null).then_some(item));
+        }
+        SbeType::Char(_) => {
+            let end = value.iter().position(|byte| *byte == 0).unwrap_or(value.len());
+            if field.m_optional && end == 0 {
+                return Ok(None);
+            }
+            item.m_type = IdoItemType::STRING;
+            item.m_string = std::str::from_utf8(&value[..end])
+                .map_err(|_| SbeError::InvalidValue(field.m_key))?
+                .to_string();
+            return Ok(Some(item));
+        }
+    };
+
+    if field.m_optional && field.m_type.null_value() == Some(integer) {
+        return Ok(None);
+    }
+    #[cfg(feature = "chrono")]
+    if field.m_type == SbeType::TimestampNanos {
+        let nanos = i64::try_from(integer).map_err(|_| SbeError::InvalidValue(field.m_key))?;
+        item.m_type = IdoItemType::DATETIME;
+        item.m_datetime = DateTime::from_timestamp_nanos(nanos);
+        return Ok(Some(item));
+    }
+    if let Ok(value) = i64::try_from(integer) {
+        item.m_type = IdoItemType::INTEGER;
+        item.m_integer = value;
+    } else {
+        item.m_type = IdoItemType::INTEGER128;
+        item.m_integer128 = integer;
+    }
+    Ok(Some(item))
+}
+
+fn write_field(field: &SbeField, item: &IdoItem, slot: &mut [u8]) -> Result<(), SbeError> {
+    let invalid = || SbeError::InvalidValue(field.m_key);
+
+    let integer = match (field.m_type, item.m_type) {
+        (SbeType::Float | SbeType::Double, IdoItemType::FLOAT) => {
+            match field.m_type {
+                SbeType::Float => slot.copy_from_slice(&(item.m_float as f32).to_le_bytes()),
+                _ => slot.copy_from_slice(&item.m_float.to_le_bytes()),
+            }
+            return Ok(());
+        }
+        (SbeType::Char(len), IdoItemType::STRING) => {
+            let bytes = item.m_string.as_bytes();
+            if bytes.len() > len {
+                return Err(invalid());
+            }
+            slot[..bytes.len()].copy_from_slice(bytes);
+            return Ok(());
+        }
+        #[cfg(feature = "chrono")]
+        (SbeType::TimestampNanos, IdoItemType::DATETIME) => {
+            i128::from(item.m_datetime.timestamp_nanos_opt().ok_or_else(invalid)?)
+        }
+        #[cfg(not(feature = "chrono"))]
+        (SbeType::TimestampNanos, IdoItemType::INTEGER) => i128::from(item.m_integer),
+        (SbeType::Float | SbeType::Double | SbeType::Char(_) | SbeType::TimestampNanos, _) => return Err(invalid()),
+        (_, IdoItemType::INTEGER) => i128::from(item.m_integer),
+        (_, IdoItemType::INTEGER128) => item.m_integer128,
+        _ => return Err(invalid()),
+    };
+
+    match field.m_type {
+        SbeType::Int8 => slot.copy_from_slice(&i8::try_from(integer).map_err(|_| invalid())?.to_le_bytes()),
+        SbeType::Int16 => slot.copy_from_slice(&i16::try_from(integer).map_err(|_| invalid())?.to_le_bytes()),
+        SbeType::Int32 => slot.copy_from_slice(&i32::try_from(integer).map_err(|_| invalid())?.to_le_bytes()),
+        SbeType::Int64 => slot.copy_from_slice(&i64::try_from(integer).map_err(|_| invalid())?.to_le_bytes()),
+        SbeType::UInt8 => slot.copy_from_slice(&u8::try_from(integer).map_err(|_| invalid())?.to_le_bytes()),
+        SbeType::UInt16 => slot.copy_from_slice(&u16::try_from(integer).map_err(|_| invalid())?.to_le_bytes()),
+        SbeType::UInt32 => slot.copy_from_slice(&u32::try_from(integer).map_err(|_| invalid())?.to_le_bytes()),
+        _ => slot.copy_from_slice(&u64::try_from(integer).map_err(|_| invalid())?.to_le_bytes()),
+    }
+    Ok(())
+}
+
+fn write_null(ty: SbeType, slot: &mut [u8]) {
+    match ty {
+        SbeType::Float => slot.copy_from_slice(&f32::NAN.to_le_bytes()),
+        SbeType::Double => slot.copy_from_slice(&f64::NAN.to_le_bytes()),
+        SbeType::Char(_) => slot.fill(0),
+        _ => {
+            let null = ty.null_value().unwrap_or_default().to_le_bytes();
+            slot.copy_from_slice(&null[..ty.size()]);
+        }
+    }
+}
+
+/// One SBE message template and how it maps to an Ido.
+#[derive(Debug, Clone)]
+pub struct SbeMessage {
+    m_template_id: u16,
+    m_schema_id: u16,
+    m_version: u16,
+    m_block: SbeBlock,
+}
+
+impl SbeMessage {
+    /// Describes the message with template id `template_id` and root
+    /// block `block`. The schema id and version written default to 0.
+    pub fn new(template_id: u16, block: SbeBlock) -> Self {
+        SbeMessage { m_template_id: template_id, m_schema_id: 0, m_version: 0, m_block: block }
+    }
+
+    /// Sets the schema id and version written in the header.
+    pub fn schema(mut self, schema_id: u16, version: u16) -> Self {
+        self.m_schema_id = schema_id;
+        self.m_version = version;
+        self
+    }
+
+    /// Fills an Ido from an encoded message.
+    ///
+    /// The block length in the header is honoured, so messages of a newer
+    /// schema version with a longer block decode as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `SbeError` if the message is truncated, of another
+    /// template, lacks a required field or holds an invalid value.
+    pub fn decode(&self, buf: &[u8]) -> Result<Ido, SbeError> {
+        let mut reader = Reader::new(buf);
+        let block_length = u16::from_le_bytes(reader.array()?);
+        let template_id = u16::from_le_bytes(reader.array()?);
+        reader.take(HEADER_LEN - 4)?;
+
+        if template_id != self.m_template_id {
+            return Err(SbeError::TemplateMismatch { expected: self.m_template_id, actual: template_id });
+        }
+
+        let ido = self.m_block.read(&mut reader, usize::from(block_length))?;
+        match reader.remaining() {
+            0 => Ok(ido),
+            n => Err(SbeError::TrailingBytes(n)),
+        }
+    }
+
+    /// Encodes an Ido as this message.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SbeError::MissingField` if a required field has no item and
+    /// `SbeError::InvalidValue` if an item does not fit its field.
+    pub fn encode(&self, ido: &Ido) -> Result<Vec<u8>, SbeError> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&self.m_block.m_block_length.to_le_bytes());
+        out.extend_from_slice(&self.m_template_id.to_le_bytes());
+        out.extend_from_slice(&self.m_schema_id.to_le_bytes());
+        out.extend_from_slice(&self.m_version.to_le_bytes());
+        self.m_block.write(ido, &mut out)?;
+        Ok(out)
+    }
+}
@@ -0,0 +1,579 @@
+//! Describing the expected shape of an Ido and checking messages against it.
+//!
+//! An [`IdoSchema`] lists the keys a message may carry, their types and
+//! whether they must be present. [`Ido::validate`] reports every violation
+//! at once, so malformed input can be rejected at the boundary instead of
+//! failing somewhere inside business logic.
+//!
+//! Keys can additionally carry [`Constraint`]s on their values, such as
+//! numeric ranges, string lengths, datetime windows and, with the `regex`
+//! feature, patterns.
+//!
+//! [`Ido::validation_report`] returns the same violations as a
+//! [`ValidationReport`], each with the [`Severity`] the schema assigns to
+//! its key. Ingestion can log the whole report and, when no violation is
+//! fatal, or by keeping only [`ValidationReport::valid_subset`], still
+//! process the message.
+
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+/// A restriction on the value of a key, checked by [`Ido::validate`].
+///
+/// A constraint only accepts items of the type it was built for; for
+/// example an integer range rejects a FLOAT item.
+///
+/// # Example
+///
+/// ```
+/// use ido::{Ido, IdoItemType};
+/// use ido::schema::{Constraint, IdoSchema};
+///
+/// let schema = IdoSchema::new()
+///     .required(1, IdoItemType::INTEGER)
+///     .constrain(1, Constraint::integer(1..=1_000_000))
+///     .optional(2, IdoItemType::STRING)
+///     .constrain(2, Constraint::length(..=12));
+///
+/// let mut ido = Ido::new();
+/// ido.set_integer(&1, 0);
+/// ido.set_string(&2, "ACC-1".to_string());
+///
+/// let violations = ido.validate(&schema).unwrap_err();
+/// assert_eq!(violations[0].to_string(), "key 1 violates integer in 1..=1000000");
+/// ```
+#[derive(Debug, Clone)]
+pub enum Constraint {
+    /// An INTEGER within the bounds.
+    Integer(Bound<i64>, Bound<i64>),
+    /// A FLOAT within the bounds; NaN never matches.
+    Float(Bound<f64>, Bound<f64>),
+    /// A STRING whose length in characters, or an ARRAY whose number of
+    /// elements, is within the bounds.
+    Length(Bound<usize>, Bound<usize>),
+    /// A DATETIME within the bounds.
+    #[cfg(feature = "chrono")]
+    DateTime(Bound<DateTime<Utc>>, Bound<DateTime<Utc>>),
+    /// A STRING matching the regular expression.
+    #[cfg(feature = "regex")]
+    Pattern(regex::Regex),
+}
+
+fn bounds<T: Copy, R: RangeBounds<T>>(range: R) -> (Bound<T>, Bound<T>) {
+    (range.start_bound().cloned(), range.end_bound().cloned())
+}
+
+fn in_bounds<T: PartialOrd>(value: &T, start: &Bound<T>, end: &Bound<T>) -> bool {
+    (start.as_ref(), end.as_ref()).contains(value)
+}
+
+fn write_bounds<T: fmt::Display>(f: &mut fmt::Formatter<'_>, start: &Bound<T>, end: &Bound<T>) -> fmt::Result {
+    match start {
+        Bound::Included(value) => write!(f, "{}", value)?,
+        Bound::Excluded(value) => write!(f, "(excluded {})", value)?,
+        Bound::Unbounded => {}
+    }
+    match end {
+        Bound::Included(value) => write!(f, "..={}", value),
+        Bound::Excluded(value) => write!(f, "..{}", value),
+        Bound::Unbounded => write!(f, ".."),
+    }
+}
+
+impl Constraint {
+    /// An integer range, e.g. `Constraint::integer(1..=100)`.
+    pub fn integer<R: RangeBounds<i64>>(range: R) -> Self {
+        let (start, end) = bounds(range);
+        Constraint::Integer(start, end)
+    }
+
+    /// A float range, e.g. `Constraint::float(0.0..1.0)`.
+    pub fn float<R: RangeBounds<f64>>(range: R) -> Self {
+        let (start, end) = bounds(range);
+        Constraint::Float(start, end)
+    }
+
+    /// A string length or array size range, e.g. `Constraint::length(1..=12)`.
+    pub fn length<R: RangeBounds<usize>>(range: R) -> Self {
+        let (start, end) = bounds(range);
+        Constraint::Length(start, end)
+    }
+
+    /// A datetime window, e.g. `Constraint::datetime(open..close)`.
+    #[cfg(feature = "chrono")]
+    pub fn datetime<R: RangeBounds<DateTime<Utc>>>(range: R) -> Self {
+        let (start, end) = bounds(range);
+        Constraint::DateTime(start, end)
+    }
+
+    /// A regular expression that the whole string must match.
+    ///
+    /// # Errors
+    ///
+    /// Returns the regex error if `pattern` does not compile.
+    #[cfg(feature = "regex")]
+    pub fn pattern(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Constraint::Pattern(regex::Regex::new(&format!("^(?:{})$", pattern))?))
+    }
+
+    /// Returns `true` if the item satisfies the constraint.
+    pub fn accepts(&self, item: &IdoItem) -> bool {
+        match (self, item.m_type) {
+            (Constraint::Integer(start, end), IdoItemType::INTEGER) => in_bounds(&item.m_integer, start, end),
+            (Constraint::Float(start, end), IdoItemType::FLOAT) => in_bounds(&item.m_float, start, end),
+            (Constraint::Length(start, end), IdoItemType::STRING) => {
+                in_bounds(&item.m_string.chars().count(), start, end)
+            }
+            (Constraint::Length(start, end), IdoItemType::ARRAY) => in_bounds(&item.m_array.len(), start, end),
+            #[cfg(feature = "chrono")]
+            (Constraint::DateTime(start, end), IdoItemType::DATETIME) => in_bounds(&item.m_datetime, start, end),
+            #[cfg(feature = "regex")]
+            (Constraint::Pattern(regex), IdoItemType::STRING) => regex.is_match(&item.m_string),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constraint::Integer(start, end) => {
+                f.write_str("integer in ")?;
+                write_bounds(f, start, end)
+            }
+            Constraint::Float(start, end) => {
+                f.write_str("float in ")?;
+                write_bounds(f, start, end)
+            }
+            Constraint::Length(start, end) => {
+                f.write_str("length in ")?;
+                write_bounds(f, start, end)
+            }
+            #[cfg(feature = "chrono")]
+            Constraint::DateTime(start, end) => {
+                f.write_str("datetime in ")?;
+                write_bounds(f, start, end)
+            }
+            #[cfg(feature = "regex")]
+            Constraint::Pattern(regex) => {
+                let pattern = regex.as_str();
+                write!(f, "string matching {}", &pattern[4..pattern.len() - 2])
+            }
+        }
+    }
+}
+
+/// The expectations for a single key.
+#[derive(Debug, Clone)]
+struct FieldSpec {
+    m_type: IdoItemType,
+    m_required: bool,
+    m_elements: Option<IdoSchema>,
+}
+
+/// Describes the keys of an Ido, their types and which ones are required.
+///
+/// # Example
+///
+/// ```
+/// use ido::{Ido, IdoItemType};
+/// use ido::schema::IdoSchema;
+///
+/// let schema = IdoSchema::new()
+///     .required(1, IdoItemType::STRING)
+///     .optional(2, IdoItemType::FLOAT);
+///
+/// let mut ido = Ido::new();
+/// ido.set_string(&1, "ORDER-1".to_string());
+/// assert!(ido.validate(&schema).is_ok());
+///
+/// ido.set_string(&2, "not a price".to_string());
+/// assert_eq!(ido.validate(&schema).unwrap_err().len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IdoSchema {
+    m_fields: Vec<(IdoKeyT, FieldSpec)>,
+    m_constraints: Vec<(IdoKeyT, Constraint)>,
+    m_severities: Vec<(IdoKeyT, Severity)>,
+    m_deny_unknown: bool,
+}
+
+impl IdoSchema {
+    /// Creates an empty schema that accepts any Ido.
+    pub fn new() -> Self {
+        IdoSchema::default()
+    }
+
+    fn field(mut self, key: IdoKeyT, spec: FieldSpec) -> Self {
+        match self.m_fields.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, existing)) => *existing = spec,
+            None => self.m_fields.push((key, spec)),
+        }
+        self
+    }
+
+    /// Declares a key that must be present with the given type.
+    pub fn required(self, key: IdoKeyT, ty: IdoItemType) -> Self {
+        self.field(key, FieldSpec { m_type: ty, m_required: true, m_elements: None })
+    }
+
+    /// Declares a key that may be absent, but must have the given type if present.
+    pub fn optional(self, key: IdoKeyT, ty: IdoItemType) -> Self {
+        self.field(key, FieldSpec { m_type: ty, m_required: false, m_elements: None })
+    }
+
+    /// Declares an ARRAY key whose elements must each satisfy `elements`.
+    pub fn array_of(self, key: IdoKeyT, required: bool, elements: IdoSchema) -> Self {
+        self.field(
+            key,
+            FieldSpec { m_type: IdoItemType::ARRAY, m_required: required, m_elements: Some(elements) },
+        )
+    }
+
+    /// Adds a constraint on the value of `key`.
+    ///
+    /// A key may carry several constraints; all of them must hold. They are
+    /// only checked when the key is present and, if declared, has the
+    /// declared type.
+    pub fn constrain(mut self, key: IdoKeyT, constraint: Constraint) -> Self {
+        self.m_constraints.push((key, constraint));
+        self
+    }
+
+    /// Sets the severity of the violations reported for `key`, which is
+    /// [`Severity::Error`] unless changed. Only [`Ido::validation_report`]
+    /// distinguishes severities.
+    pub fn severity(mut self, key: IdoKeyT, severity: Severity) -> Self {
+        match self.m_severities.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, existing)) => *existing = severity,
+            None => self.m_severities.push((key, severity)),
+        }
+        self
+    }
+
+    /// Returns the severity of the violations reported for `key`.
+    pub fn severity_of(&self, key: &IdoKeyT) -> Severity {
+        self.m_severities
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map_or(Severity::Error, |(_, severity)| *severity)
+    }
+
+    /// Reports keys that the schema does not declare as violations.
+    pub fn deny_unknown(mut self) -> Self {
+        self.m_deny_unknown = true;
+        self
+    }
+
+    /// Returns the type declared for `key`, if any.
+    pub fn type_of(&self, key: &IdoKeyT) -> Option<IdoItemType> {
+        self.spec(key).map(|spec| spec.m_type)
+    }
+
+    /// Returns `true` if `key` is declared as required.
+    pub fn is_required(&self, key: &IdoKeyT) -> bool {
+        self.spec(key).is_some_and(|spec| spec.m_required)
+    }
+
+    fn spec(&self, key: &IdoKeyT) -> Option<&FieldSpec> {
+        self.m_fields.iter().find(|(existing, _)| existing == key).map(|(_, spec)| spec)
+    }
+
+    fn check_constraints(&self, key: IdoKeyT, item: &IdoItem, violations: &mut Vec<Violation>) {
+        for (_, constraint) in self.m_constraints.iter().filter(|(constrained, _)| *constrained == key) {
+            if !constraint.accepts(item) {
+                violations.push(Violation::Constraint { key, constraint: constraint.to_string() });
+            }
+        }
+    }
+
+    fn check(&self, ido: &Ido, violations: &mut Vec<Violation>) {
+        for (key, spec) in &self.m_fields {
+            let item = match ido.live_item(key) {
+                Some(item) => item,
+                None => {
+                    if spec.m_required {
+                        violations.push(Violation::Missing { key: *key, expected: spec.m_type });
+                    }
+                    continue;
+                }
+            };
+
+            if item.m_type != spec.m_type {
+                violations.push(Violation::WrongType { key: *key, expected: spec.m_type, actual: item.m_type });
+                continue;
+            }
+            self.check_constraints(*key, item, violations);
+
+            if let Some(elements) = &spec.m_elements {
+                for (index, element) in item.m_array.iter().enumerate() {
+                    let mut nested = Vec::new();
+                    elements.check(element, &mut nested);
+                    violations.extend(nested.into_iter().map(|violation| Violation::Element {
+                        key: *key,
+                        index,
+                        violation: Box::new(violation),
+                    }));
+                }
+            }
+        }
+
+        for (key, item) in ido.ordered_items() {
+            if self.spec(&key).is_none() && ido.contains(&key) {
+                self.check_constraints(key, item, violations);
+            }
+        }
+
+        if self.m_deny_unknown {
+            for (key, _) in ido.ordered_items() {
+                if self.spec(&key).is_none() && ido.contains(&key) {
+                    violations.push(Violation::Unknown(key));
+                }
+            }
+        }
+    }
+}
+
+/// A single way in which an Ido does not match its schema.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// A required key is absent.
+    Missing { key: IdoKeyT, expected: IdoItemType },
+    /// A key holds a different type than declared.
+    WrongType { key: IdoKeyT, expected: IdoItemType, actual: IdoItemType },
+    /// A key is not declared and the schema denies unknown keys.
+    Unknown(IdoKeyT),
+    /// A value breaks a [`Constraint`], described by the second field.
+    Constraint { key: IdoKeyT, constraint: String },
+    /// An element of an array declared with [`IdoSchema::array_of`] is invalid.
+    Element { key: IdoKeyT, index: usize, violation: Box<Violation> },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Missing { key, expected } => {
+                write!(f, "key {} is missing, expected {:?}", key, expected)
+            }
+            Violation::WrongType { key, expected, actual } => {
+                write!(f, "key {} is {:?}, expected {:?}", key, actual, expected)
+            }
+            Violation::Unknown(key) => write!(f, "key {} is not in the schema", key),
+            Violation::Constraint { key, constraint } => write!(f, "key {} violates {}", key, constraint),
+            Violation::Element { key, index, violation } => {
+                write!(f, "element {} of key {}: {}", index, key, violation)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+impl Violation {
+    /// Returns the key the violation was found under; for an array element,
+    /// the key of the array.
+    pub fn key(&self) -> IdoKeyT {
+        match self {
+            Violation::Missing { key, .. }
+            | Violation::WrongType { key, .. }
+            | Violation::Unknown(key)
+            | Violation::Constraint { key, .. }
+            | Violation::Element { key, .. } => *key,
+        }
+    }
+}
+
+/// How serious a violation is, set per key with [`IdoSchema::severity`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Severity {
+    /// The value can be used or dropped; the message can still be processed.
+    Warning,
+    /// The message must not be processed as a whole.
+    #[default]
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// A violation together with its severity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    m_severity: Severity,
+    m_violation: Violation,
+}
+
+impl Issue {
+    /// Returns the severity of the violation.
+    pub fn severity(&self) -> Severity {
+        self.m_severity
+    }
+
+    /// Returns the violation.
+    pub fn violation(&self) -> &Violation {
+        &self.m_violation
+    }
+
+    /// Returns the key the violation was found under.
+    pub fn key(&self) -> IdoKeyT {
+        self.m_violation.key()
+    }
+
+    /// Returns `true` if the severity is [`Severity::Error`].
+    pub fn is_fatal(&self) -> bool {
+        self.m_severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.m_severity, self.m_violation)
+    }
+}
+
+/// Every violation found by [`Ido::validation_report`], in the order of
+/// [`Ido::validate`].
+///
+/// # Example
+///
+/// ```
+/// use ido::{Ido, IdoItemType};
+/// use ido::schema::{IdoSchema, Severity};
+///
+/// let schema = IdoSchema::new()
+///     .required(1, IdoItemType::STRING)
+///     .optional(2, IdoItemType::STRING)
+///     .severity(2, Severity::Warning);
+///
+/// let mut ido = Ido::new();
+/// ido.set_string(&1, "ORDER-1".to_string());
+/// ido.set_integer(&2, 7);
+///
+/// let report = ido.validation_report(&schema);
+/// assert!(!report.is_valid());
+/// assert!(!report.is_fatal());
+/// assert_eq!(report.to_string(), "warning: key 2 is INTEGER, expected STRING");
+/// assert_eq!(report.valid_subset(&ido).to_string(), "1=ORDER-1");
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ValidationReport {
+    m_issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    /// Returns every issue found.
+    pub fn issues(&self) -> &[Issue] {
+        &self.m_issues
+    }
+
+    /// Returns `true` if no violation was found.
+    pub fn is_valid(&self) -> bool {
+        self.m_issues.is_empty()
+    }
+
+    /// Returns `true` if any violation has [`Severity::Error`].
+    pub fn is_fatal(&self) -> bool {
+        self.m_issues.iter().any(Issue::is_fatal)
+    }
+
+    /// Returns the issues of the given severity.
+    pub fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Issue> {
+        self.m_issues.iter().filter(move |issue| issue.m_severity == severity)
+    }
+
+    /// Returns the keys with at least one violation, in report order and
+    /// without duplicates.
+    pub fn invalid_keys(&self) -> Vec<IdoKeyT> {
+        let mut keys: Vec<IdoKeyT> = Vec::new();
+        for issue in &self.m_issues {
+            if !keys.contains(&issue.key()) {
+                keys.push(issue.key());
+            }
+        }
+        keys
+    }
+
+    /// Copies the items of `ido` whose keys have no violation, whatever
+    /// its severity, into a new Ido, like [`Ido::clone_subset`].
+    pub fn valid_subset(&self, ido: &Ido) -> Ido {
+        let invalid = self.invalid_keys();
+        let keys: Vec<IdoKeyT> =
+            ido.ordered_items().map(|(key, _)| key).filter(|key| !invalid.contains(key)).collect();
+        ido.clone_subset(&keys)
+    }
+
+    /// Returns the violations, dropping their severities.
+    pub fn into_violations(self) -> Vec<Violation> {
+        self.m_issues.into_iter().map(|issue| issue.m_violation).collect()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, issue) in self.m_issues.iter().enumerate() {
+            if index > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl Ido {
+    /// Checks the Ido against a schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema to check against.
+    ///
+    /// # Errors
+    ///
+    /// Returns every violation found: declared keys first in schema order,
+    /// then constraint violations of undeclared keys and finally unknown
+    /// keys, both in insertion order. Severities are ignored; use
+    /// [`validation_report`](Ido::validation_report) to tell warnings from
+    /// errors.
+    pub fn validate(&self, schema: &IdoSchema) -> Result<(), Vec<Violation>> {
+        let report = self.validation_report(schema);
+
+        if report.is_valid() {
+            Ok(())
+        } else {
+            Err(report.into_violations())
+        }
+    }
+
+    /// Checks the Ido against a schema and returns every violation with the
+    /// severity the schema assigns to its key.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema to check against.
+    ///
+    /// # Returns
+    ///
+    /// A report listing the violations in the same order as
+    /// [`validate`](Ido::validate); see [`ValidationReport`].
+    pub fn validation_report(&self, schema: &IdoSchema) -> ValidationReport {
+        let mut violations = Vec::new();
+        schema.check(self, &mut violations);
+
+        let issues = violations
+            .into_iter()
+            .map(|violation| Issue { m_severity: schema.severity_of(&violation.key()), m_violation: violation })
+            .collect();
+        ValidationReport { m_issues: issues }
+    }
+}
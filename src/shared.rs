@@ -0,0 +1,204 @@
+//! Decoding into an Ido whose strings stay in the received buffer.
+//!
+//! [`SharedIdo`] takes ownership of an encoded buffer and decodes it
+//! without copying top-level STRING values: each one is kept as a range of
+//! the buffer and read in place. A value is only copied out when it is
+//! replaced, or when the whole Ido is turned into a regular one. On an
+//! ingest path that reads a few fields and forwards the rest, this saves
+//! one allocation per string field.
+//!
+//! Values of other types, and the Idos nested in arrays, are decoded as
+//! usual.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::codec::{self, CodecError, Reader, TAG_STRING};
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+enum SharedValue {
+    /// A UTF-8 checked range of the buffer.
+    Str(Range<usize>),
+    Owned(IdoItem),
+}
+
+/// An Ido decoded from an owned buffer, sharing its strings with it.
+///
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+/// use ido::shared::SharedIdo;
+///
+/// let mut ido = Ido::new();
+/// ido.set_string(&1, "ORDER-1".to_string());
+/// ido.set_integer(&2, 100);
+///
+/// let mut shared = SharedIdo::decode(ido.encode()).unwrap();
+/// assert_eq!(shared.get_str(&1), Some("ORDER-1"));
+/// assert!(shared.is_shared(&1));
+///
+/// shared.set_string(&1, "ORDER-2".to_string());
+/// assert!(!shared.is_shared(&1));
+///
+/// let ido = shared.into_ido();
+/// assert_eq!(ido.get_string(&1), Some("ORDER-2".to_string()));
+/// assert_eq!(ido.get_i64(&2), Some(100));
+/// ```
+pub struct SharedIdo {
+    m_buf: Arc<[u8]>,
+    m_keys: Vec<IdoKeyT>,
+    m_values: HashMap<IdoKeyT, SharedValue>,
+}
+
+impl SharedIdo {
+    /// Decodes an Ido produced by [`Ido::encode`], keeping `buf`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CodecError` if the buffer is truncated, malformed or has
+    /// bytes left over after the Ido.
+    pub fn decode(buf: impl Into<Arc<[u8]>>) -> Result<Self, CodecError> {
+        let buf = buf.into();
+        let mut reader = Reader::new(&buf);
+        let count = reader.u32()?;
+        let mut shared = SharedIdo { m_buf: Arc::clone(&buf), m_keys: Vec::new(), m_values: HashMap::new() };
+
+        for _ in 0..count {
+            let key = reader.key()?;
+            let value = if buf.get(reader.position()) == Some(&TAG_STRING) {
+                reader.u8()?;
+                let len = reader.u32()? as usize;
+                let start = reader.position();
+                std::str::from_utf8(reader.take(len)?).map_err(|_| CodecError::InvalidUtf8)?;
+                SharedValue::Str(start..start + len)
+            } else {
+                SharedValue::Owned(codec::read_item(&mut reader)?)
+            };
+            shared.insert(key, value);
+        }
+
+        match reader.remaining() {
+            0 => Ok(shared),
+            n => Err(CodecError::TrailingBytes(n)),
+        }
+    }
+
+    /// Stores a value, moving an existing key to the end like `Ido::set_item`.
+    fn insert(&mut self, key: IdoKeyT, value: SharedValue) {
+        if self.m_values.insert(key, value).is_some() {
+            self.m_keys.retain(|other| *other != key);
+        }
+        self.m_keys.push(key);
+    }
+
+    fn str_at(&self, range: &Range<usize>) -> &str {
+        // Checked when decoding.
+        std::str::from_utf8(&self.m_buf[range.clone()]).unwrap_or_default()
+    }
+
+    /// Returns the number of items.
+    pub fn size(&self) -> usize {
+        self.m_keys.len()
+    }
+
+    /// Returns `true` if an item is stored under `key`.
+    pub fn contains(&self, key: &IdoKeyT) -> bool {
+        self.m_values.contains_key(key)
+    }
+
+    /// Returns the keys in insertion order.
+    pub fn keys(&self) -> &[IdoKeyT] {
+        &self.m_keys
+    }
+
+    /// Returns `true` if the value under `key` is still read from the buffer.
+    pub fn is_shared(&self, key: &IdoKeyT) -> bool {
+        matches!(self.m_values.get(key), Some(SharedValue::Str(_)))
+    }
+
+    /// Returns the STRING under `key` without copying it.
+    ///
+    /// # Returns
+    ///
+    /// `None` if there is no item under `key` or it is not a STRING.
+    pub fn get_str(&self, key: &IdoKeyT) -> Option<&str> {
+        match self.m_values.get(key)? {
+            SharedValue::Str(range) => Some(self.str_at(range)),
+            SharedValue::Owned(item) if item.m_type == IdoItemType::STRING => Some(&item.m_string),
+            SharedValue::Owned(_) => None,
+        }
+    }
+
+    /// Returns the value under `key` rendered as a string, like
+    /// `Ido::get_string`.
+    pub fn get_string(&self, key: &IdoKeyT) -> Option<String> {
+        match self.m_values.get(key)? {
+            SharedValue::Str(range) => Some(self.str_at(range).to_string()),
+            SharedValue::Owned(item) => item.as_string(),
+        }
+    }
+
+    /// Returns the item under `key`. Shared strings are copied into a new
+    /// item; other items are borrowed.
+    pub fn get_item(&self, key: &IdoKeyT) -> Option<Cow<'_, IdoItem>> {
+        match self.m_values.get(key)? {
+            SharedValue::Str(range) => Some(Cow::Owned(self.string_item(*key, range))),
+            SharedValue::Owned(item) => Some(Cow::Borrowed(item)),
+        }
+    }
+
+    fn string_item(&self, key: IdoKeyT, range: &Range<usize>) -> IdoItem {
+        let mut item = IdoItem::new();
+        item.m_key = key;
+        item.m_type = IdoItemType::STRING;
+        item.m_string = self.str_at(range).to_string();
+        item
+    }
+
+    /// Stores an item under `key`, moving the key to the end of the
+    /// insertion order.
+    pub fn set_item(&mut self, key: &IdoKeyT, mut item: IdoItem) {
+        item.m_key = *key;
+        self.insert(*key, SharedValue::Owned(item));
+    }
+
+    /// Stores a STRING under `key`.
+    pub fn set_string(&mut self, key: &IdoKeyT, val: String) {
+        let mut item = IdoItem::new();
+        item.m_type = IdoItemType::STRING;
+        item.m_string = val;
+        self.set_item(key, item);
+    }
+
+    /// Removes the item under `key`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if an item was removed.
+    pub fn delete_item(&mut self, key: &IdoKeyT) -> bool {
+        let removed = self.m_values.remove(key).is_some();
+        if removed {
+            self.m_keys.retain(|other| other != key);
+        }
+        removed
+    }
+
+    /// Copies the shared strings out and returns a regular Ido, in
+    /// insertion order.
+    pub fn into_ido(mut self) -> Ido {
+        let mut ido = Ido::new();
+        for key in std::mem::take(&mut self.m_keys) {
+            let item = match self.m_values.remove(&key) {
+                Some(SharedValue::Str(range)) => self.string_item(key, &range),
+                Some(SharedValue::Owned(item)) => item,
+                None => continue,
+            };
+            ido.set_item(&key, item);
+        }
+        ido.clear_dirty();
+        ido
+    }
+}
@@ -0,0 +1,302 @@
+//! Single-producer single-consumer ring buffer in shared memory.
+//!
+//! The producer and consumer map the same file (typically under `/dev/shm`)
+//! and exchange encoded Idos without any system calls once both sides are
+//! open. Each record is a `u32` length followed by the output of
+//! [`Ido::encode`], written contiguously modulo the ring capacity.
+//!
+//! ```text
+//! offset   0  magic:u64 capacity:u64
+//! offset  64  head:u64   (bytes written, owned by the producer)
+//! offset 128  tail:u64   (bytes read, owned by the consumer)
+//! offset 192  data[capacity]
+//! ```
+//!
+//! Only one producer and one consumer may use a ring at a time.
+
+use memmap2::MmapMut;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::codec::{BinaryCodec, CodecError, Encode};
+use crate::Ido;
+
+const MAGIC: u64 = 0x4944_4f52_494e_4701;
+const HEAD_OFFSET: usize = 64;
+const TAIL_OFFSET: usize = 128;
+const DATA_OFFSET: usize = 192;
+const LEN_PREFIX: usize = 4;
+
+/// Errors raised by the shared-memory ring buffer.
+#[derive(Debug)]
+pub enum ShmError {
+    /// Creating or mapping the backing file failed.
+    Io(io::Error),
+    /// A record could not be decoded.
+    Codec(CodecError),
+    /// The encoded Ido is larger than the ring can ever hold.
+    TooLarge(usize),
+    /// The mapped file is not a ring created by [`ShmRingProducer`].
+    InvalidLayout,
+}
+
+impl fmt::Display for ShmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShmError::Io(err) => write!(f, "io error: {}", err),
+            ShmError::Codec(err) => write!(f, "codec error: {}", err),
+            ShmError::TooLarge(len) => write!(f, "record of {} bytes does not fit in the ring", len),
+            ShmError::InvalidLayout => write!(f, "file is not an ido ring buffer"),
+        }
+    }
+}
+
+impl std::error::Error for ShmError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ShmError::Io(err) => Some(err),
+            ShmError::Codec(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ShmError {
+    fn from(err: io::Error) -> Self {
+        ShmError::Io(err)
+    }
+}
+
+impl From<CodecError> for ShmError {
+    fn from(err: CodecError) -> Self {
+        ShmError::Codec(err)
+    }
+}
+
+struct Ring {
+    _map: MmapMut,
+    m_base: *mut u8,
+    m_capacity: usize,
+}
+
+// SAFETY: `m_base` points into the mapping owned by the ring, which moves
+// with it.
+unsafe impl Send for Ring {}
+
+impl Ring {
+    fn new(mut map: MmapMut, capacity: usize) -> Self {
+        Ring {
+            m_base: map.as_mut_ptr(),
+            _map: map,
+            m_capacity: capacity,
+        }
+    }
+
+    fn counter(&self, offset: usize) -> &AtomicU64 {
+        // SAFETY: the mapping is page aligned and at least DATA_OFFSET bytes
+        // long, so the counter is in bounds and 8-byte aligned.
+        unsafe { &*(self.m_base.add(offset) as *const AtomicU64) }
+    }
+
+    fn head(&self) -> &AtomicU64 {
+        self.counter(HEAD_OFFSET)
+    }
+
+    fn tail(&self) -> &AtomicU64 {
+        self.counter(TAIL_OFFSET)
+    }
+
+    fn data_ptr(&self) -> *mut u8 {
+        // SAFETY: DATA_OFFSET is within the mapping.
+        unsafe { self.m_base.add(DATA_OFFSET) }
+    }
+
+    fn write_at(&self, pos: u64, bytes: &[u8]) {
+        let start = (pos % self.m_capacity as u64) as usize;
+        let first = bytes.len().min(self.m_capacity - start);
+
+        // SAFETY: the producer owns the region between head and tail + capacity,
+        // which the caller has checked is large enough for `bytes`.
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), self.data_ptr().add(start), first);
+            std::ptr::copy_nonoverlapping(bytes.as_ptr().add(first), self.data_ptr(), bytes.len() - first);
+        }
+    }
+
+    fn read_at(&self, pos: u64, out: &mut [u8]) {
+        let start = (pos % self.m_capacity as u64) as usize;
+        let first = out.len().min(self.m_capacity - start);
+
+        // SAFETY: the consumer owns the region between tail and head, which the
+        // caller has checked covers `out` and fits in the ring, so `out` is no
+        // longer than the capacity and neither copy leaves the data region.
+        unsafe {
+            std::ptr::copy_nonoverlapping(self.data_ptr().add(start), out.as_mut_ptr(), first);
+            std::ptr::copy_nonoverlapping(self.data_ptr(), out.as_mut_ptr().add(first), out.len() - first);
+        }
+    }
+}
+
+/// The writing side of a shared-memory ring.
+pub struct ShmRingProducer {
+    m_ring: Ring,
+    m_scratch: Vec<u8>,
+}
+
+impl ShmRingProducer {
+    /// Creates (or truncates) the file at `path` and initialises an empty ring
+    /// with room for `capacity` bytes of records.
+    pub fn create<P: AsRef<Path>>(path: P, capacity: usize) -> Result<Self, ShmError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len((DATA_OFFSET + capacity) as u64)?;
+
+        // SAFETY: the file was just created by us; other processes are expected
+        // to follow the ring protocol described in the module documentation.
+        let mut map = unsafe { MmapMut::map_mut(&file)? };
+        map[8..16].copy_from_slice(&(capacity as u64).to_ne_bytes());
+        map[..8].copy_from_slice(&MAGIC.to_ne_bytes());
+
+        Ok(ShmRingProducer {
+            m_ring: Ring::new(map, capacity),
+            m_scratch: Vec::new(),
+        })
+    }
+
+    /// Returns the number of bytes available for new records.
+    pub fn free_space(&self) -> usize {
+        let head = self.m_ring.head().load(Ordering::Relaxed);
+        let tail = self.m_ring.tail().load(Ordering::Acquire);
+        self.m_ring.m_capacity - (head - tail) as usize
+    }
+
+    fn stage(&mut self, ido: &Ido) -> Result<(), ShmError> {
+        self.m_scratch.clear();
+        self.m_scratch.extend_from_slice(&[0u8; LEN_PREFIX]);
+        BinaryCodec.encode(ido, &mut self.m_scratch)?;
+
+        let len = self.m_scratch.len() - LEN_PREFIX;
+        if self.m_scratch.len() > self.m_ring.m_capacity {
+            return Err(ShmError::TooLarge(len));
+        }
+        self.m_scratch[..LEN_PREFIX].copy_from_slice(&(len as u32).to_ne_bytes());
+        Ok(())
+    }
+
+    fn publish_staged(&mut self) -> bool {
+        if self.m_scratch.len() > self.free_space() {
+            return false;
+        }
+
+        let head = self.m_ring.head().load(Ordering::Relaxed);
+        self.m_ring.write_at(head, &self.m_scratch);
+        self.m_ring.head().store(head + self.m_scratch.len() as u64, Ordering::Release);
+        true
+    }
+
+    /// Appends `ido` to the ring.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` if the Ido was written, `Ok(false)` if the ring is currently
+    /// too full and the consumer needs to catch up first.
+    pub fn try_send(&mut self, ido: &Ido) -> Result<bool, ShmError> {
+        self.stage(ido)?;
+        Ok(self.publish_staged())
+    }
+
+    /// Appends `ido`, spinning until the consumer has made enough room.
+    pub fn send(&mut self, ido: &Ido) -> Result<(), ShmError> {
+        self.stage(ido)?;
+        while !self.publish_staged() {
+            std::hint::spin_loop();
+        }
+        Ok(())
+    }
+}
+
+/// The reading side of a shared-memory ring.
+pub struct ShmRingConsumer {
+    m_ring: Ring,
+    m_scratch: Vec<u8>,
+}
+
+impl ShmRingConsumer {
+    /// Maps the ring previously created by a producer at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ShmError> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+
+        // SAFETY: see `ShmRingProducer::create`.
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        if map.len() < DATA_OFFSET || map[..8] != MAGIC.to_ne_bytes() {
+            return Err(ShmError::InvalidLayout);
+        }
+
+        let mut capacity = [0u8; 8];
+        capacity.copy_from_slice(&map[8..16]);
+        let capacity = u64::from_ne_bytes(capacity) as usize;
+        if DATA_OFFSET.checked_add(capacity) != Some(map.len()) {
+            return Err(ShmError::InvalidLayout);
+        }
+
+        Ok(ShmRingConsumer {
+            m_ring: Ring::new(map, capacity),
+            m_scratch: Vec::new(),
+        })
+    }
+
+    /// Takes the next Ido from the ring, or `None` if it is empty.
+    ///
+    /// # Errors
+    ///
+    /// The counters and length prefixes live in a file other processes can
+    /// write, so they are checked before anything is copied out of the ring:
+    /// returns `ShmError::InvalidLayout` if the head is behind the tail, more
+    /// than the capacity is pending, or a record is longer than what is
+    /// pending.
+    pub fn try_recv(&mut self) -> Result<Option<Ido>, ShmError> {
+        let tail = self.m_ring.tail().load(Ordering::Relaxed);
+        let head = self.m_ring.head().load(Ordering::Acquire);
+        if head == tail {
+            return Ok(None);
+        }
+
+        let pending = match head.checked_sub(tail) {
+            Some(pending) if pending <= self.m_ring.m_capacity as u64 => pending as usize,
+            _ => return Err(ShmError::InvalidLayout),
+        };
+        if pending < LEN_PREFIX {
+            return Err(ShmError::InvalidLayout);
+        }
+
+        let mut len = [0u8; LEN_PREFIX];
+        self.m_ring.read_at(tail, &mut len);
+        let len = u32::from_ne_bytes(len) as usize;
+        if len > pending - LEN_PREFIX {
+            return Err(ShmError::InvalidLayout);
+        }
+
+        self.m_scratch.resize(len, 0);
+        self.m_ring.read_at(tail + LEN_PREFIX as u64, &mut self.m_scratch);
+        self.m_ring.tail().store(tail + (LEN_PREFIX + len) as u64, Ordering::Release);
+
+        Ok(Some(Ido::decode(&self.m_scratch)?))
+    }
+
+    /// Takes the next Ido, spinning until one is available.
+    pub fn recv(&mut self) -> Result<Ido, ShmError> {
+        loop {
+            if let Some(ido) = self.try_recv()? {
+                return Ok(ido);
+            }
+            std::hint::spin_loop();
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Materializing the current state of entities from a stream of deltas.
+//!
+//! Update feeds usually send only the fields that changed, tagged with the
+//! entity they belong to, such as an order id. [`IdoStateMachine`] keeps the
+//! latest state of every entity by merging each delta into the state built
+//! from the previous ones.
+
+use std::collections::BTreeMap;
+
+use crate::{ArrayMerge, Ido, IdoKeyT, MissingKeys};
+
+/// The entity `ido` belongs to, rendered with `as_string`.
+pub(crate) fn entity(ido: &Ido, entity_key: IdoKeyT) -> Result<String, MissingKeys> {
+    ido.get_item(&entity_key)
+        .and_then(|item| item.as_string())
+        .ok_or_else(|| MissingKeys(vec![entity_key]))
+}
+
+/// Called with the entity, the delta and the state after the delta.
+pub type ApplyCallback = dyn FnMut(&str, &Ido, &Ido) + Send;
+
+/// The latest state of every entity seen in a delta stream.
+///
+/// Entities are identified by the value stored under the entity key,
+/// compared as rendered by `as_string`. The first delta for an entity
+/// creates its state; later deltas are merged into it with
+/// [`Ido::update_with`].
+///
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+/// use ido::state::IdoStateMachine;
+///
+/// let mut orders = IdoStateMachine::new(1);
+///
+/// let mut delta = Ido::new();
+/// delta.set_string(&1, "ORDER-1".to_string());
+/// delta.set_integer(&2, 100);
+/// orders.apply(&delta).unwrap();
+///
+/// let mut delta = Ido::new();
+/// delta.set_string(&1, "ORDER-1".to_string());
+/// delta.set_f64(&3, 101.25);
+/// orders.apply(&delta).unwrap();
+///
+/// let order = orders.get("ORDER-1").unwrap();
+/// assert_eq!(order.get_i64(&2), Some(100));
+/// assert_eq!(order.get_f64(&3), Some(101.25));
+/// ```
+pub struct IdoStateMachine {
+    m_entity_key: IdoKeyT,
+    m_array_merge: ArrayMerge,
+    m_states: BTreeMap<String, Ido>,
+    m_callbacks: Vec<Box<ApplyCallback>>,
+}
+
+impl IdoStateMachine {
+    /// Creates an empty state machine.
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_key` - The key identifying the entity of every delta.
+    pub fn new(entity_key: IdoKeyT) -> Self {
+        IdoStateMachine {
+            m_entity_key: entity_key,
+            m_array_merge: ArrayMerge::default(),
+            m_states: BTreeMap::new(),
+            m_callbacks: Vec::new(),
+        }
+    }
+
+    /// Sets how arrays in a delta are combined with the arrays of the state.
+    /// Arrays are replaced by default.
+    pub fn array_merge(mut self, policy: ArrayMerge) -> Self {
+        self.m_array_merge = policy;
+        self
+    }
+
+    /// Registers a callback run after every applied delta.
+    pub fn on_apply<F>(&mut self, callback: F)
+    where
+        F: FnMut(&str, &Ido, &Ido) + Send + 'static
+    {
+        self.m_callbacks.push(Box::new(callback));
+    }
+
+    /// Merges a delta into the state of its entity.
+    ///
+    /// # Returns
+    ///
+    /// The state of the entity after the delta.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingKeys` with the entity key if the delta has no value
+    /// under it.
+    pub fn apply(&mut self, delta: &Ido) -> Result<&Ido, MissingKeys> {
+        let entity = entity(delta, self.m_entity_key)?;
+
+        let state = self.m_states.entry(entity.clone()).or_default();
+        state.update_with(delta, self.m_array_merge);
+        state.clear_dirty();
+
+        for callback in &mut self.m_callbacks {
+            callback(&entity, delta, state);
+        }
+        Ok(state)
+    }
+
+    /// Applies every delta in order, stopping at the first one without an
+    /// entity.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingKeys` with the entity key if a delta has no value
+    /// under it. The deltas before it have been applied.
+    pub fn apply_all<'a, I>(&mut self, deltas: I) -> Result<(), MissingKeys>
+    where
+        I: IntoIterator<Item = &'a Ido>
+    {
+        for delta in deltas {
+            self.apply(delta)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the state of an entity.
+    pub fn get(&self, entity: &str) -> Option<&Ido> {
+        self.m_states.get(entity)
+    }
+
+    /// Forgets an entity, for instance once an order is filled.
+    ///
+    /// # Returns
+    ///
+    /// The last state of the entity.
+    pub fn remove(&mut self, entity: &str) -> Option<Ido> {
+        self.m_states.remove(entity)
+    }
+
+    /// Returns the number of entities.
+    pub fn len(&self) -> usize {
+        self.m_states.len()
+    }
+
+    /// Returns `true` if no delta has been applied yet.
+    pub fn is_empty(&self) -> bool {
+        self.m_states.is_empty()
+    }
+
+    /// Iterates over the entities and their states, ordered by entity.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Ido)> {
+        self.m_states.iter().map(|(entity, state)| (entity.as_str(), state))
+    }
+}
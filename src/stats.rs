@@ -0,0 +1,223 @@
+//! Structural summaries of Ido objects.
+//!
+//! [`Ido::stats`] walks an Ido and every Ido nested in its arrays and
+//! returns an [`IdoStats`], which is cheap to log or export as metrics.
+//! [`Ido::op_stats`] returns the [`OpStats`] counted by an Ido as it is
+//! used, to find the messages that churn the most.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{Ido, IdoItemType};
+
+/// Counts describing the shape of an Ido, including all nested Idos.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IdoStats {
+    /// Number of STRING items.
+    pub strings: usize,
+    /// Number of INTEGER and INTEGER128 items.
+    pub integers: usize,
+    /// Number of FLOAT items.
+    pub floats: usize,
+    /// Number of DATETIME items.
+    pub datetimes: usize,
+    /// Number of BOOL items.
+    pub bools: usize,
+    /// Number of ARRAY items.
+    pub arrays: usize,
+    /// Number of Idos held by all arrays.
+    pub array_elements: usize,
+    /// Total length in bytes of all string values.
+    pub string_bytes: usize,
+    /// Deepest level of array nesting; 0 for an Ido without arrays.
+    pub max_depth: usize,
+}
+
+impl IdoStats {
+    /// Returns the total number of items of every type.
+    pub fn items(&self) -> usize {
+        self.strings + self.integers + self.floats + self.datetimes + self.bools + self.arrays
+    }
+
+    fn add(&mut self, ido: &Ido, depth: usize) {
+        self.max_depth = self.max_depth.max(depth);
+
+        for (_, item) in ido.ordered_items() {
+            match item.m_type {
+                IdoItemType::STRING => {
+                    self.strings += 1;
+                    self.string_bytes += item.m_string.len();
+                }
+                IdoItemType::INTEGER | IdoItemType::INTEGER128 => self.integers += 1,
+                IdoItemType::FLOAT => self.floats += 1,
+                #[cfg(feature = "chrono")]
+                IdoItemType::DATETIME => self.datetimes += 1,
+                IdoItemType::BOOL => self.bools += 1,
+                IdoItemType::ARRAY => {
+                    self.arrays += 1;
+                    self.array_elements += item.m_array.len();
+                    for element in &item.m_array {
+                        self.add(element, depth + 1);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How often an Ido was used since it was created or its counts were
+/// reset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpStats {
+    /// Number of values stored by setters, appends and decoders.
+    pub sets: u64,
+    /// Number of values looked up by getters, found or not.
+    pub gets: u64,
+    /// Number of times the Ido was cloned.
+    pub clones: u64,
+    /// Number of items deleted, expired or evicted.
+    pub deletes: u64,
+}
+
+/// The counters behind [`OpStats`], updated through a shared reference so
+/// getters can count themselves.
+#[derive(Debug, Default)]
+pub(crate) struct OpCounters {
+    m_sets: AtomicU64,
+    m_gets: AtomicU64,
+    m_clones: AtomicU64,
+    m_deletes: AtomicU64,
+}
+
+impl OpCounters {
+    pub(crate) fn count_set(&self) {
+        self.m_sets.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn count_get(&self) {
+        self.m_gets.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn count_delete(&self) {
+        self.m_deletes.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Cloning counts against the original; the clone starts from zero.
+impl Clone for OpCounters {
+    fn clone(&self) -> Self {
+        self.m_clones.fetch_add(1, Ordering::Relaxed);
+        OpCounters::default()
+    }
+}
+
+impl Ido {
+    /// Returns how often the Ido was used since it was created or
+    /// [`reset_op_stats`](Ido::reset_op_stats) was called.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 10);
+    /// ido.set_integer(&1, 11);
+    /// ido.get_i64(&1);
+    /// ido.delete_item(&1);
+    /// let copy = ido.clone();
+    ///
+    /// let ops = ido.op_stats();
+    /// assert_eq!((ops.sets, ops.gets, ops.clones, ops.deletes), (2, 1, 1, 1));
+    /// assert_eq!(copy.op_stats().clones, 0);
+    /// ```
+    pub fn op_stats(&self) -> OpStats {
+        OpStats {
+            sets: self.m_ops.m_sets.load(Ordering::Relaxed),
+            gets: self.m_ops.m_gets.load(Ordering::Relaxed),
+            clones: self.m_ops.m_clones.load(Ordering::Relaxed),
+            deletes: self.m_ops.m_deletes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Sets every operation count back to zero.
+    pub fn reset_op_stats(&mut self) {
+        self.m_ops = OpCounters::default();
+    }
+
+    /// Summarises the structure of the Ido and everything nested in it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut leg = Ido::new();
+    /// leg.set_string(&1, "EURUSD".to_string());
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 7);
+    /// ido.append_array(&2, leg.clone());
+    /// ido.append_array(&2, leg);
+    ///
+    /// let stats = ido.stats();
+    /// assert_eq!(stats.items(), 4);
+    /// assert_eq!(stats.array_elements, 2);
+    /// assert_eq!(stats.string_bytes, 12);
+    /// assert_eq!(stats.max_depth, 1);
+    /// ```
+    pub fn stats(&self) -> IdoStats {
+        let mut stats = IdoStats::default();
+        stats.add(self, 0);
+        stats
+    }
+
+    /// Returns the number of items including those in nested Idos, at any
+    /// depth. Unlike `size`, which only counts the top level, this measures
+    /// the whole message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut leg = Ido::new();
+    /// leg.set_string(&1, "EURUSD".to_string());
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 7);
+    /// ido.append_array(&2, leg.clone());
+    /// ido.append_array(&2, leg);
+    ///
+    /// assert_eq!(ido.size(), 2);
+    /// assert_eq!(ido.deep_len(), 4);
+    /// ```
+    pub fn deep_len(&self) -> usize {
+        self.ordered_items()
+            .map(|(_, item)| 1 + item.m_array.iter().map(Ido::deep_len).sum::<usize>())
+            .sum()
+    }
+
+    /// Returns the deepest level of array nesting; 0 for an Ido without
+    /// nested Idos, 1 if its arrays hold Idos without arrays, and so on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut inner = Ido::new();
+    /// inner.append_array(&1, Ido::new());
+    ///
+    /// let mut ido = Ido::new();
+    /// assert_eq!(ido.depth(), 0);
+    /// ido.append_array(&2, inner);
+    /// assert_eq!(ido.depth(), 2);
+    /// ```
+    pub fn depth(&self) -> usize {
+        self.ordered_items()
+            .flat_map(|(_, item)| &item.m_array)
+            .map(|element| 1 + element.depth())
+            .max()
+            .unwrap_or(0)
+    }
+}
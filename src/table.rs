@@ -0,0 +1,266 @@
+//! Rendering an array of Idos as a table.
+//!
+//! Each Ido of the array becomes a row and each key found in any of them a
+//! column, in the order the keys first appear. A row without a value for a
+//! column leaves the cell empty, and nested arrays are shown as
+//! `<array of N>`. Keys are written as numbers, or as names with a
+//! dictionary attached by [`Ido::set_names`] or passed to
+//! [`NamedIdo::to_table`](crate::dictionary::NamedIdo::to_table).
+//!
+//! ```text
+//! 1        2    3
+//! -------  ---  ------
+//! ORDER-1  100  101.25
+//! ORDER-2  60
+//! ```
+//!
+//! [`Ido::array_to_csv`] writes the same table as CSV, with the column
+//! names as its header row.
+
+use std::io::{self, Write};
+
+use crate::dictionary::IdoDictionary;
+use crate::{Ido, IdoItemType, IdoKeyT};
+
+/// The header and cells of an array of Idos, as text.
+pub(crate) struct Table {
+    m_columns: Vec<String>,
+    m_rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Collects the array under `key`, or returns `None` if `key` does not
+    /// hold an ARRAY.
+    pub(crate) fn from_array(ido: &Ido, key: &IdoKeyT, names: Option<&IdoDictionary>) -> Option<Table> {
+        let item = ido.live_item(key).filter(|item| item.m_type == IdoItemType::ARRAY)?;
+        let format = ido.datetime_format();
+
+        let mut keys: Vec<IdoKeyT> = Vec::new();
+        for row in &item.m_array {
+            for (key, _) in row.live_items() {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+
+        let columns = keys
+            .iter()
+            .map(|key| match names.and_then(|names| names.name(key)) {
+                Some(name) => name.to_string(),
+                None => key.to_string(),
+            })
+            .collect();
+        let rows = item
+            .m_array
+            .iter()
+            .map(|row| {
+                keys.iter()
+                    .map(|key| row.live_item(key).and_then(|value| value.as_string_with(format)).unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+
+        Some(Table { m_columns: columns, m_rows: rows })
+    }
+
+    /// Renders the table as aligned text: a header line, a line of dashes
+    /// and one line per row, columns separated by two spaces. A table
+    /// without columns renders as an empty string.
+    pub(crate) fn to_text(&self) -> String {
+        if self.m_columns.is_empty() {
+            return String::new();
+        }
+        let widths: Vec<usize> = self
+            .m_columns
+            .iter()
+            .enumerate()
+            .map(|(column, name)| {
+                self.m_rows
+                    .iter()
+                    .map(|row| row[column].chars().count())
+                    .fold(name.chars().count(), usize::max)
+            })
+            .collect();
+
+        let mut out = String::new();
+        let mut write_line = |cells: &mut dyn Iterator<Item = &str>| {
+            let mut line = String::new();
+            for (cell, width) in cells.zip(&widths) {
+                if !line.is_empty() {
+                    line.push_str("  ");
+                }
+                line.push_str(cell);
+                line.extend(std::iter::repeat_n(' ', width - cell.chars().count()));
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        };
+
+        write_line(&mut self.m_columns.iter().map(String::as_str));
+        let dashes: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+        write_line(&mut dashes.iter().map(String::as_str));
+        for row in &self.m_rows {
+            write_line(&mut row.iter().map(String::as_str));
+        }
+        out
+    }
+
+    /// Renders the table as a Markdown table, escaping `|`, `<` and `\` and
+    /// turning line breaks into `<br>`. A table without columns renders as
+    /// an empty string.
+    pub(crate) fn to_markdown(&self) -> String {
+        if self.m_columns.is_empty() {
+            return String::new();
+        }
+        let mut out = String::new();
+        write_markdown_row(&mut out, &self.m_columns);
+        write_markdown_row(&mut out, &vec!["---".to_string(); self.m_columns.len()]);
+        for row in &self.m_rows {
+            write_markdown_row(&mut out, row);
+        }
+        out
+    }
+
+    /// Writes the table as CSV: a header row of column names, then one
+    /// record per row. Cells holding a comma, quote or line break are
+    /// quoted as RFC 4180 describes, and records end with `\n`. A table
+    /// without columns writes nothing.
+    ///
+    /// # Returns
+    ///
+    /// The number of rows written, not counting the header.
+    pub(crate) fn write_csv<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        if self.m_columns.is_empty() {
+            return Ok(0);
+        }
+        let mut line = String::new();
+        write_csv_record(&mut line, &self.m_columns);
+        writer.write_all(line.as_bytes())?;
+        for row in &self.m_rows {
+            line.clear();
+            write_csv_record(&mut line, row);
+            writer.write_all(line.as_bytes())?;
+        }
+        Ok(self.m_rows.len())
+    }
+}
+
+/// Writes one CSV record, quoting the cells that need it.
+fn write_csv_record(out: &mut String, cells: &[String]) {
+    for (index, cell) in cells.iter().enumerate() {
+        if index != 0 {
+            out.push(',');
+        }
+        if cell.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&cell.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(cell);
+        }
+    }
+    out.push('\n');
+}
+
+/// Writes one line of a Markdown table.
+pub(crate) fn write_markdown_row(out: &mut String, cells: &[String]) {
+    out.push('|');
+    for cell in cells {
+        out.push(' ');
+        for c in cell.chars() {
+            match c {
+                '|' | '<' | '\\' => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                '\n' => out.push_str("<br>"),
+                '\r' => {}
+                c => out.push(c),
+            }
+        }
+        out.push_str(" |");
+    }
+    out.push('\n');
+}
+
+impl Ido {
+    /// Renders the array of Idos under `array_key` as an aligned text
+    /// table, one row per Ido and one column per key; see the
+    /// [`table`](crate::table) module for the layout.
+    ///
+    /// # Returns
+    ///
+    /// `None` if `array_key` does not hold an ARRAY.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut first = Ido::new();
+    /// first.set_string(&1, "ORDER-1".to_string());
+    /// first.set_integer(&2, 100);
+    /// let mut second = Ido::new();
+    /// second.set_string(&1, "ORDER-2".to_string());
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.append_array(&5, first);
+    /// ido.append_array(&5, second);
+    ///
+    /// assert_eq!(ido.to_table(&5).unwrap(), "1        2\n-------  ---\nORDER-1  100\nORDER-2\n");
+    /// assert!(ido.to_table(&1).is_none());
+    /// ```
+    pub fn to_table(&self, array_key: &IdoKeyT) -> Option<String> {
+        Table::from_array(self, array_key, self.names()).map(|table| table.to_text())
+    }
+
+    /// Writes the array of Idos under `array_key` to `writer` as CSV, one
+    /// record per Ido and one column per key found in any of them; see the
+    /// [`table`](crate::table) module for the columns.
+    ///
+    /// The header row holds the keys, or their names if a dictionary is
+    /// attached with [`set_names`](Ido::set_names); use
+    /// [`NamedIdo::array_to_csv`](crate::dictionary::NamedIdo::array_to_csv)
+    /// to name them for a single call. Missing values leave the cell empty.
+    ///
+    /// # Returns
+    ///
+    /// The number of records written after the header: 0, with nothing
+    /// written, if `array_key` does not hold an ARRAY or its Idos are
+    /// empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the writer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut first = Ido::new();
+    /// first.set_string(&1, "ORDER-1".to_string());
+    /// first.set_integer(&2, 100);
+    /// let mut second = Ido::new();
+    /// second.set_string(&1, "ORDER-2, amended".to_string());
+    /// second.set_f64(&3, 101.25);
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.append_array(&5, first);
+    /// ido.append_array(&5, second);
+    ///
+    /// let mut out = Vec::new();
+    /// assert_eq!(ido.array_to_csv(&5, &mut out).unwrap(), 2);
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     "1,2,3\nORDER-1,100,\n\"ORDER-2, amended\",,101.25\n"
+    /// );
+    /// ```
+    pub fn array_to_csv<W: Write>(&self, array_key: &IdoKeyT, writer: &mut W) -> io::Result<usize> {
+        match Table::from_array(self, array_key, self.names()) {
+            Some(table) => table.write_csv(writer),
+            None => Ok(0),
+        }
+    }
+}
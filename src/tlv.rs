@@ -0,0 +1,268 @@
+//! A tag-length-value binary format for constrained counterparties.
+//!
+//! Every item is written as its key in a fixed number of bytes, the length
+//! of the value as an unsigned LEB128 varint, and the value itself. Items
+//! follow each other without a count, so an Ido spans the whole buffer:
+//!
+//! ```text
+//! ido    := item*
+//! item   := tag:u8|u16|u32 len:varint value
+//! value  := STRING     utf8*
+//!         | INTEGER    two's complement, 1 to 8 bytes
+//!         | INTEGER128 two's complement, 1 to 16 bytes
+//!         | FLOAT      f64
+//!         | DATETIME   microseconds since the epoch, 1 to 8 bytes
+//!         | ARRAY      (len:varint ido)*
+//! ```
+//!
+//! Tags and values are big-endian, and integers are written in as few
+//! bytes as hold them. The format carries no types, so the decoder has to
+//! be told the type of every key that is not a STRING with
+//! [`TlvCodec::field`]. Keys of nested Idos share the same declarations.
+
+use std::collections::HashMap;
+
+#[cfg(feature = "chrono")]
+use chrono::DateTime;
+
+use crate::codec::{CodecError, Decode, Encode, Reader};
+use crate::key::{key_from_i64, key_to_i64};
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+/// Number of bytes taken by each tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TagWidth {
+    /// Tags 0 to 255.
+    U8,
+    /// Tags 0 to 65535.
+    #[default]
+    U16,
+    /// Keys that fit in an `i32`, negative keys written as their two's
+    /// complement.
+    U32,
+}
+
+impl TagWidth {
+    fn len(self) -> usize {
+        match self {
+            TagWidth::U8 => 1,
+            TagWidth::U16 => 2,
+            TagWidth::U32 => 4,
+        }
+    }
+}
+
+/// Codec for the layout described in the module documentation.
+///
+/// # Example
+///
+/// ```
+/// use ido::Ido;
+/// use ido::IdoItemType;
+/// use ido::codec::{Decode, Encode};
+/// use ido::tlv::{TagWidth, TlvCodec};
+///
+/// let codec = TlvCodec::new().tag_width(TagWidth::U8).field(2, IdoItemType::INTEGER);
+///
+/// let mut ido = Ido::new();
+/// ido.set_string(&1, "AB".to_string());
+/// ido.set_integer(&2, 300);
+///
+/// let mut bytes = Vec::new();
+/// codec.encode(&ido, &mut bytes).unwrap();
+/// assert_eq!(bytes, [1, 2, b'A', b'B', 2, 2, 0x01, 0x2c]);
+///
+/// let decoded = codec.decode(&bytes).unwrap();
+/// assert_eq!(decoded.get_i64(&2), Some(300));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TlvCodec {
+    m_tag_width: TagWidth,
+    m_fields: HashMap<IdoKeyT, IdoItemType>,
+}
+
+fn write_varint(mut value: usize, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(reader: &mut Reader<'_>, key: IdoKeyT) -> Result<usize, CodecError> {
+    let mut value = 0usize;
+    for shift in (0..usize::BITS).step_by(7) {
+        let byte = reader.u8()?;
+        let bits = usize::from(byte & 0x7f);
+        if (bits << shift) >> shift != bits {
+            return Err(CodecError::InvalidValue(key));
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(CodecError::InvalidValue(key))
+}
+
+/// The big-endian bytes of `value` without redundant sign bytes.
+fn minimal_bytes(value: i128) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let mut start = 0;
+    while start < bytes.len() - 1 {
+        let redundant = (bytes[start] == 0x00 && bytes[start + 1] & 0x80 == 0)
+            || (bytes[start] == 0xff && bytes[start + 1] & 0x80 != 0);
+        if !redundant {
+            break;
+        }
+        start += 1;
+    }
+    bytes[start..].to_vec()
+}
+
+/// Sign-extends between 1 and `max` big-endian bytes.
+fn read_signed(bytes: &[u8], max: usize, key: IdoKeyT) -> Result<i128, CodecError> {
+    if bytes.is_empty() || bytes.len() > max {
+        return Err(CodecError::InvalidValue(key));
+    }
+    let fill = if bytes[0] & 0x80 != 0 { 0xff } else { 0x00 };
+    let mut full = [fill; 16];
+    full[16 - bytes.len()..].copy_from_slice(bytes);
+    Ok(i128::from_be_bytes(full))
+}
+
+impl TlvCodec {
+    /// Creates a codec with two-byte tags and no declared fields.
+    pub fn new() -> Self {
+        TlvCodec::default()
+    }
+
+    /// Sets the number of bytes taken by each tag.
+    pub fn tag_width(mut self, width: TagWidth) -> Self {
+        self.m_tag_width = width;
+        self
+    }
+
+    /// Declares the type `key` decodes to. Undeclared keys decode as STRING.
+    pub fn field(mut self, key: IdoKeyT, ty: IdoItemType) -> Self {
+        self.m_fields.insert(key, ty);
+        self
+    }
+
+    fn write_ido(&self, ido: &Ido, out: &mut Vec<u8>) -> Result<(), CodecError> {
+        for (key, item) in ido.live_items() {
+            self.write_item(key, item, out)?;
+        }
+        Ok(())
+    }
+
+    fn write_item(&self, key: IdoKeyT, item: &IdoItem, out: &mut Vec<u8>) -> Result<(), CodecError> {
+        let width = self.m_tag_width.len();
+        let tag = key_to_i64(key);
+        let fits = match self.m_tag_width {
+            TagWidth::U32 => i32::try_from(tag).is_ok(),
+            _ => (0..1 << (8 * width)).contains(&tag),
+        };
+        if !fits {
+            return Err(CodecError::Unsupported(key));
+        }
+        out.extend_from_slice(&(tag as u32).to_be_bytes()[4 - width..]);
+
+        let value = match item.m_type {
+            IdoItemType::STRING => item.m_string.as_bytes().to_vec(),
+            IdoItemType::INTEGER => minimal_bytes(i128::from(item.m_integer)),
+            IdoItemType::INTEGER128 => minimal_bytes(item.m_integer128),
+            IdoItemType::FLOAT => item.m_float.to_be_bytes().to_vec(),
+            IdoItemType::BOOL => vec![u8::from(item.m_bool)],
+            #[cfg(feature = "chrono")]
+            IdoItemType::DATETIME => minimal_bytes(i128::from(item.m_datetime.timestamp_micros())),
+            IdoItemType::ARRAY => {
+                let mut value = Vec::new();
+                for element in &item.m_array {
+                    let mut nested = Vec::new();
+                    self.write_ido(element, &mut nested)?;
+                    write_varint(nested.len(), &mut value);
+                    value.extend_from_slice(&nested);
+                }
+                value
+            }
+        };
+        write_varint(value.len(), out);
+        out.extend_from_slice(&value);
+        Ok(())
+    }
+
+    fn read_ido(&self, buf: &[u8]) -> Result<Ido, CodecError> {
+        let mut reader = Reader::new(buf);
+        let mut ido = Ido::new();
+
+        while reader.remaining() > 0 {
+            let mut tag = [0u8; 4];
+            let width = self.m_tag_width.len();
+            tag[4 - width..].copy_from_slice(reader.take(width)?);
+            let tag = u32::from_be_bytes(tag);
+            let tag = match self.m_tag_width {
+                TagWidth::U32 => i64::from(tag as i32),
+                _ => i64::from(tag),
+            };
+            let key = key_from_i64(tag).ok_or(CodecError::InvalidKey)?;
+
+            let len = read_varint(&mut reader, key)?;
+            let item = self.read_item(key, reader.take(len)?)?;
+            ido.set_item(&key, item);
+        }
+        Ok(ido)
+    }
+
+    fn read_item(&self, key: IdoKeyT, value: &[u8]) -> Result<IdoItem, CodecError> {
+        let mut item = IdoItem::new();
+        item.m_type = self.m_fields.get(&key).copied().unwrap_or(IdoItemType::STRING);
+
+        match item.m_type {
+            IdoItemType::STRING => {
+                item.m_string = std::str::from_utf8(value)
+                    .map_err(|_| CodecError::InvalidUtf8)?
+                    .to_string();
+            }
+            IdoItemType::INTEGER => item.m_integer = read_signed(value, 8, key)? as i64,
+            IdoItemType::INTEGER128 => item.m_integer128 = read_signed(value, 16, key)?,
+            IdoItemType::FLOAT => {
+                let bytes = value.try_into().map_err(|_| CodecError::InvalidValue(key))?;
+                item.m_float = f64::from_be_bytes(bytes);
+            }
+            #[cfg(feature = "chrono")]
+            IdoItemType::DATETIME => {
+                let micros = read_signed(value, 8, key)? as i64;
+                item.m_datetime = DateTime::from_timestamp_micros(micros).ok_or(CodecError::InvalidDateTime)?;
+            }
+            IdoItemType::BOOL => {
+                item.m_bool = match value {
+                    [0] => false,
+                    [1] => true,
+                    _ => return Err(CodecError::InvalidValue(key)),
+                };
+            }
+            IdoItemType::ARRAY => {
+                let mut reader = Reader::new(value);
+                while reader.remaining() > 0 {
+                    let len = read_varint(&mut reader, key)?;
+                    item.m_array.push(self.read_ido(reader.take(len)?)?);
+                }
+            }
+        }
+        Ok(item)
+    }
+}
+
+impl Encode for TlvCodec {
+    fn encode(&self, ido: &Ido, out: &mut Vec<u8>) -> Result<(), CodecError> {
+        let start = out.len();
+        self.write_ido(ido, out).inspect_err(|_| out.truncate(start))
+    }
+}
+
+impl Decode for TlvCodec {
+    fn decode(&self, buf: &[u8]) -> Result<Ido, CodecError> {
+        self.read_ido(buf)
+    }
+}
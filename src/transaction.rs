@@ -0,0 +1,89 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::{Ido, IdoError, IdoItem, IdoKeyT};
+
+/// The state of every key touched since `begin`, captured on first touch.
+#[derive(Clone, Default)]
+pub(crate) struct Transaction {
+    m_originals: HashMap<IdoKeyT, Option<IdoItem>>,
+    m_order: Vec<IdoKeyT>,
+}
+
+impl Transaction {
+    pub(crate) fn record(&mut self, key: IdoKeyT, old: Option<&IdoItem>) {
+        if let Entry::Vacant(entry) = self.m_originals.entry(key) {
+            entry.insert(old.cloned());
+            self.m_order.push(key);
+        }
+    }
+}
+
+impl Ido {
+    /// Opens a transaction.
+    ///
+    /// Changes made after `begin` are applied immediately but can be reverted
+    /// as a whole with `rollback`, or kept with `commit`. Transactions do not
+    /// nest.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IdoError::TransactionActive` if a transaction is already open.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_integer(&1, 100);
+    ///
+    /// ido.begin().unwrap();
+    /// ido.set_integer(&1, 200);
+    /// ido.set_string(&2, "pending".to_string());
+    /// ido.rollback().unwrap();
+    ///
+    /// assert_eq!(ido.get_i64(&1), Some(100));
+    /// assert!(!ido.contains(&2));
+    /// ```
+    pub fn begin(&mut self) -> Result<(), IdoError> {
+        if self.m_transaction.is_some() {
+            return Err(IdoError::TransactionActive);
+        }
+        self.m_transaction = Some(Transaction::default());
+        Ok(())
+    }
+
+    /// Keeps all changes made since `begin` and closes the transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IdoError::NoTransaction` if no transaction is open.
+    pub fn commit(&mut self) -> Result<(), IdoError> {
+        self.m_transaction.take().map(|_| ()).ok_or(IdoError::NoTransaction)
+    }
+
+    /// Reverts all changes made since `begin` and closes the transaction.
+    ///
+    /// Restored items go back to their original position in the insertion
+    /// order. The reverting changes are reported to listeners, the journal
+    /// and dirty tracking like any other mutation.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IdoError::NoTransaction` if no transaction is open.
+    pub fn rollback(&mut self) -> Result<(), IdoError> {
+        let mut transaction = self.m_transaction.take().ok_or(IdoError::NoTransaction)?;
+
+        for key in transaction.m_order.iter().rev() {
+            let original = transaction.m_originals.remove(key).flatten();
+            self.revert_to(*key, original);
+        }
+        Ok(())
+    }
+
+    /// Checks whether a transaction is open.
+    pub fn in_transaction(&self) -> bool {
+        self.m_transaction.is_some()
+    }
+}
@@ -0,0 +1,158 @@
+//! Read-only access to an Ido.
+
+use std::fmt;
+
+use crate::convert::{FromIdoError, IdoValue};
+use crate::{FromIdo, Ido, IdoError, IdoItem, IdoItemType, IdoKey, IdoKeyT, MissingKeys, NarrowInt, OrderedIdoIterator};
+
+/// A borrowed Ido that can only be read.
+///
+/// A view exposes the getters of [`Ido`] and none of its setters, so a
+/// function can hand out an Ido it owns without cloning it and without
+/// letting the caller change it. Views are `Copy` and can be passed around
+/// freely for as long as the Ido is borrowed.
+///
+/// # Example
+///
+/// ```
+/// use ido::{Ido, IdoView};
+///
+/// struct Order {
+///     m_fields: Ido,
+/// }
+///
+/// impl Order {
+///     fn fields(&self) -> IdoView<'_> {
+///         self.m_fields.view()
+///     }
+/// }
+///
+/// let mut fields = Ido::new();
+/// fields.set_string(&1, "EURUSD".to_string());
+/// let order = Order { m_fields: fields };
+///
+/// let view = order.fields();
+/// assert_eq!(view.get_string(&1), Some("EURUSD".to_string()));
+/// assert_eq!(view.size(), 1);
+/// ```
+#[derive(Clone, Copy)]
+pub struct IdoView<'a> {
+    m_ido: &'a Ido,
+}
+
+macro_rules! view_getters {
+    ($($name:ident -> $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("See [`Ido::", stringify!($name), "`].")]
+            pub fn $name(&self, key: &impl IdoKey) -> Option<$ty> {
+                self.m_ido.$name(key)
+            }
+        )*
+    };
+}
+
+impl<'a> IdoView<'a> {
+    /// Creates a read-only view of `ido`.
+    pub fn new(ido: &'a Ido) -> Self {
+        IdoView { m_ido: ido }
+    }
+
+    view_getters! {
+        get_item -> IdoItem,
+        get_string -> String,
+        get_i64 -> i64,
+        get_i128 -> i128,
+        get_u128 -> u128,
+        get_i32 -> i32,
+        get_i16 -> i16,
+        get_i8 -> i8,
+        get_u64 -> u64,
+        get_u32 -> u32,
+        get_u16 -> u16,
+        get_u8 -> u8,
+        get_f64 -> f64,
+        get_bool -> bool,
+        get_bool_lossy -> bool,
+    }
+
+    /// See [`Ido::try_get_int`].
+    pub fn try_get_int<T: NarrowInt>(&self, key: &impl IdoKey) -> Result<Option<T>, IdoError> {
+        self.m_ido.try_get_int(key)
+    }
+
+    /// See [`Ido::get_values`].
+    pub fn get_values(&self, key: &impl IdoKey) -> Vec<IdoItem> {
+        self.m_ido.get_values(key)
+    }
+
+    /// See [`Ido::get_value`].
+    pub fn get_value<V: IdoValue>(&self, key: &IdoKeyT) -> Result<V, FromIdoError> {
+        self.m_ido.get_value(key)
+    }
+
+    /// See [`Ido::get_array_items`].
+    pub fn get_array_items<T: FromIdo>(&self, key: &IdoKeyT) -> Result<Vec<T>, FromIdoError> {
+        self.m_ido.get_array_items(key)
+    }
+
+    /// See [`Ido::contains`].
+    pub fn contains(&self, key: &impl IdoKey) -> bool {
+        self.m_ido.contains(key)
+    }
+
+    /// See [`Ido::is_type`].
+    pub fn is_type(&self, key: &impl IdoKey, ty: &IdoItemType) -> bool {
+        self.m_ido.is_type(key, ty)
+    }
+
+    /// See [`Ido::require`].
+    pub fn require(&self, keys: &[IdoKeyT]) -> Result<(), MissingKeys> {
+        self.m_ido.require(keys)
+    }
+
+    /// See [`Ido::size`].
+    pub fn size(&self) -> usize {
+        self.m_ido.size()
+    }
+
+    /// See [`Ido::to_vec`].
+    pub fn to_vec(&self) -> Vec<(IdoKeyT, IdoItem)> {
+        self.m_ido.to_vec()
+    }
+
+    /// Borrows the items for as long as the Ido is borrowed. See
+    /// [`Ido::as_pairs`].
+    pub fn as_pairs(&self) -> Vec<(IdoKeyT, &'a IdoItem)> {
+        self.m_ido.as_pairs()
+    }
+
+    /// Iterates over the items in insertion order, for as long as the Ido
+    /// is borrowed. See [`Ido::into_ordered_iterator`].
+    pub fn into_ordered_iterator(self) -> OrderedIdoIterator<'a> {
+        self.m_ido.into_ordered_iterator()
+    }
+
+    /// Returns a copy of the Ido that the caller owns and may change.
+    pub fn to_ido(&self) -> Ido {
+        self.m_ido.clone()
+    }
+}
+
+impl<'a> From<&'a Ido> for IdoView<'a> {
+    fn from(ido: &'a Ido) -> Self {
+        IdoView::new(ido)
+    }
+}
+
+impl fmt::Display for IdoView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.m_ido, f)
+    }
+}
+
+impl Ido {
+    /// Returns a read-only view of the Ido; see [`IdoView`].
+    pub fn view(&self) -> IdoView<'_> {
+        IdoView::new(self)
+    }
+}
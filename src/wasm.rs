@@ -0,0 +1,215 @@
+//! JavaScript bindings for WebAssembly builds.
+//!
+//! Exposes [`JsIdo`] to JavaScript through `wasm-bindgen`, so a browser can
+//! decode the binary Idos a backend streams over a WebSocket and work with
+//! them as plain objects:
+//!
+//! ```js
+//! import { JsIdo } from "ido";
+//!
+//! socket.binaryType = "arraybuffer";
+//! socket.onmessage = (event) => {
+//!     const ido = JsIdo.decodeFrame(new Uint8Array(event.data));
+//!     render(ido.toObject());
+//! };
+//! ```
+//!
+//! Values map to JavaScript as follows: STRING to string, INTEGER to number
+//! (or BigInt outside the safe integer range), INTEGER128 to BigInt, FLOAT
+//! to number, DATETIME to `Date` and ARRAY to an array of objects. Going
+//! the other way, integral numbers and BigInts become INTEGER (INTEGER128
+//! beyond 64 bits) and other numbers become FLOAT.
+
+use chrono::DateTime;
+use js_sys::{Array, BigInt, Date, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::codec;
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// An Ido owned by JavaScript code.
+#[wasm_bindgen]
+pub struct JsIdo {
+    m_ido: Ido,
+}
+
+#[wasm_bindgen]
+impl JsIdo {
+    /// Creates an empty Ido.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> JsIdo {
+        JsIdo { m_ido: Ido::new() }
+    }
+
+    /// Decodes an Ido produced by `Ido::encode`.
+    pub fn decode(bytes: &[u8]) -> Result<JsIdo, JsError> {
+        Ok(JsIdo { m_ido: Ido::decode(bytes)? })
+    }
+
+    /// Decodes a single length-prefixed frame produced by `codec::write_frame`.
+    #[wasm_bindgen(js_name = decodeFrame)]
+    pub fn decode_frame(bytes: &[u8]) -> Result<JsIdo, JsError> {
+        let mut reader = bytes;
+        let ido = codec::read_frame(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(JsError::new(&format!("{} bytes after the frame", reader.len())));
+        }
+        Ok(JsIdo { m_ido: ido })
+    }
+
+    /// Encodes the Ido in the binary format.
+    pub fn encode(&self) -> Vec<u8> {
+        self.m_ido.encode()
+    }
+
+    /// Returns the number of items.
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> usize {
+        self.m_ido.size()
+    }
+
+    /// Returns the keys in insertion order.
+    pub fn keys(&self) -> Vec<IdoKeyT> {
+        self.m_ido.into_ordered_iterator().map(|(key, _)| key).collect()
+    }
+
+    /// Returns the `key=value` form of the Ido.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        self.m_ido.to_string()
+    }
+
+    /// Converts the Ido into a plain object keyed by the numeric keys.
+    ///
+    /// JavaScript orders integer-like property names numerically, so use
+    /// `toEntries` when insertion order matters.
+    #[wasm_bindgen(js_name = toObject)]
+    pub fn to_object(&self) -> Result<Object, JsError> {
+        ido_to_object(&self.m_ido)
+    }
+
+    /// Converts the Ido into an array of `[key, value]` pairs in insertion order.
+    #[wasm_bindgen(js_name = toEntries)]
+    pub fn to_entries(&self) -> Array {
+        self.m_ido
+            .live_items()
+            .map(|(key, item)| Array::of2(&JsValue::from(key), &item_to_js(item)))
+            .collect()
+    }
+
+    /// Builds an Ido from a plain object whose property names are keys.
+    #[wasm_bindgen(js_name = fromObject)]
+    pub fn from_object(object: &Object) -> Result<JsIdo, JsError> {
+        Ok(JsIdo { m_ido: ido_from_object(object)? })
+    }
+}
+
+impl Default for JsIdo {
+    fn default() -> Self {
+        JsIdo::new()
+    }
+}
+
+impl From<Ido> for JsIdo {
+    fn from(ido: Ido) -> Self {
+        JsIdo { m_ido: ido }
+    }
+}
+
+impl From<JsIdo> for Ido {
+    fn from(ido: JsIdo) -> Self {
+        ido.m_ido
+    }
+}
+
+fn item_to_js(item: &IdoItem) -> JsValue {
+    match item.m_type {
+        IdoItemType::STRING => JsValue::from_str(&item.m_string),
+        IdoItemType::INTEGER if item.m_integer.abs() <= MAX_SAFE_INTEGER => JsValue::from_f64(item.m_integer as f64),
+        IdoItemType::INTEGER => BigInt::from(item.m_integer).into(),
+        IdoItemType::INTEGER128 => BigInt::from(item.m_integer128).into(),
+        IdoItemType::FLOAT => JsValue::from_f64(item.m_float),
+        IdoItemType::BOOL => JsValue::from_bool(item.m_bool),
+        IdoItemType::DATETIME => Date::new(&JsValue::from_f64(item.m_datetime.timestamp_millis() as f64)).into(),
+        IdoItemType::ARRAY => item
+            .m_array
+            .iter()
+            .map(|element| ido_to_object(element).map(JsValue::from).unwrap_or(JsValue::NULL))
+            .collect::<Array>()
+            .into(),
+    }
+}
+
+fn ido_to_object(ido: &Ido) -> Result<Object, JsError> {
+    let object = Object::new();
+    for (key, item) in ido.live_items() {
+        Reflect::set(&object, &JsValue::from(key), &item_to_js(item))
+            .map_err(|_| JsError::new(&format!("cannot set property {}", key)))?;
+    }
+    Ok(object)
+}
+
+fn item_from_js(key: IdoKeyT, value: &JsValue) -> Result<IdoItem, JsError> {
+    let mut item = IdoItem::new();
+
+    if let Some(string) = value.as_string() {
+        item.m_type = IdoItemType::STRING;
+        item.m_string = string;
+    } else if let Some(flag) = value.as_bool() {
+        item.m_type = IdoItemType::BOOL;
+        item.m_bool = flag;
+    } else if let Some(number) = value.as_f64() {
+        if number.fract() == 0.0 && number.abs() <= MAX_SAFE_INTEGER as f64 {
+            item.m_type = IdoItemType::INTEGER;
+            item.m_integer = number as i64;
+        } else {
+            item.m_type = IdoItemType::FLOAT;
+            item.m_float = number;
+        }
+    } else if value.is_bigint() {
+        let value = i128::try_from(value.clone().unchecked_into::<BigInt>())
+            .map_err(|_| JsError::new(&format!("key {} does not fit in 128 bits", key)))?;
+        match i64::try_from(value) {
+            Ok(value) => {
+                item.m_type = IdoItemType::INTEGER;
+                item.m_integer = value;
+            }
+            Err(_) => {
+                item.m_type = IdoItemType::INTEGER128;
+                item.m_integer128 = value;
+            }
+        }
+    } else if let Some(date) = value.dyn_ref::<Date>() {
+        item.m_type = IdoItemType::DATETIME;
+        item.m_datetime = DateTime::from_timestamp_millis(date.get_time() as i64)
+            .ok_or_else(|| JsError::new(&format!("key {} has an invalid date", key)))?;
+    } else if let Some(array) = value.dyn_ref::<Array>() {
+        item.m_type = IdoItemType::ARRAY;
+        for element in array.iter() {
+            let element = element
+                .dyn_into::<Object>()
+                .map_err(|_| JsError::new(&format!("key {} has an array element that is not an object", key)))?;
+            item.m_array.push(ido_from_object(&element)?);
+        }
+    } else {
+        return Err(JsError::new(&format!("key {} has an unsupported value", key)));
+    }
+    Ok(item)
+}
+
+fn ido_from_object(object: &Object) -> Result<Ido, JsError> {
+    let mut ido = Ido::new();
+
+    for entry in Object::entries(object).iter() {
+        let entry: Array = entry.unchecked_into();
+        let name = entry.get(0).as_string().unwrap_or_default();
+        let key = name
+            .parse::<IdoKeyT>()
+            .map_err(|_| JsError::new(&format!("property {:?} is not an integer key", name)))?;
+        ido.set_item(&key, item_from_js(key, &entry.get(1))?);
+    }
+    Ok(ido)
+}
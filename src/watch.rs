@@ -0,0 +1,101 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+
+#[cfg(feature = "tokio")]
+use tokio::sync::broadcast;
+
+use crate::{Ido, IdoItem, IdoKeyT};
+
+enum WatchSender {
+    Std(Sender<IdoItem>),
+    #[cfg(feature = "tokio")]
+    Broadcast(broadcast::Sender<IdoItem>),
+}
+
+/// A channel receiving the new values of one key.
+pub(crate) struct Watcher {
+    m_key: IdoKeyT,
+    m_sender: WatchSender,
+}
+
+impl Watcher {
+    /// Sends `new` if the change is to the watched key.
+    ///
+    /// # Returns
+    ///
+    /// `false` once every receiver has been dropped.
+    pub(crate) fn notify(&self, key: IdoKeyT, new: Option<&IdoItem>) -> bool {
+        let Some(new) = new.filter(|_| key == self.m_key) else {
+            return self.is_connected();
+        };
+        match &self.m_sender {
+            WatchSender::Std(sender) => sender.send(new.clone()).is_ok(),
+            #[cfg(feature = "tokio")]
+            WatchSender::Broadcast(sender) => sender.send(new.clone()).is_ok(),
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        match &self.m_sender {
+            // A std sender only finds out about a dropped receiver on send.
+            WatchSender::Std(_) => true,
+            #[cfg(feature = "tokio")]
+            WatchSender::Broadcast(sender) => sender.receiver_count() > 0,
+        }
+    }
+}
+
+impl Ido {
+    /// Subscribes to the values of a key.
+    ///
+    /// Every time the key is set, the new item is sent on the returned
+    /// channel. Deleting the key sends nothing. The channel is dropped from
+    /// the Ido on the first change after the receiver is dropped.
+    ///
+    /// Like listeners, watchers are not copied when the Ido is cloned.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to watch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut ido = Ido::new();
+    /// let prices = ido.watch(&1);
+    ///
+    /// ido.set_f64(&1, 101.25);
+    /// ido.set_f64(&2, 99.0);
+    /// ido.set_f64(&1, 101.5);
+    ///
+    /// let received: Vec<f64> = prices.try_iter().map(|item| item.m_float).collect();
+    /// assert_eq!(received, [101.25, 101.5]);
+    /// ```
+    pub fn watch(&mut self, key: &IdoKeyT) -> Receiver<IdoItem> {
+        let (sender, receiver) = mpsc::channel();
+        self.m_listeners.m_watchers.push(Watcher { m_key: *key, m_sender: WatchSender::Std(sender) });
+        receiver
+    }
+
+    /// Subscribes to the values of a key over a tokio broadcast channel.
+    ///
+    /// Behaves like `watch`, but the channel holds at most `capacity` values
+    /// and further receivers can be created with `resubscribe`. A receiver
+    /// that falls behind by more than `capacity` values skips the oldest.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to watch.
+    /// * `capacity` - The number of values kept for slow receivers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[cfg(feature = "tokio")]
+    pub fn watch_broadcast(&mut self, key: &IdoKeyT, capacity: usize) -> broadcast::Receiver<IdoItem> {
+        let (sender, receiver) = broadcast::channel(capacity);
+        self.m_listeners.m_watchers.push(Watcher { m_key: *key, m_sender: WatchSender::Broadcast(sender) });
+        receiver
+    }
+}
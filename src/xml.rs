@@ -0,0 +1,541 @@
+//! Converting between XML documents and Idos.
+//!
+//! An Ido becomes an `<ido>` element with one child element per item, in
+//! insertion order. [`XmlKeys`] picks how a child names its key:
+//!
+//! - `Attributes` writes every item as `<item key="1">`, with the key, or
+//!   its name when a dictionary is attached by [`Ido::set_names`].
+//! - `Elements` names the element after the key, e.g. `<symbol>`. Keys
+//!   without a name, or whose name is not an XML name, are written as
+//!   `<_1>`.
+//!
+//! Values are written as element text. Every type but STRING carries a
+//! `type` attribute so that documents read back unchanged:
+//!
+//! | Ido type      | `type`        | Text                                  |
+//! |---------------|---------------|---------------------------------------|
+//! | STRING        |               | the string                            |
+//! | INTEGER       | `integer`     | number                                |
+//! | INTEGER128    | `integer128`  | number                                |
+//! | FLOAT         | `float`       | number, `NaN`, `inf` or `-inf`        |
+//! | BOOL          | `bool`        | `true` or `false`                     |
+//! | DATETIME      | `datetime`    | RFC 3339, as `xs:dateTime` expects    |
+//! | ARRAY         | `array`       | one nested `<ido>` per element        |
+//!
+//! [`Ido::from_xml`] reads either layout, resolving names through the
+//! dictionary it is given. It reads the XML this module writes: a prolog,
+//! comments, CDATA sections and character references are accepted, while
+//! namespaces and DTDs are not interpreted.
+//!
+//! ```
+//! use ido::xml::XmlKeys;
+//! use ido::Ido;
+//!
+//! let mut ido = Ido::new();
+//! ido.set_string(&1, "EURUSD".to_string());
+//! ido.set_integer(&2, 100);
+//!
+//! let xml = ido.to_xml(XmlKeys::Attributes);
+//! assert_eq!(
+//!     xml,
+//!     "<ido><item key=\"1\">EURUSD</item><item key=\"2\" type=\"integer\">100</item></ido>"
+//! );
+//! assert!(Ido::from_xml(&xml, None).unwrap() == ido);
+//!
+//! assert_eq!(
+//!     ido.to_xml(XmlKeys::Elements),
+//!     "<ido><_1>EURUSD</_1><_2 type=\"integer\">100</_2></ido>"
+//! );
+//! ```
+
+use std::fmt::{self, Write as _};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "chrono")]
+use crate::datetime::DateTimeFormat;
+use crate::dictionary::IdoDictionary;
+use crate::{Ido, IdoItem, IdoItemType, IdoKeyT};
+
+/// How the items of an Ido are named in XML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum XmlKeys {
+    /// `<item key="1">`, or `<item key="symbol">` for a named key.
+    #[default]
+    Attributes,
+    /// `<symbol>` for a named key, or `<_1>` otherwise.
+    Elements,
+}
+
+/// Errors raised while reading XML.
+#[derive(Debug, Clone, PartialEq)]
+pub enum XmlError {
+    /// The document is not well formed at this byte offset.
+    Syntax(usize),
+    /// An element or key attribute names neither a key nor an entry of the
+    /// dictionary.
+    UnknownName(String),
+    /// The text of the item under this key does not parse as its type.
+    InvalidValue(IdoKeyT),
+    /// The item under this key has a type this build cannot hold.
+    Unsupported(IdoKeyT),
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XmlError::Syntax(offset) => write!(f, "malformed xml at byte {}", offset),
+            XmlError::UnknownName(name) => write!(f, "{} is not a key or a known name", name),
+            XmlError::InvalidValue(key) => write!(f, "invalid value for key {}", key),
+            XmlError::Unsupported(key) => write!(f, "key {} has an unsupported type", key),
+        }
+    }
+}
+
+impl std::error::Error for XmlError {}
+
+/// Writes `ido` as an `<ido>` element, naming keys found in `names`.
+pub(crate) fn write_ido(out: &mut String, ido: &Ido, keys: XmlKeys, names: Option<&IdoDictionary>) {
+    out.push_str("<ido>");
+    for (key, item) in ido.live_items() {
+
+        let name = names.and_then(|names| names.name(&key));
+        let element = match keys {
+            XmlKeys::Attributes => {
+                out.push_str("<item key=\"");
+                match name {
+                    Some(name) => escape(out, name),
+                    None => {
+                        let _ = write!(out, "{}", key);
+                    }
+                }
+                out.push('"');
+                "item".to_string()
+            }
+            XmlKeys::Elements => {
+                let element = match name.filter(|name| is_name(name)) {
+                    Some(name) => name.to_string(),
+                    None => format!("_{}", key),
+                };
+                out.push('<');
+                out.push_str(&element);
+                element
+            }
+        };
+
+        let kind = match item.m_type {
+            IdoItemType::STRING => None,
+            IdoItemType::INTEGER => Some("integer"),
+            IdoItemType::INTEGER128 => Some("integer128"),
+            IdoItemType::FLOAT => Some("float"),
+            IdoItemType::BOOL => Some("bool"),
+            #[cfg(feature = "chrono")]
+            IdoItemType::DATETIME => Some("datetime"),
+            IdoItemType::ARRAY => Some("array"),
+        };
+        if let Some(kind) = kind {
+            let _ = write!(out, " type=\"{}\"", kind);
+        }
+        out.push('>');
+
+        match item.m_type {
+            IdoItemType::STRING => escape(out, &item.m_string),
+            IdoItemType::INTEGER => {
+                let _ = write!(out, "{}", item.m_integer);
+            }
+            IdoItemType::INTEGER128 => {
+                let _ = write!(out, "{}", item.m_integer128);
+            }
+            IdoItemType::FLOAT => {
+                let _ = write!(out, "{}", item.m_float);
+            }
+            IdoItemType::BOOL => out.push_str(if item.m_bool { "true" } else { "false" }),
+            #[cfg(feature = "chrono")]
+            IdoItemType::DATETIME => out.push_str(&DateTimeFormat::rfc3339().format(&item.m_datetime)),
+            IdoItemType::ARRAY => {
+                for element in &item.m_array {
+                    write_ido(out, element, keys, names);
+                }
+            }
+        }
+
+        let _ = write!(out, "</{}>", element);
+    }
+    out.push_str("</ido>");
+}
+
+/// Escapes `text` for use as element text or an attribute value.
+fn escape(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\r' => out.push_str("&#13;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// Whether `name` can be written as an element name as it is.
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Replaces the entity and character references in `raw`, which starts at
+/// byte `offset` of the document.
+fn unescape(raw: &str, offset: usize) -> Result<String, XmlError> {
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let error = XmlError::Syntax(offset + raw.len() - rest.len() + amp);
+        let after = &rest[amp + 1..];
+        let semi = after.find(';').ok_or(error.clone())?;
+        let entity = &after[..semi];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|decimal| decimal.parse::<u32>()))
+                .and_then(Result::ok)
+                .and_then(char::from_u32)
+                .ok_or(error)?,
+        };
+        out.push(c);
+        rest = &after[semi + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// A start tag: its name, its attributes and whether it is empty (`<a/>`).
+struct Tag<'a> {
+    name: &'a str,
+    attributes: Vec<(&'a str, String)>,
+    empty: bool,
+}
+
+impl Tag<'_> {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| *attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    names: Option<&'a IdoDictionary>,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn error(&self) -> XmlError {
+        XmlError::Syntax(self.pos)
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), XmlError> {
+        if !self.rest().starts_with(token) {
+            return Err(self.error());
+        }
+        self.pos += token.len();
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Moves past the next `end`, returning the text before it.
+    fn skip_until(&mut self, end: &str) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        let len = rest.find(end).ok_or(self.error())?;
+        self.pos += len + end.len();
+        Ok(&rest[..len])
+    }
+
+    /// Moves past whitespace, comments, processing instructions and
+    /// doctype declarations.
+    fn skip_misc(&mut self) -> Result<(), XmlError> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<?") {
+                self.skip_until("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_until("-->")?;
+            } else if rest.starts_with("<!DOCTYPE") {
+                self.skip_until(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=' | '<'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error());
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    fn start_tag(&mut self) -> Result<Tag<'a>, XmlError> {
+        self.expect("<")?;
+        let name = self.name()?;
+        let mut attributes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(Tag { name, attributes, empty: true });
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                return Ok(Tag { name, attributes, empty: false });
+            }
+
+            let attribute = self.name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some('"') => "\"",
+                Some('\'') => "'",
+                _ => return Err(self.error()),
+            };
+            self.pos += 1;
+            let start = self.pos;
+            let raw = self.skip_until(quote)?;
+            attributes.push((attribute, unescape(raw, start)?));
+        }
+    }
+
+    fn end_tag(&mut self, name: &str) -> Result<(), XmlError> {
+        let start = self.pos;
+        self.expect("</")?;
+        if self.name()? != name {
+            return Err(XmlError::Syntax(start));
+        }
+        self.skip_whitespace();
+        self.expect(">")
+    }
+
+    /// Reads the text of an element up to and including its end tag.
+    fn text(&mut self, name: &str) -> Result<String, XmlError> {
+        let mut text = String::new();
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                text.push_str(self.skip_until("]]>")?);
+            } else if rest.starts_with("<!--") {
+                self.skip_until("-->")?;
+            } else if rest.starts_with("</") {
+                self.end_tag(name)?;
+                return Ok(text);
+            } else if rest.is_empty() || rest.starts_with('<') {
+                return Err(self.error());
+            } else {
+                let len = rest.find('<').unwrap_or(rest.len());
+                text.push_str(&unescape(&rest[..len], self.pos)?);
+                self.pos += len;
+            }
+        }
+    }
+
+    fn ido(&mut self) -> Result<Ido, XmlError> {
+        let start = self.pos;
+        let tag = self.start_tag()?;
+        if tag.name != "ido" {
+            return Err(XmlError::Syntax(start));
+        }
+
+        let mut ido = Ido::new();
+        if tag.empty {
+            return Ok(ido);
+        }
+        loop {
+            self.skip_misc()?;
+            if self.rest().starts_with("</") {
+                self.end_tag("ido")?;
+                return Ok(ido);
+            }
+            let (key, item) = self.item()?;
+            ido.set_item(&key, item);
+        }
+    }
+
+    /// Resolves an element name or key attribute to a key.
+    fn key(&self, name: &str) -> Result<IdoKeyT, XmlError> {
+        self.names
+            .and_then(|names| names.key(name))
+            .or_else(|| name.strip_prefix('_').unwrap_or(name).parse().ok())
+            .ok_or_else(|| XmlError::UnknownName(name.to_string()))
+    }
+
+    fn item(&mut self) -> Result<(IdoKeyT, IdoItem), XmlError> {
+        let tag = self.start_tag()?;
+        let key = match tag.attribute("key") {
+            Some(name) if tag.name == "item" => self.key(name)?,
+            _ => self.key(tag.name)?,
+        };
+
+        let mut item = IdoItem::new();
+        let kind = tag.attribute("type").unwrap_or("string");
+        if kind == "array" {
+            item.m_type = IdoItemType::ARRAY;
+            if !tag.empty {
+                loop {
+                    self.skip_misc()?;
+                    if self.rest().starts_with("</") {
+                        self.end_tag(tag.name)?;
+                        break;
+                    }
+                    item.m_array.push(self.ido()?);
+                }
+            }
+            return Ok((key, item));
+        }
+
+        let text = if tag.empty { String::new() } else { self.text(tag.name)? };
+        match kind {
+            "string" => {
+                item.m_type = IdoItemType::STRING;
+                item.m_string = text;
+            }
+            "integer" => {
+                item.m_type = IdoItemType::INTEGER;
+                item.m_integer = text.trim().parse().map_err(|_| XmlError::InvalidValue(key))?;
+            }
+            "integer128" => {
+                item.m_type = IdoItemType::INTEGER128;
+                item.m_integer128 = text.trim().parse().map_err(|_| XmlError::InvalidValue(key))?;
+            }
+            "float" => {
+                item.m_type = IdoItemType::FLOAT;
+                item.m_float = text.trim().parse().map_err(|_| XmlError::InvalidValue(key))?;
+            }
+            "bool" => {
+                item.m_type = IdoItemType::BOOL;
+                item.m_bool = match text.trim() {
+                    "true" | "1" => true,
+                    "false" | "0" => false,
+                    _ => return Err(XmlError::InvalidValue(key)),
+                };
+            }
+            #[cfg(feature = "chrono")]
+            "datetime" => {
+                item.m_type = IdoItemType::DATETIME;
+                item.m_datetime = DateTime::parse_from_rfc3339(text.trim())
+                    .map_err(|_| XmlError::InvalidValue(key))?
+                    .with_timezone(&Utc);
+            }
+            #[cfg(not(feature = "chrono"))]
+            "datetime" => return Err(XmlError::Unsupported(key)),
+            _ => return Err(XmlError::InvalidValue(key)),
+        }
+        Ok((key, item))
+    }
+}
+
+impl Ido {
+    /// Renders the Ido as an XML `<ido>` element; see the
+    /// [`xml`](crate::xml) module for the layout.
+    ///
+    /// Keys are written as numbers, or as names if a dictionary is attached
+    /// with [`set_names`](Ido::set_names); use
+    /// [`NamedIdo::to_xml`](crate::dictionary::NamedIdo::to_xml) to name
+    /// them for a single call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use ido::dictionary::IdoDictionary;
+    /// use ido::xml::XmlKeys;
+    /// use ido::Ido;
+    ///
+    /// let names: IdoDictionary = [(1, "symbol")].into_iter().collect();
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "AT&T".to_string());
+    /// ido.set_bool(&2, true);
+    /// ido.set_names(Arc::new(names));
+    ///
+    /// assert_eq!(
+    ///     ido.to_xml(XmlKeys::Elements),
+    ///     "<ido><symbol>AT&amp;T</symbol><_2 type=\"bool\">true</_2></ido>"
+    /// );
+    /// ```
+    pub fn to_xml(&self, keys: XmlKeys) -> String {
+        let mut out = String::new();
+        write_ido(&mut out, self, keys, self.names());
+        out
+    }
+
+    /// Reads an Ido from an XML document written by [`to_xml`](Ido::to_xml)
+    /// with either [`XmlKeys`] layout.
+    ///
+    /// Element names and key attributes are looked up in `names` first,
+    /// then read as a key, with or without the leading `_` of the
+    /// `Elements` layout. Items without a `type` attribute are read as
+    /// STRING.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`XmlError::Syntax`] for a malformed document or one whose
+    /// root is not `<ido>`, [`XmlError::UnknownName`] for a name that is
+    /// neither in `names` nor a key, and [`XmlError::InvalidValue`] for
+    /// text that does not parse as its `type`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::dictionary::IdoDictionary;
+    /// use ido::Ido;
+    ///
+    /// let names: IdoDictionary = [(1, "symbol"), (2, "quantity")].into_iter().collect();
+    /// let xml = r#"<?xml version="1.0"?>
+    /// <ido>
+    ///   <symbol>EURUSD</symbol>
+    ///   <quantity type="integer">100</quantity>
+    /// </ido>"#;
+    ///
+    /// let ido = Ido::from_xml(xml, Some(&names)).unwrap();
+    /// assert_eq!(ido.get_string(&1), Some("EURUSD".to_string()));
+    /// assert_eq!(ido.get_i64(&2), Some(100));
+    /// ```
+    pub fn from_xml(xml: &str, names: Option<&IdoDictionary>) -> Result<Ido, XmlError> {
+        let mut parser = Parser { text: xml, pos: 0, names };
+        parser.skip_misc()?;
+        let ido = parser.ido()?;
+        parser.skip_misc()?;
+        if !parser.rest().is_empty() {
+            return Err(parser.error());
+        }
+        Ok(ido)
+    }
+}
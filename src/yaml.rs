@@ -0,0 +1,538 @@
+//! Converting between YAML documents and Idos.
+//!
+//! An Ido becomes a block mapping with one entry per item, in insertion
+//! order. Entries are named by the key, or by its name with a dictionary
+//! attached by [`Ido::set_names`]. Values are plain scalars whose type is
+//! inferred as the YAML core schema infers it, so that a document reads
+//! back into items of the same types:
+//!
+//! | Ido type      | YAML                                                     |
+//! |---------------|----------------------------------------------------------|
+//! | STRING        | plain, or double-quoted if it would read as another type |
+//! | INTEGER       | integer                                                  |
+//! | INTEGER128    | integer, tagged `!i128` when it fits in 64 bits          |
+//! | FLOAT         | float with a `.` or exponent, `.nan`, `.inf` or `-.inf`  |
+//! | BOOL          | `true` or `false`                                        |
+//! | DATETIME      | RFC 3339 timestamp                                       |
+//! | ARRAY         | block sequence of mappings, or `[]`                      |
+//!
+//! [`Ido::from_yaml`] reads the block subset of YAML that fixtures are
+//! written in: nested mappings under sequence entries, plain, single- and
+//! double-quoted scalars, comments and document markers. Flow collections
+//! other than `{}` and `[]`, block scalars, anchors, aliases and nulls are
+//! rejected. Without the `chrono` feature, timestamps are read as strings.
+//!
+//! ```yaml
+//! 1: EURUSD
+//! 2: 100
+//! 3: "007"
+//! 5:
+//!   - 1: leg one
+//!     2: 1.25
+//!   - {}
+//! ```
+
+use std::fmt::{self, Write as _};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "chrono")]
+use crate::datetime::DateTimeFormat;
+use crate::dictionary::IdoDictionary;
+use crate::json::write_string;
+use crate::{Ido, IdoItem, IdoItemType};
+
+/// Returned by [`Ido::from_yaml`] for a document it cannot read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YamlError {
+    /// The line of the document, counting from 1.
+    pub line: usize,
+    /// What is wrong with the line.
+    pub reason: String,
+}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for YamlError {}
+
+/// Writes the items of `ido` as block mapping entries indented by
+/// `indent`, naming keys found in `names`. With `inline` the first entry
+/// continues the current line, after the `- ` of a sequence entry.
+pub(crate) fn write_mapping(out: &mut String, ido: &Ido, indent: usize, names: Option<&IdoDictionary>, inline: bool) {
+    let mut first = true;
+    for (key, item) in ido.live_items() {
+        if !(first && inline) {
+            pad(out, indent);
+        }
+        first = false;
+
+        match names.and_then(|names| names.name(&key)) {
+            Some(name) => write_text(out, name),
+            None => {
+                let _ = write!(out, "{}", key);
+            }
+        }
+        out.push(':');
+
+        match item.m_type {
+            IdoItemType::STRING => {
+                out.push(' ');
+                write_text(out, &item.m_string);
+            }
+            IdoItemType::INTEGER => {
+                let _ = write!(out, " {}", item.m_integer);
+            }
+            IdoItemType::INTEGER128 if i64::try_from(item.m_integer128).is_ok() => {
+                let _ = write!(out, " !i128 {}", item.m_integer128);
+            }
+            IdoItemType::INTEGER128 => {
+                let _ = write!(out, " {}", item.m_integer128);
+            }
+            IdoItemType::FLOAT if item.m_float.is_nan() => out.push_str(" .nan"),
+            IdoItemType::FLOAT if item.m_float.is_infinite() => {
+                out.push_str(if item.m_float > 0.0 { " .inf" } else { " -.inf" });
+            }
+            IdoItemType::FLOAT => {
+                let _ = write!(out, " {:?}", item.m_float);
+            }
+            IdoItemType::BOOL => out.push_str(if item.m_bool { " true" } else { " false" }),
+            #[cfg(feature = "chrono")]
+            IdoItemType::DATETIME => {
+                out.push(' ');
+                out.push_str(&DateTimeFormat::rfc3339().format(&item.m_datetime));
+            }
+            IdoItemType::ARRAY if item.m_array.is_empty() => out.push_str(" []"),
+            IdoItemType::ARRAY => {
+                for element in &item.m_array {
+                    out.push('\n');
+                    pad(out, indent + 2);
+                    out.push_str("- ");
+                    let start = out.len();
+                    write_mapping(out, element, indent + 4, names, true);
+                    if out.len() == start {
+                        out.push_str("{}");
+                    } else {
+                        // The nested mapping ends its last entry itself.
+                        out.pop();
+                    }
+                }
+            }
+        }
+        out.push('\n');
+    }
+}
+
+fn pad(out: &mut String, indent: usize) {
+    out.extend(std::iter::repeat_n(' ', indent));
+}
+
+/// Writes `text` plain, or double-quoted if it would not read back as the
+/// same string.
+fn write_text(out: &mut String, text: &str) {
+    let quote = text.is_empty()
+        || text != text.trim()
+        || text.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`".contains(c))
+        || text.ends_with(':')
+        || text.contains(": ")
+        || text.contains(" #")
+        || text.chars().any(char::is_control)
+        || is_null(text)
+        || plain_item(text).is_some();
+    if quote {
+        write_string(out, text);
+    } else {
+        out.push_str(text);
+    }
+}
+
+fn is_null(text: &str) -> bool {
+    matches!(text, "~" | "null" | "Null" | "NULL")
+}
+
+fn is_number(text: &str) -> bool {
+    text.chars().any(|c| c.is_ascii_digit())
+        && text.chars().all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | 'e' | 'E'))
+}
+
+/// Reads a plain scalar that is not a string.
+fn plain_item(text: &str) -> Option<IdoItem> {
+    let mut item = IdoItem::new();
+
+    if is_number(text) {
+        if let Ok(value) = text.parse::<i64>() {
+            item.m_type = IdoItemType::INTEGER;
+            item.m_integer = value;
+        } else if let Ok(value) = text.parse::<i128>() {
+            item.m_type = IdoItemType::INTEGER128;
+            item.m_integer128 = value;
+        } else {
+            item.m_type = IdoItemType::FLOAT;
+            item.m_float = text.parse().ok()?;
+        }
+        return Some(item);
+    }
+
+    match text {
+        "true" | "True" | "TRUE" | "false" | "False" | "FALSE" => {
+            item.m_type = IdoItemType::BOOL;
+            item.m_bool = text.eq_ignore_ascii_case("true");
+        }
+        ".nan" | ".NaN" | ".NAN" => {
+            item.m_type = IdoItemType::FLOAT;
+            item.m_float = f64::NAN;
+        }
+        ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf" | "+.INF" => {
+            item.m_type = IdoItemType::FLOAT;
+            item.m_float = f64::INFINITY;
+        }
+        "-.inf" | "-.Inf" | "-.INF" => {
+            item.m_type = IdoItemType::FLOAT;
+            item.m_float = f64::NEG_INFINITY;
+        }
+        _ => {
+            #[cfg(feature = "chrono")]
+            if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+                item.m_type = IdoItemType::DATETIME;
+                item.m_datetime = datetime.with_timezone(&Utc);
+                return Some(item);
+            }
+            return None;
+        }
+    }
+    Some(item)
+}
+
+/// Drops a trailing ` # comment` from a plain scalar.
+fn strip_comment(text: &str) -> &str {
+    if text.starts_with('#') {
+        return "";
+    }
+    match text.find(" #") {
+        Some(index) => text[..index].trim_end(),
+        None => text,
+    }
+}
+
+/// A line holding content, with its indentation measured in spaces.
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    number: usize,
+    indent: usize,
+    text: &'a str,
+}
+
+impl Line<'_> {
+    fn error(&self, reason: impl Into<String>) -> YamlError {
+        YamlError { line: self.number, reason: reason.into() }
+    }
+
+    /// Whether the line starts a sequence entry.
+    fn is_entry(&self) -> bool {
+        self.text == "-" || self.text.starts_with("- ")
+    }
+
+    /// Reads a double-quoted scalar at the start of `text`, returning it and
+    /// the rest of `text`.
+    fn double_quoted<'t>(&self, text: &'t str) -> Result<(String, &'t str), YamlError> {
+        let mut out = String::new();
+        let mut chars = text.char_indices().skip(1);
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => return Ok((out, &text[index + 1..])),
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('0') => '\0',
+                        Some(kind @ ('x' | 'u' | 'U')) => {
+                            let len = match kind {
+                                'x' => 2,
+                                'u' => 4,
+                                _ => 8,
+                            };
+                            let hex: String = chars.by_ref().take(len).map(|(_, c)| c).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .filter(|_| hex.len() == len)
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error(format!("invalid escape \\{}{}", kind, hex)))?
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    out.push(escaped);
+                }
+                c => out.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    /// Reads a single-quoted scalar at the start of `text`, returning it and
+    /// the rest of `text`.
+    fn single_quoted<'t>(&self, text: &'t str) -> Result<(String, &'t str), YamlError> {
+        let mut out = String::new();
+        let mut rest = &text[1..];
+        loop {
+            let end = rest.find('\'').ok_or_else(|| self.error("unterminated string"))?;
+            out.push_str(&rest[..end]);
+            rest = &rest[end + 1..];
+            match rest.strip_prefix('\'') {
+                Some(after) => {
+                    out.push('\'');
+                    rest = after;
+                }
+                None => return Ok((out, rest)),
+            }
+        }
+    }
+
+    fn quoted<'t>(&self, text: &'t str) -> Result<Option<(String, &'t str)>, YamlError> {
+        match text.chars().next() {
+            Some('"') => self.double_quoted(text).map(Some),
+            Some('\'') => self.single_quoted(text).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Splits a `key: value` entry into its key text and value text.
+    fn entry(&self) -> Result<(String, &str), YamlError> {
+        let (name, rest) = match self.quoted(self.text)? {
+            Some(quoted) => quoted,
+            None => {
+                let end = self
+                    .text
+                    .find(": ")
+                    .or_else(|| self.text.strip_suffix(':').map(str::len))
+                    .ok_or_else(|| self.error("expected `key: value`"))?;
+                (self.text[..end].trim_end().to_string(), &self.text[end..])
+            }
+        };
+
+        let value = rest
+            .trim_start()
+            .strip_prefix(':')
+            .ok_or_else(|| self.error("expected `key: value`"))?;
+        if !value.is_empty() && !value.starts_with(' ') {
+            return Err(self.error("expected a space after `:`"));
+        }
+        let value = value.trim();
+        Ok((name, if value.starts_with('#') { "" } else { value }))
+    }
+
+    /// Reads the scalar `value` of an entry.
+    fn scalar(&self, value: &str) -> Result<IdoItem, YamlError> {
+        let mut item = IdoItem::new();
+
+        if let Some((text, rest)) = self.quoted(value)? {
+            if !strip_comment(rest.trim_start()).is_empty() {
+                return Err(self.error("unexpected text after string"));
+            }
+            item.m_type = IdoItemType::STRING;
+            item.m_string = text;
+            return Ok(item);
+        }
+
+        let text = strip_comment(value);
+        if text == "[]" {
+            item.m_type = IdoItemType::ARRAY;
+        } else if let Some(number) = text.strip_prefix("!i128 ") {
+            item.m_type = IdoItemType::INTEGER128;
+            item.m_integer128 = number
+                .trim()
+                .parse()
+                .map_err(|_| self.error(format!("invalid !i128 value {}", number)))?;
+        } else if text.starts_with(|c: char| "&*!|>[{%@`".contains(c)) {
+            return Err(self.error(format!("unsupported value {}", text)));
+        } else if is_null(text) {
+            return Err(self.error("null values are not supported"));
+        } else if let Some(plain) = plain_item(text) {
+            item = plain;
+        } else {
+            item.m_type = IdoItemType::STRING;
+            item.m_string = text.to_string();
+        }
+        Ok(item)
+    }
+}
+
+struct Parser<'a> {
+    lines: Vec<Line<'a>>,
+    next: usize,
+    names: Option<&'a IdoDictionary>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Line<'a>> {
+        self.lines.get(self.next).copied()
+    }
+
+    /// Reads the entries of a block mapping indented by `indent`.
+    fn mapping(&mut self, indent: usize) -> Result<Ido, YamlError> {
+        let mut ido = Ido::new();
+        while let Some(line) = self.peek() {
+            if line.indent < indent || (line.indent == indent && line.is_entry()) {
+                break;
+            }
+            if line.indent > indent {
+                return Err(line.error("unexpected indentation"));
+            }
+
+            let (name, value) = line.entry()?;
+            let key = self
+                .names
+                .and_then(|names| names.key(&name))
+                .or_else(|| name.parse().ok())
+                .ok_or_else(|| line.error(format!("unknown key {}", name)))?;
+            self.next += 1;
+
+            let item = if value.is_empty() {
+                match self.peek() {
+                    Some(next) if next.is_entry() && next.indent >= indent => self.sequence(next.indent)?,
+                    _ => return Err(line.error("missing value")),
+                }
+            } else {
+                line.scalar(value)?
+            };
+            ido.set_item(&key, item);
+        }
+        Ok(ido)
+    }
+
+    /// Reads the entries of a block sequence indented by `indent` as an
+    /// ARRAY item.
+    fn sequence(&mut self, indent: usize) -> Result<IdoItem, YamlError> {
+        let mut item = IdoItem::new();
+        item.m_type = IdoItemType::ARRAY;
+
+        while let Some(line) = self.peek() {
+            if line.indent != indent || !line.is_entry() {
+                break;
+            }
+
+            let rest = &line.text[1..];
+            let content = rest.trim_start();
+            let element = if strip_comment(content).is_empty() {
+                self.next += 1;
+                match self.peek() {
+                    Some(next) if next.indent > indent => self.mapping(next.indent)?,
+                    _ => return Err(line.error("missing sequence entry")),
+                }
+            } else if strip_comment(content) == "{}" {
+                self.next += 1;
+                Ido::new()
+            } else {
+                // Continue with the first entry of the mapping as if it
+                // started on a line of its own.
+                let indent = indent + 1 + rest.len() - content.len();
+                self.lines[self.next] = Line { indent, text: content, ..line };
+                self.mapping(indent)?
+            };
+            item.m_array.push(element);
+        }
+        Ok(item)
+    }
+}
+
+impl Ido {
+    /// Renders the Ido as a YAML block mapping; see the
+    /// [`yaml`](crate::yaml) module for the mapping.
+    ///
+    /// Keys are written as numbers, or as names if a dictionary is attached
+    /// with [`set_names`](Ido::set_names); use
+    /// [`NamedIdo::to_yaml`](crate::dictionary::NamedIdo::to_yaml) to name
+    /// them for a single call. An empty Ido is written as `{}`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::Ido;
+    ///
+    /// let mut leg = Ido::new();
+    /// leg.set_f64(&2, 1.25);
+    ///
+    /// let mut ido = Ido::new();
+    /// ido.set_string(&1, "EURUSD".to_string());
+    /// ido.set_string(&3, "100".to_string());
+    /// ido.append_array(&5, leg);
+    ///
+    /// assert_eq!(ido.to_yaml(), "1: EURUSD\n3: \"100\"\n5:\n  - 2: 1.25\n");
+    /// ```
+    pub fn to_yaml(&self) -> String {
+        let mut out = String::new();
+        write_mapping(&mut out, self, 0, self.names(), false);
+        if out.is_empty() {
+            out.push_str("{}\n");
+        }
+        out
+    }
+
+    /// Reads an Ido from a YAML document such as [`to_yaml`](Ido::to_yaml)
+    /// writes.
+    ///
+    /// Keys are looked up in `names` first, then read as numbers. Each
+    /// value is typed as described in the [`yaml`](crate::yaml) module.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`YamlError`] naming the first line that is outside the
+    /// subset read, holds an unknown key or holds a null.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ido::dictionary::IdoDictionary;
+    /// use ido::Ido;
+    ///
+    /// let names: IdoDictionary = [(1, "symbol"), (5, "legs")].into_iter().collect();
+    /// let fixture = "\
+    /// symbol: EURUSD
+    /// 2: 100
+    /// legs:
+    ///   - 2: 1.25
+    ///     4: true
+    /// ";
+    ///
+    /// let ido = Ido::from_yaml(fixture, Some(&names)).unwrap();
+    /// assert_eq!(ido.get_string(&1), Some("EURUSD".to_string()));
+    /// assert_eq!(ido.get_i64(&2), Some(100));
+    /// assert_eq!(ido.get_item(&5).unwrap().m_array[0].get_bool(&4), Some(true));
+    /// ```
+    pub fn from_yaml(yaml: &str, names: Option<&IdoDictionary>) -> Result<Ido, YamlError> {
+        let mut lines = Vec::new();
+        for (index, raw) in yaml.lines().enumerate() {
+            let text = raw.trim_end();
+            let content = text.trim_start_matches(' ');
+            let line = Line { number: index + 1, indent: text.len() - content.len(), text: content };
+            if content.is_empty() || content.starts_with('#') || matches!(content, "---" | "...") {
+                continue;
+            }
+            if content.starts_with('\t') {
+                return Err(line.error("tabs cannot indent"));
+            }
+            lines.push(line);
+        }
+
+        if let [line] = lines[..] {
+            if strip_comment(line.text) == "{}" {
+                return Ok(Ido::new());
+            }
+        }
+
+        let indent = lines.first().map_or(0, |line| line.indent);
+        let mut parser = Parser { lines, next: 0, names };
+        let ido = parser.mapping(indent)?;
+        match parser.peek() {
+            Some(line) => Err(line.error("expected a mapping entry")),
+            None => Ok(ido),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use ido::{Ido, IdoItemType};
+
+fn noise(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 56) as u8
+        })
+        .collect()
+}
+
+fn depth(ido: &Ido) -> usize {
+    ido.into_ordered_iterator()
+        .filter(|(_, item)| item.m_type == IdoItemType::ARRAY)
+        .flat_map(|(_, item)| item.m_array.into_iter().map(|element| depth(&element) + 1))
+        .max()
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_arbitrary_codec_round_trip() {
+    for seed in 0..64 {
+        let data = noise(seed, 4096);
+        let ido = Ido::arbitrary(&mut Unstructured::new(&data)).unwrap();
+
+        let decoded = Ido::decode(&ido.encode()).unwrap();
+        assert_eq!(ido.diff_report(&decoded), "", "seed {}", seed);
+        assert!(depth(&ido) <= 3);
+    }
+}
+
+#[test]
+fn test_arbitrary_empty_input() {
+    let ido = Ido::arbitrary(&mut Unstructured::new(&[])).unwrap();
+    assert_eq!(ido.size(), 0);
+}
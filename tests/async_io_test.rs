@@ -0,0 +1,68 @@
+#![cfg(feature = "tokio")]
+
+use ido::async_io::{read_frame_async, write_frame_async};
+use ido::codec::{read_frame, write_frame, CodecError, MAX_FRAME_LEN};
+use ido::Ido;
+
+fn sample() -> Ido {
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+    leg.set_f64(&2, 1.0825);
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.set_integer(&2, 100);
+    ido.append_array(&3, leg);
+    ido
+}
+
+#[tokio::test]
+async fn test_async_roundtrip_over_duplex() {
+    // A small buffer forces partial reads and writes.
+    let (mut client, mut server) = tokio::io::duplex(7);
+    let ido = sample();
+
+    let writer = async {
+        for _ in 0..3 {
+            ido.encode_to_async(&mut client).await.unwrap();
+        }
+    };
+    let reader = async {
+        let mut decoded = Vec::new();
+        for _ in 0..3 {
+            decoded.push(Ido::decode_from_async(&mut server).await.unwrap());
+        }
+        decoded
+    };
+    let ((), decoded) = tokio::join!(writer, reader);
+
+    assert_eq!(decoded.len(), 3);
+    assert!(decoded.iter().all(|decoded| decoded.encode() == ido.encode()));
+}
+
+#[tokio::test]
+async fn test_async_matches_blocking_frames() {
+    let ido = sample();
+
+    let mut async_wire = Vec::new();
+    write_frame_async(&mut async_wire, &ido).await.unwrap();
+    let mut blocking_wire = Vec::new();
+    write_frame(&mut blocking_wire, &ido).unwrap();
+    assert_eq!(async_wire, blocking_wire);
+
+    let decoded = read_frame(&mut async_wire.as_slice()).unwrap();
+    assert_eq!(decoded.get_i64(&2), Some(100));
+    let decoded = read_frame_async(&mut blocking_wire.as_slice()).await.unwrap();
+    assert_eq!(decoded.get_i64(&2), Some(100));
+}
+
+#[tokio::test]
+async fn test_async_read_errors() {
+    let mut wire = Vec::new();
+    write_frame(&mut wire, &sample()).unwrap();
+    let truncated = &wire[..wire.len() - 1];
+    assert!(matches!(read_frame_async(&mut &truncated[..]).await, Err(CodecError::Io(_))));
+
+    let oversized = ((MAX_FRAME_LEN + 1) as u32).to_le_bytes();
+    assert!(matches!(read_frame_async(&mut &oversized[..]).await, Err(CodecError::FrameTooLarge(_))));
+}
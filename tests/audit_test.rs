@@ -0,0 +1,79 @@
+use std::sync::Mutex;
+
+use ido::{audit, Ido};
+
+// The counts are process-wide, so the tests of this file take turns.
+static AUDIT: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_audit_counts_reads_and_writes() {
+    let _guard = AUDIT.lock().unwrap_or_else(|err| err.into_inner());
+    audit::reset();
+    audit::enable();
+    assert!(audit::is_enabled());
+
+    let mut leg = Ido::new();
+    leg.set_string(&3, "EURUSD".to_string());
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 5);
+    ido.set_integer(&1, 6);
+    ido.set_f64(&2, 1.25);
+    ido.append_array(&4, leg.clone());
+    ido.append_array(&4, leg);
+
+    ido.get_i64(&1);
+    ido.get_i32(&1);
+    ido.get_string(&9);
+    let _ = ido.get_value::<f64>(&2);
+    audit::disable();
+    ido.get_i64(&1);
+
+    let usage = audit::usage();
+    assert_eq!(usage[&1].writes, 2);
+    assert_eq!(usage[&1].reads, 2);
+    #[cfg(feature = "chrono")]
+    assert!(usage[&1].last_read.is_some());
+    assert_eq!(usage[&2].reads, 1);
+    assert_eq!(usage[&3].writes, 1);
+    assert_eq!(usage[&3].reads, 0);
+    #[cfg(feature = "chrono")]
+    assert!(usage[&3].last_read.is_none());
+    assert_eq!(usage[&4].writes, 2);
+    assert_eq!(usage[&9].reads, 1);
+    assert_eq!(usage[&9].writes, 0);
+}
+
+#[test]
+fn test_audit_disabled_and_snapshot() {
+    let _guard = AUDIT.lock().unwrap_or_else(|err| err.into_inner());
+    audit::reset();
+    audit::disable();
+
+    let mut ido = Ido::new();
+    ido.set_integer(&10, 1);
+    ido.get_i64(&10);
+    assert!(audit::usage().is_empty());
+
+    audit::enable();
+    ido.set_integer(&20, 1);
+    ido.get_i64(&20);
+    ido.get_i64(&30);
+    let snapshot = audit::snapshot();
+    audit::disable();
+
+    // Building the snapshot is not audited.
+    assert_eq!(audit::usage().keys().copied().collect::<Vec<_>>(), vec![20, 30]);
+
+    let rows = snapshot.get_item(&audit::ENTRIES).unwrap().m_array;
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get_i64(&audit::KEY), Some(20));
+    assert_eq!(rows[0].get_i64(&audit::READS), Some(1));
+    assert_eq!(rows[0].get_i64(&audit::WRITES), Some(1));
+    #[cfg(feature = "chrono")]
+    assert!(rows[0].contains(&audit::LAST_WRITE));
+    assert_eq!(rows[1].get_i64(&audit::KEY), Some(30));
+    assert_eq!(rows[1].get_i64(&audit::WRITES), Some(0));
+    assert!(!rows[1].contains(&audit::LAST_WRITE));
+    #[cfg(feature = "chrono")]
+    assert!(rows[1].contains(&audit::LAST_READ));
+}
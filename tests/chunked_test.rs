@@ -0,0 +1,130 @@
+use ido::chunked::{read_chunked, write_chunked, ChunkDecoder};
+use ido::codec::CodecError;
+use ido::Ido;
+
+fn large() -> Ido {
+    let mut ido = Ido::new();
+    for key in 0..50 {
+        let mut row = Ido::new();
+        row.set_string(&1, format!("ROW-{}", key));
+        row.set_integer(&2, 100);
+        ido.append_array(&5, row);
+    }
+    ido.set_string(&1, "x".repeat(300));
+    ido
+}
+
+/// Splits `wire` into its chunks, each with its length prefix.
+fn chunks(wire: &[u8]) -> Vec<&[u8]> {
+    let mut out = Vec::new();
+    let mut rest = wire;
+    while !rest.is_empty() {
+        let len = u32::from_le_bytes(rest[..4].try_into().unwrap()) as usize;
+        out.push(&rest[..4 + len]);
+        rest = &rest[4 + len..];
+    }
+    out
+}
+
+#[test]
+fn test_chunked_round_trip() {
+    let ido = large();
+    let mut wire = Vec::new();
+    let count = write_chunked(&mut wire, &ido, 100).unwrap();
+    assert_eq!(count, ido.encoded_len().div_ceil(100));
+
+    let parts = chunks(&wire);
+    assert_eq!(parts.len(), count);
+    assert!(parts.iter().all(|chunk| chunk.len() <= 4 + 5 + 100));
+    for (seq, chunk) in parts.iter().enumerate() {
+        assert_eq!(u32::from_le_bytes(chunk[4..8].try_into().unwrap()), seq as u32);
+        assert_eq!(chunk[8], u8::from(seq + 1 == count));
+    }
+
+    let decoded = read_chunked(&mut wire.as_slice()).unwrap();
+    assert!(decoded == ido);
+}
+
+#[test]
+fn test_small_ido_is_one_chunk() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 42);
+
+    let mut wire = Vec::new();
+    assert_eq!(write_chunked(&mut wire, &ido, 1024).unwrap(), 1);
+    assert_eq!(wire.len(), 4 + 5 + ido.encoded_len());
+    assert_eq!(read_chunked(&mut wire.as_slice()).unwrap().get_i64(&1), Some(42));
+}
+
+#[test]
+fn test_chunk_decoder_byte_by_byte() {
+    let first = large();
+    let mut second = Ido::new();
+    second.set_integer(&1, 7);
+
+    let mut wire = Vec::new();
+    write_chunked(&mut wire, &first, 64).unwrap();
+    write_chunked(&mut wire, &second, 64).unwrap();
+
+    let mut decoder = ChunkDecoder::new();
+    let mut decoded = Vec::new();
+    for byte in &wire {
+        decoder.feed(std::slice::from_ref(byte));
+        while let Some(ido) = decoder.next_ido().unwrap() {
+            decoded.push(ido);
+        }
+    }
+    assert_eq!(decoded.len(), 2);
+    assert!(decoded[0] == first);
+    assert_eq!(decoded[1].get_i64(&1), Some(7));
+    assert_eq!(decoder.buffered(), 0);
+}
+
+#[test]
+fn test_chunk_decoder_resyncs() {
+    let mut small = Ido::new();
+    small.set_integer(&1, 7);
+    let mut wire = Vec::new();
+    write_chunked(&mut wire, &large(), 64).unwrap();
+    let big = chunks(&wire).iter().map(|chunk| chunk.to_vec()).collect::<Vec<_>>();
+    let mut single = Vec::new();
+    write_chunked(&mut single, &small, 64).unwrap();
+
+    // A missing chunk is reported and the rest of that Ido is skipped.
+    let mut decoder = ChunkDecoder::new();
+    decoder.feed(&big[0]);
+    decoder.feed(&big[2]);
+    assert!(matches!(decoder.next_ido(), Err(CodecError::ChunkOutOfOrder(2))));
+    decoder.feed(&big[3]);
+    decoder.feed(&single);
+    assert_eq!(decoder.next_ido().unwrap().unwrap().get_i64(&1), Some(7));
+
+    // An Ido cut short by its writer is dropped when the next one starts.
+    decoder.feed(&big[0]);
+    decoder.feed(&big[1]);
+    decoder.feed(&single);
+    assert_eq!(decoder.next_ido().unwrap().unwrap().get_i64(&1), Some(7));
+    assert!(decoder.next_ido().unwrap().is_none());
+}
+
+#[test]
+fn test_chunk_errors() {
+    let mut decoder = ChunkDecoder::new();
+    decoder.feed(&[2, 0, 0, 0, 0, 0]);
+    assert!(matches!(decoder.next_ido(), Err(CodecError::InvalidChunk)));
+
+    let mut wire = Vec::new();
+    write_chunked(&mut wire, &large(), 64).unwrap();
+    let mut decoder = ChunkDecoder::new().with_max_len(200);
+    decoder.feed(&wire);
+    assert!(matches!(decoder.next_ido(), Err(CodecError::FrameTooLarge(_))));
+
+    let truncated = &wire[..wire.len() - 1];
+    assert!(matches!(read_chunked(&mut &truncated[..]), Err(CodecError::Io(_))));
+}
+
+#[test]
+#[should_panic(expected = "chunk size 0 is out of range")]
+fn test_zero_chunk_size() {
+    let _ = write_chunked(&mut Vec::new(), &Ido::new(), 0);
+}
@@ -0,0 +1,178 @@
+use std::cmp::Ordering;
+use std::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{TimeZone, Utc};
+use ido::{Ido, IdoItem, IdoItemType};
+
+fn integer(value: i64) -> IdoItem {
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::INTEGER;
+    item.m_integer = value;
+    item
+}
+
+fn integer128(value: i128) -> IdoItem {
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::INTEGER128;
+    item.m_integer128 = value;
+    item
+}
+
+fn float(value: f64) -> IdoItem {
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::FLOAT;
+    item.m_float = value;
+    item
+}
+
+fn string(value: &str) -> IdoItem {
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::STRING;
+    item.m_string = value.to_string();
+    item
+}
+
+fn array(values: &[i64]) -> IdoItem {
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::ARRAY;
+    item.m_array = values
+        .iter()
+        .map(|value| {
+            let mut ido = Ido::new();
+            ido.set_integer(&1, *value);
+            ido
+        })
+        .collect();
+    item
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_cmp_same_type() {
+    assert!(integer(1) < integer(2));
+    assert!(string("abc") < string("abd"));
+    assert!(float(-0.5) < float(0.25));
+    assert!(string("abc") == string("abc"));
+
+    let mut early = IdoItem::new();
+    early.m_type = IdoItemType::DATETIME;
+    early.m_datetime = Utc.with_ymd_and_hms(2023, 7, 1, 12, 30, 0).unwrap();
+    let mut late = early.clone();
+    late.m_datetime = early.m_datetime + chrono::Duration::seconds(1);
+    assert!(early < late);
+}
+
+#[test]
+fn test_cmp_ignores_key() {
+    let mut other = integer(5);
+    other.m_key = 42;
+    assert!(integer(5) == other);
+}
+
+#[test]
+fn test_cmp_numeric_cross_type() {
+    assert!(integer(3) == integer128(3));
+    assert!(integer(-1) < integer128(i128::MAX));
+    assert!(integer(2) == float(2.0));
+    assert!(integer(2) < float(2.5));
+    assert!(float(-2.5) < integer(-2));
+    assert!(integer128(i128::MAX) < float(1e39));
+    assert!(integer128(i128::MIN) > float(-1e39));
+
+    // 2^53 + 1 rounds to 2^53 as a float, yet is still greater.
+    assert!(integer((1 << 53) + 1) > float((1u64 << 53) as f64));
+}
+
+#[test]
+fn test_cmp_unordered() {
+    assert_eq!(integer(1).partial_cmp(&string("1")), None);
+    assert_eq!(float(f64::NAN).partial_cmp(&float(1.0)), None);
+    assert_eq!(integer(1).partial_cmp(&float(f64::NAN)), None);
+    assert!(float(f64::NAN) != float(f64::NAN));
+}
+
+#[test]
+fn test_cmp_arrays() {
+    assert!(array(&[1, 2]) == array(&[1, 2]));
+    assert_eq!(array(&[1]).partial_cmp(&array(&[1, 2])), Some(Ordering::Less));
+    assert_eq!(array(&[1, 2]).partial_cmp(&array(&[1, 3])), None);
+}
+
+#[test]
+fn test_cmp_sort_items() {
+    let mut items = vec![integer(7), float(2.5), integer128(-4), integer(3)];
+    items.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    assert!(items == vec![integer128(-4), float(2.5), integer(3), integer(7)]);
+}
+
+#[test]
+fn test_ido_eq() {
+    let mut first = Ido::new();
+    first.set_integer(&1, 10);
+    first.set_string(&2, "abc".to_string());
+
+    let mut second = Ido::new();
+    second.set_string(&2, "abc".to_string());
+    second.set_integer(&1, 10);
+    assert!(first == second);
+
+    second.set_integer(&3, 1);
+    assert!(first != second);
+
+    second.set_with_ttl(&3, integer(1), Duration::ZERO);
+    assert!(first == second);
+
+    second.set_integer(&1, 11);
+    assert!(first != second);
+}
+
+#[test]
+fn test_eq_on() {
+    let mut a = Ido::new();
+    a.set_string(&1, "ORDER-1".to_string());
+    a.set_integer(&2, 100);
+    a.set_integer(&60, 1);
+
+    let mut b = Ido::new();
+    b.set_f64(&2, 100.0);
+    b.set_string(&1, "ORDER-1".to_string());
+    b.set_integer(&60, 2);
+    b.set_with_ttl(&3, IdoItem::new(), Duration::ZERO);
+
+    assert!(a.eq_on(&b, &[1, 2]));
+    assert!(a.eq_on(&b, &[1, 2, 3, 4]));
+    assert!(a.eq_on(&b, &[]));
+    assert!(!a.eq_on(&b, &[1, 60]));
+
+    b.delete_item(&2);
+    assert!(!a.eq_on(&b, &[2]));
+}
+
+#[test]
+fn test_eq_on_deep() {
+    let fill = |quantity: i64, time: i64| {
+        let mut ido = Ido::new();
+        ido.set_integer(&2, quantity);
+        ido.set_integer(&60, time);
+        ido
+    };
+
+    let mut a = Ido::new();
+    a.append_array(&10, fill(100, 1));
+    a.append_array(&10, fill(50, 2));
+
+    let mut b = Ido::new();
+    b.append_array(&10, fill(100, 3));
+    b.append_array(&10, fill(50, 4));
+
+    assert!(!a.eq_on(&b, &[10]));
+    assert!(a.eq_on_deep(&b, &[2, 10]));
+    assert!(!a.eq_on_deep(&b, &[2, 10, 60]));
+    // Without 2 in the keys, elements are compared on nothing at all.
+    assert!(a.eq_on_deep(&b, &[10]));
+
+    b.append_array(&10, fill(10, 5));
+    assert!(!a.eq_on_deep(&b, &[2, 10]));
+}
@@ -0,0 +1,434 @@
+#[cfg(feature = "chrono")]
+use chrono::{TimeZone, Utc};
+use ido::codec::{read_frame, write_frame, BinaryCodec, CodecError, Decode, Encode, FrameDecoder, DEFAULT_MAX_DEPTH, MAX_FRAME_LEN};
+use ido::{Ido, IdoItem, IdoItemType, IdoKeyT, IdoLimits};
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_encode_decode_roundtrip() {
+    let mut nested = Ido::new();
+    nested.set_string(&100, String::from("nested"));
+
+    let mut datetime = IdoItem::new();
+    datetime.m_type = IdoItemType::DATETIME;
+    datetime.m_datetime = Utc.with_ymd_and_hms(2023, 7, 1, 12, 30, 0).unwrap();
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, String::from("value1"));
+    ido.set_integer(&2, -42);
+    ido.set_f64(&3, 2.5);
+    ido.set_item(&4, datetime.clone());
+    ido.append_array(&5, nested.clone());
+    ido.append_array(&5, nested);
+    ido.set_i128(&6, i128::MIN);
+    ido.set_bool(&7, true);
+
+    let decoded = Ido::decode(&ido.encode()).unwrap();
+
+    assert_eq!(decoded.size(), 7);
+    assert_eq!(decoded.get_string(&1), Some(String::from("value1")));
+    assert_eq!(decoded.get_i64(&2), Some(-42));
+    assert_eq!(decoded.get_f64(&3), Some(2.5));
+    assert_eq!(decoded.get_item(&4).unwrap().m_datetime, datetime.m_datetime);
+    assert_eq!(decoded.get_item(&5).unwrap().m_array.len(), 2);
+    assert_eq!(decoded.get_i128(&6), Some(i128::MIN));
+    assert_eq!(decoded.get_bool(&7), Some(true));
+}
+
+#[test]
+fn test_encode_preserves_order_after_overwrite() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 1);
+    ido.set_integer(&2, 2);
+    ido.set_integer(&1, 3);
+
+    let decoded = Ido::decode(&ido.encode()).unwrap();
+
+    assert_eq!(decoded.to_string(), "2=2,1=3");
+}
+
+#[test]
+fn test_codec_traits() {
+    let mut ido = Ido::new();
+    ido.set_integer(&7, 7);
+
+    let mut buf = Vec::new();
+    BinaryCodec.encode(&ido, &mut buf).unwrap();
+
+    assert_eq!(BinaryCodec.decode(&buf).unwrap().get_i64(&7), Some(7));
+}
+
+#[test]
+fn test_decode_errors() {
+    let mut ido = Ido::new();
+    ido.set_string(&1, String::from("value1"));
+    let bytes = ido.encode();
+
+    assert!(matches!(Ido::decode(&bytes[..bytes.len() - 1]), Err(CodecError::UnexpectedEof)));
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(Ido::decode(&trailing), Err(CodecError::TrailingBytes(1))));
+
+    let mut bad_tag = bytes;
+    bad_tag[5] = 99;
+    assert!(matches!(Ido::decode(&bad_tag), Err(CodecError::UnknownTypeTag(99))));
+}
+
+#[test]
+fn test_varint_keys() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 1);
+    assert_eq!(ido.encode(), [1, 0, 0, 0, 2, 2, 1, 0, 0, 0, 0, 0, 0, 0]);
+
+    // Zigzag encoding: -1 is 1, 63 is 126, -65 and 64 need a second byte.
+    for (key, len) in [(-1, 1), (63, 1), (64, 2), (-65, 2), (-2_147_483_648, 5), (2_147_483_647, 5)] {
+        let mut ido = Ido::new();
+        ido.set_bool(&key, true);
+        let bytes = ido.encode();
+        assert_eq!(bytes.len(), 4 + len + 2, "key {}", key);
+        assert_eq!(ido.encoded_len(), bytes.len());
+        assert_eq!(Ido::decode(&bytes).unwrap().get_bool(&key), Some(true));
+    }
+
+    let overlong = [1, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 7, 1];
+    assert!(matches!(Ido::decode(&overlong), Err(CodecError::InvalidKey)));
+    // 2^32, zigzag-encoded.
+    let wide = [1, 0, 0, 0, 0x80, 0x80, 0x80, 0x80, 0x20, 7, 1];
+    match Ido::decode(&wide) {
+        #[cfg(feature = "wide-keys")]
+        Ok(ido) => assert_eq!(ido.get_bool(&(1 << 32)), Some(true)),
+        #[cfg(not(feature = "wide-keys"))]
+        Err(CodecError::InvalidKey) => {}
+        _ => panic!("unexpected result for a key of 2^32"),
+    }
+}
+
+#[test]
+fn test_frames() {
+    let mut first = Ido::new();
+    first.set_integer(&1, 1);
+    let mut second = Ido::new();
+    second.set_integer(&1, 2);
+
+    let mut wire = Vec::new();
+    write_frame(&mut wire, &first).unwrap();
+    write_frame(&mut wire, &second).unwrap();
+
+    let mut reader = wire.as_slice();
+    assert_eq!(read_frame(&mut reader).unwrap().get_i64(&1), Some(1));
+    assert_eq!(read_frame(&mut reader).unwrap().get_i64(&1), Some(2));
+    assert!(matches!(read_frame(&mut reader), Err(CodecError::Io(_))));
+}
+
+#[test]
+fn test_decode_with_limits() {
+    let mut leaf = Ido::new();
+    leaf.set_integer(&1, 1);
+    let mut nested = Ido::new();
+    nested.append_array(&2, leaf.clone());
+    nested.append_array(&2, leaf);
+    let mut ido = Ido::new();
+    ido.append_array(&3, nested);
+    ido.set_item(&4, {
+        let mut empty = IdoItem::new();
+        empty.m_type = IdoItemType::ARRAY;
+        empty
+    });
+    let bytes = ido.encode();
+
+    let unbounded = Ido::decode_with_limits(&bytes, IdoLimits::default()).unwrap();
+    assert_eq!(unbounded.depth(), 2);
+
+    let limits = IdoLimits { max_depth: Some(2), max_array_len: Some(2), ..IdoLimits::default() };
+    let decoded = Ido::decode_with_limits(&bytes, limits).unwrap();
+    assert_eq!(decoded.limits(), limits);
+
+    let shallow = IdoLimits { max_depth: Some(1), ..IdoLimits::default() };
+    assert!(matches!(Ido::decode_with_limits(&bytes, shallow), Err(CodecError::DepthExceeded(2))));
+
+    let narrow = IdoLimits { max_array_len: Some(1), ..IdoLimits::default() };
+    assert!(matches!(Ido::decode_with_limits(&bytes, narrow), Err(CodecError::ArrayTooLong(2))));
+
+    // Empty arrays do not add depth.
+    let flat = IdoLimits { max_depth: Some(0), ..IdoLimits::default() };
+    assert!(Ido::decode_with_limits(&Ido::new().encode(), flat).is_ok());
+    assert!(matches!(Ido::decode_with_limits(&bytes, flat), Err(CodecError::DepthExceeded(3))));
+}
+
+/// Encodes `depth` arrays nested in one another under key 0, built by hand
+/// so the test does not need an Ido that deep.
+fn nested_arrays(depth: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(4 + 14 * depth);
+    for level in (1..=depth).rev() {
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&[0, 5]);
+        bytes.extend_from_slice(&(8 + 14 * (level as u32 - 1)).to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+    }
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn test_decode_bounds_depth_by_default() {
+    let deepest = Ido::decode(&nested_arrays(DEFAULT_MAX_DEPTH)).unwrap();
+    assert_eq!(deepest.depth(), DEFAULT_MAX_DEPTH);
+    assert!(matches!(Ido::decode(&nested_arrays(DEFAULT_MAX_DEPTH + 1)), Err(CodecError::DepthExceeded(0))));
+
+    // Deep enough to overflow the stack if it were decoded recursively.
+    let hostile = nested_arrays(1_000_000);
+    assert!(hostile.len() <= MAX_FRAME_LEN);
+    assert!(matches!(Ido::decode(&hostile), Err(CodecError::DepthExceeded(0))));
+    assert!(matches!(
+        Ido::decode_with_limits(&hostile, IdoLimits::default()),
+        Err(CodecError::DepthExceeded(0))
+    ));
+
+    let mut frame = (hostile.len() as u32).to_le_bytes().to_vec();
+    frame.extend_from_slice(&hostile);
+    assert!(matches!(read_frame(&mut frame.as_slice()), Err(CodecError::DepthExceeded(0))));
+    let mut decoder = FrameDecoder::new();
+    decoder.feed(&frame);
+    assert!(matches!(decoder.next_frame(), Err(CodecError::DepthExceeded(0))));
+
+    let deeper = IdoLimits { max_depth: Some(DEFAULT_MAX_DEPTH + 1), ..IdoLimits::default() };
+    assert!(Ido::decode_with_limits(&nested_arrays(DEFAULT_MAX_DEPTH + 1), deeper).is_ok());
+}
+
+#[test]
+fn test_decode_with_memory_limit() {
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 7);
+    ido.append_array(&2, leg);
+    let bytes = ido.encode();
+
+    let decoded = Ido::decode(&bytes).unwrap();
+    assert_eq!(decoded.memory_used(), ido.memory_used());
+
+    let exact = IdoLimits { max_bytes: Some(ido.memory_used()), ..IdoLimits::default() };
+    assert_eq!(Ido::decode_with_limits(&bytes, exact).unwrap().limits(), exact);
+
+    let tight = IdoLimits { max_bytes: Some(ido.memory_used() - 1), ..IdoLimits::default() };
+    assert!(matches!(Ido::decode_with_limits(&bytes, tight), Err(CodecError::MemoryExceeded(2))));
+}
+
+#[test]
+fn test_canonical_bytes() {
+    let mut leg = Ido::new();
+    leg.set_i128(&2, 7);
+    leg.set_f64(&1, -0.0);
+
+    let mut a = Ido::new();
+    a.set_string(&3, "NYSE".to_string());
+    a.set_f64(&1, 1.5);
+    a.append_array(&2, leg);
+    a.set_f64(&4, f64::NAN);
+    a.set_i128(&5, i128::MAX);
+    a.set_f64(&6, 1e30);
+
+    let mut leg = Ido::new();
+    leg.set_integer(&1, 0);
+    leg.set_integer(&2, 7);
+
+    let mut b = Ido::new();
+    b.set_f64(&6, 1e30);
+    b.set_i128(&5, i128::MAX);
+    b.set_f64(&4, -f64::NAN);
+    b.append_array(&2, leg);
+    b.set_f64(&1, 1.5);
+    b.set_string(&3, "NYSE".to_string());
+    b.set_with_ttl(&7, IdoItem::new(), std::time::Duration::ZERO);
+
+    assert_eq!(a.canonical_bytes(), b.canonical_bytes());
+    assert_ne!(a.encode(), b.encode());
+
+    let decoded = Ido::decode(&a.canonical_bytes()).unwrap();
+    assert_eq!(decoded.get_i128(&6), Some(1_000_000_000_000_000_019_884_624_838_656));
+    assert_eq!(decoded.get_item(&2).unwrap().m_array[0].get_i64(&1), Some(0));
+
+    b.set_f64(&1, 1.25);
+    assert_ne!(a.canonical_bytes(), b.canonical_bytes());
+}
+
+fn nested_sample() -> Ido {
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+    leg.set_f64(&2, 1.0825);
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.set_string(&2, String::new());
+    ido.append_array(&3, leg.clone());
+    ido.append_array(&3, leg);
+    ido.set_i128(&4, -7);
+    #[cfg(feature = "chrono")]
+    ido.set_item(&5, {
+        let mut stamp = IdoItem::new();
+        stamp.m_type = IdoItemType::DATETIME;
+        stamp.m_datetime = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        stamp
+    });
+    #[cfg(not(feature = "chrono"))]
+    ido.set_bool(&5, true);
+    ido
+}
+
+#[test]
+fn test_encode_into() {
+    let ido = nested_sample();
+    let expected = ido.encode();
+    assert_eq!(ido.encoded_len(), expected.len());
+
+    let mut buf = vec![0xaa; expected.len() + 3];
+    assert_eq!(ido.encode_into(&mut buf).unwrap(), expected.len());
+    assert_eq!(&buf[..expected.len()], expected.as_slice());
+    assert_eq!(&buf[expected.len()..], [0xaa; 3]);
+
+    let mut short = vec![0u8; expected.len() - 1];
+    assert!(matches!(ido.encode_into(&mut short), Err(CodecError::BufferTooSmall(n)) if n == expected.len()));
+    assert!(short.iter().all(|byte| *byte == 0));
+}
+
+/// Accepts at most three bytes of the first slice per call.
+struct Trickle(Vec<u8>);
+
+impl std::io::Write for Trickle {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(3);
+        self.0.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_encode_vectored() {
+    let ido = nested_sample();
+    let vectored = ido.encode_vectored();
+    assert_eq!(vectored.len(), ido.encoded_len());
+    assert!(!vectored.is_empty());
+
+    let slices = vectored.io_slices();
+    assert_eq!(slices.iter().map(|slice| slice.len()).sum::<usize>(), vectored.len());
+    // String values get slices of their own.
+    assert!(slices.iter().any(|slice| slice[..] == *b"ORDER-1"));
+    assert_eq!(slices.iter().filter(|slice| slice[..] == *b"EURUSD").count(), 2);
+
+    let mut trickle = Trickle(Vec::new());
+    vectored.write_to(&mut trickle).unwrap();
+    assert_eq!(trickle.0, ido.encode());
+}
+
+/// Returns at most one byte per call.
+struct OneByte<'a>(&'a [u8]);
+
+impl std::io::Read for OneByte<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match (self.0.split_first(), buf.first_mut()) {
+            (Some((byte, rest)), Some(slot)) => {
+                *slot = *byte;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn test_encode_to_decode_from() {
+    let ido = nested_sample();
+    let mut streamed = Vec::new();
+    ido.encode_to(&mut streamed).unwrap();
+
+    let mut framed = Vec::new();
+    write_frame(&mut framed, &ido).unwrap();
+    assert_eq!(streamed, framed);
+
+    let mut trickle = Trickle(Vec::new());
+    ido.encode_to(&mut trickle).unwrap();
+    assert_eq!(trickle.0, framed);
+
+    let mut reader = OneByte(&streamed);
+    let decoded = Ido::decode_from(&mut reader).unwrap();
+    assert_eq!(decoded.encode(), ido.encode());
+    assert!(Ido::decode_from(&mut reader).is_err());
+}
+
+#[test]
+fn test_frame_decoder() {
+    let first = nested_sample();
+    let mut second = Ido::new();
+    second.set_integer(&1, 2);
+
+    let mut wire = Vec::new();
+    first.encode_to(&mut wire).unwrap();
+    second.encode_to(&mut wire).unwrap();
+
+    let mut decoder = FrameDecoder::new();
+    let mut decoded = Vec::new();
+    for byte in &wire {
+        decoder.feed(std::slice::from_ref(byte));
+        while let Some(ido) = decoder.next_frame().unwrap() {
+            decoded.push(ido);
+        }
+    }
+    assert_eq!(decoded.len(), 2);
+    assert_eq!(decoded[0].encode(), first.encode());
+    assert_eq!(decoded[1].get_i64(&1), Some(2));
+    assert_eq!(decoder.buffered(), 0);
+
+    let mut reader = OneByte(&wire);
+    let mut frames = 0;
+    while decoder.read_from(&mut reader).unwrap() > 0 {
+        frames += usize::from(decoder.next_frame().unwrap().is_some());
+    }
+    assert_eq!(frames, 2);
+
+    let mut decoder = FrameDecoder::new();
+    decoder.feed(&((MAX_FRAME_LEN + 1) as u32).to_le_bytes());
+    assert!(matches!(decoder.next_frame(), Err(CodecError::FrameTooLarge(_))));
+}
+
+#[test]
+fn test_decode_keys() {
+    let ido = nested_sample();
+    let mut bytes = ido.encode();
+
+    let decoded = Ido::decode_keys(&bytes, &[3, 4, 99]).unwrap();
+    assert_eq!(decoded.size(), 2);
+    assert_eq!(decoded.get_i128(&4), Some(-7));
+    assert_eq!(decoded.get_item(&3).unwrap().m_array.len(), 2);
+    assert!(!decoded.contains(&1));
+    assert_eq!(Ido::decode_keys(&bytes, &[]).unwrap().size(), 0);
+
+    // Order of the selected keys follows the buffer.
+    let keys: Vec<IdoKeyT> = Ido::decode_keys(&bytes, &[5, 1]).unwrap().into_iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, [1, 5]);
+
+    // The string under 1 becomes invalid UTF-8; only decoding it fails.
+    let at = bytes.windows(7).position(|window| window == b"ORDER-1").unwrap();
+    bytes[at] = 0xff;
+    assert!(Ido::decode_keys(&bytes, &[4]).is_ok());
+    assert!(matches!(Ido::decode_keys(&bytes, &[1]), Err(CodecError::InvalidUtf8)));
+
+    bytes.push(0);
+    assert!(matches!(Ido::decode_keys(&bytes, &[4]), Err(CodecError::TrailingBytes(1))));
+    assert!(matches!(Ido::decode_keys(&bytes[..10], &[4]), Err(CodecError::UnexpectedEof)));
+}
+
+#[test]
+fn test_decode_invalid_bool() {
+    let mut ido = Ido::new();
+    ido.set_bool(&3, false);
+    let mut bytes = ido.encode();
+    *bytes.last_mut().unwrap() = 2;
+
+    assert!(matches!(Ido::decode(&bytes), Err(CodecError::InvalidValue(3))));
+}
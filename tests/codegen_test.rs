@@ -0,0 +1,68 @@
+#![cfg(feature = "codegen")]
+
+use chrono::Utc;
+use ido::codegen::{generate, CodegenError};
+
+#[allow(dead_code)]
+mod generated {
+    include!("data/messages_generated.rs");
+}
+
+use generated::{Leg, Order};
+
+#[test]
+fn test_generate_matches_checked_in_output() {
+    let code = generate(include_str!("data/messages.json")).unwrap();
+    assert_eq!(code, include_str!("data/messages_generated.rs"));
+}
+
+#[test]
+fn test_generated_accessors() {
+    let now = Utc::now();
+    let mut leg = Leg::new();
+    leg.set_symbol("EURUSD".to_string());
+
+    let mut order = Order::new();
+    order.set_order_id("ORDER-1".to_string());
+    order.set_qty(100);
+    order.set_sent_at(now);
+    order.push_legs(leg);
+
+    assert_eq!(order.order_id(), Some("ORDER-1".to_string()));
+    assert_eq!(order.qty(), Some(100));
+    assert_eq!(order.sent_at(), Some(now));
+    assert_eq!(order.legs()[0].symbol(), Some("EURUSD".to_string()));
+    assert_eq!(order.legs()[0].ratio(), None);
+    assert_eq!(Order::LEGS, 4);
+    assert!(order.as_ido().validate(&Order::schema()).is_ok());
+
+    let ido = order.into_ido();
+    assert!(Order::from_ido(ido).legs().len() == 1);
+    assert!(Order::new().as_ido().validate(&Order::schema()).is_err());
+}
+
+#[test]
+fn test_generate_errors() {
+    let invalid = |json: &str| match generate(json) {
+        Err(CodegenError::Invalid(reason)) => reason,
+        _ => panic!("expected an invalid catalogue"),
+    };
+
+    assert_eq!(
+        invalid(r#"{"messages": [{"name": "A", "fields": [{"name": "x", "key": 1, "type": "BLOB"}]}]}"#),
+        "field x has unknown type BLOB"
+    );
+    assert_eq!(
+        invalid(r#"{"messages": [{"name": "A", "fields": [{"name": "x", "key": 1, "type": "ARRAY", "of": "B"}]}]}"#),
+        "field x refers to unknown message B"
+    );
+    assert_eq!(
+        invalid(
+            r#"{"messages": [{"name": "A", "fields": [
+                {"name": "x", "key": 1, "type": "STRING"}, {"name": "y", "key": 1, "type": "STRING"}]}]}"#
+        ),
+        "field y of A reuses a key or name"
+    );
+    assert_eq!(invalid(r#"{"messages": [{"name": "my-type", "fields": []}]}"#), "message name \"my-type\" is not an identifier");
+    assert!(matches!(generate(r#"{"messages": 1}"#), Err(CodegenError::Parse(_))));
+}
@@ -0,0 +1,109 @@
+#[cfg(feature = "chrono")]
+use chrono::DateTime;
+use ido::codec::{CodecError, Decode, Encode};
+use ido::compat::{CppCodec, TypeTags};
+use ido::Ido;
+#[cfg(feature = "chrono")]
+use ido::compat::Endianness;
+#[cfg(feature = "chrono")]
+use ido::{IdoItem, IdoItemType};
+
+fn encode(codec: &CppCodec, ido: &Ido) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    codec.encode(ido, &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn test_compat_little_endian_layout() {
+    let mut ido = Ido::new();
+    ido.set_string(&7, "ab".to_string());
+
+    assert_eq!(
+        encode(&CppCodec::new(), &ido),
+        [1, 0, 0, 0, 7, 0, 0, 0, 0, 2, 0, 0, 0, b'a', b'b']
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_compat_decode_big_endian_with_custom_tags() {
+    let codec = CppCodec::new()
+        .endianness(Endianness::Big)
+        .tags(TypeTags { string: b'S', integer: b'I', float: b'F', datetime: b'D', array: b'A' });
+
+    #[rustfmt::skip]
+    let bytes = [
+        0, 0, 0, 2,
+        0, 0, 0, 1, b'D', 0, 0, 0, 0, 0, 0, 0, 3,
+        0, 0, 0, 2, b'A', 0, 0, 0, 1,
+            0, 0, 0, 1,
+            0, 0, 0, 9, b'I', 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+    ];
+
+    let ido = codec.decode(&bytes).unwrap();
+    assert_eq!(ido.get_item(&1).unwrap().m_datetime, DateTime::from_timestamp_micros(3).unwrap());
+
+    let legs = ido.get_item(&2).unwrap().m_array;
+    assert_eq!(legs.len(), 1);
+    assert_eq!(legs[0].get_i64(&9), Some(-2));
+
+    assert_eq!(encode(&codec, &ido), bytes);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_compat_round_trip() {
+    let mut leg = Ido::new();
+    leg.set_f64(&1, 1.25);
+
+    let mut stamp = IdoItem::new();
+    stamp.m_type = IdoItemType::DATETIME;
+    stamp.m_datetime = DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap();
+
+    let expected = stamp.m_datetime;
+
+    let mut ido = Ido::new();
+    ido.set_string(&3, "EURUSD".to_string());
+    ido.set_item(&1, stamp);
+    ido.append_array(&2, leg.clone());
+    ido.append_array(&2, leg);
+
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let codec = CppCodec::new().endianness(endianness);
+        let decoded = codec.decode(&encode(&codec, &ido)).unwrap();
+        assert_eq!(decoded.get_string(&3), Some("EURUSD".to_string()));
+        assert_eq!(decoded.get_item(&1).unwrap().m_datetime, expected);
+        assert_eq!(decoded.get_item(&2).unwrap().m_array.len(), 2);
+        assert_eq!(decoded.get_item(&2).unwrap().m_array[1].get_f64(&1), Some(1.25));
+    }
+}
+
+#[test]
+fn test_compat_decode_errors() {
+    let codec = CppCodec::new();
+
+    assert!(matches!(codec.decode(&[1, 0, 0, 0, 1, 0, 0, 0, 9]), Err(CodecError::UnknownTypeTag(9))));
+    assert!(matches!(codec.decode(&[1, 0, 0, 0, 1, 0, 0, 0, 1, 0]), Err(CodecError::UnexpectedEof)));
+    assert!(matches!(codec.decode(&[0, 0, 0, 0, 0]), Err(CodecError::TrailingBytes(1))));
+}
+
+#[test]
+#[should_panic(expected = "type tags must be distinct")]
+fn test_compat_duplicate_tags() {
+    let _ = CppCodec::new().tags(TypeTags { string: 1, integer: 1, float: 2, datetime: 3, array: 4 });
+}
+
+#[test]
+fn test_compat_integer128() {
+    let codec = CppCodec::new();
+
+    let mut ido = Ido::new();
+    ido.set_i128(&1, -5);
+    assert_eq!(codec.decode(&encode(&codec, &ido)).unwrap().get_i64(&1), Some(-5));
+
+    ido.set_i128(&2, i128::from(i64::MAX) + 1);
+    let mut bytes = vec![9];
+    assert!(matches!(codec.encode(&ido, &mut bytes), Err(CodecError::Unsupported(2))));
+    assert_eq!(bytes, [9]);
+}
@@ -0,0 +1,162 @@
+use std::fs;
+use std::path::PathBuf;
+
+#[cfg(feature = "chrono")]
+use chrono::DateTime;
+use ido::config::ConfigError;
+use ido::dictionary::IdoDictionary;
+use ido::{Ido, IdoItemType};
+
+fn config_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("ido-config-{}-{}.conf", name, std::process::id()))
+}
+
+#[test]
+fn test_from_env() {
+    std::env::set_var("IDO_TEST_ENV_RATE", "1.25");
+    std::env::set_var("IDO_TEST_ENV_START", "2024-01-02T03:04:05Z");
+    std::env::set_var("IDO_TEST_ENV_ACCOUNT", "\"0042\"");
+
+    let key_map: IdoDictionary = [(1, "rate"), (2, "start"), (3, "account"), (4, "missing")].into_iter().collect();
+    let ido = Ido::from_env("IDO_TEST_ENV_", &key_map).unwrap();
+
+    assert_eq!(ido.size(), 3);
+    assert_eq!(ido.get_f64(&1), Some(1.25));
+    #[cfg(feature = "chrono")]
+    assert_eq!(
+        ido.get_item(&2).unwrap().m_datetime,
+        DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z").unwrap()
+    );
+    assert_eq!(ido.get_string(&3), Some("0042".to_string()));
+}
+
+#[test]
+fn test_from_config_file() {
+    let path = config_path("valid");
+    fs::write(&path, "# gateway\n1 = localhost\n\n2=9000\n3 = inf\n4 = -1e3\n2 = 9001\n").unwrap();
+
+    let ido = Ido::from_config_file(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(ido.get_string(&1), Some("localhost".to_string()));
+    assert_eq!(ido.get_i64(&2), Some(9001));
+    assert!(ido.is_type(&3, &IdoItemType::STRING));
+    assert_eq!(ido.get_f64(&4), Some(-1000.0));
+}
+
+#[test]
+fn test_from_config_file_errors() {
+    let path = config_path("invalid");
+    fs::write(&path, "1 = ok\nport = 9000\n").unwrap();
+
+    let result = Ido::from_config_file(&path);
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(ConfigError::Parse { line: 2, .. })));
+
+    assert!(matches!(Ido::from_config_file(config_path("absent")), Err(ConfigError::Io(_))));
+}
+
+#[test]
+fn test_from_toml() {
+    let toml = r#"
+# gateway
+name = "gw-1"          # trailing comment
+ports = [ 9000, 0x2329,
+          9_002, ]     # multi-line array
+ratio = 1.5e-3
+"quoted key" = 'C:\path'
+enabled = true
+started = 2024-01-02 03:04:05Z
+opened = 2024-01-02
+banner = """
+line one \
+  still one
+line two"""
+
+[limits]
+max.depth = 4
+buckets = [[1, 2], []]
+
+[[route]]
+name = "primary"
+weight = { value = -inf }
+
+[[route]]
+name = "backup"
+
+[route.retry]
+count = 3
+"#;
+    let key_map: IdoDictionary = [
+        (1, "name"),
+        (2, "ports"),
+        (3, "ratio"),
+        (4, "quoted key"),
+        (5, "enabled"),
+        (6, "started"),
+        (7, "opened"),
+        (8, "banner"),
+        (10, "limits"),
+        (11, "max"),
+        (12, "depth"),
+        (13, "buckets"),
+        (20, "route"),
+        (21, "weight"),
+        (22, "value"),
+        (23, "retry"),
+        (24, "count"),
+    ]
+    .into_iter()
+    .collect();
+    let ido = Ido::from_toml(toml, &key_map).unwrap();
+
+    assert_eq!(ido.get_string(&1), Some("gw-1".to_string()));
+    let ports: Vec<_> = ido.get_item(&2).unwrap().m_array.iter().map(|port| port.get_i64(&0)).collect();
+    assert_eq!(ports, [Some(9000), Some(9001), Some(9002)]);
+    assert_eq!(ido.get_f64(&3), Some(0.0015));
+    assert_eq!(ido.get_string(&4), Some("C:\\path".to_string()));
+    assert_eq!(ido.get_bool(&5), Some(true));
+    #[cfg(feature = "chrono")]
+    assert_eq!(
+        ido.get_item(&6).unwrap().m_datetime,
+        DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z").unwrap()
+    );
+    assert_eq!(ido.get_string(&7), Some("2024-01-02".to_string()));
+    assert_eq!(ido.get_string(&8), Some("line one still one\nline two".to_string()));
+
+    let limits = &ido.get_item(&10).unwrap().m_array[0];
+    assert_eq!(limits.get_item(&11).unwrap().m_array[0].get_i64(&12), Some(4));
+    let buckets = limits.get_item(&13).unwrap().m_array;
+    assert_eq!(buckets[0].get_item(&0).unwrap().m_array.len(), 2);
+    assert!(buckets[1].get_item(&0).unwrap().m_array.is_empty());
+
+    let routes = ido.get_item(&20).unwrap().m_array;
+    assert_eq!(routes.len(), 2);
+    assert_eq!(routes[0].get_item(&21).unwrap().m_array[0].get_f64(&22), Some(f64::NEG_INFINITY));
+    assert_eq!(routes[1].get_string(&1), Some("backup".to_string()));
+    assert_eq!(routes[1].get_item(&23).unwrap().m_array[0].get_i64(&24), Some(3));
+
+    let numeric = Ido::from_toml("1 = 2\n[3]\n4 = 'x'\n", &IdoDictionary::new()).unwrap();
+    assert_eq!(numeric.get_i64(&1), Some(2));
+    assert_eq!(numeric.get_item(&3).unwrap().m_array[0].get_string(&4), Some("x".to_string()));
+}
+
+#[test]
+fn test_from_toml_errors() {
+    let line = |toml: &str| match Ido::from_toml(toml, &IdoDictionary::new()) {
+        Err(ConfigError::Parse { line, reason }) => Some((line, reason)),
+        _ => None,
+    };
+
+    assert_eq!(line("1 = 1\nport = 2\n"), Some((2, "unknown key port".to_string())));
+    assert_eq!(line("1 = 1\n1 = 2\n"), Some((2, "duplicate key 1".to_string())));
+    assert_eq!(line("[1]\n[1]\n"), Some((2, "table 1 is defined twice".to_string())));
+    assert_eq!(line("1 = 1\n[[1]]\n"), Some((2, "1 is not an array of tables".to_string())));
+    assert_eq!(line("1 = \"open\n"), Some((1, "unterminated string".to_string())));
+    assert_eq!(line("1 = 2 3\n"), Some((1, "expected the end of the line".to_string())));
+    assert_eq!(line("1 = nope\n"), Some((1, "invalid value nope".to_string())));
+    assert_eq!(
+        line("1 = 99999999999999999999\n"),
+        Some((1, "integer 99999999999999999999 does not fit in 64 bits".to_string()))
+    );
+}
@@ -0,0 +1,55 @@
+use ido::conflate::IdoConflator;
+use ido::{ArrayMerge, Ido, IdoKeyT};
+
+fn tick(symbol: &str, key: IdoKeyT, value: i64) -> Ido {
+    let mut ido = Ido::new();
+    ido.set_string(&1, symbol.to_string());
+    ido.set_integer(&key, value);
+    ido
+}
+
+#[test]
+fn test_conflator_merges_pending() {
+    let mut queue = IdoConflator::new(1);
+    assert!(queue.is_empty());
+
+    queue.push(tick("A", 2, 100)).unwrap();
+    queue.push(tick("B", 2, 200)).unwrap();
+    queue.push(tick("A", 3, 5)).unwrap();
+    queue.push(tick("A", 2, 101)).unwrap();
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.conflated(), 2);
+    assert_eq!(queue.peek("A").unwrap().get_i64(&3), Some(5));
+
+    let a = queue.pop().unwrap();
+    assert_eq!(a.get_i64(&2), Some(101));
+    assert_eq!(a.get_i64(&3), Some(5));
+
+    // A new update after delivery starts a fresh entry at the back.
+    queue.push(tick("A", 2, 102)).unwrap();
+    let drained = queue.drain();
+    let symbols: Vec<String> = drained.iter().filter_map(|ido| ido.get_string(&1)).collect();
+    assert_eq!(symbols, ["B", "A"]);
+    assert_eq!(drained[1].get_i64(&3), None);
+    assert!(queue.pop().is_none());
+}
+
+#[test]
+fn test_conflator_missing_entity() {
+    let mut queue = IdoConflator::new(1);
+    let mut update = Ido::new();
+    update.set_integer(&2, 100);
+    assert_eq!(queue.push(update).unwrap_err().keys(), [1]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn test_conflator_array_merge() {
+    let mut queue = IdoConflator::new(1).array_merge(ArrayMerge::Append);
+    for _ in 0..3 {
+        let mut update = tick("A", 2, 100);
+        update.append_array(&5, tick("fill", 7, 10));
+        queue.push(update).unwrap();
+    }
+    assert_eq!(queue.pop().unwrap().get_item(&5).unwrap().m_array.len(), 3);
+}
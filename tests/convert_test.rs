@@ -0,0 +1,117 @@
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+use ido::convert::{FromIdo, FromIdoError, IdoValue, ToIdo};
+use ido::{Ido, IdoItemType, IdoKeyT};
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl ToIdo for Point {
+    fn to_ido(&self) -> Ido {
+        let mut ido = Ido::new();
+        self.x.write(&mut ido, 1);
+        self.y.write(&mut ido, 2);
+        ido
+    }
+}
+
+impl FromIdo for Point {
+    fn from_ido(ido: &Ido) -> Result<Self, FromIdoError> {
+        Ok(Point { x: i32::read(ido, 1)?, y: i32::read(ido, 2)? })
+    }
+}
+
+fn round_trip<V: IdoValue>(value: V, key: IdoKeyT) -> Result<V, FromIdoError> {
+    let mut ido = Ido::new();
+    value.write(&mut ido, key);
+    V::read(&ido, key)
+}
+
+#[test]
+fn test_scalar_values() {
+    #[cfg(feature = "chrono")]
+    let now = Utc::now();
+
+    assert_eq!(round_trip(-5i64, 1), Ok(-5));
+    assert_eq!(round_trip(200u8, 1), Ok(200));
+    assert_eq!(round_trip(i128::MAX, 1), Ok(i128::MAX));
+    assert_eq!(round_trip(1.5f64, 1), Ok(1.5));
+    assert_eq!(round_trip(0.25f32, 1), Ok(0.25));
+    assert_eq!(round_trip("abc".to_string(), 1), Ok("abc".to_string()));
+    #[cfg(feature = "chrono")]
+    assert_eq!(round_trip::<DateTime<Utc>>(now, 1), Ok(now));
+    assert_eq!(round_trip(Some(3i64), 1), Ok(Some(3)));
+    assert_eq!(round_trip(None::<i64>, 1), Ok(None));
+}
+
+#[test]
+fn test_value_errors() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 300);
+    ido.set_string(&2, "x".to_string());
+
+    assert_eq!(u8::read(&ido, 1), Err(FromIdoError::OutOfRange(1)));
+    assert_eq!(i64::read(&ido, 3), Err(FromIdoError::Missing(3)));
+    assert_eq!(
+        f64::read(&ido, 2),
+        Err(FromIdoError::WrongType { key: 2, expected: IdoItemType::FLOAT, actual: IdoItemType::STRING })
+    );
+    assert!(Option::<f64>::read(&ido, 2).is_err());
+}
+
+#[test]
+fn test_vec_values() {
+    let points = vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }];
+
+    let mut ido = Ido::new();
+    points.write(&mut ido, 5);
+    assert_eq!(ido.to_string(), "5=[{1=1,2=2},{1=3,2=4}]");
+
+    let read = Vec::<Point>::read(&ido, 5).unwrap();
+    assert_eq!(read.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>(), vec![(1, 2), (3, 4)]);
+    assert!(Vec::<Point>::read(&ido, 6).unwrap().is_empty());
+
+    let mut bad = Ido::new();
+    bad.set_integer(&1, 9);
+    ido.append_array(&5, bad);
+    assert_eq!(
+        Vec::<Point>::read(&ido, 5).err(),
+        Some(FromIdoError::Element { key: 5, index: 2, error: Box::new(FromIdoError::Missing(2)) })
+    );
+}
+
+#[test]
+fn test_generic_accessors() {
+    let mut ido = Ido::new();
+    ido.set_value(&1, 7u16);
+    ido.append_array_item(&2, &Point { x: 1, y: 2 });
+    ido.append_array_item(&2, &Point { x: 3, y: 4 });
+
+    assert_eq!(ido.get_value::<u16>(&1), Ok(7));
+    assert_eq!(ido.get_value::<String>(&1).unwrap_err().to_string(), "key 1 is INTEGER, expected STRING");
+
+    let points: Vec<Point> = ido.get_array_items(&2).unwrap();
+    assert_eq!(points.len(), 2);
+    assert_eq!(points[1].y, 4);
+    assert!(ido.get_array_items::<Point>(&1).is_err());
+}
+
+#[test]
+fn test_builtin_impls() {
+    use std::collections::BTreeMap;
+
+    let mut ido = Ido::new();
+    ido.set_integer(&3, 30);
+    ido.set_integer(&1, 10);
+    assert_eq!(Ido::from_ido(&ido).unwrap().to_string(), ido.to_string());
+
+    let map = BTreeMap::<IdoKeyT, i64>::from_ido(&ido).unwrap();
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), vec![(1, 10), (3, 30)]);
+
+    let mut map = BTreeMap::new();
+    map.insert(2, "b".to_string());
+    map.insert(1, "a".to_string());
+    assert_eq!(map.to_ido().to_string(), "1=a,2=b");
+}
@@ -0,0 +1,83 @@
+use ido::csv::CsvError;
+use ido::dictionary::IdoDictionary;
+use ido::{Ido, IdoError, IdoItemType, IdoLimits};
+
+fn key_map() -> IdoDictionary {
+    [(1, "symbol"), (2, "qty"), (3, "price"), (4, "active"), (6, "note")].into_iter().collect()
+}
+
+#[test]
+fn test_append_array_from_csv() {
+    let csv = "symbol,qty,price,active,ignored,note,7\r\n\
+               EURUSD,100,1.25,true,x,\"say \"\"hi\"\",\nthen leave\",170141183460469231731687303715884105727\r\n\
+               \r\n\
+               007,-5,2,FALSE,y,,1\r\n";
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "BATCH-1".to_string());
+    assert_eq!(ido.append_array_from_csv(&5, csv.as_bytes(), &key_map()).unwrap(), 2);
+    assert_eq!(ido.append_array_from_csv(&5, "symbol\nGBPUSD".as_bytes(), &key_map()).unwrap(), 1);
+
+    let rows = ido.get_item(&5).unwrap().m_array;
+    assert_eq!(rows.len(), 3);
+
+    assert_eq!(rows[0].get_string(&1), Some("EURUSD".to_string()));
+    assert_eq!(rows[0].get_i64(&2), Some(100));
+    assert_eq!(rows[0].get_f64(&3), Some(1.25));
+    assert_eq!(rows[0].get_bool(&4), Some(true));
+    assert_eq!(rows[0].get_string(&6), Some("say \"hi\",\nthen leave".to_string()));
+    assert_eq!(rows[0].get_i128(&7), Some(i128::MAX));
+
+    assert_eq!(rows[1].get_string(&1), Some("007".to_string()));
+    assert!(rows[1].is_type(&3, &IdoItemType::FLOAT));
+    assert_eq!(rows[1].get_f64(&3), Some(2.0));
+    assert_eq!(rows[1].get_bool(&4), Some(false));
+    assert!(rows[1].is_type(&7, &IdoItemType::INTEGER128));
+    assert!(!rows[1].contains(&6));
+    assert_eq!(rows[1].size(), 5);
+
+    assert_eq!(rows[2].get_string(&1), Some("GBPUSD".to_string()));
+}
+
+#[test]
+fn test_csv_round_trip() {
+    let mut ido = Ido::new();
+    ido.append_array_from_csv(&5, "1,2\nORDER-1,100\n\"ORDER-2, amended\",\n".as_bytes(), &IdoDictionary::new())
+        .unwrap();
+
+    let mut out = Vec::new();
+    ido.array_to_csv(&5, &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "1,2\nORDER-1,100\n\"ORDER-2, amended\",\n");
+}
+
+#[test]
+fn test_append_array_from_csv_errors() {
+    let mut ido = Ido::new();
+
+    assert_eq!(ido.append_array_from_csv(&5, "".as_bytes(), &key_map()).unwrap(), 0);
+    assert!(!ido.contains(&5));
+
+    let result = ido.append_array_from_csv(&5, "symbol,qty\nEURUSD,1\nGBPUSD\n".as_bytes(), &key_map());
+    assert!(matches!(result, Err(CsvError::Parse { line: 3, .. })));
+    assert!(!ido.contains(&5));
+
+    let result = ido.append_array_from_csv(&5, "symbol\n\"EURUSD\n".as_bytes(), &key_map());
+    assert!(matches!(result, Err(CsvError::Parse { line: 2, .. })));
+
+    ido.set_limits(IdoLimits { max_array_len: Some(1), ..IdoLimits::default() });
+    let result = ido.append_array_from_csv(&5, "symbol\nEURUSD\nGBPUSD\n".as_bytes(), &key_map());
+    assert!(matches!(result, Err(CsvError::Ido(IdoError::ArrayTooLong { .. }))));
+    assert_eq!(ido.get_item(&5).unwrap().m_array.len(), 1);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_append_array_from_csv_datetime() {
+    let mut ido = Ido::new();
+    let csv = "1,2\n2024-01-02T03:04:05Z,2024-01-02T03:04:05Z\n2024-01-03T00:00:00Z,tomorrow\n";
+    ido.append_array_from_csv(&5, csv.as_bytes(), &IdoDictionary::new()).unwrap();
+
+    let rows = ido.get_item(&5).unwrap().m_array;
+    assert!(rows[1].is_type(&1, &IdoItemType::DATETIME));
+    assert!(rows[0].is_type(&2, &IdoItemType::STRING));
+}
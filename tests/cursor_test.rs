@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use ido::{Ido, IdoItem, IdoKeyT};
+
+fn ido() -> Ido {
+    let mut ido = Ido::new();
+    ido.set_integer(&3, 30);
+    ido.set_integer(&1, 10);
+    ido.set_with_ttl(&4, IdoItem::new(), Duration::ZERO);
+    ido.set_integer(&2, 20);
+    ido
+}
+
+#[test]
+fn test_cursor_next_and_peek() {
+    let ido = ido();
+    let mut cursor = ido.cursor();
+
+    assert_eq!(cursor.peek().map(|(key, _)| key), Some(3));
+    assert_eq!(cursor.peek().map(|(key, _)| key), Some(3));
+    assert_eq!(cursor.next().map(|(_, item)| item.m_integer), Some(30));
+
+    // The expired item under 4 is skipped.
+    let rest: Vec<IdoKeyT> = cursor.by_ref().map(|(key, _)| key).collect();
+    assert_eq!(rest, [1, 2]);
+    assert!(cursor.peek().is_none());
+
+    cursor.rewind();
+    assert_eq!(cursor.count(), 3);
+}
+
+#[test]
+fn test_cursor_seek_key() {
+    let ido = ido();
+    let mut cursor = ido.cursor();
+
+    assert!(cursor.seek_key(&1));
+    assert_eq!(cursor.next().map(|(key, _)| key), Some(1));
+
+    assert!(!cursor.seek_key(&4));
+    assert!(!cursor.seek_key(&99));
+    assert_eq!(cursor.peek().map(|(key, _)| key), Some(2));
+
+    assert!(cursor.seek_key(&3));
+    let keys: Vec<IdoKeyT> = cursor.map(|(key, _)| key).collect();
+    assert_eq!(keys, [3, 1, 2]);
+}
+
+#[test]
+fn test_cursor_empty() {
+    let ido = Ido::new();
+    let mut cursor = ido.cursor();
+    assert!(cursor.peek().is_none());
+    assert!(cursor.next().is_none());
+}
+
+#[test]
+fn test_chunks() {
+    let mut ido = ido();
+    ido.set_integer(&5, 50);
+
+    let chunks: Vec<Vec<IdoKeyT>> = ido.chunks(2).map(|chunk| chunk.iter().map(|(key, _)| *key).collect()).collect();
+    assert_eq!(chunks, [vec![3, 1], vec![2, 5]]);
+
+    let chunks: Vec<usize> = ido.chunks(3).map(|chunk| chunk.len()).collect();
+    assert_eq!(chunks, [3, 1]);
+
+    assert_eq!(ido.chunks(10).count(), 1);
+    assert_eq!(Ido::new().chunks(1).count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "chunk size")]
+fn test_chunks_zero() {
+    Ido::new().chunks(0);
+}
+
+#[test]
+fn test_find_any_all() {
+    let ido = ido();
+
+    let (key, item) = ido.find(|_, item| item.m_integer < 25).unwrap();
+    assert_eq!((key, item.m_integer), (1, 10));
+
+    // The expired item under 4 is never passed to the predicate.
+    let mut seen = Vec::new();
+    assert!(ido.find(|key, _| {
+        seen.push(key);
+        false
+    })
+    .is_none());
+    assert_eq!(seen, [3, 1, 2]);
+
+    assert!(ido.any(|key, _| key == 2));
+    assert!(!ido.any(|key, _| key == 4));
+    assert!(ido.all(|_, item| item.m_integer >= 10));
+    assert!(!ido.all(|key, _| key != 1));
+
+    let empty = Ido::new();
+    assert!(!empty.any(|_, _| true));
+    assert!(empty.all(|_, _| false));
+}
+
+#[test]
+fn test_position_of() {
+    let mut ido = ido();
+    assert_eq!(ido.position_of(&3), Some(0));
+    assert_eq!(ido.position_of(&1), Some(1));
+    // The expired item under 4 is not counted.
+    assert_eq!(ido.position_of(&2), Some(2));
+    assert_eq!(ido.position_of(&4), None);
+    assert_eq!(ido.position_of(&9), None);
+
+    // Overwriting a key moves it to the end.
+    ido.set_integer(&3, 31);
+    assert_eq!(ido.position_of(&3), Some(2));
+    let positions: Vec<usize> = ido.cursor().map(|(key, _)| ido.position_of(&key).unwrap()).collect();
+    assert_eq!(positions, [0, 1, 2]);
+}
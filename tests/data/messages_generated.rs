@@ -0,0 +1,132 @@
+// @generated by ido::codegen. Do not edit.
+
+/// Typed wrapper around an Ido for the `Leg` message.
+#[derive(Clone, Default)]
+pub struct Leg {
+    m_ido: ::ido::Ido,
+}
+
+impl Leg {
+    pub const SYMBOL: ::ido::IdoKeyT = 55;
+    pub const RATIO: ::ido::IdoKeyT = 2;
+
+    /// Creates an empty message.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps an existing Ido without checking it.
+    pub fn from_ido(ido: ::ido::Ido) -> Self {
+        Leg { m_ido: ido }
+    }
+
+    /// Returns the wrapped Ido.
+    pub fn as_ido(&self) -> &::ido::Ido {
+        &self.m_ido
+    }
+
+    /// Unwraps the Ido.
+    pub fn into_ido(self) -> ::ido::Ido {
+        self.m_ido
+    }
+
+    /// Returns the schema of the message.
+    pub fn schema() -> ::ido::schema::IdoSchema {
+        ::ido::schema::IdoSchema::new()
+            .required(Self::SYMBOL, ::ido::IdoItemType::STRING)
+            .optional(Self::RATIO, ::ido::IdoItemType::FLOAT)
+    }
+
+    pub fn symbol(&self) -> Option<String> {
+        self.m_ido.get_value(&Self::SYMBOL).ok()
+    }
+
+    pub fn set_symbol(&mut self, value: String) {
+        self.m_ido.set_value(&Self::SYMBOL, value);
+    }
+
+    pub fn ratio(&self) -> Option<f64> {
+        self.m_ido.get_value(&Self::RATIO).ok()
+    }
+
+    pub fn set_ratio(&mut self, value: f64) {
+        self.m_ido.set_value(&Self::RATIO, value);
+    }
+}
+
+/// Typed wrapper around an Ido for the `Order` message.
+#[derive(Clone, Default)]
+pub struct Order {
+    m_ido: ::ido::Ido,
+}
+
+impl Order {
+    pub const ORDER_ID: ::ido::IdoKeyT = 1;
+    pub const QTY: ::ido::IdoKeyT = 2;
+    pub const SENT_AT: ::ido::IdoKeyT = 3;
+    pub const LEGS: ::ido::IdoKeyT = 4;
+
+    /// Creates an empty message.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps an existing Ido without checking it.
+    pub fn from_ido(ido: ::ido::Ido) -> Self {
+        Order { m_ido: ido }
+    }
+
+    /// Returns the wrapped Ido.
+    pub fn as_ido(&self) -> &::ido::Ido {
+        &self.m_ido
+    }
+
+    /// Unwraps the Ido.
+    pub fn into_ido(self) -> ::ido::Ido {
+        self.m_ido
+    }
+
+    /// Returns the schema of the message.
+    pub fn schema() -> ::ido::schema::IdoSchema {
+        ::ido::schema::IdoSchema::new()
+            .required(Self::ORDER_ID, ::ido::IdoItemType::STRING)
+            .required(Self::QTY, ::ido::IdoItemType::INTEGER)
+            .optional(Self::SENT_AT, ::ido::IdoItemType::DATETIME)
+            .array_of(Self::LEGS, false, Leg::schema())
+    }
+
+    pub fn order_id(&self) -> Option<String> {
+        self.m_ido.get_value(&Self::ORDER_ID).ok()
+    }
+
+    pub fn set_order_id(&mut self, value: String) {
+        self.m_ido.set_value(&Self::ORDER_ID, value);
+    }
+
+    pub fn qty(&self) -> Option<i64> {
+        self.m_ido.get_value(&Self::QTY).ok()
+    }
+
+    pub fn set_qty(&mut self, value: i64) {
+        self.m_ido.set_value(&Self::QTY, value);
+    }
+
+    pub fn sent_at(&self) -> Option<::chrono::DateTime<::chrono::Utc>> {
+        self.m_ido.get_value(&Self::SENT_AT).ok()
+    }
+
+    pub fn set_sent_at(&mut self, value: ::chrono::DateTime<::chrono::Utc>) {
+        self.m_ido.set_value(&Self::SENT_AT, value);
+    }
+
+    pub fn legs(&self) -> Vec<Leg> {
+        self.m_ido
+            .get_item(&Self::LEGS)
+            .map(|item| item.m_array.into_iter().map(Leg::from_ido).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn push_legs(&mut self, value: Leg) {
+        self.m_ido.append_array(&Self::LEGS, value.into_ido());
+    }
+}
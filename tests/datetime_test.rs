@@ -0,0 +1,55 @@
+#![cfg(feature = "chrono")]
+
+use chrono::{DateTime, TimeZone, Utc};
+use ido::datetime::{DateTimeFormat, InvalidPattern};
+use ido::dictionary::IdoDictionary;
+use ido::{Ido, IdoItem, IdoItemType};
+
+fn datetime_item(datetime: DateTime<Utc>) -> IdoItem {
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::DATETIME;
+    item.m_datetime = datetime;
+    item
+}
+
+#[test]
+fn test_datetime_formats() {
+    let stamp = Utc.with_ymd_and_hms(2023, 7, 1, 12, 30, 0).unwrap() + chrono::Duration::nanoseconds(123_456_789);
+
+    assert_eq!(DateTimeFormat::standard().format(&stamp), "2023-07-01 12:30:00.1234");
+    assert_eq!(DateTimeFormat::rfc3339().format(&stamp), "2023-07-01T12:30:00.123456789Z");
+    assert_eq!(DateTimeFormat::epoch_seconds().format(&stamp), "1688214600");
+    assert_eq!(DateTimeFormat::epoch_micros().format(&stamp), "1688214600123456");
+    assert_eq!(DateTimeFormat::epoch_nanos().format(&stamp), "1688214600123456789");
+
+    let before_epoch = DateTime::from_timestamp(-1, 500_000_000).unwrap();
+    assert_eq!(DateTimeFormat::epoch_millis().format(&before_epoch), "-500");
+}
+
+#[test]
+fn test_datetime_invalid_pattern() {
+    assert_eq!(DateTimeFormat::pattern("%Y %.4f"), Err(InvalidPattern("%Y %.4f".to_string())));
+    assert!(DateTimeFormat::pattern("%Y-%m-%dT%H:%M:%S%.3f").is_ok());
+}
+
+#[test]
+fn test_to_string_datetime_format() {
+    let stamp = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+
+    let mut nested = Ido::new();
+    nested.set_item(&1, datetime_item(stamp));
+
+    let mut ido = Ido::new();
+    ido.set_item(&1, datetime_item(stamp));
+    ido.append_array(&2, nested);
+
+    assert_eq!(ido.to_string(), "1=2024-01-02 03:04:05.0000,2=[{1=2024-01-02 03:04:05.0000}]");
+
+    ido.set_datetime_format(DateTimeFormat::rfc3339());
+    assert_eq!(ido.to_string(), "1=2024-01-02T03:04:05Z,2=[{1=2024-01-02T03:04:05Z}]");
+    assert_eq!(ido.to_string_with_format(&DateTimeFormat::epoch_seconds()), "1=1704164645,2=[{1=1704164645}]");
+    assert_eq!(ido.datetime_format(), &DateTimeFormat::rfc3339());
+
+    let names: IdoDictionary = [(1, "SENT")].into_iter().collect();
+    assert_eq!(ido.with_names(&names).to_string(), "SENT=2024-01-02T03:04:05Z,2=[{SENT=2024-01-02T03:04:05Z}]");
+}
@@ -0,0 +1,62 @@
+use ido::codec::{CodecError, Decode, Encode};
+use ido::delimited::DelimitedCodec;
+use ido::Ido;
+
+fn encode(codec: &DelimitedCodec, ido: &Ido) -> Result<Vec<u8>, CodecError> {
+    let mut bytes = Vec::new();
+    codec.encode(ido, &mut bytes)?;
+    Ok(bytes)
+}
+
+#[test]
+fn test_delimited_round_trip() {
+    let mut ido = Ido::new();
+    ido.set_string(&1, "a^b|c\\d".to_string());
+    ido.set_integer(&2, -5);
+    ido.set_f64(&3, 1.5);
+    ido.set_string(&4, String::new());
+
+    let codec = DelimitedCodec::new();
+    let bytes = encode(&codec, &ido).unwrap();
+    assert_eq!(bytes, b"1|a\\^b\\|c\\\\d^2|-5^3|1.5^4|");
+
+    let decoded = codec.decode(&bytes).unwrap();
+    assert_eq!(decoded.to_string(), ido.to_string());
+    assert_eq!(decoded.get_string(&4), Some(String::new()));
+}
+
+#[test]
+fn test_delimited_custom_separators() {
+    let codec = DelimitedCodec::new().field_separator(';').value_separator('=').escape(None);
+
+    let decoded = codec.decode(b"35=D;;55=a|b^c;").unwrap();
+    assert_eq!(decoded.size(), 2);
+    assert_eq!(decoded.get_string(&55), Some("a|b^c".to_string()));
+
+    let mut ido = Ido::new();
+    ido.set_string(&58, "x=y".to_string());
+    assert!(matches!(encode(&codec, &ido), Err(CodecError::Unsupported(58))));
+
+    // The first value separator splits; later ones belong to the value.
+    let codec = DelimitedCodec::new().value_separator('=');
+    assert_eq!(codec.decode(b"1=a=b").unwrap().get_string(&1), Some("a=b".to_string()));
+}
+
+#[test]
+fn test_delimited_errors() {
+    let codec = DelimitedCodec::new();
+    assert!(matches!(codec.decode(b"1|a^nokey"), Err(CodecError::InvalidField(4))));
+    assert!(matches!(codec.decode(b"1|a^x|b"), Err(CodecError::InvalidField(4))));
+    assert!(matches!(codec.decode(b"1|a\\"), Err(CodecError::InvalidField(0))));
+    assert!(matches!(codec.decode(&[b'1', b'|', 0xff]), Err(CodecError::InvalidUtf8)));
+
+    let mut ido = Ido::new();
+    ido.append_array(&1, Ido::new());
+    assert!(matches!(encode(&codec, &ido), Err(CodecError::Unsupported(1))));
+}
+
+#[test]
+#[should_panic(expected = "must be distinct")]
+fn test_delimited_same_separators() {
+    DelimitedCodec::new().value_separator('^');
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "derive")]
+
+use ido::convert::FromIdoError;
+use ido::{define_ido_keys, FromIdo, Ido, IdoKey, IntoIdo, ToIdo};
+
+define_ido_keys! {
+    SYMBOL = 55,
+}
+
+#[derive(IntoIdo, FromIdo, Debug, PartialEq)]
+struct Leg {
+    #[ido(key = SYMBOL)]
+    symbol: String,
+    #[ido(key = 2)]
+    ratio: f64,
+}
+
+#[derive(IntoIdo, FromIdo, Debug, PartialEq)]
+struct Order {
+    #[ido(key = 1)]
+    id: i64,
+    #[ido(key = 2)]
+    account: Option<String>,
+    #[ido(key = 3)]
+    legs: Vec<Leg>,
+    #[ido(skip)]
+    cache: Vec<u8>,
+}
+
+#[test]
+fn test_derive_round_trip() {
+    let order = Order {
+        id: 42,
+        account: Some("ACC-1".to_string()),
+        legs: vec![
+            Leg { symbol: "EURUSD".to_string(), ratio: 1.0 },
+            Leg { symbol: "GBPUSD".to_string(), ratio: -0.5 },
+        ],
+        cache: vec![1, 2, 3],
+    };
+
+    let ido = order.to_ido();
+    assert_eq!(ido.to_string(), "1=42,2=ACC-1,3=[{55=EURUSD,2=1},{55=GBPUSD,2=-0.5}]");
+
+    let decoded = Order::from_ido(&ido).unwrap();
+    assert_eq!(decoded.legs, order.legs);
+    assert_eq!(decoded.account, order.account);
+    assert!(decoded.cache.is_empty());
+}
+
+#[test]
+fn test_derive_errors() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 42);
+
+    let mut leg = Ido::new();
+    leg.set_string(&SYMBOL, "EURUSD".to_string());
+    ido.append_array(&3, leg);
+
+    assert_eq!(
+        Order::from_ido(&ido).unwrap_err(),
+        FromIdoError::Element { key: 3, index: 0, error: Box::new(FromIdoError::Missing(2)) }
+    );
+    assert_eq!(Order::from_ido(&Ido::new()).unwrap_err(), FromIdoError::Missing(1));
+}
+
+#[derive(IdoKey)]
+#[repr(i32)]
+enum OrderField {
+    Id = 1,
+    Price = 44,
+    Symbol = 55,
+}
+
+#[test]
+fn test_derive_ido_key() {
+    assert_eq!(OrderField::Price.ido_key(), 44);
+
+    let mut ido = Ido::new();
+    ido.set_integer(&OrderField::Id, 42);
+    ido.set_integer(&OrderField::Price, 100);
+    ido.set_string(&OrderField::Symbol, "EURUSD".to_string());
+
+    assert_eq!(ido.to_string(), "1=42,44=100,55=EURUSD");
+    assert_eq!(ido.get_i64(&44), Some(100));
+    assert_eq!(ido.get_string(&OrderField::Symbol), Some("EURUSD".to_string()));
+    assert!(ido.contains(&OrderField::Id));
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use ido::dictionary::IdoDictionary;
+use ido::{Ido, IdoKeyT};
+
+#[test]
+fn test_dictionary_insert_replaces() {
+    let mut dictionary = IdoDictionary::new();
+    assert!(dictionary.is_empty());
+
+    dictionary.insert(1, "ORDER_ID");
+    dictionary.insert(1, "CL_ORD_ID");
+    assert_eq!(dictionary.name(&1), Some("CL_ORD_ID"));
+    assert_eq!(dictionary.key("ORDER_ID"), None);
+
+    dictionary.insert(2, "CL_ORD_ID");
+    assert_eq!(dictionary.name(&1), None);
+    assert_eq!(dictionary.key("CL_ORD_ID"), Some(2));
+    assert_eq!(dictionary.len(), 1);
+
+    assert_eq!(dictionary.remove(&2), Some("CL_ORD_ID".to_string()));
+    assert_eq!(dictionary.key("CL_ORD_ID"), None);
+}
+
+#[test]
+fn test_dictionary_iter() {
+    let dictionary: IdoDictionary = [(3, "QTY"), (1, "ORDER_ID")].into_iter().collect();
+    let entries: Vec<(IdoKeyT, &str)> = dictionary.iter().collect();
+    assert_eq!(entries, vec![(1, "ORDER_ID"), (3, "QTY")]);
+}
+
+#[test]
+fn test_with_names() {
+    let names: IdoDictionary = [(1, "ORDER_ID"), (2, "LEGS"), (10, "SYMBOL")].into_iter().collect();
+
+    let mut leg = Ido::new();
+    leg.set_string(&10, "EURUSD".to_string());
+    leg.set_integer(&11, 5);
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.append_array(&2, leg);
+    ido.set_integer(&3, 100);
+
+    assert_eq!(ido.with_names(&names).to_string(), "ORDER_ID=ORDER-1,LEGS=[{SYMBOL=EURUSD,11=5}],3=100");
+    assert_eq!(ido.to_string(), "1=ORDER-1,2=[{10=EURUSD,11=5}],3=100");
+}
+
+#[test]
+fn test_attached_names() {
+    let names = Arc::new([(1, "LEGS"), (10, "SYMBOL"), (11, "PRICE")].into_iter().collect::<IdoDictionary>());
+
+    let mut leg = Ido::new();
+    leg.set_string(&10, "EURUSD".to_string());
+    leg.set_f64(&11, 1.25);
+
+    let mut ido = Ido::new();
+    ido.append_array(&1, leg.clone());
+    ido.set_integer(&2, 100);
+    let plain = ido.clone();
+    ido.set_names(Arc::clone(&names));
+
+    assert_eq!(ido.to_string(), "LEGS=[{SYMBOL=EURUSD,PRICE=1.25}],2=100");
+    assert_eq!(ido.clone().to_string(), ido.to_string());
+    assert_eq!(ido.to_table(&1).unwrap(), "SYMBOL  PRICE\n------  -----\nEURUSD  1.25\n");
+    assert!(ido.to_markdown().contains("| LEGS | array of 1 |"));
+    assert!(ido.to_html().contains("<th>PRICE</th>"));
+
+    let mut changed = plain.clone();
+    changed.set_integer(&2, 60);
+    assert_eq!(ido.change_log(&changed), "2: 100 → 60\n");
+
+    // Names only affect textual output.
+    assert!(ido == plain);
+    assert!(ido.encode() == plain.encode());
+    assert!(ido.names().is_some_and(|attached| attached.name(&1) == Some("LEGS")));
+
+    let other: IdoDictionary = [(2, "QTY")].into_iter().collect();
+    assert_eq!(ido.with_names(&other).to_string(), "1=[{10=EURUSD,11=1.25}],QTY=100");
+
+    ido.clear_names();
+    assert!(ido.names().is_none());
+    assert_eq!(ido.to_string(), plain.to_string());
+}
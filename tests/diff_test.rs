@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use ido::dictionary::IdoDictionary;
+use ido::{Ido, IdoItem, IdoItemType};
+
+fn leg(symbol: &str, price: f64) -> Ido {
+    let mut leg = Ido::new();
+    leg.set_string(&10, symbol.to_string());
+    leg.set_f64(&11, price);
+    leg
+}
+
+#[test]
+fn test_diff_report_equal() {
+    let mut before = Ido::new();
+    before.set_integer(&1, 10);
+    before.set_f64(&2, f64::NAN);
+    before.append_array(&3, leg("EURUSD", 1.1));
+
+    let mut after = Ido::new();
+    after.append_array(&3, leg("EURUSD", 1.1));
+    after.set_f64(&2, f64::NAN);
+    after.set_integer(&1, 10);
+
+    assert_eq!(before.diff_report(&after), "");
+}
+
+#[test]
+fn test_diff_report_type_change() {
+    let mut before = Ido::new();
+    before.set_integer(&1, 10);
+    before.set_integer(&2, 2);
+
+    let mut after = Ido::new();
+    after.set_string(&1, "10".to_string());
+    after.set_f64(&2, 2.0);
+
+    assert_eq!(before.diff_report(&after), "~ 1: 10 -> \"10\"\n~ 2: 2 (INTEGER) -> 2 (FLOAT)\n");
+}
+
+#[test]
+fn test_diff_report_arrays() {
+    let names: IdoDictionary = [(1, "LEGS"), (10, "SYMBOL"), (11, "PRICE")].into_iter().collect();
+
+    let mut before = Ido::new();
+    before.append_array(&1, leg("EURUSD", 1.1));
+    before.append_array(&1, leg("USDJPY", 150.0));
+
+    let mut after = Ido::new();
+    after.append_array(&1, leg("EURUSD", 1.1));
+    after.append_array(&1, leg("USDJPY", 151.5));
+    after.append_array(&1, leg("GBPUSD", 1.27));
+
+    assert_eq!(
+        before.with_names(&names).diff_report(&after),
+        "~ LEGS[1].PRICE: 150 -> 151.5\n+ LEGS[2]: [SYMBOL=\"GBPUSD\",PRICE=1.27]\n"
+    );
+    assert_eq!(after.diff_report(&before), "~ 1[1].11: 151.5 -> 150\n- 1[2]: [10=\"GBPUSD\",11=1.27]\n");
+}
+
+#[test]
+fn test_diff_report_ignores_expired() {
+    let before = Ido::new();
+
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::INTEGER;
+    item.m_integer = 1;
+    let mut after = Ido::new();
+    after.set_with_ttl(&1, item, Duration::ZERO);
+
+    assert_eq!(before.diff_report(&after), "");
+}
+
+#[test]
+fn test_change_log() {
+    let names: IdoDictionary = [(1, "LEGS"), (2, "SIDE"), (3, "QTY"), (10, "SYMBOL"), (11, "PRICE")].into_iter().collect();
+
+    let mut before = Ido::new();
+    before.append_array(&1, leg("EURUSD", 1.25));
+    before.set_string(&2, "BUY".to_string());
+    before.set_integer(&3, 10);
+    before.set_integer(&4, 7);
+
+    let mut after = Ido::new();
+    after.append_array(&1, leg("EURUSD", 1.5));
+    after.append_array(&1, leg("GBPUSD", 1.27));
+    after.set_string(&2, "SELL".to_string());
+    after.set_string(&4, "7".to_string());
+    after.set_integer(&5, 1);
+
+    assert_eq!(
+        before.with_names(&names).change_log(&after),
+        "LEGS[0].PRICE: 1.25 → 1.5\n\
+         LEGS[1]: (none) → [SYMBOL=GBPUSD,PRICE=1.27]\n\
+         SIDE: BUY → SELL\n\
+         QTY: 10 → (removed)\n\
+         4: 7 (INTEGER) → 7 (STRING)\n\
+         5: (none) → 1\n"
+    );
+    assert_eq!(before.change_log(&before), "");
+}
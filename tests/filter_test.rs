@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use ido::filter::{FilterError, IdoFilter};
+use ido::{Ido, IdoItem};
+
+fn order() -> Ido {
+    let mut ido = Ido::new();
+    ido.set_string(&1, "NYSE".to_string());
+    ido.set_integer(&7, 100);
+    ido.set_f64(&8, 101.25);
+    ido.set_i128(&9, i128::MAX);
+    ido
+}
+
+fn matches(filter: &str) -> bool {
+    IdoFilter::parse(filter).unwrap().matches(&order())
+}
+
+#[test]
+fn test_filter_comparisons() {
+    assert!(matches("1 == 'NYSE'"));
+    assert!(matches("1 == \"NYSE\""));
+    assert!(matches("1 != 'LSE'"));
+    assert!(matches("1 < 'O'"));
+    assert!(matches("7 >= 100"));
+    assert!(!matches("7 > 100"));
+    assert!(matches("7 <= 100.5"));
+    assert!(matches("8 > 101"));
+    assert!(matches("8 == 101.25"));
+    assert!(matches("9 > 1e30"));
+    assert!(matches("9 == 170141183460469231731687303715884105727"));
+    assert!(matches("7 > -1"));
+}
+
+#[test]
+fn test_filter_missing_and_unordered() {
+    assert!(!matches("2 == 1"));
+    assert!(!matches("2 != 1"));
+    assert!(matches("!(2 == 1)"));
+    assert!(!matches("1 > 5"));
+    assert!(!matches("1 != 5"));
+}
+
+#[test]
+fn test_filter_logic() {
+    assert!(matches("1 && 7"));
+    assert!(!matches("1 && 2"));
+    assert!(matches("2 || 7 == 100"));
+    assert!(matches("!2"));
+    assert!(!matches("!!2"));
+    // && binds tighter than ||.
+    assert!(matches("7 == 100 || 2 && 3"));
+    assert!(!matches("(7 == 100 || 2) && 3"));
+}
+
+#[test]
+fn test_filter_expired_key() {
+    let filter = IdoFilter::parse("5").unwrap();
+    let mut ido = Ido::new();
+    ido.set_with_ttl(&5, IdoItem::new(), Duration::ZERO);
+    assert!(!filter.matches(&ido));
+}
+
+#[test]
+fn test_filter_escapes() {
+    let mut ido = Ido::new();
+    ido.set_string(&1, "it's".to_string());
+    assert!("1 == 'it\\'s'".parse::<IdoFilter>().unwrap().matches(&ido));
+}
+
+#[test]
+fn test_filter_errors() {
+    assert_eq!(IdoFilter::parse("").err(), Some(FilterError::UnexpectedEnd));
+    assert_eq!(IdoFilter::parse("1 ==").err(), Some(FilterError::UnexpectedEnd));
+    assert_eq!(IdoFilter::parse("1 == 'a").err(), Some(FilterError::UnexpectedEnd));
+    assert_eq!(IdoFilter::parse("1 == 2 3").err(), Some(FilterError::UnexpectedToken(7)));
+    assert_eq!(IdoFilter::parse("(1 == 2").err(), Some(FilterError::UnexpectedEnd));
+    assert_eq!(IdoFilter::parse("1 = 2").err(), Some(FilterError::UnexpectedToken(2)));
+    assert_eq!(IdoFilter::parse("1.5 == 2").err(), Some(FilterError::InvalidLiteral(0)));
+    assert_eq!(IdoFilter::parse("1 == 2x").err(), Some(FilterError::InvalidLiteral(5)));
+    assert_eq!(IdoFilter::parse("'a' == 1").err(), Some(FilterError::UnexpectedToken(0)));
+}
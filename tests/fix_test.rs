@@ -0,0 +1,87 @@
+use ido::fix::{self, FixError, FixSpec};
+use ido::Ido;
+
+fn message(body: &str) -> Vec<u8> {
+    let body = body.replace('|', "\x01");
+    let mut out = format!("8=FIX.4.4\x019={}\x01{}", body.len(), body).into_bytes();
+    let sum = out.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    out.extend_from_slice(format!("10={:03}\x01", sum).as_bytes());
+    out
+}
+
+fn spec() -> FixSpec {
+    FixSpec::new().group(453, &[448, 447, 452, 802]).group(802, &[523, 803])
+}
+
+#[test]
+fn test_fix_decode_nested_groups() {
+    let wire = message("35=D|11=ORD1|453=2|448=AB|802=2|523=X|803=1|523=Y|803=2|448=CD|452=3|55=EURUSD|");
+    let ido = fix::decode(&wire, &spec()).unwrap();
+
+    assert_eq!(ido.get_string(&11), Some("ORD1".to_string()));
+    assert_eq!(ido.get_string(&55), Some("EURUSD".to_string()));
+    assert!(!ido.contains(&9));
+    assert!(!ido.contains(&10));
+
+    let parties = ido.get_item(&453).unwrap().m_array;
+    assert_eq!(parties.len(), 2);
+    assert_eq!(parties[0].get_item(&802).unwrap().m_array[1].get_string(&523), Some("Y".to_string()));
+    assert_eq!(parties[1].get_string(&452), Some("3".to_string()));
+    assert!(!parties[1].contains(&55));
+
+    assert_eq!(fix::encode(&ido).unwrap(), wire);
+}
+
+#[test]
+fn test_fix_decode_empty_group() {
+    let wire = message("35=D|453=0|55=EURUSD|");
+    let ido = fix::decode(&wire, &spec()).unwrap();
+
+    assert!(ido.get_item(&453).unwrap().m_array.is_empty());
+    assert_eq!(fix::encode(&ido).unwrap(), wire);
+}
+
+#[test]
+fn test_fix_decode_errors() {
+    let spec = spec();
+
+    let mut wire = message("35=0|");
+    let len = wire.len();
+    wire[len - 2] = b'0';
+    assert!(matches!(fix::decode(&wire, &spec), Err(FixError::Checksum { .. })));
+
+    let wire = String::from_utf8(message("35=0|")).unwrap().replace("9=5", "9=6");
+    assert_eq!(
+        fix::decode(wire.as_bytes(), &spec).err(),
+        Some(FixError::BodyLength { declared: 6, actual: 5 })
+    );
+
+    assert_eq!(fix::decode(b"35=0\x01", &spec).err(), Some(FixError::MissingHeader(8)));
+    assert_eq!(fix::decode(b"8=FIX.4.4\x019=0\x01", &spec).err(), Some(FixError::MissingHeader(10)));
+    assert_eq!(fix::decode(b"8=FIX.4.4\x01bad\x01", &spec).err(), Some(FixError::InvalidField(10)));
+    assert_eq!(fix::decode(&message("453=2|448=AB|"), &spec).err(), Some(FixError::InvalidGroup(453)));
+}
+
+#[test]
+fn test_fix_encode_typed_values() {
+    let mut ido = Ido::new();
+    ido.set_string(&8, "FIX.4.2".to_string());
+    ido.set_string(&35, "D".to_string());
+    ido.set_integer(&38, 100);
+    ido.set_f64(&44, 1.25);
+
+    let wire = fix::encode(&ido).unwrap();
+    assert!(wire.starts_with(b"8=FIX.4.2\x019=20\x0135=D\x0138=100\x0144=1.25\x0110="));
+    assert!(fix::decode(&wire, &FixSpec::new()).is_ok());
+}
+
+#[test]
+fn test_fix_encode_errors() {
+    let mut ido = Ido::new();
+    ido.set_string(&35, "D".to_string());
+    assert_eq!(fix::encode(&ido), Err(FixError::MissingHeader(8)));
+
+    ido.set_string(&8, "FIX.4.4".to_string());
+    ido.set_string(&58, "a\x01b".to_string());
+    assert_eq!(fix::encode(&ido), Err(FixError::InvalidValue(58)));
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "chrono")]
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use ido::Ido;
+use ido::history::IdoHistory;
+
+fn at(second: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(second, 0).unwrap()
+}
+
+fn version(value: i64) -> Ido {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, value);
+    ido
+}
+
+#[test]
+fn test_history_ring_buffer() {
+    let mut history = IdoHistory::new(3);
+    assert!(history.is_empty());
+    assert!(history.latest().is_none());
+
+    for second in 0..5 {
+        history.record_at(at(second), &version(second));
+    }
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.capacity(), 3);
+    assert_eq!(history.latest().unwrap().get_i64(&1), Some(4));
+    assert_eq!(history.nth_back(2).unwrap().get_i64(&1), Some(2));
+    assert!(history.nth_back(3).is_none());
+
+    let times: Vec<i64> = history.iter().map(|(timestamp, _)| timestamp.timestamp()).collect();
+    assert_eq!(times, [2, 3, 4]);
+
+    history.clear();
+    assert!(history.is_empty());
+}
+
+#[test]
+fn test_history_at() {
+    let mut history = IdoHistory::new(4);
+    history.record_at(at(10), &version(10));
+    history.record_at(at(20), &version(20));
+    history.record_at(at(15), &version(15));
+
+    assert!(history.at(at(9)).is_none());
+    assert_eq!(history.at(at(10)).unwrap().get_i64(&1), Some(10));
+    assert_eq!(history.at(at(17)).unwrap().get_i64(&1), Some(15));
+    assert_eq!(history.at(at(99)).unwrap().get_i64(&1), Some(20));
+    assert_eq!(history.nth_back(1).unwrap().get_i64(&1), Some(15));
+}
+
+#[test]
+fn test_history_out_of_order_when_full() {
+    let mut history = IdoHistory::new(2);
+    history.record_at(at(10), &version(10));
+    history.record_at(at(20), &version(20));
+    history.record_at(at(5), &version(5));
+
+    let values: Vec<i64> = history.iter().filter_map(|(_, ido)| ido.get_i64(&1)).collect();
+    assert_eq!(values, [10, 20]);
+}
+
+#[test]
+fn test_history_ago() {
+    let mut history = IdoHistory::new(2);
+    history.record(&version(1));
+    assert!(history.ago(Duration::from_secs(60)).is_none());
+    assert_eq!(history.ago(Duration::ZERO).unwrap().get_i64(&1), Some(1));
+}
+
+#[test]
+#[should_panic(expected = "capacity")]
+fn test_history_zero_capacity() {
+    IdoHistory::new(0);
+}
@@ -0,0 +1,45 @@
+use ido::dictionary::IdoDictionary;
+use ido::Ido;
+
+fn order() -> Ido {
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+    leg.set_f64(&2, 1.25);
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "Tom & \"Jerry\"".to_string());
+    ido.append_array(&5, leg.clone());
+    ido.append_array(&5, leg);
+    ido
+}
+
+#[test]
+fn test_to_html() {
+    assert_eq!(
+        order().to_html(),
+        "<table class=\"ido\">\n\
+         <tr><th>1</th><td>Tom &amp; &quot;Jerry&quot;</td></tr>\n\
+         <tr><th>5</th><td><details><summary>array of 2</summary>\n\
+         <table class=\"ido\">\n\
+         <tr><th>1</th><td>EURUSD</td></tr>\n\
+         <tr><th>2</th><td>1.25</td></tr>\n\
+         </table>\n\
+         <table class=\"ido\">\n\
+         <tr><th>1</th><td>EURUSD</td></tr>\n\
+         <tr><th>2</th><td>1.25</td></tr>\n\
+         </table>\n\
+         </details></td></tr>\n\
+         </table>\n"
+    );
+    assert_eq!(Ido::new().to_html(), "<table class=\"ido\">\n</table>\n");
+}
+
+#[test]
+fn test_to_html_with_names() {
+    let names: IdoDictionary = [(1, "<NAME>"), (5, "LEGS")].into_iter().collect();
+    let html = order().with_names(&names).to_html();
+
+    assert!(html.starts_with("<table class=\"ido\">\n<tr><th>&lt;NAME&gt;</th><td>Tom &amp;"));
+    assert!(html.contains("<tr><th>LEGS</th><td><details><summary>array of 2</summary>\n"));
+    assert!(html.contains("<tr><th>2</th><td>1.25</td></tr>\n"));
+}
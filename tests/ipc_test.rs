@@ -0,0 +1,146 @@
+#![cfg(any(unix, windows))]
+
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::thread;
+
+use ido::ipc::{IdoIpcListener, IdoIpcStream};
+use ido::net::NetError;
+use ido::Ido;
+
+#[cfg(unix)]
+fn socket_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("ido-{}-{}.sock", name, std::process::id()))
+}
+
+#[cfg(windows)]
+fn socket_path(name: &str) -> PathBuf {
+    PathBuf::from(format!(r"\\.\pipe\ido-{}-{}", name, std::process::id()))
+}
+
+fn io_kind(result: Result<impl Sized, NetError>) -> Option<ErrorKind> {
+    match result {
+        Err(NetError::Io(err)) => Some(err.kind()),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_ipc_request_reply() {
+    let path = socket_path("reply");
+    let listener = IdoIpcListener::bind(&path).unwrap();
+
+    let handle = thread::spawn(move || {
+        let mut server = listener.accept().unwrap();
+        let request = server.recv().unwrap();
+
+        let mut reply = Ido::new();
+        reply.set_integer(&2, request.get_i64(&1).unwrap() * 2);
+        server.send(&reply).unwrap();
+    });
+
+    let mut client = IdoIpcStream::connect(&path).unwrap();
+    let mut request = Ido::new();
+    request.set_integer(&1, 21);
+    client.send(&request).unwrap();
+
+    assert_eq!(client.recv().unwrap().get_i64(&2), Some(42));
+    handle.join().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ipc_listener_removes_socket() {
+    let path = socket_path("cleanup");
+    let listener = IdoIpcListener::bind(&path).unwrap();
+    assert!(path.exists());
+
+    drop(listener);
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_ipc_recv_after_peer_closed() {
+    let path = socket_path("closed");
+    let listener = IdoIpcListener::bind(&path).unwrap();
+
+    let client = IdoIpcStream::connect(&path).unwrap();
+    let mut server = listener.accept().unwrap();
+    drop(client);
+
+    assert_eq!(io_kind(server.recv()), Some(ErrorKind::UnexpectedEof));
+}
+
+#[test]
+fn test_ipc_bind_in_use() {
+    let path = socket_path("in-use");
+    let _listener = IdoIpcListener::bind(&path).unwrap();
+
+    assert_eq!(io_kind(IdoIpcListener::bind(&path)), Some(ErrorKind::AddrInUse));
+}
+
+#[test]
+fn test_ipc_recv_timeout() {
+    let path = socket_path("timeout");
+    let listener = IdoIpcListener::bind(&path).unwrap();
+
+    let mut client = IdoIpcStream::connect(&path).unwrap();
+    let mut server = listener.accept().unwrap();
+    server.set_read_timeout(Some(std::time::Duration::from_millis(20))).unwrap();
+
+    let kind = io_kind(server.recv());
+    assert!(matches!(kind, Some(ErrorKind::WouldBlock | ErrorKind::TimedOut)));
+
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 42);
+    client.send(&ido).unwrap();
+    assert_eq!(server.recv().unwrap().get_i64(&1), Some(42));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ipc_bind_keeps_regular_file() {
+    let path = socket_path("regular");
+    std::fs::write(&path, "not a socket").unwrap();
+
+    assert_eq!(io_kind(IdoIpcListener::bind(&path)), Some(ErrorKind::InvalidInput));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "not a socket");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ipc_bind_replaces_stale_socket() {
+    let path = socket_path("stale");
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+
+    let listener = IdoIpcListener::bind(&path).unwrap();
+    let _client = IdoIpcStream::connect(&path).unwrap();
+    listener.accept().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ipc_recv_keeps_partial_frame_after_timeout() {
+    use std::io::Write;
+    use std::os::unix::net::UnixStream;
+
+    let path = socket_path("partial");
+    let listener = IdoIpcListener::bind(&path).unwrap();
+
+    let mut client = UnixStream::connect(&path).unwrap();
+    let mut server = listener.accept().unwrap();
+    server.set_read_timeout(Some(std::time::Duration::from_millis(20))).unwrap();
+
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 42);
+    let mut wire = Vec::new();
+    ido.encode_to(&mut wire).unwrap();
+
+    client.write_all(&wire[..6]).unwrap();
+    assert!(server.recv().is_err());
+
+    client.write_all(&wire[6..]).unwrap();
+    assert_eq!(server.recv().unwrap().get_i64(&1), Some(42));
+}
@@ -0,0 +1,59 @@
+use ido::iter::IdoIteratorExt;
+use ido::{Ido, IdoItemType, IdoKeyT};
+
+fn sample() -> Ido {
+    let mut ido = Ido::new();
+    ido.set_string(&3, "eurusd".to_string());
+    ido.set_integer(&1, 100);
+    ido.set_string(&1500, "internal".to_string());
+    ido.set_f64(&2, 1.25);
+    ido
+}
+
+#[test]
+fn test_map_values_and_filter_keys() {
+    let ido = sample();
+    let result: Ido = ido
+        .into_ordered_iterator()
+        .filter_keys(|key| *key <= 1000)
+        .map_values(|mut item| {
+            if item.m_type == IdoItemType::STRING {
+                item.m_string = item.m_string.to_uppercase();
+            }
+            item
+        })
+        .collect();
+
+    let keys: Vec<IdoKeyT> = result.into_ordered_iterator().map(|(key, _)| key).collect();
+    assert_eq!(keys, vec![3, 1, 2]);
+    assert_eq!(result.get_string(&3), Some("EURUSD".to_string()));
+    assert_eq!(result.get_i64(&1), Some(100));
+    assert_eq!(result.get_f64(&2), Some(1.25));
+    assert_eq!(ido.get_string(&3), Some("eurusd".to_string()));
+}
+
+#[test]
+fn test_map_into_ido() {
+    let result = sample().into_iter().map_into_ido(|key, item| match item.m_type {
+        IdoItemType::STRING => Some((key * 10, item)),
+        _ => None,
+    });
+
+    assert_eq!(result.size(), 2);
+    assert_eq!(result.get_string(&30), Some("eurusd".to_string()));
+    assert_eq!(result.get_string(&15000), Some("internal".to_string()));
+}
+
+#[test]
+fn test_collect_keeps_last_item() {
+    let mut first = Ido::new();
+    first.set_integer(&1, 5);
+    let mut second = Ido::new();
+    second.set_integer(&1, 6);
+    second.set_integer(&2, 7);
+
+    let merged: Ido = first.into_iter().chain(second).collect();
+    assert_eq!(merged.get_i64(&1), Some(6));
+    assert_eq!(merged.get_i64(&2), Some(7));
+    assert_eq!(Ido::new().into_iter().filter_keys(|_| true).count(), 0);
+}
@@ -0,0 +1,130 @@
+use ido::Ido;
+
+fn value(item: &Option<ido::IdoItem>) -> Option<String> {
+    item.as_ref().and_then(|item| item.as_string())
+}
+
+#[test]
+fn test_journal_records_mutations() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 1);
+    ido.enable_journal();
+
+    ido.set_integer(&1, 2);
+    ido.set_string(&2, "value2".to_string());
+    ido.append_array(&3, Ido::new());
+    ido.delete_item(&2);
+
+    let history = ido.history();
+    assert_eq!(history.len(), 4);
+
+    assert_eq!(history[0].key, 1);
+    assert_eq!(value(&history[0].old), Some("1".to_string()));
+    assert_eq!(value(&history[0].new), Some("2".to_string()));
+
+    assert_eq!(history[1].key, 2);
+    assert_eq!(value(&history[1].old), None);
+
+    assert_eq!(value(&history[2].new), Some("<array of 1>".to_string()));
+
+    assert_eq!(history[3].key, 2);
+    assert_eq!(value(&history[3].old), Some("value2".to_string()));
+    assert_eq!(value(&history[3].new), None);
+
+    #[cfg(feature = "chrono")]
+    assert!(history.windows(2).all(|pair| pair[0].timestamp <= pair[1].timestamp));
+}
+
+#[test]
+fn test_journal_disabled_by_default() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 1);
+
+    assert!(!ido.is_journal_enabled());
+    assert!(ido.history().is_empty());
+}
+
+#[test]
+fn test_journal_clear_and_disable() {
+    let mut ido = Ido::new();
+    ido.enable_journal();
+    ido.set_integer(&1, 1);
+    ido.clear();
+
+    assert_eq!(ido.history().len(), 2);
+
+    ido.clear_history();
+    assert!(ido.history().is_empty());
+    assert!(ido.is_journal_enabled());
+
+    ido.set_integer(&1, 1);
+    ido.disable_journal();
+    assert!(ido.history().is_empty());
+}
+
+#[test]
+fn test_undo_redo() {
+    let mut ido = Ido::new();
+    ido.enable_journal();
+
+    ido.set_integer(&1, 10);
+    ido.set_integer(&2, 20);
+    ido.set_integer(&1, 11);
+    ido.delete_item(&2);
+
+    assert!(ido.undo());
+    assert_eq!(ido.get_i64(&2), Some(20));
+    assert!(ido.undo());
+    assert_eq!(ido.get_i64(&1), Some(10));
+    assert_eq!(ido.to_string(), "1=10,2=20");
+    assert_eq!(ido.history().len(), 2);
+
+    assert!(ido.redo());
+    assert_eq!(ido.get_i64(&1), Some(11));
+    assert!(ido.redo());
+    assert!(!ido.contains(&2));
+    assert!(!ido.redo());
+
+    assert!(ido.undo());
+    assert!(ido.undo());
+    assert!(ido.undo());
+    assert!(ido.undo());
+    assert_eq!(ido.size(), 0);
+    assert!(!ido.undo());
+}
+
+#[test]
+fn test_new_mutation_clears_redo() {
+    let mut ido = Ido::new();
+    ido.enable_journal();
+
+    ido.set_integer(&1, 10);
+    ido.set_integer(&1, 11);
+    ido.undo();
+
+    ido.set_integer(&2, 20);
+    assert!(!ido.redo());
+    assert_eq!(ido.get_i64(&1), Some(10));
+}
+
+#[test]
+fn test_undo_array_append() {
+    let mut ido = Ido::new();
+    ido.enable_journal();
+
+    ido.append_array(&1, Ido::new());
+    ido.append_array(&1, Ido::new());
+    ido.undo();
+
+    assert_eq!(ido.get_item(&1).unwrap().m_array.len(), 1);
+}
+
+#[test]
+fn test_undo_without_journal() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 10);
+
+    assert!(!ido.undo());
+    assert!(!ido.redo());
+    assert_eq!(ido.get_i64(&1), Some(10));
+}
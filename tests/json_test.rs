@@ -0,0 +1,93 @@
+#![cfg(feature = "chrono")]
+
+use std::io;
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use ido::dictionary::IdoDictionary;
+use ido::{Ido, IdoItem, IdoItemType};
+
+fn rows() -> Ido {
+    let mut fill = Ido::new();
+    fill.set_bool(&1, true);
+
+    let mut first = Ido::new();
+    first.set_string(&1, "say \"hi\"\n\u{1}".to_string());
+    first.set_integer(&2, -5);
+    first.set_i128(&3, i128::MAX);
+    first.set_f64(&4, 1.5);
+
+    let mut second = Ido::new();
+    second.set_f64(&4, f64::NAN);
+    let mut sent = IdoItem::new();
+    sent.m_type = IdoItemType::DATETIME;
+    sent.m_datetime = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+    second.set_item(&5, sent);
+    second.append_array(&6, fill.clone());
+    second.append_array(&6, fill);
+
+    let mut ido = Ido::new();
+    ido.append_array(&9, first);
+    ido.append_array(&9, second);
+    ido.append_array(&9, Ido::new());
+    ido.set_integer(&10, 1);
+    ido
+}
+
+#[test]
+fn test_write_array_as_jsonl() {
+    let mut out = Vec::new();
+    assert_eq!(rows().write_array_as_jsonl(&9, &mut out).unwrap(), 3);
+
+    let text = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            format!("{{\"1\":\"say \\\"hi\\\"\\n\\u0001\",\"2\":-5,\"3\":{},\"4\":1.5}}", i128::MAX).as_str(),
+            "{\"4\":null,\"5\":\"2024-01-02 03:04:05.0000\",\"6\":[{\"1\":true},{\"1\":true}]}",
+            "{}",
+        ]
+    );
+}
+
+#[test]
+fn test_write_array_as_jsonl_names() {
+    let mut row = Ido::new();
+    row.set_string(&1, "EURUSD".to_string());
+    row.set_integer(&2, 100);
+    let mut ido = Ido::new();
+    ido.append_array(&9, row);
+    ido.set_names(Arc::new(IdoDictionary::from_iter([(1, "symbol")])));
+
+    let mut out = Vec::new();
+    ido.write_array_as_jsonl(&9, &mut out).unwrap();
+    assert_eq!(out, b"{\"symbol\":\"EURUSD\",\"2\":100}\n");
+}
+
+#[test]
+fn test_write_array_as_jsonl_not_an_array() {
+    let ido = rows();
+    let mut out = Vec::new();
+    assert_eq!(ido.write_array_as_jsonl(&10, &mut out).unwrap(), 0);
+    assert_eq!(ido.write_array_as_jsonl(&11, &mut out).unwrap(), 0);
+    assert!(out.is_empty());
+}
+
+struct Broken;
+
+impl io::Write for Broken {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::Error::other("closed"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_write_array_as_jsonl_error() {
+    let err = rows().write_array_as_jsonl(&9, &mut Broken).unwrap_err();
+    assert_eq!(err.to_string(), "closed");
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "ido-kafka")]
+
+use ido::kafka::{IdoDeserializer, IdoKafkaError, IdoSerializer, SchemaRef, SCHEMA_HEADER};
+use ido::registry::{Migration, SchemaRegistry};
+use ido::schema::IdoSchema;
+use ido::{Ido, IdoItemType};
+use rdkafka::message::{Header, OwnedHeaders, OwnedMessage};
+use rdkafka::Timestamp;
+
+fn message(payload: Option<Vec<u8>>, headers: OwnedHeaders) -> OwnedMessage {
+    OwnedMessage::new(payload, None, "orders".to_string(), Timestamp::NotAvailable, 0, 0, Some(headers))
+}
+
+#[test]
+fn test_kafka_round_trip_without_schema() {
+    let mut ido = Ido::new();
+    ido.set_string(&1, "ORD1".to_string());
+
+    let serializer = IdoSerializer::new();
+    let received = message(Some(serializer.serialize(&ido)), serializer.headers());
+
+    let deserializer = IdoDeserializer::new();
+    assert_eq!(deserializer.schema_of(&received).unwrap(), None);
+    assert_eq!(deserializer.deserialize(&received).unwrap().to_string(), "1=ORD1");
+}
+
+#[test]
+fn test_kafka_upgrades_through_registry() {
+    let mut registry = SchemaRegistry::new();
+    registry
+        .register("order", 1, IdoSchema::new().required(1, IdoItemType::INTEGER), Migration::new())
+        .unwrap();
+    registry
+        .register(
+            "order",
+            2,
+            IdoSchema::new().required(10, IdoItemType::INTEGER),
+            Migration::new().rename(1, 10),
+        )
+        .unwrap();
+
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 100);
+
+    let serializer = IdoSerializer::new().schema("order", 1);
+    let received = message(Some(serializer.serialize(&ido)), serializer.headers());
+
+    let deserializer = IdoDeserializer::new().registry(registry);
+    assert_eq!(
+        deserializer.schema_of(&received).unwrap(),
+        Some(SchemaRef { message_type: "order".to_string(), version: 1 })
+    );
+    assert_eq!(deserializer.deserialize(&received).unwrap().to_string(), "10=100");
+}
+
+#[test]
+fn test_kafka_errors() {
+    let deserializer = IdoDeserializer::new().registry(SchemaRegistry::new());
+
+    let received = message(None, OwnedHeaders::new());
+    assert!(matches!(deserializer.deserialize(&received), Err(IdoKafkaError::EmptyPayload)));
+
+    let received = message(Some(vec![1]), OwnedHeaders::new());
+    assert!(matches!(deserializer.deserialize(&received), Err(IdoKafkaError::Codec(_))));
+
+    let headers = OwnedHeaders::new().insert(Header { key: SCHEMA_HEADER, value: Some("order") });
+    let received = message(Some(Ido::new().encode()), headers);
+    assert!(matches!(deserializer.deserialize(&received), Err(IdoKafkaError::InvalidHeader(_))));
+
+    let serializer = IdoSerializer::new().schema("quote", 1);
+    let received = message(Some(Ido::new().encode()), serializer.headers());
+    assert!(matches!(deserializer.deserialize(&received), Err(IdoKafkaError::Registry(_))));
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "chrono")]
+
+use chrono::{TimeZone, Utc};
+use ido::codec::CodecError;
+use ido::lazy::LazyIdo;
+use ido::{Ido, IdoItem, IdoItemType};
+
+fn sample() -> Ido {
+    let mut leg = Ido::new();
+    leg.set_string(&10, "EURUSD".to_string());
+
+    let mut stamp = IdoItem::new();
+    stamp.m_type = IdoItemType::DATETIME;
+    stamp.m_datetime = Utc.with_ymd_and_hms(2023, 7, 1, 12, 30, 0).unwrap();
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.set_integer(&2, 100);
+    ido.set_f64(&3, 101.25);
+    ido.set_item(&4, stamp);
+    ido.append_array(&5, leg);
+    ido.set_i128(&6, i128::MAX);
+    ido
+}
+
+#[test]
+fn test_lazy_decodes_on_access() {
+    let bytes = sample().encode();
+    let lazy = LazyIdo::new(&bytes).unwrap();
+
+    assert_eq!(lazy.keys(), &[1, 2, 3, 4, 5, 6]);
+    assert!(lazy.contains(&5));
+    assert!(!lazy.contains(&7));
+    assert!((1..=6).all(|key| !lazy.is_decoded(&key)));
+
+    let array = lazy.get_item(&5).unwrap().unwrap();
+    assert_eq!(array.m_key, 5);
+    assert_eq!(array.m_array[0].get_string(&10), Some("EURUSD".to_string()));
+    assert!(lazy.is_decoded(&5));
+    assert!(!lazy.is_decoded(&4));
+
+    assert_eq!(lazy.get_item(&6).unwrap().unwrap().m_integer128, i128::MAX);
+    assert!(lazy.get_item(&7).unwrap().is_none());
+    assert_eq!(lazy.to_ido().unwrap().to_string(), sample().to_string());
+}
+
+#[test]
+fn test_lazy_repeated_key() {
+    // count=3, then 1=INTEGER 1, 2=INTEGER 2, 1=INTEGER 3; as a zigzag
+    // varint, key k is the single byte 2k.
+    let mut bytes = 3u32.to_le_bytes().to_vec();
+    for (key, value) in [(1u8, 1i64), (2, 2), (1, 3)] {
+        bytes.push(key * 2);
+        bytes.push(2);
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    let lazy = LazyIdo::new(&bytes).unwrap();
+    assert_eq!(lazy.keys(), &[2, 1]);
+    assert_eq!(lazy.get_item(&1).unwrap().unwrap().m_integer, 3);
+}
+
+#[test]
+fn test_lazy_errors() {
+    let bytes = sample().encode();
+    assert!(matches!(LazyIdo::new(&bytes[..bytes.len() - 1]), Err(CodecError::UnexpectedEof)));
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(LazyIdo::new(&trailing), Err(CodecError::TrailingBytes(1))));
+
+    // 1=STRING with invalid UTF-8 is only reported when read.
+    let mut invalid = 1u32.to_le_bytes().to_vec();
+    invalid.push(2);
+    invalid.push(1);
+    invalid.extend_from_slice(&1u32.to_le_bytes());
+    invalid.push(0xff);
+
+    let lazy = LazyIdo::new(&invalid).unwrap();
+    assert!(matches!(lazy.get_item(&1), Err(CodecError::InvalidUtf8)));
+}
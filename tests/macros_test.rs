@@ -0,0 +1,60 @@
+use ido::convert::FromIdoError;
+use ido::{define_ido_keys, get_tuple, Ido, IdoItemType, IdoKeyT};
+
+mod fields {
+    ido::define_ido_keys! {
+        dictionary pub fn names;
+
+        pub SYMBOL = 55,
+        pub SIDE = 54,
+    }
+}
+
+define_ido_keys! {
+    ORDER_ID = 1,
+    /// Limit price.
+    PRICE = 2,
+    QTY = PRICE + 1
+}
+
+#[test]
+fn test_define_ido_keys() {
+    let keys: [IdoKeyT; 3] = [ORDER_ID, PRICE, QTY];
+    assert_eq!(keys, [1, 2, 3]);
+
+    let mut ido = Ido::new();
+    ido.set_f64(&PRICE, 101.25);
+    assert_eq!(ido.get_f64(&2), Some(101.25));
+}
+
+#[test]
+fn test_define_ido_keys_dictionary() {
+    let names = fields::names();
+    assert_eq!(names.len(), 2);
+    assert_eq!(names.name(&fields::SYMBOL), Some("SYMBOL"));
+    assert_eq!(names.key("SIDE"), Some(54));
+}
+
+#[test]
+fn test_get_tuple() {
+    let mut ido = Ido::new();
+    ido.set_integer(&ORDER_ID, 42);
+    ido.set_f64(&PRICE, 101.25);
+    ido.set_string(&fields::SYMBOL, "EURUSD".to_string());
+
+    let (id, price, symbol, qty) =
+        get_tuple!(ido, (ORDER_ID, u32), (PRICE, f64), (fields::SYMBOL, String), (QTY, Option<i64>)).unwrap();
+    assert_eq!((id, price, symbol.as_str(), qty), (42, 101.25, "EURUSD", None));
+
+    let (single,) = get_tuple!(&ido, (1, i64)).unwrap();
+    assert_eq!(single, 42);
+
+    let errors = get_tuple!(ido, (ORDER_ID, String), (PRICE, f64), (QTY, i64)).unwrap_err();
+    assert_eq!(
+        errors.errors(),
+        [
+            FromIdoError::WrongType { key: 1, expected: IdoItemType::STRING, actual: IdoItemType::INTEGER },
+            FromIdoError::Missing(3),
+        ]
+    );
+}
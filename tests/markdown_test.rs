@@ -0,0 +1,61 @@
+use ido::dictionary::IdoDictionary;
+use ido::Ido;
+
+fn order() -> Ido {
+    let mut fill = Ido::new();
+    fill.set_integer(&1, 60);
+
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EUR|USD".to_string());
+    leg.append_array(&7, fill);
+
+    let mut other = Ido::new();
+    other.set_f64(&2, 1.25);
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "line one\nline two".to_string());
+    ido.append_array(&5, leg);
+    ido.append_array(&5, other);
+    ido
+}
+
+#[test]
+fn test_to_markdown() {
+    assert_eq!(
+        order().to_markdown(),
+        "| Key | Value |\n\
+         | --- | --- |\n\
+         | 1 | line one<br>line two |\n\
+         | 5 | array of 2 |\n\
+         \n**5**\n\n\
+         | 1 | 7 | 2 |\n\
+         | --- | --- | --- |\n\
+         | EUR\\|USD | \\<array of 1> |  |\n\
+         |  |  | 1.25 |\n\
+         \n**5[0].7**\n\n\
+         | 1 |\n\
+         | --- |\n\
+         | 60 |\n"
+    );
+    assert_eq!(Ido::new().to_markdown(), "| Key | Value |\n| --- | --- |\n");
+}
+
+#[test]
+fn test_to_markdown_with_names() {
+    let names: IdoDictionary = [(5, "LEGS"), (7, "FILLS"), (1, "ID")].into_iter().collect();
+    let markdown = order().with_names(&names).to_markdown();
+
+    assert!(markdown.contains("| LEGS | array of 2 |\n"));
+    assert!(markdown.contains("\n**LEGS**\n\n| ID | FILLS | 2 |\n"));
+    assert!(markdown.contains("\n**LEGS[0].FILLS**\n\n| ID |\n"));
+}
+
+#[test]
+fn test_to_markdown_empty_array() {
+    let mut ido = order();
+    let mut item = ido.get_item(&5).unwrap();
+    item.m_array.clear();
+    ido.set_item(&5, item);
+
+    assert!(ido.to_markdown().ends_with("| 5 | array of 0 |\n\n**5**\n\n_empty_\n"));
+}
@@ -0,0 +1,108 @@
+use std::io::{ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use ido::codec::write_frame;
+use ido::net::{IdoPublisher, IdoSubscriber, NetError, ReconnectPolicy};
+use ido::Ido;
+
+fn message(value: i64) -> Ido {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, value);
+    ido
+}
+
+#[test]
+fn test_publish_to_multiple_subscribers() {
+    let mut publisher = IdoPublisher::bind("127.0.0.1:0").unwrap();
+    let addr = publisher.local_addr().unwrap();
+
+    let mut first = IdoSubscriber::connect(addr).unwrap();
+    let mut second = IdoSubscriber::connect(addr).unwrap();
+
+    assert_eq!(publisher.publish(&message(1)).unwrap(), 2);
+    assert_eq!(publisher.publish(&message(2)).unwrap(), 2);
+
+    assert_eq!(first.recv().unwrap().get_i64(&1), Some(1));
+    assert_eq!(first.recv().unwrap().get_i64(&1), Some(2));
+    assert_eq!(second.recv().unwrap().get_i64(&1), Some(1));
+    assert_eq!(second.recv().unwrap().get_i64(&1), Some(2));
+}
+
+#[test]
+fn test_subscriber_reconnects() {
+    let publisher = IdoPublisher::bind("127.0.0.1:0").unwrap();
+    let addr = publisher.local_addr().unwrap();
+
+    let policy = ReconnectPolicy {
+        max_attempts: Some(50),
+        initial_delay: Duration::from_millis(10),
+        max_delay: Duration::from_millis(50),
+    };
+    let mut subscriber = IdoSubscriber::connect(addr).unwrap().with_reconnect(policy);
+
+    let handle = thread::spawn(move || {
+        let mut publisher = publisher;
+        publisher.accept_pending().unwrap();
+        drop(publisher);
+
+        let mut publisher = IdoPublisher::bind(addr).unwrap();
+        while publisher.publish(&message(7)).unwrap() == 0 {
+            thread::sleep(Duration::from_millis(10));
+        }
+    });
+
+    assert_eq!(subscriber.recv().unwrap().get_i64(&1), Some(7));
+    handle.join().unwrap();
+}
+
+#[test]
+fn test_subscriber_gives_up() {
+    let publisher = IdoPublisher::bind("127.0.0.1:0").unwrap();
+    let mut subscriber = IdoSubscriber::connect(publisher.local_addr().unwrap())
+        .unwrap()
+        .with_reconnect(ReconnectPolicy::never());
+
+    drop(publisher);
+
+    assert!(matches!(subscriber.recv(), Err(NetError::ReconnectFailed(0))));
+}
+
+#[test]
+fn test_subscriber_keeps_partial_frame_after_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut subscriber = IdoSubscriber::connect(listener.local_addr().unwrap()).unwrap();
+    subscriber.set_read_timeout(Some(Duration::from_millis(50))).unwrap();
+    let (mut publisher, _) = listener.accept().unwrap();
+
+    let mut wire = Vec::new();
+    write_frame(&mut wire, &message(1)).unwrap();
+    write_frame(&mut wire, &message(2)).unwrap();
+
+    publisher.write_all(&wire[..6]).unwrap();
+    let timeout = subscriber.recv().err();
+    assert!(matches!(timeout, Some(NetError::Io(err)) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)));
+    assert!(subscriber.is_connected());
+
+    publisher.write_all(&wire[6..]).unwrap();
+    assert_eq!(subscriber.recv().unwrap().get_i64(&1), Some(1));
+    assert_eq!(subscriber.recv().unwrap().get_i64(&1), Some(2));
+}
+
+#[test]
+fn test_publisher_drops_stalled_subscriber() {
+    let mut publisher = IdoPublisher::bind("127.0.0.1:0").unwrap();
+    publisher.set_write_timeout(Some(Duration::from_millis(20))).unwrap();
+    let _stalled = TcpStream::connect(publisher.local_addr().unwrap()).unwrap();
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "x".repeat(1 << 20));
+
+    let mut published = 0;
+    while publisher.publish(&ido).unwrap() > 0 {
+        published += 1;
+        assert!(published < 1000, "stalled subscriber was never dropped");
+    }
+    assert_eq!(publisher.subscriber_count(), 0);
+}
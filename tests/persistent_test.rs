@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use ido::persistent::PersistentIdo;
+use ido::{Ido, IdoItem, IdoKeyT};
+
+#[test]
+fn test_persistent_versions() {
+    let empty = PersistentIdo::new();
+    let v1 = empty.set_string(&1, "ORDER-1".to_string()).set_integer(&2, 100);
+    let v2 = v1.set_integer(&2, 60).set_f64(&3, 101.25);
+    let v3 = v2.delete_item(&1);
+
+    assert!(empty.is_empty());
+    assert_eq!(v1.size(), 2);
+    assert_eq!(v2.size(), 3);
+    assert_eq!(v3.size(), 2);
+
+    assert_eq!(v1.get_i64(&2), Some(100));
+    assert_eq!(v2.get_i64(&2), Some(60));
+    assert_eq!(v2.get_f64(&3), Some(101.25));
+    assert_eq!(v2.get_string(&1), Some("ORDER-1".to_string()));
+    assert!(!v3.contains(&1));
+    assert!(v1.get_item(&3).is_none());
+
+    assert!(std::ptr::eq(v1.get_item(&1).unwrap(), v2.get_item(&1).unwrap()));
+    assert!(std::ptr::eq(v2.get_item(&3).unwrap(), v3.get_item(&3).unwrap()));
+    assert_eq!(v3.delete_item(&99).size(), 2);
+}
+
+/// The value stored under `key`; `IdoKeyT` is already `i64` with `wide-keys`.
+#[allow(clippy::useless_conversion)]
+fn value(key: IdoKeyT) -> i64 {
+    i64::from(key)
+}
+
+#[test]
+fn test_persistent_many_keys() {
+    let keys: Vec<IdoKeyT> = (-500..500).map(|n| n * 7919).chain([IdoKeyT::MIN, IdoKeyT::MAX, 0]).collect();
+    let mut ido = PersistentIdo::new();
+    for key in &keys {
+        ido = ido.set_integer(key, value(*key));
+    }
+    let full = ido.clone();
+    assert_eq!(full.size(), 1002);
+
+    let mut sorted = keys.clone();
+    sorted.sort();
+    sorted.dedup();
+    let listed: Vec<IdoKeyT> = full.items().iter().map(|item| item.m_key).collect();
+    assert_eq!(listed, sorted);
+
+    let deleted: Vec<IdoKeyT> = sorted.iter().copied().step_by(2).collect();
+    for key in &deleted {
+        ido = ido.delete_item(key);
+    }
+    for key in &sorted {
+        let expected = (!deleted.contains(key)).then_some(value(*key));
+        assert_eq!(ido.get_i64(key), expected, "key {}", key);
+        assert_eq!(full.get_i64(key), Some(value(*key)));
+    }
+    assert_eq!(ido.size(), ido.items().len());
+
+    for key in &keys {
+        ido = ido.delete_item(key);
+    }
+    assert!(ido.is_empty());
+    assert!(ido.items().is_empty());
+}
+
+#[test]
+fn test_persistent_from_ido() {
+    let mut ido = Ido::new();
+    ido.set_string(&3, "C".to_string());
+    ido.set_integer(&1, 1);
+    ido.set_with_ttl(&2, IdoItem::new(), Duration::ZERO);
+    ido.append_array(&4, Ido::new());
+
+    let persistent = PersistentIdo::from(&ido);
+    assert_eq!(persistent.size(), 3);
+    assert!(!persistent.contains(&2));
+
+    let back = persistent.to_ido();
+    assert_eq!(back.to_string(), "1=1,3=C,4=[{}]");
+    assert!(back.dirty_keys().is_empty());
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "proto")]
+
+use chrono::{TimeZone, Utc};
+use ido::proto::ido_field::Value;
+use ido::proto::{IdoField, IdoMessage, ProtoError};
+use ido::dictionary::IdoDictionary;
+use ido::{Ido, IdoItem, IdoItemType};
+use prost::Message;
+use prost_types::value::Kind;
+use prost_types::{Any, ListValue, Struct};
+
+#[test]
+fn test_proto_roundtrip() {
+    let mut nested = Ido::new();
+    nested.set_string(&100, String::from("nested"));
+
+    let mut datetime = IdoItem::new();
+    datetime.m_type = IdoItemType::DATETIME;
+    datetime.m_datetime = Utc.with_ymd_and_hms(2023, 7, 1, 12, 30, 0).unwrap();
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, String::from("value1"));
+    ido.set_integer(&2, -42);
+    ido.set_f64(&3, 2.5);
+    ido.set_item(&4, datetime.clone());
+    ido.append_array(&5, nested);
+    ido.set_i128(&6, -170_000_000_000_000_000_000_000);
+    ido.set_bool(&7, true);
+
+    let bytes = ido.to_proto().encode_to_vec();
+    let decoded = Ido::from_proto(&IdoMessage::decode(bytes.as_slice()).unwrap()).unwrap();
+
+    assert_eq!(decoded.size(), 7);
+    assert_eq!(decoded.get_string(&1), Some(String::from("value1")));
+    assert_eq!(decoded.get_i64(&2), Some(-42));
+    assert_eq!(decoded.get_f64(&3), Some(2.5));
+    assert_eq!(decoded.get_item(&4).unwrap().m_datetime, datetime.m_datetime);
+
+    let array = decoded.get_item(&5).unwrap().m_array;
+    assert_eq!(array[0].get_string(&100), Some(String::from("nested")));
+    assert_eq!(decoded.get_i128(&6), Some(-170_000_000_000_000_000_000_000));
+    assert_eq!(decoded.get_bool(&7), Some(true));
+}
+
+#[test]
+fn test_proto_preserves_order() {
+    let mut ido = Ido::new();
+    ido.set_integer(&3, 1);
+    ido.set_integer(&1, 2);
+    ido.set_integer(&2, 3);
+
+    let keys: Vec<i64> = ido.to_proto().fields.iter().map(|field| field.key).collect();
+    assert_eq!(keys, vec![3, 1, 2]);
+}
+
+#[test]
+fn test_proto_missing_value() {
+    let message = IdoMessage {
+        fields: vec![IdoField { key: 7, value: None }],
+    };
+
+    assert!(matches!(Ido::from_proto(&message), Err(ProtoError::MissingValue(7))));
+}
+
+#[test]
+fn test_proto_invalid_integer128() {
+    let message = IdoMessage {
+        fields: vec![IdoField { key: 7, value: Some(Value::Integer128Value("12a".to_string())) }],
+    };
+
+    assert!(matches!(Ido::from_proto(&message), Err(ProtoError::InvalidInteger(7))));
+}
+
+#[test]
+fn test_proto_any_roundtrip() {
+    let mut ido = Ido::new();
+    ido.set_string(&1, String::from("value1"));
+    ido.set_i128(&2, i128::MIN);
+
+    let mut any = ido.to_any();
+    assert_eq!(any.type_url, "/ido.IdoMessage");
+    assert!(Ido::from_any(&any).unwrap() == ido);
+
+    any.type_url = "type.googleapis.com/ido.IdoMessage".to_string();
+    assert!(Ido::from_any(&any).unwrap() == ido);
+
+    let other = Any { type_url: "/google.protobuf.Struct".to_string(), value: any.value.clone() };
+    assert!(matches!(Ido::from_any(&other), Err(ProtoError::Decode(_))));
+}
+
+#[test]
+fn test_proto_struct_roundtrip() {
+    let names: IdoDictionary = [(1, "symbol"), (5, "legs")].into_iter().collect();
+
+    let mut leg = Ido::new();
+    leg.set_string(&1, String::from("EURUSD"));
+    leg.set_f64(&2, 1.25);
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, String::from("ORDER-1"));
+    ido.set_integer(&2, -42);
+    ido.set_bool(&3, true);
+    ido.append_array(&5, leg);
+
+    let message = ido.with_names(&names).to_struct();
+    assert_eq!(message.fields["symbol"].kind, Some(Kind::StringValue(String::from("ORDER-1"))));
+    assert_eq!(message.fields["2"].kind, Some(Kind::NumberValue(-42.0)));
+    assert_eq!(message.fields["3"].kind, Some(Kind::BoolValue(true)));
+    assert!(matches!(&message.fields["legs"].kind, Some(Kind::ListValue(list)) if list.values.len() == 1));
+    assert!(Ido::from_struct(&message, Some(&names)).unwrap() == ido);
+
+    let mut datetime = IdoItem::new();
+    datetime.m_type = IdoItemType::DATETIME;
+    datetime.m_datetime = Utc.with_ymd_and_hms(2023, 7, 1, 12, 30, 0).unwrap();
+    ido.set_item(&6, datetime);
+    ido.set_i128(&7, i128::MAX);
+
+    let message = ido.to_struct();
+    assert_eq!(message.fields["6"].kind, Some(Kind::StringValue(String::from("2023-07-01T12:30:00Z"))));
+    assert_eq!(message.fields["7"].kind, Some(Kind::StringValue(i128::MAX.to_string())));
+}
+
+#[test]
+fn test_proto_from_struct() {
+    let value = |kind| prost_types::Value { kind: Some(kind) };
+    let mut nested = Struct::default();
+    nested.fields.insert(String::from("1"), value(Kind::StringValue(String::from("inner"))));
+
+    let mut message = Struct::default();
+    message.fields.insert(String::from("1"), value(Kind::NumberValue(1.5)));
+    message.fields.insert(String::from("2"), value(Kind::NumberValue(1e300)));
+    message.fields.insert(String::from("3"), value(Kind::NullValue(0)));
+    message.fields.insert(String::from("4"), value(Kind::StructValue(nested.clone())));
+    message.fields.insert(
+        String::from("5"),
+        value(Kind::ListValue(ListValue {
+            values: vec![value(Kind::StructValue(nested)), value(Kind::NumberValue(7.0))],
+        })),
+    );
+    let ido = Ido::from_struct(&message, None).unwrap();
+
+    assert_eq!(ido.get_f64(&1), Some(1.5));
+    assert_eq!(ido.get_f64(&2), Some(1e300));
+    assert!(!ido.contains(&3));
+    assert_eq!(ido.get_item(&4).unwrap().m_array[0].get_string(&1), Some(String::from("inner")));
+
+    let list = ido.get_item(&5).unwrap().m_array;
+    assert_eq!(list[0].get_string(&1), Some(String::from("inner")));
+    assert_eq!(list[1].get_i64(&0), Some(7));
+
+    message.fields.insert(String::from("price"), value(Kind::NumberValue(1.0)));
+    assert_eq!(Ido::from_struct(&message, None).err(), Some(ProtoError::UnknownName(String::from("price"))));
+}
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use ido::ranges::{KeyRanges, RangePolicy};
+use ido::{Ido, IdoError, IdoKeyT};
+
+fn ranges() -> Arc<KeyRanges> {
+    Arc::new(
+        KeyRanges::new()
+            .reserve(0..1000, "framework")
+            .reserve(1000..=1999, "application")
+            .reserve(5000.., "framework"),
+    )
+}
+
+#[test]
+fn test_reserve_and_owner_of() {
+    let ranges = ranges();
+
+    assert_eq!(ranges.owner_of(&0), Some("framework"));
+    assert_eq!(ranges.owner_of(&999), Some("framework"));
+    assert_eq!(ranges.owner_of(&1000), Some("application"));
+    assert_eq!(ranges.owner_of(&1999), Some("application"));
+    assert_eq!(ranges.owner_of(&2000), None);
+    assert_eq!(ranges.owner_of(&IdoKeyT::MAX), Some("framework"));
+    assert_eq!(ranges.owner_of(&-1), None);
+
+    let framework: Vec<_> = ranges.ranges_of("framework").cloned().collect();
+    assert_eq!(framework, vec![0..=999, 5000..=IdoKeyT::MAX]);
+    assert_eq!(ranges.ranges_of("nobody").count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "overlap keys 0..=999 reserved for framework")]
+fn test_reserve_overlap() {
+    let _ = KeyRanges::new().reserve(0..1000, "framework").reserve(999..1200, "application");
+}
+
+#[test]
+#[should_panic(expected = "reserved key range for application is empty")]
+fn test_reserve_empty() {
+    let _ = KeyRanges::new().reserve(10..10, "application");
+}
+
+#[test]
+fn test_reject_foreign_keys() {
+    let mut ido = Ido::new();
+    ido.set_key_ranges(ranges(), "application", RangePolicy::Reject);
+
+    ido.set_integer(&1500, 1);
+    ido.set_integer(&3000, 2);
+    assert_eq!(ido.get_i64(&1500), Some(1));
+    assert_eq!(ido.get_i64(&3000), Some(2));
+
+    let item = ido.get_item(&1500).unwrap();
+    assert_eq!(
+        ido.try_set_item(&10, item),
+        Err(IdoError::ForeignKey { key: 10, owner: "framework".to_string() })
+    );
+    assert_eq!(
+        ido.try_append_array(&6000, Ido::new()),
+        Err(IdoError::ForeignKey { key: 6000, owner: "framework".to_string() })
+    );
+    assert!(!ido.contains(&10));
+    assert!(!ido.contains(&6000));
+    assert!(ido.foreign_writes().is_empty());
+
+    let (registry, owner) = ido.key_ranges().unwrap();
+    assert_eq!(owner, "application");
+    assert_eq!(registry.owner_of(&10), Some("framework"));
+}
+
+#[test]
+fn test_reject_skips_write_in_setters() {
+    let mut ido = Ido::new();
+    ido.set_key_ranges(ranges(), "application", RangePolicy::Reject);
+    ido.set_integer(&10, 1);
+    assert!(!ido.contains(&10));
+    assert_eq!(ido.take_rejected(), Some(IdoError::ForeignKey { key: 10, owner: "framework".to_string() }));
+}
+
+#[test]
+fn test_warn_records_foreign_keys() {
+    let mut ido = Ido::new();
+    ido.set_key_ranges(ranges(), "application", RangePolicy::Warn);
+
+    ido.set_integer(&10, 1);
+    ido.set_integer(&5, 2);
+    ido.set_integer(&10, 3);
+    ido.append_array(&6000, Ido::new());
+    ido.set_integer(&1500, 4);
+
+    assert_eq!(ido.get_i64(&10), Some(3));
+    assert_eq!(ido.get_item(&6000).unwrap().m_array.len(), 1);
+    assert_eq!(ido.foreign_writes(), vec![5, 10, 6000]);
+}
+
+#[test]
+fn test_delete_and_clear() {
+    let mut ido = Ido::new();
+    ido.set_integer(&10, 1);
+    ido.set_key_ranges(ranges(), "application", RangePolicy::Reject);
+
+    assert!(ido.try_set_item(&10, ido.get_item(&10).unwrap()).is_err());
+    ido.delete_item(&10);
+    assert!(!ido.contains(&10));
+
+    ido.clear_key_ranges();
+    assert!(ido.key_ranges().is_none());
+    ido.set_integer(&10, 2);
+    assert_eq!(ido.get_i64(&10), Some(2));
+}
@@ -0,0 +1,101 @@
+use ido::registry::{Migration, RegistryError, SchemaRegistry};
+use ido::schema::{IdoSchema, Violation};
+use ido::{Ido, IdoItemType};
+
+fn registry() -> SchemaRegistry {
+    let mut registry = SchemaRegistry::new();
+    registry
+        .register(
+            "quote",
+            1,
+            IdoSchema::new().required(1, IdoItemType::STRING).required(2, IdoItemType::FLOAT),
+            Migration::new(),
+        )
+        .unwrap();
+    registry
+        .register(
+            "quote",
+            2,
+            IdoSchema::new()
+                .required(1, IdoItemType::STRING)
+                .required(3, IdoItemType::FLOAT)
+                .required(4, IdoItemType::INTEGER),
+            Migration::new().rename(2, 3).default_value(4, 1i64),
+        )
+        .unwrap();
+    registry
+        .register(
+            "quote",
+            5,
+            IdoSchema::new()
+                .required(1, IdoItemType::STRING)
+                .required(3, IdoItemType::FLOAT)
+                .deny_unknown(),
+            Migration::new().remove(4),
+        )
+        .unwrap();
+    registry
+}
+
+#[test]
+fn test_upgrade_across_versions() {
+    let registry = registry();
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "EURUSD".to_string());
+    ido.set_f64(&2, 1.25);
+    assert_eq!(registry.upgrade("quote", 1, &mut ido), Ok(5));
+    assert_eq!(ido.to_string(), "1=EURUSD,3=1.25");
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "EURUSD".to_string());
+    ido.set_f64(&3, 1.5);
+    ido.set_integer(&4, 9);
+    assert_eq!(registry.upgrade("quote", 2, &mut ido), Ok(5));
+    assert_eq!(ido.to_string(), "1=EURUSD,3=1.5");
+
+    assert_eq!(registry.upgrade("quote", 5, &mut ido), Ok(5));
+}
+
+#[test]
+fn test_default_does_not_overwrite() {
+    let mut ido = Ido::new();
+    ido.set_integer(&4, 3);
+    Migration::new().default_value(4, 1i64).default_value(5, 2i64).apply(&mut ido);
+    assert_eq!(ido.to_string(), "4=3,5=2");
+}
+
+#[test]
+fn test_registry_lookups() {
+    let registry = registry();
+    assert_eq!(registry.latest("quote").map(|(version, _)| version), Some(5));
+    assert!(registry.schema("quote", 2).unwrap().is_required(&4));
+    assert!(registry.schema("quote", 3).is_none());
+    assert!(registry.latest("trade").is_none());
+}
+
+#[test]
+fn test_registry_errors() {
+    let mut registry = registry();
+    let mut ido = Ido::new();
+
+    assert_eq!(
+        registry.upgrade("trade", 1, &mut ido),
+        Err(RegistryError::UnknownType("trade".to_string()))
+    );
+    assert_eq!(
+        registry.upgrade("quote", 3, &mut ido),
+        Err(RegistryError::UnknownVersion("quote".to_string(), 3))
+    );
+    assert_eq!(
+        registry.upgrade("quote", 2, &mut ido),
+        Err(RegistryError::Invalid(vec![
+            Violation::Missing { key: 1, expected: IdoItemType::STRING },
+            Violation::Missing { key: 3, expected: IdoItemType::FLOAT },
+        ]))
+    );
+    assert_eq!(
+        registry.register("quote", 5, IdoSchema::new(), Migration::new()),
+        Err(RegistryError::VersionNotNewer { message_type: "quote".to_string(), version: 5, latest: 5 })
+    );
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "chrono")]
+
+use chrono::DateTime;
+use ido::sbe::{SbeBlock, SbeError, SbeMessage, SbeType};
+use ido::{Ido, IdoItem, IdoItemType};
+
+fn message() -> SbeMessage {
+    let entry = SbeBlock::new(11)
+        .field(270, 0, SbeType::Int64)
+        .optional(271, 8, SbeType::Int16)
+        .field(279, 10, SbeType::UInt8);
+    let root = SbeBlock::new(21)
+        .field(60, 0, SbeType::TimestampNanos)
+        .field(55, 8, SbeType::Char(4))
+        .optional(31, 12, SbeType::Double)
+        .optional(32, 20, SbeType::UInt8)
+        .group(268, entry)
+        .var_data(58);
+    SbeMessage::new(46, root).schema(1, 9)
+}
+
+fn sample() -> Ido {
+    let mut stamp = IdoItem::new();
+    stamp.m_type = IdoItemType::DATETIME;
+    stamp.m_datetime = DateTime::from_timestamp(1_688_214_600, 123).unwrap();
+
+    let mut ido = Ido::new();
+    ido.set_item(&60, stamp);
+    ido.set_string(&55, "ESZ3".to_string());
+    ido.set_f64(&31, 4500.25);
+    for (price, size) in [(450_025, Some(10)), (450_000, None)] {
+        let mut entry = Ido::new();
+        entry.set_integer(&270, price);
+        if let Some(size) = size {
+            entry.set_integer(&271, size);
+        }
+        entry.set_integer(&279, 0);
+        ido.append_array(&268, entry);
+    }
+    ido.set_string(&58, "open".to_string());
+    ido
+}
+
+#[test]
+fn test_sbe_round_trip() {
+    let bytes = message().encode(&sample()).unwrap();
+    assert_eq!(&bytes[..8], &[21, 0, 46, 0, 1, 0, 9, 0]);
+    assert_eq!(bytes.len(), 8 + 21 + 4 + 2 * 11 + 2 + 4);
+
+    let decoded = message().decode(&bytes).unwrap();
+    assert_eq!(decoded.diff_report(&sample()), "");
+    assert!(!decoded.contains(&32));
+    assert!(!decoded.get_item(&268).unwrap().m_array[1].contains(&271));
+}
+
+#[test]
+fn test_sbe_longer_block() {
+    let mut bytes = message().encode(&sample()).unwrap();
+    // A newer schema version appended two bytes to the root block.
+    bytes[0] = 23;
+    bytes.splice(8 + 21..8 + 21, [0xaa, 0xbb]);
+
+    let decoded = message().decode(&bytes).unwrap();
+    assert_eq!(decoded.diff_report(&sample()), "");
+}
+
+#[test]
+fn test_sbe_unsigned_64() {
+    let message = SbeMessage::new(1, SbeBlock::new(8).field(1, 0, SbeType::UInt64));
+
+    let mut ido = Ido::new();
+    ido.set_i128(&1, i128::from(u64::MAX - 1));
+    let decoded = message.decode(&message.encode(&ido).unwrap()).unwrap();
+    assert_eq!(decoded.get_i128(&1), Some(i128::from(u64::MAX - 1)));
+    assert!(decoded.is_type(&1, &IdoItemType::INTEGER128));
+}
+
+#[test]
+fn test_sbe_errors() {
+    let mut ido = sample();
+    ido.delete_item(&55);
+    assert_eq!(message().encode(&ido).err(), Some(SbeError::MissingField(55)));
+
+    let mut ido = sample();
+    ido.set_string(&55, "TOO LONG".to_string());
+    assert_eq!(message().encode(&ido).err(), Some(SbeError::InvalidValue(55)));
+
+    let mut ido = sample();
+    ido.set_integer(&32, 256);
+    assert_eq!(message().encode(&ido).err(), Some(SbeError::InvalidValue(32)));
+
+    let bytes = message().encode(&sample()).unwrap();
+    assert_eq!(message().decode(&bytes[..bytes.len() - 1]).err(), Some(SbeError::Truncated));
+
+    let other = SbeMessage::new(47, SbeBlock::new(0));
+    assert_eq!(other.decode(&bytes).err(), Some(SbeError::TemplateMismatch { expected: 47, actual: 46 }));
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert_eq!(message().decode(&trailing).err(), Some(SbeError::TrailingBytes(1)));
+}
+
+#[test]
+#[should_panic(expected = "field 2 does not fit")]
+fn test_sbe_field_outside_block() {
+    SbeBlock::new(4).field(2, 2, SbeType::Int32);
+}
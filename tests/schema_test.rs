@@ -0,0 +1,176 @@
+#[cfg(feature = "chrono")]
+use chrono::{Duration, Utc};
+use ido::schema::{Constraint, IdoSchema, Severity, Violation};
+use ido::{Ido, IdoItemType};
+#[cfg(feature = "chrono")]
+use ido::IdoItem;
+
+fn order_schema() -> IdoSchema {
+    IdoSchema::new()
+        .required(1, IdoItemType::STRING)
+        .required(2, IdoItemType::INTEGER)
+        .optional(3, IdoItemType::FLOAT)
+}
+
+#[test]
+fn test_validate_ok() {
+    let mut ido = Ido::new();
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.set_integer(&2, 100);
+    ido.set_integer(&99, 1);
+
+    assert!(ido.validate(&order_schema()).is_ok());
+    assert!(order_schema().is_required(&1));
+    assert!(!order_schema().is_required(&3));
+    assert_eq!(order_schema().type_of(&3), Some(IdoItemType::FLOAT));
+}
+
+#[test]
+fn test_validate_reports_all_violations() {
+    let mut ido = Ido::new();
+    ido.set_integer(&99, 1);
+    ido.set_string(&2, "100".to_string());
+    ido.set_string(&3, "1.5".to_string());
+
+    let violations = ido.validate(&order_schema().deny_unknown()).unwrap_err();
+    assert_eq!(
+        violations,
+        vec![
+            Violation::Missing { key: 1, expected: IdoItemType::STRING },
+            Violation::WrongType { key: 2, expected: IdoItemType::INTEGER, actual: IdoItemType::STRING },
+            Violation::WrongType { key: 3, expected: IdoItemType::FLOAT, actual: IdoItemType::STRING },
+            Violation::Unknown(99),
+        ]
+    );
+    assert_eq!(violations[1].to_string(), "key 2 is STRING, expected INTEGER");
+}
+
+#[test]
+fn test_validate_array_elements() {
+    let leg_schema = IdoSchema::new().required(1, IdoItemType::STRING);
+    let schema = IdoSchema::new().array_of(5, true, leg_schema);
+
+    let mut good = Ido::new();
+    good.set_string(&1, "EURUSD".to_string());
+
+    let mut ido = Ido::new();
+    ido.append_array(&5, good);
+    ido.append_array(&5, Ido::new());
+
+    let violations = ido.validate(&schema).unwrap_err();
+    assert_eq!(
+        violations,
+        vec![Violation::Element {
+            key: 5,
+            index: 1,
+            violation: Box::new(Violation::Missing { key: 1, expected: IdoItemType::STRING }),
+        }]
+    );
+    assert_eq!(violations[0].to_string(), "element 1 of key 5: key 1 is missing, expected STRING");
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_constraints() {
+    let now = Utc::now();
+    let schema = IdoSchema::new()
+        .required(1, IdoItemType::INTEGER)
+        .constrain(1, Constraint::integer(1..=100))
+        .required(2, IdoItemType::FLOAT)
+        .constrain(2, Constraint::float(0.0..1.0))
+        .optional(3, IdoItemType::STRING)
+        .constrain(3, Constraint::length(2..=4))
+        .constrain(4, Constraint::datetime(now - Duration::hours(1)..now));
+
+    let mut stamp = IdoItem::new();
+    stamp.m_type = IdoItemType::DATETIME;
+    stamp.m_datetime = now - Duration::minutes(5);
+
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 100);
+    ido.set_f64(&2, 0.5);
+    ido.set_string(&3, "ab".to_string());
+    ido.set_item(&4, stamp.clone());
+    assert!(ido.validate(&schema).is_ok());
+
+    stamp.m_datetime = now + Duration::minutes(5);
+    ido.set_integer(&1, 101);
+    ido.set_f64(&2, 1.0);
+    ido.set_string(&3, "abcde".to_string());
+    ido.set_item(&4, stamp);
+
+    let violations: Vec<String> = ido.validate(&schema).unwrap_err().iter().map(|v| v.to_string()).collect();
+    assert_eq!(violations.len(), 4);
+    assert_eq!(violations[0], "key 1 violates integer in 1..=100");
+    assert_eq!(violations[1], "key 2 violates float in 0..1");
+    assert_eq!(violations[2], "key 3 violates length in 2..=4");
+    assert!(violations[3].starts_with("key 4 violates datetime in "));
+}
+
+#[test]
+fn test_constraint_type_mismatch() {
+    let schema = IdoSchema::new().constrain(1, Constraint::integer(..10));
+
+    let mut ido = Ido::new();
+    ido.set_f64(&1, 5.0);
+    assert_eq!(
+        ido.validate(&schema).unwrap_err(),
+        vec![Violation::Constraint { key: 1, constraint: "integer in ..10".to_string() }]
+    );
+
+    let array_schema = IdoSchema::new().constrain(2, Constraint::length(1..));
+    let mut ido = Ido::new();
+    ido.append_array(&2, Ido::new());
+    assert!(ido.validate(&array_schema).is_ok());
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_pattern_constraint() {
+    let schema = IdoSchema::new()
+        .required(1, IdoItemType::STRING)
+        .constrain(1, Constraint::pattern("[A-Z]{3}/[A-Z]{3}").unwrap());
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "EUR/USD".to_string());
+    assert!(ido.validate(&schema).is_ok());
+
+    ido.set_string(&1, "EUR/USDX".to_string());
+    assert_eq!(
+        ido.validate(&schema).unwrap_err()[0].to_string(),
+        "key 1 violates string matching [A-Z]{3}/[A-Z]{3}"
+    );
+    assert!(Constraint::pattern("(").is_err());
+}
+
+#[test]
+fn test_validation_report() {
+    let schema = order_schema().deny_unknown().severity(3, Severity::Warning).severity(99, Severity::Warning);
+    assert_eq!(schema.severity_of(&1), Severity::Error);
+    assert_eq!(schema.severity_of(&3), Severity::Warning);
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.set_integer(&2, 100);
+    assert!(ido.validation_report(&schema).is_valid());
+
+    ido.set_string(&3, "1.5".to_string());
+    ido.set_integer(&99, 1);
+    let report = ido.validation_report(&schema);
+    assert!(!report.is_valid());
+    assert!(!report.is_fatal());
+    assert_eq!(report.invalid_keys(), vec![3, 99]);
+    assert_eq!(report.to_string(), "warning: key 3 is STRING, expected FLOAT\nwarning: key 99 is not in the schema");
+    assert_eq!(report.valid_subset(&ido).to_string(), "1=ORDER-1,2=100");
+    assert!(ido.validate(&schema).is_err());
+
+    ido.delete_item(&1);
+    let report = ido.validation_report(&schema);
+    assert!(report.is_fatal());
+    let fatal: Vec<&Violation> = report.with_severity(Severity::Error).map(|issue| issue.violation()).collect();
+    assert_eq!(fatal, [&Violation::Missing { key: 1, expected: IdoItemType::STRING }]);
+    assert_eq!(report.issues()[0].key(), 1);
+    assert!(report.issues()[0].is_fatal());
+    assert_eq!(report.valid_subset(&ido).to_string(), "2=100");
+    assert_eq!(report.into_violations(), ido.validate(&schema).unwrap_err());
+}
@@ -0,0 +1,71 @@
+use ido::codec::CodecError;
+use ido::shared::SharedIdo;
+use ido::{Ido, IdoItem, IdoKeyT};
+
+fn sample() -> Ido {
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.set_integer(&2, 100);
+    ido.set_string(&3, String::new());
+    ido.append_array(&4, leg);
+    ido
+}
+
+#[test]
+fn test_shared_reads() {
+    let shared = SharedIdo::decode(sample().encode()).unwrap();
+
+    assert_eq!(shared.size(), 4);
+    assert_eq!(shared.keys(), [1, 2, 3, 4]);
+    assert!(shared.contains(&2));
+    assert!(shared.is_shared(&1));
+    assert!(shared.is_shared(&3));
+    assert!(!shared.is_shared(&2));
+
+    assert_eq!(shared.get_str(&1), Some("ORDER-1"));
+    assert_eq!(shared.get_str(&3), Some(""));
+    assert_eq!(shared.get_str(&2), None);
+    assert_eq!(shared.get_string(&2), Some("100".to_string()));
+    assert_eq!(shared.get_item(&1).unwrap().m_string, "ORDER-1");
+    assert_eq!(shared.get_item(&4).unwrap().m_array[0].get_string(&1), Some("EURUSD".to_string()));
+    assert!(shared.get_item(&5).is_none());
+}
+
+#[test]
+fn test_shared_mutation_and_conversion() {
+    let original = sample();
+    let mut shared = SharedIdo::decode(original.encode()).unwrap();
+    assert!(shared.into_ido() == original);
+
+    shared = SharedIdo::decode(original.encode()).unwrap();
+    shared.set_string(&1, "ORDER-2".to_string());
+    assert!(!shared.is_shared(&1));
+    assert_eq!(shared.get_str(&1), Some("ORDER-2"));
+    assert_eq!(shared.keys(), [2, 3, 4, 1]);
+
+    assert!(shared.delete_item(&3));
+    assert!(!shared.delete_item(&3));
+    shared.set_item(&5, IdoItem::new());
+
+    let ido = shared.into_ido();
+    let keys: Vec<IdoKeyT> = ido.into_ordered_iterator().map(|(key, _)| key).collect();
+    assert_eq!(keys, [2, 4, 1, 5]);
+}
+
+#[test]
+fn test_shared_decode_errors() {
+    let mut bytes = sample().encode();
+    let at = bytes.windows(7).position(|window| window == b"ORDER-1").unwrap();
+    bytes[at] = 0xff;
+    assert!(matches!(SharedIdo::decode(bytes.clone()), Err(CodecError::InvalidUtf8)));
+
+    bytes.truncate(at + 2);
+    assert!(matches!(SharedIdo::decode(bytes), Err(CodecError::UnexpectedEof)));
+
+    let mut bytes = sample().encode();
+    bytes.push(0);
+    assert!(matches!(SharedIdo::decode(bytes), Err(CodecError::TrailingBytes(1))));
+}
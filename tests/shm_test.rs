@@ -0,0 +1,134 @@
+#![cfg(feature = "shm")]
+
+use std::fs::OpenOptions;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use ido::shm::{ShmError, ShmRingConsumer, ShmRingProducer};
+use ido::Ido;
+
+fn ring_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("ido-ring-{}-{}", name, std::process::id()))
+}
+
+/// Overwrites the file at `path` from `offset` on, as another process
+/// sharing the ring could.
+fn corrupt(path: &Path, offset: u64, bytes: &[u8]) {
+    let mut file = OpenOptions::new().write(true).open(path).unwrap();
+    file.seek(SeekFrom::Start(offset)).unwrap();
+    file.write_all(bytes).unwrap();
+}
+
+fn message(value: i64) -> Ido {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, value);
+    ido.set_string(&2, format!("payload {}", value));
+    ido
+}
+
+#[test]
+fn test_ring_send_recv() {
+    let path = ring_path("basic");
+    let mut producer = ShmRingProducer::create(&path, 1024).unwrap();
+    let mut consumer = ShmRingConsumer::open(&path).unwrap();
+
+    assert!(consumer.try_recv().unwrap().is_none());
+
+    assert!(producer.try_send(&message(1)).unwrap());
+    assert!(producer.try_send(&message(2)).unwrap());
+
+    assert_eq!(consumer.try_recv().unwrap().unwrap().get_i64(&1), Some(1));
+    assert_eq!(consumer.try_recv().unwrap().unwrap().get_i64(&1), Some(2));
+    assert!(consumer.try_recv().unwrap().is_none());
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_ring_full_and_too_large() {
+    let path = ring_path("full");
+    let mut producer = ShmRingProducer::create(&path, 64).unwrap();
+    let mut consumer = ShmRingConsumer::open(&path).unwrap();
+
+    assert!(producer.try_send(&message(1)).unwrap());
+    assert!(!producer.try_send(&message(2)).unwrap());
+
+    consumer.try_recv().unwrap().unwrap();
+    assert!(producer.try_send(&message(2)).unwrap());
+
+    let mut large = Ido::new();
+    large.set_string(&1, "x".repeat(100));
+    assert!(matches!(producer.try_send(&large), Err(ShmError::TooLarge(_))));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_ring_wraps_across_threads() {
+    let path = ring_path("threads");
+    let mut producer = ShmRingProducer::create(&path, 256).unwrap();
+    let mut consumer = ShmRingConsumer::open(&path).unwrap();
+
+    let handle = thread::spawn(move || {
+        for value in 0..1000 {
+            producer.send(&message(value)).unwrap();
+        }
+    });
+
+    for value in 0..1000 {
+        let ido = consumer.recv().unwrap();
+        assert_eq!(ido.get_i64(&1), Some(value));
+        assert_eq!(ido.get_string(&2), Some(format!("payload {}", value)));
+    }
+    handle.join().unwrap();
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_ring_rejects_foreign_file() {
+    let path = ring_path("foreign");
+    std::fs::write(&path, vec![0u8; 512]).unwrap();
+
+    assert!(matches!(ShmRingConsumer::open(&path), Err(ShmError::InvalidLayout)));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_ring_rejects_corrupted_header() {
+    const HEAD: u64 = 64;
+    const TAIL: u64 = 128;
+    const DATA: u64 = 192;
+
+    let path = ring_path("corrupted");
+    let mut producer = ShmRingProducer::create(&path, 64).unwrap();
+    let mut consumer = ShmRingConsumer::open(&path).unwrap();
+    assert!(producer.try_send(&message(1)).unwrap());
+    let head = std::fs::read(&path).unwrap()[HEAD as usize..HEAD as usize + 8].to_vec();
+
+    // Tail ahead of head.
+    corrupt(&path, TAIL, &u64::MAX.to_ne_bytes());
+    assert!(matches!(consumer.try_recv(), Err(ShmError::InvalidLayout)));
+
+    // More pending than the ring can hold.
+    corrupt(&path, TAIL, &0u64.to_ne_bytes());
+    corrupt(&path, HEAD, &1_000_000u64.to_ne_bytes());
+    assert!(matches!(consumer.try_recv(), Err(ShmError::InvalidLayout)));
+
+    // Too little pending for a length prefix.
+    corrupt(&path, HEAD, &2u64.to_ne_bytes());
+    assert!(matches!(consumer.try_recv(), Err(ShmError::InvalidLayout)));
+
+    // A length prefix beyond the pending bytes and the capacity.
+    corrupt(&path, HEAD, &head);
+    corrupt(&path, DATA, &u32::MAX.to_ne_bytes());
+    assert!(matches!(consumer.try_recv(), Err(ShmError::InvalidLayout)));
+
+    // A capacity larger than the file.
+    corrupt(&path, 8, &u64::MAX.to_ne_bytes());
+    assert!(matches!(ShmRingConsumer::open(&path), Err(ShmError::InvalidLayout)));
+
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,76 @@
+use std::sync::{Arc, Mutex};
+
+use ido::state::IdoStateMachine;
+use ido::{ArrayMerge, Ido, IdoKeyT};
+
+fn delta(order: &str, key: IdoKeyT, quantity: i64) -> Ido {
+    let mut ido = Ido::new();
+    ido.set_string(&1, order.to_string());
+    ido.set_integer(&key, quantity);
+    ido
+}
+
+#[test]
+fn test_state_machine_apply() {
+    let mut orders = IdoStateMachine::new(1);
+    assert!(orders.is_empty());
+
+    orders.apply(&delta("A", 2, 100)).unwrap();
+    orders.apply(&delta("B", 2, 50)).unwrap();
+    let state = orders.apply(&delta("A", 3, 40)).unwrap();
+    assert_eq!(state.get_i64(&2), Some(100));
+    assert_eq!(state.get_i64(&3), Some(40));
+    assert!(state.dirty_keys().is_empty());
+
+    orders.apply(&delta("A", 2, 60)).unwrap();
+    assert_eq!(orders.len(), 2);
+    assert_eq!(orders.get("A").unwrap().get_i64(&2), Some(60));
+    assert_eq!(orders.get("B").unwrap().size(), 2);
+
+    let entities: Vec<&str> = orders.iter().map(|(entity, _)| entity).collect();
+    assert_eq!(entities, ["A", "B"]);
+
+    assert!(orders.remove("B").is_some());
+    assert!(orders.get("B").is_none());
+}
+
+#[test]
+fn test_state_machine_missing_entity() {
+    let mut orders = IdoStateMachine::new(1);
+    let mut anonymous = Ido::new();
+    anonymous.set_integer(&2, 100);
+
+    let deltas = [delta("A", 2, 100), anonymous, delta("B", 2, 100)];
+    let error = orders.apply_all(&deltas).unwrap_err();
+    assert_eq!(error.keys(), [1]);
+    assert_eq!(orders.len(), 1);
+}
+
+#[test]
+fn test_state_machine_array_merge() {
+    let mut orders = IdoStateMachine::new(1).array_merge(ArrayMerge::Append);
+    let mut fill = Ido::new();
+    fill.set_integer(&7, 10);
+
+    let mut first = delta("A", 2, 100);
+    first.append_array(&5, fill.clone());
+    let mut second = delta("A", 2, 100);
+    second.append_array(&5, fill);
+
+    orders.apply_all([&first, &second]).unwrap();
+    assert_eq!(orders.get("A").unwrap().get_item(&5).unwrap().m_array.len(), 2);
+}
+
+#[test]
+fn test_state_machine_callbacks() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let mut orders = IdoStateMachine::new(1);
+    let log = Arc::clone(&seen);
+    orders.on_apply(move |entity, delta, state| {
+        log.lock().unwrap().push((entity.to_string(), delta.size(), state.size()));
+    });
+
+    orders.apply(&delta("A", 2, 100)).unwrap();
+    orders.apply(&delta("A", 3, 40)).unwrap();
+    assert_eq!(*seen.lock().unwrap(), [("A".to_string(), 2, 2), ("A".to_string(), 2, 3)]);
+}
@@ -0,0 +1,95 @@
+#[cfg(feature = "chrono")]
+use chrono::Utc;
+use ido::stats::{IdoStats, OpStats};
+use ido::Ido;
+#[cfg(feature = "chrono")]
+use ido::{IdoItem, IdoItemType};
+
+#[test]
+fn test_stats_empty() {
+    assert_eq!(Ido::new().stats(), IdoStats::default());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_stats_nested() {
+    let mut inner = Ido::new();
+    inner.set_f64(&1, 1.5);
+    inner.set_string(&2, "ab".to_string());
+
+    let mut middle = Ido::new();
+    let mut stamp = IdoItem::new();
+    stamp.m_type = IdoItemType::DATETIME;
+    stamp.m_datetime = Utc::now();
+    middle.set_item(&1, stamp);
+    middle.append_array(&2, inner.clone());
+    middle.append_array(&2, inner);
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "abcd".to_string());
+    ido.set_integer(&2, 3);
+    ido.append_array(&3, middle);
+
+    let stats = ido.stats();
+    assert_eq!(
+        stats,
+        IdoStats {
+            strings: 3,
+            integers: 1,
+            floats: 2,
+            datetimes: 1,
+            bools: 0,
+            arrays: 2,
+            array_elements: 3,
+            string_bytes: 8,
+            max_depth: 2,
+        }
+    );
+    assert_eq!(stats.items(), 9);
+    assert_eq!(ido.deep_len(), stats.items());
+    assert_eq!(ido.depth(), stats.max_depth);
+}
+
+#[test]
+fn test_deep_len_and_depth() {
+    let mut ido = Ido::new();
+    assert_eq!((ido.deep_len(), ido.depth()), (0, 0));
+
+    ido.set_integer(&1, 1);
+    let mut leg = Ido::new();
+    leg.set_integer(&1, 5);
+    ido.append_array(&2, leg);
+    assert_eq!((ido.deep_len(), ido.depth()), (3, 1));
+
+    let mut nested = Ido::new();
+    nested.append_array(&1, ido.clone());
+    nested.append_array(&1, ido);
+    assert_eq!((nested.deep_len(), nested.depth()), (7, 2));
+}
+
+#[test]
+fn test_op_stats() {
+    let mut ido = Ido::new();
+    assert_eq!(ido.op_stats(), OpStats::default());
+
+    ido.set_integer(&1, 10);
+    ido.set_string(&2, "EURUSD".to_string());
+    ido.append_array(&3, Ido::new());
+    ido.append_array(&3, Ido::new());
+    ido.get_i64(&1);
+    ido.get_string(&9);
+    ido.delete_item(&9);
+    ido.delete_range(1..3);
+    let _copy = ido.clone();
+    let _again = ido.clone();
+
+    assert_eq!(ido.op_stats(), OpStats { sets: 4, gets: 2, clones: 2, deletes: 2 });
+
+    ido.reset_op_stats();
+    ido.clear();
+    assert_eq!(ido.op_stats(), OpStats { deletes: 1, ..OpStats::default() });
+
+    ido.set_integer(&1, 10);
+    let decoded = Ido::decode(&ido.encode()).unwrap();
+    assert_eq!(decoded.op_stats(), OpStats { sets: 1, ..OpStats::default() });
+}
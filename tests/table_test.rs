@@ -0,0 +1,89 @@
+use ido::dictionary::IdoDictionary;
+use ido::Ido;
+
+fn orders() -> Ido {
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+
+    let mut first = Ido::new();
+    first.set_string(&1, "ORDER-1".to_string());
+    first.set_integer(&2, 100);
+    first.append_array(&4, leg);
+
+    let mut second = Ido::new();
+    second.set_f64(&3, 1.5);
+    second.set_string(&1, "ORDER-2".to_string());
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "BATCH-1".to_string());
+    ido.append_array(&5, first);
+    ido.append_array(&5, second);
+    ido
+}
+
+#[test]
+fn test_to_table() {
+    let table = orders().to_table(&5).unwrap();
+    assert_eq!(
+        table,
+        "1        2    4             3\n\
+         -------  ---  ------------  ---\n\
+         ORDER-1  100  <array of 1>\n\
+         ORDER-2                     1.5\n"
+    );
+}
+
+#[test]
+fn test_to_table_with_names() {
+    let names: IdoDictionary = [(1, "ORDER_ID"), (3, "PRICE")].into_iter().collect();
+    let table = orders().with_names(&names).to_table(&5).unwrap();
+    assert_eq!(
+        table,
+        "ORDER_ID  2    4             PRICE\n\
+         --------  ---  ------------  -----\n\
+         ORDER-1   100  <array of 1>\n\
+         ORDER-2                      1.5\n"
+    );
+}
+
+#[test]
+fn test_to_table_not_an_array() {
+    let ido = orders();
+    assert!(ido.to_table(&1).is_none());
+    assert!(ido.to_table(&9).is_none());
+
+    let mut empty = Ido::new();
+    empty.set_item(&5, ido.get_item(&5).map(|mut item| {
+        item.m_array.clear();
+        item
+    }).unwrap());
+    assert_eq!(empty.to_table(&5).unwrap(), "");
+}
+
+#[test]
+fn test_array_to_csv() {
+    let mut ido = orders();
+    let mut third = Ido::new();
+    third.set_string(&1, "say \"hi\",\nthen leave".to_string());
+    ido.append_array(&5, third);
+
+    let mut out = Vec::new();
+    assert_eq!(ido.array_to_csv(&5, &mut out).unwrap(), 3);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "1,2,4,3\n\
+         ORDER-1,100,<array of 1>,\n\
+         ORDER-2,,,1.5\n\
+         \"say \"\"hi\"\",\nthen leave\",,,\n"
+    );
+
+    let names: IdoDictionary = [(1, "ORDER_ID"), (3, "PRICE, USD")].into_iter().collect();
+    let mut out = Vec::new();
+    assert_eq!(ido.with_names(&names).array_to_csv(&5, &mut out).unwrap(), 3);
+    assert!(String::from_utf8(out).unwrap().starts_with("ORDER_ID,2,4,\"PRICE, USD\"\nORDER-1,"));
+
+    let mut out = Vec::new();
+    assert_eq!(ido.array_to_csv(&1, &mut out).unwrap(), 0);
+    assert_eq!(ido.array_to_csv(&9, &mut out).unwrap(), 0);
+    assert!(out.is_empty());
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "chrono")]
+
+use chrono::{TimeZone, Utc};
+use ido::codec::{CodecError, Decode, Encode};
+use ido::tlv::{TagWidth, TlvCodec};
+use ido::{Ido, IdoItem, IdoItemType};
+
+fn codec() -> TlvCodec {
+    TlvCodec::new()
+        .field(2, IdoItemType::INTEGER)
+        .field(3, IdoItemType::FLOAT)
+        .field(4, IdoItemType::DATETIME)
+        .field(5, IdoItemType::ARRAY)
+        .field(6, IdoItemType::INTEGER128)
+}
+
+fn encode(codec: &TlvCodec, ido: &Ido) -> Result<Vec<u8>, CodecError> {
+    let mut bytes = Vec::new();
+    codec.encode(ido, &mut bytes)?;
+    Ok(bytes)
+}
+
+#[test]
+fn test_tlv_round_trip() {
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+    leg.set_integer(&2, -1);
+
+    let mut stamp = IdoItem::new();
+    stamp.m_type = IdoItemType::DATETIME;
+    stamp.m_datetime = Utc.with_ymd_and_hms(2023, 7, 1, 12, 30, 0).unwrap();
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.set_integer(&2, i64::MIN);
+    ido.set_f64(&3, 101.25);
+    ido.set_item(&4, stamp);
+    ido.append_array(&5, leg.clone());
+    ido.append_array(&5, leg);
+    ido.set_i128(&6, i128::MAX);
+
+    let bytes = encode(&codec(), &ido).unwrap();
+    let decoded = codec().decode(&bytes).unwrap();
+    assert_eq!(decoded.diff_report(&ido), "");
+}
+
+#[test]
+fn test_tlv_minimal_integers() {
+    let mut ido = Ido::new();
+    for (key, value) in [(2, 0), (7, 127), (8, 128), (9, -128), (10, -129)] {
+        ido.set_integer(&key, value);
+    }
+
+    let bytes = encode(&TlvCodec::new().tag_width(TagWidth::U8), &ido).unwrap();
+    assert_eq!(bytes, [2, 1, 0x00, 7, 1, 0x7f, 8, 2, 0x00, 0x80, 9, 1, 0x80, 10, 2, 0xff, 0x7f]);
+}
+
+#[test]
+fn test_tlv_long_value_varint() {
+    let mut ido = Ido::new();
+    ido.set_string(&1, "x".repeat(300));
+
+    let bytes = encode(&TlvCodec::new(), &ido).unwrap();
+    assert_eq!(&bytes[..4], &[0x00, 0x01, 0xac, 0x02]);
+    assert_eq!(TlvCodec::new().decode(&bytes).unwrap().get_string(&1), Some("x".repeat(300)));
+}
+
+#[test]
+fn test_tlv_tag_width() {
+    let mut ido = Ido::new();
+    ido.set_string(&256, "a".to_string());
+
+    assert!(matches!(encode(&TlvCodec::new().tag_width(TagWidth::U8), &ido), Err(CodecError::Unsupported(256))));
+
+    let mut negative = Ido::new();
+    negative.set_string(&-1, "a".to_string());
+    assert!(matches!(encode(&TlvCodec::new(), &negative), Err(CodecError::Unsupported(-1))));
+
+    let codec = TlvCodec::new().tag_width(TagWidth::U32);
+    let bytes = encode(&codec, &negative).unwrap();
+    assert_eq!(bytes, [0xff, 0xff, 0xff, 0xff, 1, b'a']);
+    assert_eq!(codec.decode(&bytes).unwrap().get_string(&-1), Some("a".to_string()));
+}
+
+#[test]
+fn test_tlv_decode_errors() {
+    let codec = codec();
+    assert!(matches!(codec.decode(&[0, 1, 5, b'a']), Err(CodecError::UnexpectedEof)));
+    assert!(matches!(codec.decode(&[0, 2, 9, 0, 0, 0, 0, 0, 0, 0, 0, 1]), Err(CodecError::InvalidValue(2))));
+    assert!(matches!(codec.decode(&[0, 3, 1, 0]), Err(CodecError::InvalidValue(3))));
+    assert!(matches!(codec.decode(&[0, 1, 1, 0xff]), Err(CodecError::InvalidUtf8)));
+    assert!(codec.decode(&[]).unwrap().size() == 0);
+}
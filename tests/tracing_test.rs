@@ -0,0 +1,76 @@
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+
+use ido::Ido;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::with_default;
+use tracing::{Event, Metadata, Subscriber};
+
+/// Collects span names and their recorded fields as `name field=value` strings.
+#[derive(Clone, Default)]
+struct Recorder {
+    m_spans: Arc<Mutex<Vec<String>>>,
+}
+
+struct FieldWriter<'a>(&'a mut String);
+
+impl Visit for FieldWriter<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push_str(&format!(" {}={:?}", field.name(), value));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut spans = self.m_spans.lock().unwrap();
+        let mut line = span.metadata().name().to_string();
+        span.record(&mut FieldWriter(&mut line));
+        spans.push(line);
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.m_spans.lock().unwrap();
+        let line = &mut spans[span.into_u64() as usize - 1];
+        values.record(&mut FieldWriter(line));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+    fn event(&self, _: &Event<'_>) {}
+    fn enter(&self, _: &Id) {}
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn test_spans() {
+    let recorder = Recorder::default();
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 42);
+    ido.set_string(&2, "ABC".to_string());
+
+    with_default(recorder.clone(), || {
+        let bytes = ido.encode();
+        let decoded = Ido::decode(&bytes).unwrap();
+
+        let mut target = Ido::new();
+        target.update(&decoded);
+        let _ = target.to_string();
+    });
+
+    let spans = recorder.m_spans.lock().unwrap();
+    assert_eq!(
+        *spans,
+        vec![
+            "ido.encode items=2 bytes=23",
+            "ido.decode bytes=23 items=2",
+            "ido.update items=2",
+            "ido.to_string items=2",
+        ]
+    );
+}
@@ -0,0 +1,65 @@
+use ido::{Ido, IdoError};
+
+#[test]
+fn test_rollback_restores_state() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 10);
+    ido.set_integer(&2, 20);
+    ido.set_integer(&3, 30);
+
+    ido.begin().unwrap();
+    assert!(ido.in_transaction());
+
+    ido.set_integer(&1, 11);
+    ido.set_integer(&1, 12);
+    ido.delete_item(&2);
+    ido.set_string(&4, "new".to_string());
+    ido.append_array(&3, Ido::new());
+
+    ido.rollback().unwrap();
+
+    assert!(!ido.in_transaction());
+    assert_eq!(ido.to_string(), "1=10,2=20,3=30");
+}
+
+#[test]
+fn test_commit_keeps_changes() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 10);
+
+    ido.begin().unwrap();
+    ido.set_integer(&1, 11);
+    ido.set_integer(&2, 20);
+    ido.commit().unwrap();
+
+    assert_eq!(ido.to_string(), "1=11,2=20");
+    assert_eq!(ido.rollback(), Err(IdoError::NoTransaction));
+}
+
+#[test]
+fn test_transaction_errors() {
+    let mut ido = Ido::new();
+
+    assert_eq!(ido.commit(), Err(IdoError::NoTransaction));
+    assert_eq!(ido.rollback(), Err(IdoError::NoTransaction));
+
+    ido.begin().unwrap();
+    assert_eq!(ido.begin(), Err(IdoError::TransactionActive));
+}
+
+#[test]
+fn test_rollback_after_clear() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 10);
+    ido.set_integer(&2, 20);
+
+    ido.begin().unwrap();
+    ido.clear();
+    ido.set_integer(&5, 50);
+    ido.rollback().unwrap();
+
+    assert_eq!(ido.size(), 2);
+    assert_eq!(ido.get_i64(&1), Some(10));
+    assert_eq!(ido.get_i64(&2), Some(20));
+    assert!(!ido.contains(&5));
+}
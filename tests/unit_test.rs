@@ -1,4 +1,9 @@
-use ido::{Ido, IdoKeyT, IdoItemType, IdoItem};
+#![allow(clippy::approx_constant, clippy::assertions_on_constants)]
+
+#[cfg(feature = "chrono")]
+use chrono::Utc;
+use ido::{ArrayMerge, FloatPolicy, Ido, IdoError, IdoFlags, IdoKeyT, IdoItemType, IdoItem, IdoLimits, CapacityPolicy, Keep, MissingKeys, OverflowPolicy};
+use std::sync::{Arc, Mutex};
 
 #[test]
 fn test_set_item() {
@@ -188,6 +193,103 @@ fn test_update() {
     assert_eq!(ido1.get_string(&3).unwrap(), "value3");
 }
 
+#[test]
+fn test_update_with_array_merge() {
+    let row = |id: &str, qty: i64| {
+        let mut row = Ido::new();
+        row.set_string(&1, id.to_string());
+        row.set_integer(&2, qty);
+        row
+    };
+
+    let mut base = Ido::new();
+    base.append_array(&5, row("a", 1));
+    base.append_array(&5, row("b", 2));
+    base.set_integer(&6, 0);
+
+    let mut delta = Ido::new();
+    delta.append_array(&5, row("b", 20));
+    delta.append_array(&5, Ido::new());
+    delta.set_integer(&6, 1);
+
+    let mut replaced = base.clone();
+    replaced.update_with(&delta, ArrayMerge::Replace);
+    assert_eq!(replaced.to_string(), "5=[{1=b,2=20},{}],6=1");
+
+    let mut appended = base.clone();
+    appended.update_with(&delta, ArrayMerge::Append);
+    assert_eq!(appended.to_string(), "5=[{1=a,2=1},{1=b,2=2},{1=b,2=20},{}],6=1");
+
+    let mut keyed = base.clone();
+    keyed.update_with(&delta, ArrayMerge::Keyed(1));
+    assert_eq!(keyed.to_string(), "5=[{1=a,2=1},{1=b,2=20},{}],6=1");
+
+    let mut scalar = Ido::new();
+    scalar.set_integer(&5, 7);
+    scalar.update_with(&delta, ArrayMerge::Append);
+    assert_eq!(scalar.get_item(&5).unwrap().m_array.len(), 2);
+}
+
+#[test]
+fn test_dedup_array() {
+    let row = |id: Option<i64>, seq: i64| {
+        let mut row = Ido::new();
+        if let Some(id) = id {
+            row.set_integer(&1, id);
+        }
+        row.set_integer(&2, seq);
+        row
+    };
+
+    let mut feed = Ido::new();
+    feed.append_array(&5, row(Some(1), 1));
+    feed.append_array(&5, row(None, 2));
+    feed.append_array(&5, row(Some(2), 3));
+    feed.append_array(&5, row(Some(1), 4));
+    feed.append_array(&5, row(None, 5));
+    feed.append_array(&5, row(Some(2), 6));
+    feed.set_integer(&6, 0);
+
+    let mut first = feed.clone();
+    assert_eq!(first.dedup_array(&5, &1, Keep::First), 2);
+    assert_eq!(first.to_string(), "6=0,5=[{1=1,2=1},{2=2},{1=2,2=3},{2=5}]");
+    assert_eq!(first.dedup_array(&5, &1, Keep::First), 0);
+
+    let mut last = feed.clone();
+    assert_eq!(last.dedup_array(&5, &1, Keep::Last), 2);
+    assert_eq!(last.to_string(), "6=0,5=[{2=2},{1=1,2=4},{2=5},{1=2,2=6}]");
+
+    assert_eq!(feed.dedup_array(&6, &1, Keep::First), 0);
+    assert_eq!(feed.dedup_array(&7, &1, Keep::First), 0);
+}
+
+#[test]
+fn test_array_binary_search_by_key() {
+    let target = |value: i64| {
+        let mut item = IdoItem::new();
+        item.m_type = IdoItemType::INTEGER;
+        item.m_integer = value;
+        item
+    };
+
+    let mut book = Ido::new();
+    book.append_array(&5, Ido::new());
+    for price in [10, 20, 30, 40] {
+        let mut level = Ido::new();
+        level.set_integer(&1, price);
+        book.append_array(&5, level);
+    }
+    book.set_integer(&6, 0);
+
+    assert_eq!(book.array_binary_search_by_key(&5, &1, &target(10)), Some(Ok(1)));
+    assert_eq!(book.array_binary_search_by_key(&5, &1, &target(40)), Some(Ok(4)));
+    assert_eq!(book.array_binary_search_by_key(&5, &1, &target(25)), Some(Err(3)));
+    assert_eq!(book.array_binary_search_by_key(&5, &1, &target(50)), Some(Err(5)));
+    assert_eq!(book.array_binary_search_by_key(&5, &1, &target(5)), Some(Err(1)));
+    assert_eq!(book.array_binary_search_by_key(&6, &1, &target(5)), None);
+    assert_eq!(book.array_binary_search_by_key(&7, &1, &target(5)), None);
+}
+
 #[test]
 fn test_contains() {
     let mut ido = Ido::new();
@@ -337,4 +439,886 @@ fn test_delete_item() {
 
     assert_eq!(ido.get_string(&1), None);
     assert_eq!(ido.get_string(&2), Some("value2".to_string()));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_on_change() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+
+    let mut ido = Ido::new();
+    let id = ido.on_change(move |key, old, new| {
+        let old = old.and_then(|item| item.as_string());
+        let new = new.and_then(|item| item.as_string());
+        sink.lock().unwrap().push((key, old, new));
+    });
+
+    ido.set_integer(&1, 42);
+    ido.set_integer(&1, 43);
+    ido.append_array(&2, Ido::new());
+    ido.append_array(&2, Ido::new());
+    ido.delete_item(&1);
+
+    let mut other = Ido::new();
+    other.set_string(&3, "value3".to_string());
+    ido.update(&other);
+
+    assert_eq!(*events.lock().unwrap(), vec![
+        (1, None, Some("42".to_string())),
+        (1, Some("42".to_string()), Some("43".to_string())),
+        (2, None, Some("<array of 1>".to_string())),
+        (2, Some("<array of 1>".to_string()), Some("<array of 2>".to_string())),
+        (1, Some("43".to_string()), None),
+        (3, None, Some("value3".to_string())),
+    ]);
+
+    assert!(ido.remove_listener(id));
+    ido.set_integer(&4, 1);
+    assert_eq!(events.lock().unwrap().len(), 6);
+    assert!(!ido.remove_listener(id));
+}
+
+#[test]
+fn test_on_change_not_cloned() {
+    let count = Arc::new(Mutex::new(0));
+    let sink = count.clone();
+
+    let mut ido = Ido::new();
+    ido.on_change(move |_, _, _| *sink.lock().unwrap() += 1);
+
+    let mut copy = ido.clone();
+    copy.set_integer(&1, 1);
+    assert_eq!(*count.lock().unwrap(), 0);
+
+    ido.set_integer(&1, 1);
+    ido.clear();
+    assert_eq!(*count.lock().unwrap(), 2);
+}
+
+#[test]
+fn test_dirty_tracking() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 10);
+    ido.set_integer(&2, 20);
+    ido.set_integer(&3, 30);
+
+    assert_eq!(ido.dirty_keys(), vec![1, 2, 3]);
+    ido.clear_dirty();
+    assert!(ido.dirty_keys().is_empty());
+
+    ido.set_integer(&3, 31);
+    ido.append_array(&4, Ido::new());
+    ido.delete_item(&1);
+
+    assert!(ido.is_dirty(&1));
+    assert!(!ido.is_dirty(&2));
+    assert_eq!(ido.dirty_keys(), vec![1, 3, 4]);
+
+    let delta = ido.take_dirty();
+    assert_eq!(delta.size(), 2);
+    assert_eq!(delta.get_i64(&3), Some(31));
+    assert!(delta.is_type(&4, &IdoItemType::ARRAY));
+    assert!(ido.dirty_keys().is_empty());
+}
+
+#[test]
+fn test_item_version() {
+    let mut ido = Ido::new();
+    assert_eq!(ido.item_version(&1), None);
+
+    ido.set_integer(&1, 10);
+    assert_eq!(ido.item_version(&1), Some(1));
+
+    ido.set_string(&1, "ten".to_string());
+    assert_eq!(ido.item_version(&1), Some(2));
+    assert_eq!(ido.get_item(&1).unwrap().version(), 2);
+
+    ido.append_array(&2, Ido::new());
+    ido.append_array(&2, Ido::new());
+    assert_eq!(ido.item_version(&2), Some(2));
+}
+
+#[test]
+fn test_item_version_after_undo() {
+    let mut ido = Ido::new();
+    ido.enable_journal();
+    ido.set_integer(&1, 10);
+    ido.set_integer(&1, 11);
+
+    ido.undo();
+    assert_eq!(ido.get_i64(&1), Some(10));
+    assert_eq!(ido.item_version(&1), Some(3));
+}
+
+#[test]
+fn test_set_if_version() {
+    let mut ido = Ido::new();
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::INTEGER;
+    item.m_integer = 5;
+
+    assert_eq!(
+        ido.set_if_version(&1, 1, item.clone()),
+        Err(IdoError::VersionMismatch { key: 1, expected: 1, actual: 0 })
+    );
+    assert!(ido.set_if_version(&1, 0, item.clone()).is_ok());
+    assert!(ido.set_if_version(&1, 1, item.clone()).is_ok());
+    assert!(ido.set_if_version(&1, 1, item).is_err());
+    assert_eq!(ido.item_version(&1), Some(2));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_modified_at() {
+    let mut ido = Ido::new();
+    assert_eq!(ido.modified_at(&1), None);
+
+    let before = Utc::now();
+    ido.set_integer(&1, 10);
+    ido.set_integer(&2, 20);
+    let first = ido.modified_at(&1).unwrap();
+    assert!(first >= before);
+    assert_eq!(ido.get_item(&1).unwrap().modified_at(), first);
+
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    let checkpoint = Utc::now();
+    ido.set_integer(&1, 11);
+    ido.append_array(&3, Ido::new());
+
+    assert!(ido.modified_at(&1).unwrap() > first);
+    assert_eq!(ido.items_modified_since(checkpoint), vec![1, 3]);
+    assert_eq!(ido.items_modified_since(before), vec![2, 1, 3]);
+}
+
+#[test]
+fn test_set_with_ttl() {
+    let mut ido = Ido::new();
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::INTEGER;
+    item.m_integer = 7;
+
+    ido.set_with_ttl(&1, item.clone(), std::time::Duration::from_secs(60));
+    ido.set_with_ttl(&2, item.clone(), std::time::Duration::ZERO);
+    assert_eq!(ido.get_i64(&1), Some(7));
+    assert_eq!(ido.get_i64(&2), None);
+    assert!(!ido.contains(&2));
+    assert!(!ido.is_type(&2, &IdoItemType::INTEGER));
+
+    ido.set_item(&1, ido.get_item(&1).unwrap());
+    #[cfg(feature = "chrono")]
+    assert!(ido.get_item(&1).unwrap().expires_at().is_none());
+}
+
+#[test]
+fn test_expired_items_are_skipped_everywhere() {
+    let mut nested = Ido::new();
+    nested.set_integer(&1, 1);
+    nested.set_with_ttl(&2, IdoItem::new(), std::time::Duration::ZERO);
+
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 10);
+    ido.set_with_ttl(&2, IdoItem::new(), std::time::Duration::ZERO);
+    ido.set_string(&3, "value3".to_string());
+    ido.append_array(&4, nested);
+
+    let keys: Vec<IdoKeyT> = ido.into_ordered_iterator().map(|(key, _)| key).collect();
+    assert_eq!(keys, vec![1, 3, 4]);
+    let owned = ido.clone().into_iter();
+    assert_eq!(owned.len(), 3);
+    assert_eq!(owned.map(|(key, _)| key).collect::<Vec<IdoKeyT>>(), keys);
+    assert_eq!(ido.to_string(), "1=10,3=value3,4=[{1=1}]");
+    assert_eq!(format!("{}", ido), "1=10,3=value3,4=[{1=1}]");
+
+    let bytes = ido.encode();
+    assert_eq!(bytes.len(), ido.encoded_len());
+    let decoded = Ido::decode(&bytes).unwrap();
+    assert_eq!(decoded.size(), 3);
+    assert_eq!(decoded.get_item(&4).unwrap().m_array[0].size(), 1);
+    assert!(decoded == ido);
+
+    let mut wire = Vec::new();
+    ido.encode_to(&mut wire).unwrap();
+    assert_eq!(&wire[4..], bytes.as_slice());
+
+    let mut merged = Ido::new();
+    merged.update(&ido);
+    assert!(!merged.contains(&2));
+    assert_eq!(merged.size(), 3);
+}
+
+#[test]
+fn test_item_expiring_later_is_hidden_from_every_read_path() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 10);
+    ido.set_with_ttl(&2, IdoItem::new(), std::time::Duration::from_millis(1));
+    ido.set_string(&3, "value3".to_string());
+    assert_eq!(ido.to_vec().len(), 3);
+    std::thread::sleep(std::time::Duration::from_millis(5));
+
+    let mut expected = Ido::new();
+    expected.set_integer(&1, 10);
+    expected.set_string(&3, "value3".to_string());
+
+    let keys = |pairs: Vec<(IdoKeyT, IdoItem)>| pairs.into_iter().map(|(key, _)| key).collect::<Vec<IdoKeyT>>();
+    assert_eq!(keys(ido.clone().into_iter().collect()), vec![1, 3]);
+    assert_eq!(keys(ido.into_ordered_iterator().collect()), vec![1, 3]);
+    assert_eq!(keys(ido.to_vec()), vec![1, 3]);
+    assert_eq!(ido.as_pairs().len(), 2);
+    assert_eq!(ido.to_string(), "1=10,3=value3");
+    assert_eq!(format!("{}", ido), "1=10,3=value3");
+    assert_eq!(ido.encode(), expected.encode());
+    assert_eq!(ido.encoded_len(), expected.encoded_len());
+    assert!(ido.get_item(&2).is_none());
+    assert!(!ido.set_flag(&2, IdoFlags::SENSITIVE));
+    assert!(!ido.clear_flag(&2, IdoFlags::SENSITIVE));
+}
+
+#[test]
+fn test_expire() {
+    let mut ido = Ido::new();
+    let deleted = Arc::new(Mutex::new(Vec::new()));
+    let sink = deleted.clone();
+    ido.on_change(move |key, _, new| {
+        if new.is_none() {
+            sink.lock().unwrap().push(key);
+        }
+    });
+
+    ido.set_integer(&1, 10);
+    ido.set_with_ttl(&2, IdoItem::new(), std::time::Duration::from_millis(1));
+    ido.set_with_ttl(&3, IdoItem::new(), std::time::Duration::from_secs(60));
+    std::thread::sleep(std::time::Duration::from_millis(5));
+
+    assert_eq!(ido.expire(), 1);
+    assert_eq!(ido.expire(), 0);
+    assert_eq!(ido.size(), 2);
+    assert_eq!(*deleted.lock().unwrap(), vec![2]);
+}
+
+#[test]
+fn test_flags() {
+    let mut ido = Ido::new();
+    assert!(!ido.set_flag(&1, IdoFlags::SENSITIVE));
+
+    ido.set_string(&1, "secret".to_string());
+    ido.set_integer(&2, 5);
+    assert!(ido.set_flag(&1, IdoFlags::SENSITIVE | IdoFlags::MANDATORY));
+    ido.set_flag(&2, IdoFlags::DERIVED);
+
+    ido.set_string(&1, "other".to_string());
+    assert!(ido.has_flag(&1, IdoFlags::SENSITIVE));
+    assert!(!ido.has_flag(&1, IdoFlags::DERIVED));
+    assert_eq!(ido.get_item(&1).unwrap().flags(), IdoFlags::SENSITIVE | IdoFlags::MANDATORY);
+    assert_eq!(ido.keys_with_flag(IdoFlags::MANDATORY), vec![1]);
+
+    ido.clear_flag(&1, IdoFlags::SENSITIVE);
+    assert!(!ido.has_flag(&1, IdoFlags::SENSITIVE));
+    assert!(ido.has_flag(&1, IdoFlags::MANDATORY));
+
+    ido.delete_item(&2);
+    ido.set_integer(&2, 6);
+    assert!(ido.get_item(&2).unwrap().flags().is_empty());
+    assert_eq!(format!("{:?}", IdoFlags::SENSITIVE | IdoFlags::DERIVED), "IdoFlags(SENSITIVE | DERIVED)");
+
+    ido.set_with_ttl(&3, IdoItem::new(), std::time::Duration::ZERO);
+    assert!(!ido.set_flag(&3, IdoFlags::SENSITIVE));
+    assert!(!ido.clear_flag(&3, IdoFlags::SENSITIVE));
+    assert!(!ido.has_flag(&3, IdoFlags::SENSITIVE));
+    assert!(ido.keys_with_flag(IdoFlags::SENSITIVE).is_empty());
+}
+
+#[test]
+fn test_require() {
+    let mut ido = Ido::new();
+    assert_eq!(ido.require(&[]), Ok(()));
+
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.set_integer(&3, 100);
+    assert_eq!(ido.require(&[1, 3]), Ok(()));
+    assert_eq!(ido.require(&[4, 1, 2]), Err(MissingKeys(vec![4, 2])));
+
+    ido.set_with_ttl(&2, IdoItem::new(), std::time::Duration::ZERO);
+    assert_eq!(ido.require(&[2]), Err(MissingKeys(vec![2])));
+}
+
+#[test]
+fn test_strict_mode() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 10);
+    ido.set_string(&1, "ten".to_string());
+    assert!(!ido.is_strict());
+
+    ido.set_strict(true);
+    ido.set_string(&1, "eleven".to_string());
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::FLOAT;
+    assert_eq!(
+        ido.try_set_item(&1, item.clone()),
+        Err(IdoError::TypeMismatch { key: 1, expected: IdoItemType::STRING, actual: IdoItemType::FLOAT })
+    );
+    assert!(ido.try_append_array(&1, Ido::new()).is_err());
+    assert!(ido.set_if_version(&1, 3, item.clone()).is_err());
+    assert_eq!(ido.get_string(&1), Some("eleven".to_string()));
+
+    ido.try_append_array(&2, Ido::new()).unwrap();
+    ido.try_append_array(&2, Ido::new()).unwrap();
+    assert_eq!(ido.get_item(&2).unwrap().m_array.len(), 2);
+
+    ido.delete_item(&1);
+    assert!(ido.try_set_item(&1, item).is_ok());
+}
+
+#[test]
+fn test_strict_mode_skips_rejected_writes() {
+    let mut ido = Ido::new();
+    ido.set_strict(true);
+    ido.set_integer(&1, 10);
+    assert_eq!(ido.take_rejected(), None);
+    ido.set_string(&1, "ten".to_string());
+    assert_eq!(ido.get_i64(&1), Some(10));
+    assert_eq!(
+        ido.take_rejected(),
+        Some(IdoError::TypeMismatch { key: 1, expected: IdoItemType::INTEGER, actual: IdoItemType::STRING })
+    );
+    assert_eq!(ido.take_rejected(), None);
+
+    ido.append_array(&1, Ido::new());
+    assert_eq!(ido.get_i64(&1), Some(10));
+    assert!(matches!(ido.take_rejected(), Some(IdoError::TypeMismatch { key: 1, .. })));
+}
+
+#[test]
+fn test_strict_mode_rollback() {
+    let mut ido = Ido::new();
+    ido.set_strict(true);
+    ido.set_integer(&1, 10);
+    ido.set_integer(&2, 20);
+
+    ido.begin().unwrap();
+    ido.delete_item(&1);
+    ido.set_string(&1, "ten".to_string());
+    ido.rollback().unwrap();
+    assert_eq!(ido.get_i64(&1), Some(10));
+}
+
+#[test]
+fn test_from_template() {
+    let mut template = Ido::new();
+    template.enable_journal();
+    template.set_string(&1, "NEW".to_string());
+    template.append_array(&2, Ido::new());
+
+    let mut ido = Ido::from_template(&template);
+    assert_eq!(ido.to_string(), template.to_string());
+    assert!(ido.dirty_keys().is_empty());
+    assert!(!ido.is_journal_enabled());
+
+    ido.set_string(&1, "FILLED".to_string());
+    assert_eq!(template.get_string(&1), Some("NEW".to_string()));
+}
+
+#[test]
+fn test_clone_subset() {
+    let mut leg = Ido::new();
+    leg.set_string(&10, "EURUSD".to_string());
+
+    let mut ido = Ido::new();
+    ido.enable_journal();
+    ido.set_integer(&3, 30);
+    ido.append_array(&2, leg);
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.set_with_ttl(&4, IdoItem::new(), std::time::Duration::ZERO);
+
+    let mut subset = ido.clone_subset(&[1, 2, 4, 5, 1]);
+    assert_eq!(subset.size(), 2);
+    assert_eq!(subset.to_string(), "2=[{10=EURUSD}],1=ORDER-1");
+    assert!(subset.dirty_keys().is_empty());
+    assert!(!subset.is_journal_enabled());
+
+    subset.append_array(&2, Ido::new());
+    assert_eq!(ido.get_item(&2).unwrap().m_array.len(), 1);
+}
+
+#[test]
+fn test_split_off() {
+    let mut ido = Ido::new();
+    ido.set_integer(&4, 40);
+    ido.set_string(&1, "one".to_string());
+    ido.set_integer(&3, 30);
+    ido.set_string(&2, "two".to_string());
+    ido.clear_dirty();
+
+    let deleted = Arc::new(Mutex::new(Vec::new()));
+    let seen = deleted.clone();
+    ido.on_change(move |key, _, new| {
+        if new.is_none() {
+            seen.lock().unwrap().push(key);
+        }
+    });
+
+    let strings = ido.split_off(|_, item| item.get_type() == IdoItemType::STRING);
+    assert_eq!(strings.to_string(), "1=one,2=two");
+    assert_eq!(ido.to_string(), "4=40,3=30");
+    assert_eq!(ido.dirty_keys(), vec![1, 2]);
+    assert_eq!(*deleted.lock().unwrap(), vec![1, 2]);
+    assert!(strings.dirty_keys().is_empty());
+
+    assert_eq!(ido.split_off(|_, _| false).size(), 0);
+    assert_eq!(ido.size(), 2);
+}
+
+#[test]
+fn test_key_ranges() {
+    let mut ido = Ido::new();
+    ido.set_integer(&102, 2);
+    ido.set_integer(&1, 1);
+    ido.set_integer(&100, 0);
+    ido.set_with_ttl(&101, IdoItem::new(), std::time::Duration::ZERO);
+    ido.set_integer(&200, 3);
+    ido.clear_dirty();
+
+    let block = ido.get_range(100..200);
+    assert_eq!(block.to_string(), "102=2,100=0");
+    assert!(block.dirty_keys().is_empty());
+    assert_eq!(ido.get_range(..=100).to_string(), "1=1,100=0");
+    assert_eq!(ido.get_range(300..).size(), 0);
+
+    assert_eq!(ido.delete_range(100..200), 3);
+    assert_eq!(ido.to_string(), "1=1,200=3");
+    assert_eq!(ido.dirty_keys(), vec![100, 101, 102]);
+    assert_eq!(ido.delete_range(100..200), 0);
+    assert_eq!(ido.delete_range(..), 2);
+    assert_eq!(ido.size(), 0);
+}
+
+#[test]
+fn test_apply_defaults() {
+    let mut template = Ido::new();
+    template.set_string(&1, "NEW".to_string());
+    template.set_integer(&2, 0);
+    template.set_f64(&3, 1.5);
+
+    let mut ido = Ido::new();
+    ido.set_integer(&2, 100);
+
+    assert_eq!(ido.apply_defaults(&template), 2);
+    assert_eq!(ido.get_i64(&2), Some(100));
+    assert_eq!(ido.get_string(&1), Some("NEW".to_string()));
+    assert_eq!(ido.get_f64(&3), Some(1.5));
+    assert_eq!(ido.apply_defaults(&template), 0);
+}
+
+#[test]
+fn test_append_value() {
+    let mut ido = Ido::new();
+    assert!(ido.get_values(&5).is_empty());
+
+    ido.set_integer(&1, 7);
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::INTEGER;
+
+    item.m_integer = 10;
+    ido.append_value(&5, item.clone());
+    assert!(ido.is_type(&5, &IdoItemType::INTEGER));
+    assert_eq!(ido.get_values(&5).len(), 1);
+
+    item.m_integer = 11;
+    ido.append_value(&5, item.clone());
+    item.m_integer = 12;
+    ido.append_value(&5, item);
+
+    assert!(ido.is_type(&5, &IdoItemType::ARRAY));
+    let values: Vec<i64> = ido.get_values(&5).iter().map(|value| value.m_integer).collect();
+    assert_eq!(values, vec![10, 11, 12]);
+    assert_eq!(ido.get_values(&1).len(), 1);
+
+    let mut group = Ido::new();
+    group.set_integer(&2, 3);
+    let mut array = IdoItem::new();
+    array.m_type = IdoItemType::ARRAY;
+    array.m_array.push(group);
+    ido.append_value(&6, array);
+    assert_eq!(ido.get_values(&6)[0].m_array[0].get_i64(&2), Some(3));
+}
+
+#[test]
+fn test_append_value_strict() {
+    let mut ido = Ido::new();
+    ido.set_strict(true);
+    ido.set_integer(&1, 10);
+
+    assert!(matches!(ido.try_append_value(&1, IdoItem::new()), Err(IdoError::TypeMismatch { .. })));
+    assert_eq!(ido.get_i64(&1), Some(10));
+}
+
+#[test]
+fn test_i128() {
+    let mut ido = Ido::new();
+    let large = i128::from(i64::MAX) * 1_000;
+    ido.set_i128(&1, large);
+    ido.set_integer(&2, -3);
+
+    assert_eq!(ido.get_i128(&1), Some(large));
+    assert_eq!(ido.get_u128(&1), Some(large as u128));
+    assert!(ido.is_type(&1, &IdoItemType::INTEGER128));
+    assert_eq!(ido.get_i64(&1), None);
+    assert_eq!(ido.get_i128(&2), Some(-3));
+    assert_eq!(ido.get_u128(&2), None);
+    assert_eq!(ido.to_string(), format!("1={},2=-3", large));
+}
+
+#[test]
+fn test_float_policy() {
+    let mut ido = Ido::new();
+    assert_eq!(ido.float_policy(), FloatPolicy::Allow);
+    ido.set_f64(&1, f64::NAN);
+    ido.set_f64(&2, 2.5);
+    assert_eq!(ido.is_finite(&1), Some(false));
+    assert_eq!(ido.is_finite(&2), Some(true));
+    assert_eq!(ido.is_finite(&3), None);
+
+    ido.set_float_policy(FloatPolicy::Reject);
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::FLOAT;
+    item.m_float = f64::INFINITY;
+    assert!(matches!(ido.try_set_item(&2, item.clone()), Err(IdoError::NonFinite { key: 2, .. })));
+    assert_eq!(ido.get_f64(&2), Some(2.5));
+
+    ido.set_float_policy(FloatPolicy::Null);
+    ido.set_item(&2, item);
+    assert!(!ido.contains(&2));
+    ido.set_f64(&3, f64::NEG_INFINITY);
+    assert!(!ido.contains(&3));
+}
+
+#[test]
+fn test_float_policy_reject_skips_write() {
+    let mut ido = Ido::new();
+    ido.set_float_policy(FloatPolicy::Reject);
+    ido.set_f64(&1, 1.5);
+    ido.set_f64(&1, f64::NAN);
+    assert_eq!(ido.get_f64(&1), Some(1.5));
+    assert!(matches!(ido.take_rejected(), Some(IdoError::NonFinite { key: 1, value }) if value.is_nan()));
+}
+
+#[test]
+fn test_into_iter_insertion_order() {
+    let mut ido = Ido::new();
+    for (key, value) in [(5, 50), (3, 30), (9, 90), (1, 10)] {
+        ido.set_integer(&key, value);
+    }
+    ido.set_integer(&3, 31);
+
+    let iter = ido.into_iter();
+    assert_eq!(iter.len(), 4);
+
+    let items: Vec<(IdoKeyT, i64)> = iter.map(|(key, item)| (key, item.m_integer)).collect();
+    assert_eq!(items, vec![(5, 50), (9, 90), (1, 10), (3, 31)]);
+}
+
+#[test]
+fn test_limits() {
+    let mut leaf = Ido::new();
+    leaf.set_integer(&1, 1);
+    let mut nested = Ido::new();
+    nested.append_array(&2, leaf.clone());
+
+    let mut ido = Ido::new();
+    ido.set_limits(IdoLimits { max_depth: Some(2), max_array_len: Some(2), ..IdoLimits::default() });
+    assert_eq!(ido.limits().max_depth, Some(2));
+
+    ido.append_array(&1, nested.clone());
+    ido.append_array(&1, leaf.clone());
+    assert_eq!(
+        ido.try_append_array(&1, leaf.clone()),
+        Err(IdoError::ArrayTooLong { key: 1, len: 3, max: 2 })
+    );
+
+    let mut deep = Ido::new();
+    deep.append_array(&3, nested.clone());
+    assert_eq!(ido.try_append_array(&4, deep), Err(IdoError::DepthExceeded { key: 4, depth: 3, max: 2 }));
+    assert!(!ido.contains(&4));
+
+    let mut wide = Ido::new();
+    for _ in 0..3 {
+        wide.append_array(&5, leaf.clone());
+    }
+    assert_eq!(ido.try_append_array(&6, wide), Err(IdoError::ArrayTooLong { key: 6, len: 3, max: 2 }));
+    assert_eq!(ido.get_item(&1).unwrap().m_array.len(), 2);
+}
+
+#[test]
+fn test_limits_append_array_skips_write() {
+    let mut ido = Ido::new();
+    ido.set_limits(IdoLimits { max_array_len: Some(1), ..IdoLimits::default() });
+    ido.append_array(&1, Ido::new());
+    ido.append_array(&1, Ido::new());
+    assert_eq!(ido.get_item(&1).unwrap().m_array.len(), 1);
+    assert_eq!(ido.take_rejected(), Some(IdoError::ArrayTooLong { key: 1, len: 2, max: 1 }));
+}
+
+#[test]
+fn test_memory_limit() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 10);
+    let item = ido.memory_used();
+    assert_eq!(item, ido.get_item(&1).unwrap().approx_bytes());
+
+    ido.set_limits(IdoLimits { max_bytes: Some(2 * item + 4), ..IdoLimits::default() });
+    ido.set_string(&2, "ABCD".to_string());
+    assert_eq!(ido.memory_used(), 2 * item + 4);
+    let mut longer = ido.get_item(&2).unwrap();
+    longer.m_string.push('E');
+    assert_eq!(
+        ido.try_set_item(&2, longer),
+        Err(IdoError::MemoryExceeded { key: 2, bytes: 2 * item + 5, max: 2 * item + 4 })
+    );
+    assert!(ido.try_set_item(&3, ido.get_item(&1).unwrap()).is_err());
+    assert_eq!(ido.get_string(&2), Some("ABCD".to_string()));
+
+    // Shrinking is always allowed, and frees room for other keys.
+    ido.set_string(&2, String::new());
+    assert_eq!(ido.memory_used(), 2 * item);
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+    assert!(matches!(ido.try_append_array(&2, leg), Err(IdoError::MemoryExceeded { key: 2, .. })));
+
+    ido.delete_item(&2);
+    ido.append_array(&3, Ido::new());
+    assert_eq!(ido.memory_used(), 2 * item);
+    ido.clear();
+    assert_eq!(ido.memory_used(), 0);
+}
+
+#[test]
+fn test_memory_limit_set_with_ttl() {
+    let mut ido = Ido::new();
+    ido.set_limits(IdoLimits { max_bytes: Some(1), ..IdoLimits::default() });
+    let bytes = IdoItem::new().approx_bytes();
+    let ttl = std::time::Duration::from_secs(60);
+    assert_eq!(
+        ido.try_set_with_ttl(&1, IdoItem::new(), ttl),
+        Err(IdoError::MemoryExceeded { key: 1, bytes, max: 1 })
+    );
+    ido.set_with_ttl(&1, IdoItem::new(), ttl);
+    assert!(!ido.contains(&1));
+    assert_eq!(ido.take_rejected(), Some(IdoError::MemoryExceeded { key: 1, bytes, max: 1 }));
+}
+
+#[test]
+fn test_write_to() {
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.set_f64(&2, 1.5);
+    ido.set_i128(&3, i128::MAX);
+    ido.append_array(&4, leg.clone());
+    ido.append_array(&4, leg);
+
+    let mut out = String::from(">");
+    ido.write_to(&mut out).unwrap();
+    assert_eq!(out, format!(">{}", ido));
+    assert_eq!(ido.to_string(), format!("{}", ido));
+    assert_eq!(ido.to_string(), format!("1=ORDER-1,2=1.5,3={},4=[{{1=EURUSD}},{{1=EURUSD}}]", i128::MAX));
+    assert_eq!(Ido::new().to_string(), "");
+}
+
+#[test]
+fn test_overflow_policy() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 70_000);
+    ido.set_integer(&2, -1);
+    ido.set_integer(&3, 100);
+    ido.set_string(&4, "100".to_string());
+    assert_eq!(ido.overflow_policy(), OverflowPolicy::Wrap);
+    assert_eq!(ido.get_u16(&1), Some(70_000u32 as u16));
+    assert_eq!(ido.get_u64(&2), Some(u64::MAX));
+
+    ido.set_overflow_policy(OverflowPolicy::Saturate);
+    assert_eq!(ido.get_u16(&1), Some(u16::MAX));
+    assert_eq!(ido.get_i8(&1), Some(i8::MAX));
+    assert_eq!(ido.get_u32(&2), Some(0));
+    assert_eq!(ido.get_i32(&1), Some(70_000));
+
+    ido.set_overflow_policy(OverflowPolicy::Null);
+    assert_eq!(ido.get_u16(&1), None);
+    assert_eq!(ido.get_u8(&2), None);
+    assert_eq!(ido.get_u8(&3), Some(100));
+    assert_eq!(ido.try_get_int::<u8>(&1), Ok(None));
+
+    ido.set_overflow_policy(OverflowPolicy::Reject);
+    assert_eq!(ido.get_i16(&1), None);
+    assert_eq!(ido.try_get_int::<i16>(&1), Err(IdoError::OutOfRange { key: 1, value: 70_000, target: "i16" }));
+    assert_eq!(ido.try_get_int::<u64>(&2), Err(IdoError::OutOfRange { key: 2, value: -1, target: "u64" }));
+    assert_eq!(ido.try_get_int::<i16>(&3), Ok(Some(100)));
+    assert_eq!(ido.try_get_int::<i16>(&4), Ok(None));
+    assert_eq!(ido.try_get_int::<i16>(&5), Ok(None));
+    assert_eq!(ido.clone().overflow_policy(), OverflowPolicy::Reject);
+}
+
+#[test]
+fn test_bool() {
+    let mut ido = Ido::new();
+    ido.set_bool(&1, true);
+    ido.set_integer(&2, 0);
+    ido.set_i128(&3, 1);
+    ido.set_integer(&4, 2);
+    ido.set_string(&5, "TRUE".to_string());
+    ido.set_string(&6, "n".to_string());
+    ido.set_string(&7, "yes".to_string());
+    ido.set_f64(&8, 1.0);
+
+    assert_eq!(ido.get_bool(&1), Some(true));
+    assert_eq!(ido.get_bool(&2), None);
+    assert_eq!(ido.get_item(&1).unwrap().get_type(), IdoItemType::BOOL);
+    assert_eq!(ido.to_string(), "1=true,2=0,3=1,4=2,5=TRUE,6=n,7=yes,8=1");
+
+    let lossy: Vec<Option<bool>> = (1..=9).map(|key| ido.get_bool_lossy(&key)).collect();
+    assert_eq!(lossy, [Some(true), Some(false), Some(true), None, Some(true), Some(false), None, None, None]);
+}
+
+#[test]
+fn test_order_by_keys() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 1);
+    ido.set_integer(&2, 2);
+    ido.set_integer(&3, 3);
+    ido.set_integer(&4, 4);
+    ido.clear_dirty();
+    let version = ido.get_item(&3).unwrap().version();
+
+    ido.order_by_keys(&[3, 9, 1, 3]);
+    let keys: Vec<IdoKeyT> = ido.into_ordered_iterator().map(|(key, _)| key).collect();
+    assert_eq!(keys, [3, 1, 2, 4]);
+    assert_eq!(ido.get_item(&3).unwrap().version(), version);
+    assert!(ido.dirty_keys().is_empty());
+
+    // Later inserts still go to the end, and encoding follows the new order.
+    ido.set_integer(&5, 5);
+    ido.order_by_keys(&[]);
+    assert_eq!(ido.to_string(), "3=3,1=1,2=2,4=4,5=5");
+    assert_eq!(Ido::decode(&ido.encode()).unwrap().to_string(), "3=3,1=1,2=2,4=4,5=5");
+}
+
+#[test]
+fn test_capacity_evict_oldest() {
+    let deleted = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&deleted);
+
+    let mut ido = Ido::new();
+    ido.set_capacity(CapacityPolicy::EvictOldest(3));
+    assert_eq!(ido.capacity(), CapacityPolicy::EvictOldest(3));
+    ido.on_change(move |key, _, new| {
+        if new.is_none() {
+            seen.lock().unwrap().push(key);
+        }
+    });
+
+    for (key, value) in [(1, 1), (2, 2), (3, 3), (4, 4), (5, 5)] {
+        ido.set_integer(&key, value);
+    }
+    // Replacing and appending to existing keys never evicts.
+    ido.set_integer(&3, 30);
+    ido.append_array(&6, Ido::new());
+    ido.append_array(&6, Ido::new());
+
+    assert_eq!(ido.size(), 3);
+    assert_eq!(ido.to_string(), "5=5,3=30,6=[{},{}]");
+    assert_eq!(*deleted.lock().unwrap(), [1, 2, 4]);
+}
+
+#[test]
+fn test_capacity_reject() {
+    let mut ido = Ido::new();
+    ido.set_integer(&1, 1);
+    ido.set_integer(&2, 2);
+    ido.set_capacity(CapacityPolicy::Reject(2));
+
+    assert_eq!(ido.try_set_item(&3, ido.get_item(&1).unwrap()), Err(IdoError::CapacityExceeded { key: 3, max: 2 }));
+    assert_eq!(ido.try_append_array(&3, Ido::new()), Err(IdoError::CapacityExceeded { key: 3, max: 2 }));
+    assert!(!ido.contains(&3));
+
+    ido.set_integer(&2, 20);
+    ido.delete_item(&1);
+    ido.set_integer(&3, 3);
+    assert_eq!(ido.to_string(), "2=20,3=3");
+
+    ido.set_capacity(CapacityPolicy::Unbounded);
+    ido.set_integer(&4, 4);
+    assert_eq!(ido.size(), 3);
+}
+
+#[test]
+fn test_capacity_reject_skips_write() {
+    let mut ido = Ido::new();
+    ido.set_capacity(CapacityPolicy::Reject(1));
+    ido.set_integer(&1, 1);
+    ido.set_integer(&2, 2);
+    assert!(!ido.contains(&2));
+    assert_eq!(ido.take_rejected(), Some(IdoError::CapacityExceeded { key: 2, max: 1 }));
+}
+
+#[test]
+fn test_to_vec_and_as_pairs() {
+    let mut ido = Ido::new();
+    ido.set_integer(&3, 1);
+    ido.set_string(&1, "EURUSD".to_string());
+    ido.set_with_ttl(&2, IdoItem::new(), std::time::Duration::ZERO);
+    ido.set_integer(&3, 4);
+
+    let pairs = ido.to_vec();
+    assert_eq!(pairs.iter().map(|(key, _)| *key).collect::<Vec<IdoKeyT>>(), vec![1, 3]);
+    assert_eq!(pairs[0].1.m_string, "EURUSD");
+    assert_eq!(pairs[1].1.m_integer, 4);
+
+    let borrowed = ido.as_pairs();
+    assert_eq!(borrowed.len(), 2);
+    assert!(borrowed.iter().zip(&pairs).all(|((key, item), (owned_key, owned))| key == owned_key && *item == owned));
+    assert!(Ido::new().to_vec().is_empty());
+}
+
+#[test]
+fn test_to_string_with() {
+    let mut leg = Ido::new();
+    leg.set_string(&554, "leg-secret".to_string());
+    leg.set_integer(&2, 5);
+
+    let mut ido = Ido::new();
+    ido.set_string(&554, "secret".to_string());
+    ido.set_f64(&44, 1.5);
+    ido.append_array(&5, leg);
+    ido.append_array(&6, Ido::new());
+
+    let masked = ido.to_string_with(|key, item| match key {
+        554 => Some("***".to_string()),
+        44 => Some(format!("{}%", item.m_float * 100.0)),
+        6 => Some(format!("{} legs", item.m_array.len())),
+        _ => None,
+    });
+    assert_eq!(masked, "554=***,44=150%,5=[{554=***,2=5}],6=1 legs");
+    assert_eq!(ido.to_string_with(|_, _| None), ido.to_string());
+}
+
+#[test]
+fn test_array_rendering() {
+    let mut fill = Ido::new();
+    fill.set_integer(&1, 100);
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+    leg.append_array(&2, fill.clone());
+    leg.append_array(&2, fill);
+
+    let mut ido = Ido::new();
+    ido.append_array(&3, leg);
+    ido.append_array(&3, Ido::new());
+    ido.set_integer(&4, 5);
+
+    let mut empty = IdoItem::new();
+    empty.m_type = IdoItemType::ARRAY;
+    ido.set_item(&6, empty);
+
+    assert_eq!(ido.to_string(), "3=[{1=EURUSD,2=[{1=100},{1=100}]},{}],4=5,6=[]");
+    assert_eq!(ido.to_string_indexed(), "3=[0:{1=EURUSD,2=[0:{1=100},1:{1=100}]},1:{}],4=5,6=[]");
+}
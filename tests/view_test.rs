@@ -0,0 +1,58 @@
+use ido::{Ido, IdoItemType, IdoView};
+
+fn order() -> Ido {
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.set_integer(&2, 100);
+    ido.set_f64(&3, 1.25);
+    ido.set_bool(&4, true);
+    ido.append_array(&5, leg);
+    ido
+}
+
+fn describe(view: IdoView<'_>) -> String {
+    format!("{} x {}", view.get_string(&1).unwrap_or_default(), view.get_i64(&2).unwrap_or_default())
+}
+
+#[test]
+fn test_view_getters() {
+    let ido = order();
+    let view = ido.view();
+
+    assert_eq!(view.size(), 5);
+    assert_eq!(view.get_string(&1), Some("ORDER-1".to_string()));
+    assert_eq!(view.get_i64(&2), Some(100));
+    assert_eq!(view.get_u8(&2), Some(100));
+    assert_eq!(view.try_get_int::<i8>(&2), Ok(Some(100)));
+    assert_eq!(view.get_f64(&3), Some(1.25));
+    assert_eq!(view.get_bool(&4), Some(true));
+    assert_eq!(view.get_value::<f64>(&3), Ok(1.25));
+    assert_eq!(view.get_item(&5).unwrap().m_array.len(), 1);
+    assert!(view.contains(&5));
+    assert!(view.is_type(&5, &IdoItemType::ARRAY));
+    assert!(view.require(&[1, 2, 6]).is_err());
+    assert_eq!(view.to_string(), ido.to_string());
+
+    let keys: Vec<_> = view.into_ordered_iterator().map(|(key, _)| key).collect();
+    assert_eq!(keys, vec![1, 2, 3, 4, 5]);
+    assert_eq!(view.to_vec().len(), 5);
+    assert_eq!(view.as_pairs()[1].1.m_integer, 100);
+}
+
+#[test]
+fn test_view_copy_and_to_ido() {
+    let ido = order();
+    let view = IdoView::from(&ido);
+    let copy = view;
+
+    assert_eq!(describe(view), "ORDER-1 x 100");
+    assert_eq!(describe(copy), "ORDER-1 x 100");
+
+    let mut owned = copy.to_ido();
+    owned.set_integer(&2, 60);
+    assert_eq!(owned.get_i64(&2), Some(60));
+    assert_eq!(ido.get_i64(&2), Some(100));
+}
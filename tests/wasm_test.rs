@@ -0,0 +1,37 @@
+#![cfg(feature = "wasm")]
+
+use ido::codec;
+use ido::wasm::JsIdo;
+use ido::Ido;
+
+fn sample() -> Ido {
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+
+    let mut ido = Ido::new();
+    ido.set_integer(&3, 42);
+    ido.set_f64(&1, 1.5);
+    ido.append_array(&2, leg);
+    ido
+}
+
+#[test]
+fn test_wasm_decode_round_trip() {
+    let ido = sample();
+    let js = JsIdo::decode(&ido.encode()).unwrap();
+
+    assert_eq!(js.size(), 3);
+    assert_eq!(js.keys(), vec![3, 1, 2]);
+    assert_eq!(js.to_js_string(), ido.to_string());
+    assert_eq!(js.encode(), ido.encode());
+}
+
+#[test]
+fn test_wasm_decode_frame() {
+    let ido = sample();
+    let mut frame = Vec::new();
+    codec::write_frame(&mut frame, &ido).unwrap();
+
+    let js = JsIdo::decode_frame(&frame).unwrap();
+    assert_eq!(Ido::from(js).to_string(), ido.to_string());
+}
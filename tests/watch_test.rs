@@ -0,0 +1,63 @@
+use ido::Ido;
+
+#[test]
+fn test_watch() {
+    let mut ido = Ido::new();
+    let receiver = ido.watch(&1);
+
+    ido.set_integer(&1, 100);
+    ido.set_integer(&2, 200);
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.delete_item(&1);
+
+    let mut other = Ido::new();
+    other.set_integer(&1, 300);
+    ido.update(&other);
+
+    let received: Vec<String> = receiver.try_iter().filter_map(|item| item.as_string()).collect();
+    assert_eq!(received, ["100", "ORDER-1", "300"]);
+}
+
+#[test]
+fn test_watch_multiple_receivers() {
+    let mut ido = Ido::new();
+    let first = ido.watch(&1);
+    let second = ido.watch(&1);
+
+    ido.set_integer(&1, 100);
+    assert_eq!(first.try_recv().unwrap().m_integer, 100);
+    assert_eq!(second.try_recv().unwrap().m_integer, 100);
+
+    drop(first);
+    ido.set_integer(&1, 200);
+    ido.set_integer(&1, 300);
+    assert_eq!(second.try_iter().map(|item| item.m_integer).collect::<Vec<_>>(), [200, 300]);
+}
+
+#[test]
+fn test_watch_not_cloned() {
+    let mut ido = Ido::new();
+    let receiver = ido.watch(&1);
+
+    let mut copy = ido.clone();
+    copy.set_integer(&1, 100);
+    assert!(receiver.try_recv().is_err());
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_watch_broadcast() {
+    let mut ido = Ido::new();
+    let mut receiver = ido.watch_broadcast(&1, 2);
+    let mut late = receiver.resubscribe();
+
+    ido.set_integer(&1, 100);
+    ido.set_integer(&1, 200);
+    assert_eq!(receiver.try_recv().unwrap().m_integer, 100);
+
+    ido.set_integer(&1, 300);
+    assert!(late.try_recv().is_err());
+    assert_eq!(late.try_recv().unwrap().m_integer, 200);
+    assert_eq!(receiver.try_recv().unwrap().m_integer, 200);
+    assert_eq!(receiver.try_recv().unwrap().m_integer, 300);
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use ido::dictionary::IdoDictionary;
+use ido::xml::{XmlError, XmlKeys};
+use ido::Ido;
+
+fn order() -> Ido {
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+    leg.set_f64(&2, 1.25);
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "Tom & \"Jerry\" <tj>".to_string());
+    ido.set_i128(&2, i128::MAX);
+    ido.set_bool(&3, false);
+    ido.set_f64(&4, f64::NAN);
+    ido.append_array(&5, leg.clone());
+    ido.append_array(&5, leg);
+    ido
+}
+
+#[test]
+fn test_to_xml() {
+    assert_eq!(
+        order().to_xml(XmlKeys::Attributes),
+        "<ido>\
+         <item key=\"1\">Tom &amp; &quot;Jerry&quot; &lt;tj&gt;</item>\
+         <item key=\"2\" type=\"integer128\">170141183460469231731687303715884105727</item>\
+         <item key=\"3\" type=\"bool\">false</item>\
+         <item key=\"4\" type=\"float\">NaN</item>\
+         <item key=\"5\" type=\"array\">\
+         <ido><item key=\"1\">EURUSD</item><item key=\"2\" type=\"float\">1.25</item></ido>\
+         <ido><item key=\"1\">EURUSD</item><item key=\"2\" type=\"float\">1.25</item></ido>\
+         </item>\
+         </ido>"
+    );
+    assert_eq!(Ido::new().to_xml(XmlKeys::Elements), "<ido></ido>");
+}
+
+#[test]
+fn test_xml_round_trip() {
+    let names: IdoDictionary = [(1, "name"), (5, "legs"), (3, "not a name")].into_iter().collect();
+    let mut ido = order();
+    ido.set_f64(&4, -0.5);
+
+    for keys in [XmlKeys::Attributes, XmlKeys::Elements] {
+        assert!(Ido::from_xml(&ido.to_xml(keys), None).unwrap() == ido);
+
+        let xml = ido.with_names(&names).to_xml(keys);
+        assert!(Ido::from_xml(&xml, Some(&names)).unwrap() == ido);
+    }
+
+    let mut named = ido.clone();
+    named.set_names(Arc::new(names));
+    let xml = named.to_xml(XmlKeys::Elements);
+    assert!(xml.starts_with("<ido><name>Tom &amp;"));
+    assert!(xml.contains("<_3 type=\"bool\">false</_3>"));
+    assert!(xml.contains("<legs type=\"array\"><ido><name>EURUSD</name>"));
+    assert!(named.to_xml(XmlKeys::Attributes).starts_with("<ido><item key=\"name\">"));
+}
+
+#[test]
+fn test_from_xml() {
+    let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+               <!-- sent by the counterparty -->\n\
+               <ido>\n  \
+                 <item key='1'><![CDATA[a < b]]> &#x26; &#99;</item>\n  \
+                 <_2 type=\"integer\"> 42 </_2>\n  \
+                 <item key=\"3\"/>\n  \
+                 <item key=\"5\" type=\"array\"><ido/></item>\n\
+               </ido>\n";
+    let ido = Ido::from_xml(xml, None).unwrap();
+
+    assert_eq!(ido.get_string(&1), Some("a < b & c".to_string()));
+    assert_eq!(ido.get_i64(&2), Some(42));
+    assert_eq!(ido.get_string(&3), Some(String::new()));
+    assert!(ido.get_item(&5).unwrap().m_array == vec![Ido::new()]);
+}
+
+#[test]
+fn test_from_xml_errors() {
+    assert_eq!(Ido::from_xml("<ido><item key=\"1\">a</ido>", None).err(), Some(XmlError::Syntax(20)));
+    assert_eq!(Ido::from_xml("<root></root>", None).err(), Some(XmlError::Syntax(0)));
+    assert_eq!(Ido::from_xml("<ido></ido><ido/>", None).err(), Some(XmlError::Syntax(11)));
+    assert_eq!(
+        Ido::from_xml("<ido><price>1</price></ido>", None).err(),
+        Some(XmlError::UnknownName("price".to_string()))
+    );
+    assert_eq!(
+        Ido::from_xml("<ido><_7 type=\"integer\">x</_7></ido>", None).err(),
+        Some(XmlError::InvalidValue(7))
+    );
+    assert_eq!(
+        Ido::from_xml("<ido><_7 type=\"complex\">1</_7></ido>", None).err(),
+        Some(XmlError::InvalidValue(7))
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_xml_datetime() {
+    use chrono::{TimeZone, Utc};
+    use ido::{IdoItem, IdoItemType};
+
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::DATETIME;
+    item.m_datetime = Utc.with_ymd_and_hms(2023, 7, 1, 12, 30, 0).unwrap();
+
+    let mut ido = Ido::new();
+    ido.set_item(&1, item);
+
+    let xml = ido.to_xml(XmlKeys::Elements);
+    assert_eq!(xml, "<ido><_1 type=\"datetime\">2023-07-01T12:30:00Z</_1></ido>");
+    assert!(Ido::from_xml(&xml, None).unwrap() == ido);
+}
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use ido::dictionary::IdoDictionary;
+use ido::yaml::YamlError;
+use ido::Ido;
+
+fn order() -> Ido {
+    let mut leg = Ido::new();
+    leg.set_string(&1, "EURUSD".to_string());
+    leg.set_f64(&2, 1.0);
+
+    let mut ido = Ido::new();
+    ido.set_string(&1, "ORDER-1".to_string());
+    ido.set_string(&2, "true".to_string());
+    ido.set_integer(&3, -7);
+    ido.set_i128(&4, 12);
+    ido.set_f64(&6, f64::INFINITY);
+    ido.set_bool(&7, false);
+    ido.append_array(&5, leg);
+    ido.append_array(&5, Ido::new());
+    ido
+}
+
+#[test]
+fn test_to_yaml() {
+    assert_eq!(
+        order().to_yaml(),
+        "1: ORDER-1\n\
+         2: \"true\"\n\
+         3: -7\n\
+         4: !i128 12\n\
+         6: .inf\n\
+         7: false\n\
+         5:\n  \
+           - 1: EURUSD\n    \
+             2: 1.0\n  \
+           - {}\n"
+    );
+    assert_eq!(Ido::new().to_yaml(), "{}\n");
+}
+
+#[test]
+fn test_yaml_round_trip() {
+    let mut ido = order();
+    ido.set_string(&8, "line one\nline \"two\"".to_string());
+    ido.set_string(&9, "a: b # c".to_string());
+    ido.set_string(&10, String::new());
+    ido.set_i128(&11, i128::MIN);
+    ido.set_f64(&12, 2.5e-8);
+
+    let mut nested = Ido::new();
+    nested.append_array(&5, order());
+    ido.append_array(&13, nested);
+
+    assert!(Ido::from_yaml(&ido.to_yaml(), None).unwrap() == ido);
+
+    let names: IdoDictionary = [(1, "id"), (5, "legs"), (13, "has space")].into_iter().collect();
+    let yaml = ido.with_names(&names).to_yaml();
+    assert!(yaml.starts_with("id: ORDER-1\n"));
+    assert!(yaml.contains("\nlegs:\n  - id: EURUSD\n"));
+    assert!(yaml.contains("\nhas space:\n  - legs:\n      - id: ORDER-1\n"));
+    assert!(Ido::from_yaml(&yaml, Some(&names)).unwrap() == ido);
+
+    let mut named = ido.clone();
+    named.set_names(Arc::new(names));
+    assert_eq!(named.to_yaml(), yaml);
+}
+
+#[test]
+fn test_from_yaml() {
+    let yaml = "---\n\
+                # fixture\n\
+                1: plain text  # trailing comment\n\
+                2: 'it''s'\n\
+                3: \"\\u00e9\\t\"\n\
+                4: 170141183460469231731687303715884105727\n\
+                5:\n\
+                - 1: leg\n\
+                \x20 2: [] \n\
+                -\n\
+                \x20 3: .NaN\n\
+                6: 1e3\n\
+                7: \"x\" # quoted\n\
+                ...\n";
+    let ido = Ido::from_yaml(yaml, None).unwrap();
+
+    assert_eq!(ido.get_string(&1), Some("plain text".to_string()));
+    assert_eq!(ido.get_string(&2), Some("it's".to_string()));
+    assert_eq!(ido.get_string(&3), Some("\u{e9}\t".to_string()));
+    assert_eq!(ido.get_i128(&4), Some(i128::MAX));
+    assert_eq!(ido.get_f64(&6), Some(1000.0));
+    assert_eq!(ido.get_string(&7), Some("x".to_string()));
+
+    let legs = ido.get_item(&5).unwrap().m_array;
+    assert_eq!(legs.len(), 2);
+    assert_eq!(legs[0].get_string(&1), Some("leg".to_string()));
+    assert!(legs[0].get_item(&2).unwrap().m_array.is_empty());
+    assert!(legs[1].get_f64(&3).unwrap().is_nan());
+
+    assert!(Ido::from_yaml("", None).unwrap() == Ido::new());
+    assert!(Ido::from_yaml("{}  # nothing\n", None).unwrap() == Ido::new());
+}
+
+#[test]
+fn test_from_yaml_errors() {
+    let error = |yaml: &str| Ido::from_yaml(yaml, None).err().map(|error| (error.line, error.reason));
+
+    assert_eq!(error("1: a\n2 b\n"), Some((2, "expected `key: value`".to_string())));
+    assert_eq!(error("1: a\n  2: b\n"), Some((2, "unexpected indentation".to_string())));
+    assert_eq!(error("price: 1\n"), Some((1, "unknown key price".to_string())));
+    assert_eq!(error("1: ~\n"), Some((1, "null values are not supported".to_string())));
+    assert_eq!(error("1: &anchor a\n"), Some((1, "unsupported value &anchor a".to_string())));
+    assert_eq!(error("1:\n2: a\n"), Some((1, "missing value".to_string())));
+    assert_eq!(error("1: \"open\n"), Some((1, "unterminated string".to_string())));
+    assert_eq!(error("- 1: a\n"), Some((1, "expected a mapping entry".to_string())));
+    assert_eq!(error("1:\n  - - 1: a\n"), Some((2, "unexpected indentation".to_string())));
+    assert_eq!(
+        Ido::from_yaml("1: !i128 x\n", None).err(),
+        Some(YamlError { line: 1, reason: "invalid !i128 value x".to_string() })
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_yaml_datetime() {
+    use chrono::{TimeZone, Utc};
+    use ido::{IdoItem, IdoItemType};
+
+    let mut item = IdoItem::new();
+    item.m_type = IdoItemType::DATETIME;
+    item.m_datetime = Utc.with_ymd_and_hms(2023, 7, 1, 12, 30, 0).unwrap();
+
+    let mut ido = Ido::new();
+    ido.set_item(&1, item);
+    ido.set_string(&2, "2023-07-01T12:30:00Z".to_string());
+
+    let yaml = ido.to_yaml();
+    assert_eq!(yaml, "1: 2023-07-01T12:30:00Z\n2: \"2023-07-01T12:30:00Z\"\n");
+    assert!(Ido::from_yaml(&yaml, None).unwrap() == ido);
+}